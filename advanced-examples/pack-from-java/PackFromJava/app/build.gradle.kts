@@ -33,6 +33,8 @@ android {
 
         sourceSets.getByName("main") {
             jniLibs.srcDirs("src/main/jniLibs")
+            // The Java half of pack-java
+            java.srcDirs("src/main/java", "../../../pack-java/java")
         }
     }
 
@@ -22,13 +22,18 @@ import android.net.Uri;
 import android.os.Bundle;
 import android.util.Log;
 import android.view.View;
+import android.widget.Toast;
 import androidx.activity.EdgeToEdge;
 import androidx.appcompat.app.AppCompatActivity;
 import androidx.core.graphics.Insets;
 import androidx.core.view.ViewCompat;
 import androidx.core.view.WindowInsetsCompat;
+import com.google.pack.PackException;
+import com.google.pack.PackKeys;
+import com.google.pack.PackPackage;
 import java.io.IOException;
 import java.io.OutputStream;
+import java.util.Base64;
 
 public class MainActivity extends AppCompatActivity {
 
@@ -56,51 +61,68 @@ public class MainActivity extends AppCompatActivity {
 
     public void onApkClick(View v) {
         var samplePackage = createSamplePackage();
-        var apk = samplePackage.compileApk();
-        saveFileAs(
-            "output.apk",
-            "application/vnd.android.package-archive",
-            apk
-        );
+        try {
+            var apk = samplePackage.compileApk();
+            saveFileAs(
+                "output.apk",
+                "application/vnd.android.package-archive",
+                apk
+            );
+        } catch (PackException e) {
+            showError(e);
+        }
     }
 
     public void onAabClick(View v) {
         var samplePackage = createSamplePackage();
-        var aab = samplePackage.compileAab();
-        saveFileAs("output.aab", "application/x-authorware-bin", aab);
+        try {
+            var aab = samplePackage.compileAab();
+            saveFileAs("output.aab", "application/x-authorware-bin", aab);
+        } catch (PackException e) {
+            showError(e);
+        }
+    }
+
+    private void showError(PackException e) {
+        // getPath(), getLine() and getColumn() say where the problem is, when
+        // it's in one of the package's files
+        Log.e("MainActivity", "Failed to compile: " + e.getCode(), e);
+        Toast.makeText(this, e.getMessage(), Toast.LENGTH_LONG).show();
     }
 
     private PackPackage createSamplePackage() {
         var samplePackage = new PackPackage();
-        samplePackage.combinedPemString = StaticExampleData.COMBINED_PEM_STRING;
+        samplePackage.keys = PackKeys.fromPem(
+            StaticExampleData.COMBINED_PEM_STRING
+        );
 
-        samplePackage.androidManifest = StaticExampleData.ANDROID_MANIFEST;
+        samplePackage.setAndroidManifest(StaticExampleData.ANDROID_MANIFEST);
 
-        var watch_face_info = PackPackage.Resource.fromStringContents(
+        var watch_face_info = PackPackage.Resource.fromString(
             "xml",
             "watch_face_info.xml",
             StaticExampleData.WATCH_FACE_INFO
         );
         samplePackage.resources.add(watch_face_info);
 
-        var strings = PackPackage.Resource.fromStringContents(
+        var strings = PackPackage.Resource.fromString(
             "values",
             "strings.xml",
             StaticExampleData.STRINGS
         );
         samplePackage.resources.add(strings);
 
-        var watchface = PackPackage.Resource.fromStringContents(
+        var watchface = PackPackage.Resource.fromString(
             "raw",
             "watchface.xml",
             StaticExampleData.WATCH_FACE
         );
         samplePackage.resources.add(watchface);
 
-        var preview = PackPackage.Resource.fromBase64Contents(
+        var preview = new PackPackage.Resource(
             "drawable",
             "preview.png",
-            StaticExampleData.PREVIEW_PNG
+            Base64.getDecoder().decode(StaticExampleData.PREVIEW_PNG)
         );
         samplePackage.resources.add(preview);
 
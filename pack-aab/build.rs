@@ -17,8 +17,11 @@ fn main() -> Result<()> {
     prost_build::compile_protos(
         &[
             "src/proto/BundleConfig.proto",
+            "src/proto/Commands.proto",
             "src/proto/Resources.proto",
-            "src/proto/Configuration.proto"
+            "src/proto/Configuration.proto",
+            "src/proto/Files.proto",
+            "src/proto/Targeting.proto"
         ],
         &["src/"]
     )?;
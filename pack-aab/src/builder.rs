@@ -0,0 +1,180 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// pack-api always builds a bundle the same way, from a Package. AabBuilder is
+// for assembling one piece by piece instead, eg. when the resources come from
+// somewhere other than a res/ directory.
+use std::sync::Arc;
+
+use pack_asset_compiler::{
+    build_cache::BuildCache, public_xml_parser::ResourceDeclarations,
+    resource_internal_types::Resource, xml_namespaces::ToolsNamespace
+};
+use pack_common::Result;
+
+use crate::{
+    construct_aab, construct_bundle_metadata, modules::construct_asset_files, AabModule,
+    BundleConfigOptions, BASE_MODULE_NAME
+};
+
+/// Assembles the files of an Android App Bundle.
+///
+/// ```ignore
+/// let files = AabBuilder::new("com.example.watchface", manifest)
+///     .resources(resources)
+///     .asset(pack_zip::File { path: "fonts/clock.ttf".into(), data: font })
+///     .module(hires_backgrounds)
+///     .build()?;
+/// ```
+///
+/// The result isn't signed or zipped, see `pack_sign` and `pack_zip` for that.
+#[derive(Debug, Clone)]
+pub struct AabBuilder {
+    package_name: String,
+    android_manifest: String,
+    resources: Vec<Resource>,
+    declarations: ResourceDeclarations,
+    assets: Vec<pack_zip::File>,
+    modules: Vec<AabModule>,
+    bundle_config: BundleConfigOptions,
+    bundle_metadata: Vec<(String, Vec<u8>)>,
+    cache: Option<Arc<BuildCache>>,
+    sort_attributes: bool,
+    tools_namespace: ToolsNamespace,
+    compatibility_mode: bool,
+    inline_string_references: bool
+}
+
+impl AabBuilder {
+    /// Starts a bundle for `package_name`, eg. `com.example.watchface`, whose
+    /// base module has the given AndroidManifest.xml. The package name must
+    /// match the one in the manifest.
+    pub fn new(package_name: impl Into<String>, android_manifest: impl Into<String>) -> Self {
+        AabBuilder {
+            package_name: package_name.into(),
+            android_manifest: android_manifest.into(),
+            resources: vec![],
+            declarations: ResourceDeclarations::default(),
+            assets: vec![],
+            modules: vec![],
+            bundle_config: BundleConfigOptions::default(),
+            bundle_metadata: vec![],
+            cache: None,
+            sort_attributes: false,
+            tools_namespace: ToolsNamespace::default(),
+            compatibility_mode: false,
+            inline_string_references: false
+        }
+    }
+
+    /// Adds a resource to the base module.
+    pub fn resource(mut self, resource: Resource) -> Self {
+        self.resources.push(resource);
+        self
+    }
+
+    /// Adds resources to the base module.
+    pub fn resources(mut self, resources: impl IntoIterator<Item = Resource>) -> Self {
+        self.resources.extend(resources);
+        self
+    }
+
+    /// Marks base module resources as public or private, and declares which
+    /// of them runtime resource overlays can replace.
+    pub fn declarations(mut self, declarations: ResourceDeclarations) -> Self {
+        self.declarations.extend(declarations);
+        self
+    }
+
+    /// Adds a file to the base module's assets/ directory. Its path is
+    /// relative to assets/, eg. `fonts/clock.ttf`.
+    pub fn asset(mut self, asset: pack_zip::File) -> Self {
+        self.assets.push(asset);
+        self
+    }
+
+    /// Adds a module besides `base`, such as an asset pack.
+    pub fn module(mut self, module: AabModule) -> Self {
+        self.modules.push(module);
+        self
+    }
+
+    /// Sets how Google Play generates APKs from the bundle.
+    pub fn bundle_config(mut self, bundle_config: BundleConfigOptions) -> Self {
+        self.bundle_config = bundle_config;
+        self
+    }
+
+    /// Stores a file in `BUNDLE-METADATA/`. `path` must be in the form
+    /// `<namespace>/<name>`, eg. `com.example.tool/data.bin`.
+    pub fn bundle_metadata(mut self, path: impl Into<String>, contents: Vec<u8>) -> Self {
+        self.bundle_metadata.push((path.into(), contents));
+        self
+    }
+
+    /// Reuses ProtoXML compiled by previous builds with identical inputs.
+    pub fn cache(mut self, cache: Arc<BuildCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Sorts the attributes of each ProtoXML element by resource ID and then
+    /// by name, as aapt2 does, instead of keeping them in document order.
+    pub fn sort_attributes(mut self, sort_attributes: bool) -> Self {
+        self.sort_attributes = sort_attributes;
+        self
+    }
+
+    /// Sets what happens to `tools:` attributes. By default they're stripped,
+    /// as they are from APKs.
+    pub fn tools_namespace(mut self, tools_namespace: ToolsNamespace) -> Self {
+        self.tools_namespace = tools_namespace;
+        self
+    }
+
+    /// Reproduces what aapt2 and bundletool write wherever PACK's output
+    /// would otherwise differ. See [construct_aab] for what changes.
+    pub fn compatibility_mode(mut self, compatibility_mode: bool) -> Self {
+        self.compatibility_mode = compatibility_mode;
+        self
+    }
+
+    /// Replaces attributes that refer to strings, eg. `@string/app_name`,
+    /// with the strings themselves in the manifest and the base module's XML
+    /// files.
+    pub fn inline_string_references(mut self, inline_string_references: bool) -> Self {
+        self.inline_string_references = inline_string_references;
+        self
+    }
+
+    /// Compiles everything that was added into the files of the bundle.
+    pub fn build(self) -> Result<Vec<pack_zip::File>> {
+        let mut files = construct_aab(
+            &self.package_name,
+            self.android_manifest,
+            &self.resources,
+            &self.declarations,
+            &self.modules,
+            &self.bundle_config,
+            self.cache.as_deref(),
+            self.sort_attributes,
+            self.tools_namespace,
+            self.compatibility_mode,
+            self.inline_string_references
+        )?;
+        files.extend(construct_asset_files(BASE_MODULE_NAME, &self.assets)?);
+        files.extend(construct_bundle_metadata(&self.bundle_metadata)?);
+        Ok(files)
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// BundleConfig.pb tells Google Play how to turn the bundle into APKs. Most of
+// its fields only matter for apps with code, but compression and splitting
+// settings are useful for watch faces too.
+use pack_common::BundleConfigOptions;
+
+use crate::{
+    android::bundle::{
+        resource_optimizations::CollapsedResourceNames, split_dimension, BundleConfig, Bundletool,
+        Compression, Optimizations, ResourceOptimizations, SplitDimension, SplitsConfig,
+        StoreArchive, UncompressDexFiles, UncompressNativeLibraries
+    },
+    inner_proto, proto
+};
+
+/// We will lie and claim to be this version of BundleTool
+const BUNDLETOOL_SPOOF_VERSION: &str = "1.15.6";
+
+/// Creates a proto object for the `BundleConfig.pb` file which is required at the root
+/// of an App Bundle.
+pub fn construct_bundle_config(options: &BundleConfigOptions) -> BundleConfig {
+    let mut split_dimensions = vec![];
+    for (value, enabled) in [
+        (
+            split_dimension::Value::ScreenDensity,
+            options.split_by_density
+        ),
+        (split_dimension::Value::Language, options.split_by_language)
+    ] {
+        if let Some(enabled) = enabled {
+            split_dimensions.push(inner_proto! {SplitDimension,
+                value: value as i32,
+                negate: !enabled
+            });
+        }
+    }
+
+    inner_proto! {BundleConfig,
+        bundletool: proto! {Bundletool,
+            version: BUNDLETOOL_SPOOF_VERSION.into()
+        },
+        compression: proto! {Compression,
+            uncompressed_glob: options.uncompressed_globs.clone()
+        },
+        optimizations: proto! {Optimizations,
+            splits_config: proto! {SplitsConfig, split_dimension: split_dimensions },
+            uncompress_native_libraries: proto! {UncompressNativeLibraries,
+                enabled: options.uncompress_native_libraries
+            },
+            uncompress_dex_files: proto! {UncompressDexFiles,
+                enabled: options.uncompress_dex_files
+            },
+            resource_optimizations: proto! {ResourceOptimizations,
+                collapsed_resource_names: proto! {CollapsedResourceNames,
+                    collapse_resource_names: options.collapse_resource_names
+                }
+            },
+            store_archive: options.store_archive.map(|enabled| StoreArchive { enabled })
+        }
+    }
+}
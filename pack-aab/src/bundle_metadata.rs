@@ -0,0 +1,49 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Bundles can carry files that aren't part of any APK, for tools and stores to
+// read, such as obfuscation mappings. They live in BUNDLE-METADATA/ and are
+// namespaced by the tool that owns them, eg.
+// BUNDLE-METADATA/com.android.tools.build.obfuscation/proguard.map
+use pack_common::{PackError, Result};
+
+pub(crate) const BUNDLE_METADATA_DIR: &str = "BUNDLE-METADATA";
+
+/// Places each `(path, contents)` pair under `BUNDLE-METADATA/`.
+///
+/// Paths must be in the form `<namespace>/<name>`, where the namespace is
+/// usually a reverse domain name like `com.example.tool`.
+pub fn construct_bundle_metadata(metadata: &[(String, Vec<u8>)]) -> Result<Vec<pack_zip::File>> {
+    let mut files: Vec<pack_zip::File> = vec![];
+    for (path, contents) in metadata {
+        let segments: Vec<&str> = path.split('/').collect();
+        if segments.len() < 2
+            || segments
+                .iter()
+                .any(|segment| segment.is_empty() || *segment == "." || *segment == "..")
+            || path.contains('\\')
+        {
+            return Err(PackError::BundleMetadataPathInvalid(path.clone()));
+        }
+        let full_path = format!("{BUNDLE_METADATA_DIR}/{path}");
+        if files.iter().any(|file| file.path == full_path) {
+            return Err(PackError::BundleMetadataPathInvalid(path.clone()));
+        }
+        files.push(pack_zip::File {
+            path: full_path,
+            data: contents.clone()
+        });
+    }
+    Ok(files)
+}
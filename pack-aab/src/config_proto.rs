@@ -0,0 +1,52 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Conversions between the asset compiler's ResourceConfig and the equivalent
+// aapt2 Configuration proto, for the dimensions PACK supports.
+use pack_asset_compiler::resource_config::ResourceConfig;
+
+use crate::aapt::pb::{configuration::ScreenRound, Configuration};
+
+/// Returns the `Configuration` proto for a [ResourceConfig].
+pub fn resource_config_to_proto(config: &ResourceConfig) -> Configuration {
+    Configuration {
+        // aapt2 stores locales as BCP-47 tags, eg. "de-AT"
+        locale: config.locale_tag().unwrap_or_default(),
+        screen_round: match config.round {
+            Some(true) => ScreenRound::Round,
+            Some(false) => ScreenRound::Notround,
+            None => ScreenRound::Unset
+        } as i32,
+        density: config.density.unwrap_or(0) as u32,
+        sdk_version: config.sdk_version.unwrap_or(0) as u32,
+        ..Default::default()
+    }
+}
+
+/// Returns the [ResourceConfig] for a `Configuration` proto, ignoring any
+/// dimensions PACK doesn't support.
+pub fn resource_config_from_proto(config: &Configuration) -> ResourceConfig {
+    let mut locale = config.locale.split('-').filter(|part| !part.is_empty());
+    ResourceConfig {
+        language: locale.next().map(str::to_string),
+        region: locale.next().map(str::to_string),
+        round: match ScreenRound::try_from(config.screen_round) {
+            Ok(ScreenRound::Round) => Some(true),
+            Ok(ScreenRound::Notround) => Some(false),
+            _ => None
+        },
+        density: (config.density != 0).then_some(config.density as u16),
+        sdk_version: (config.sdk_version != 0).then_some(config.sdk_version as u16)
+    }
+}
@@ -0,0 +1,101 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Applies public.xml-style declarations to the entries of a resources.pb.
+use pack_asset_compiler::{
+    public_xml_parser::{OverlayablePolicy, ResourceDeclarations, ResourceVisibility},
+    resource_table::ResourceIds
+};
+use pack_common::{PackError, Result};
+
+use crate::aapt::pb::{
+    overlayable_item::Policy, visibility::Level, Entry, Overlayable, OverlayableItem, Type
+};
+
+/// Sets the visibility and overlayable item of each declared entry, and
+/// returns the table's list of `<overlayable>`s.
+pub(crate) fn apply_declarations(
+    res_types: &mut [Type],
+    ids: &ResourceIds,
+    declarations: &ResourceDeclarations
+) -> Result<Vec<Overlayable>> {
+    for (res_type, name, visibility) in &declarations.visibility {
+        let entry = find_entry(res_types, ids, res_type, name)?;
+        if let Some(entry_visibility) = entry.visibility.as_mut() {
+            entry_visibility.level = match visibility {
+                ResourceVisibility::Public => Level::Public,
+                ResourceVisibility::Private => Level::Private
+            } as i32;
+        }
+    }
+
+    let mut overlayables = vec![];
+    for (overlayable_idx, overlayable) in declarations.overlayables.iter().enumerate() {
+        overlayables.push(Overlayable {
+            name: overlayable.name.clone(),
+            source: None,
+            actor: overlayable.actor.clone().unwrap_or_default()
+        });
+        for item in &overlayable.items {
+            let entry = find_entry(res_types, ids, &item.res_type, &item.name)?;
+            if entry.overlayable_item.is_some() {
+                return Err(PackError::ResourceDeclarationInvalid(format!(
+                    "{}/{} is in more than one <overlayable>.",
+                    item.res_type, item.name
+                )));
+            }
+            entry.overlayable_item = Some(OverlayableItem {
+                policy: item
+                    .policies
+                    .iter()
+                    .map(|policy| policy_to_proto(*policy) as i32)
+                    .collect(),
+                overlayable_idx: overlayable_idx as u32,
+                ..Default::default()
+            });
+        }
+    }
+    Ok(overlayables)
+}
+
+fn find_entry<'a>(
+    res_types: &'a mut [Type],
+    ids: &ResourceIds,
+    res_type: &str,
+    name: &str
+) -> Result<&'a mut Entry> {
+    let type_idx = ids.types.iter().position(|(t, _)| t == res_type);
+    let entry_idx =
+        type_idx.and_then(|type_idx| ids.types[type_idx].1.iter().position(|e| e == name));
+    match (type_idx, entry_idx) {
+        (Some(type_idx), Some(entry_idx)) => Ok(&mut res_types[type_idx].entry[entry_idx]),
+        _ => Err(PackError::ResourceDeclarationInvalid(format!(
+            "{res_type}/{name} is declared, but there is no such resource."
+        )))
+    }
+}
+
+fn policy_to_proto(policy: OverlayablePolicy) -> Policy {
+    match policy {
+        OverlayablePolicy::Public => Policy::Public,
+        OverlayablePolicy::System => Policy::System,
+        OverlayablePolicy::Vendor => Policy::Vendor,
+        OverlayablePolicy::Product => Policy::Product,
+        OverlayablePolicy::Signature => Policy::Signature,
+        OverlayablePolicy::Odm => Policy::Odm,
+        OverlayablePolicy::Oem => Policy::Oem,
+        OverlayablePolicy::Actor => Policy::Actor,
+        OverlayablePolicy::ConfigSignature => Policy::ConfigSignature
+    }
+}
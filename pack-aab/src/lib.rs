@@ -31,98 +31,146 @@ pub mod aapt {
         include!(concat!(env!("OUT_DIR"), "/aapt.pb.rs"));
     }
 }
+mod builder;
+mod bundle_config;
+mod bundle_metadata;
+mod config_proto;
+mod declarations;
+mod modules;
+mod proto_decoder;
 mod proto_util;
 mod proto_xml;
+mod targeting;
+mod validation;
 
 use std::io::Cursor;
 
 use aapt::pb::{
-    file_reference, item, value, ConfigValue, Configuration, Entry, EntryId, FileReference, Item,
-    Package, PackageId, ResourceTable, Source, StringPool, ToolFingerprint, Type, TypeId, Value,
-    Visibility
+    file_reference, item, reference, value, ConfigValue, Entry, EntryId, FileReference, Item,
+    Package, PackageId, Reference, ResourceTable, Source, StringPool, ToolFingerprint, Type,
+    TypeId, Value, Visibility
 };
-use android::bundle::{BundleConfig, Bundletool};
+use bundle_config::construct_bundle_config;
+use declarations::apply_declarations;
 use deku::prelude::*;
-use pack_asset_compiler::{resource_internal_types::Resource, string_pool::construct_string_pool};
-use pack_common::{PackError, Result};
+use modules::{construct_module_files, validate_module_names};
+use pack_asset_compiler::{
+    build_cache::BuildCache, inline_strings::StringValues, resource_table::ResourceIds,
+    string_pool::construct_string_pool
+};
+use pack_common::{map_in_order, Result};
 use prost::Message;
-use proto_xml::xml_string_to_proto_xml;
+use proto_xml::{add_platform_build_attributes, xml_string_to_proto_xml};
 
-/// We will lie and claim to be this version of BundleTool
-const BUNDLETOOL_SPOOF_VERSION: &str = "1.15.6";
-const USER_PACKAGE_ID: u32 = 0x7F;
+pub use builder::AabBuilder;
+pub use bundle_metadata::construct_bundle_metadata;
+pub use config_proto::{resource_config_from_proto, resource_config_to_proto};
+pub use modules::AabModule;
+// The types AabBuilder takes, so that users don't need to depend on pack-asset-compiler
+pub use pack_asset_compiler::{
+    public_xml_parser::ResourceDeclarations,
+    resource_config::ResourceConfig,
+    resource_internal_types::{FileResource, Resource, StringResource},
+    xml_namespaces::ToolsNamespace
+};
+// Shared with pack-api, which needs them even when built without bundles
+pub use pack_common::{BundleConfigOptions, ModuleDelivery, ModuleKind};
+pub use proto_decoder::{decode_proto_resource_table, decode_proto_xml};
+pub use proto_xml::proto_xml_to_xml_string;
+pub use targeting::{construct_assets_targeting, construct_native_targeting};
+pub use validation::validate;
 
-/// Creates a proto object for the `BundleConfig.pb` file which is required at the root
-/// of an App Bundle.
-///
-/// Luckily, DWF uses very few of the available fields for this file.
-fn construct_bundle_config() -> BundleConfig {
-    inner_proto! {BundleConfig,
-        bundletool: proto! {Bundletool,
-            version: BUNDLETOOL_SPOOF_VERSION.into()
-        }
-    }
-}
+const BASE_MODULE_NAME: &str = "base";
 
-// TODO: Share this from somewhere common in asset-compiler
-fn construct_resource_string_pool(
-    resources: &mut [Resource],
-    raw_application_label: &Option<String>
-) -> Result<Vec<u8>> {
-    // bundletool appears to prepend the app's android:label here, but I've
-    // tested and it works with any arbitrary string.
-    // For that reason, we'll follow what they do, but it's not a fatal
-    // error if your app doesn't define a label.
-    let application_label = if let Some(label) = raw_application_label {
-        get_application_label(label, resources)?
-    } else {
-        "app"
-    };
-    let path_strings: Vec<String> = resources
-        .iter()
-        .map(|res| format!("{}/{}", application_label, res.get_string_pool_string()))
-        .collect();
-    // The real bundletool always adds a "" string at position 0, I would guess
-    // for returning a ResourceID for any empty attribute, but it's unnecessary.
-    Ok(construct_string_pool(&path_strings)?.to_bytes()?)
+// What aapt2 calls itself in the resource tables it writes. Like the
+// bundletool version in BundleConfig.pb, the version is one we claim to be.
+const AAPT2_TOOL_NAME: &str = "Android Asset Packaging Tool (aapt)";
+const AAPT2_SPOOF_VERSION: &str = "2.19-10229193";
+
+// The source pool lists the files that resources were compiled from, and each
+// value refers to its file by index. Several values can share a file, such as
+// all the strings from one strings.xml.
+//
+// Returns the pool and each resource's index into it.
+fn construct_source_pool(resources: &[Resource]) -> Result<(Vec<u8>, Vec<u32>)> {
+    // bundletool always puts an empty string first
+    let mut source_paths = vec![String::new()];
+    let mut path_indices = vec![];
+    for res in resources {
+        let source_path = match res {
+            Resource::File(file) => file.get_path(),
+            Resource::String(string) => {
+                format!("res/values{}/strings.xml", string.config.directory_suffix())
+            }
+            // Which values file an alias came from isn't kept track of
+            Resource::Alias(alias) => format!("res/values{}", alias.config.directory_suffix())
+        };
+        let path_idx = match source_paths.iter().position(|path| *path == source_path) {
+            Some(path_idx) => path_idx,
+            None => {
+                source_paths.push(source_path);
+                source_paths.len() - 1
+            }
+        };
+        path_indices.push(path_idx as u32);
+    }
+    Ok((
+        construct_string_pool(&source_paths)?.to_bytes()?,
+        path_indices
+    ))
 }
 
-fn construct_tool_fingerprint() -> Vec<ToolFingerprint> {
+// In compatibility mode, the table claims to be from aapt2, as bundles built
+// with Android's own tools are
+fn construct_tool_fingerprint(compatibility_mode: bool) -> Vec<ToolFingerprint> {
+    if compatibility_mode {
+        return vec![ToolFingerprint {
+            tool: AAPT2_TOOL_NAME.into(),
+            version: AAPT2_SPOOF_VERSION.into()
+        }];
+    }
     vec![ToolFingerprint {
         tool: "pack-aab".into(),
         version: env!("CARGO_PKG_VERSION").into()
     }]
 }
 
-fn construct_types_table(sorted_resources: &mut Vec<Resource>) -> Result<Vec<Type>> {
-    let mut res_types = vec![];
-
-    let mut previous_type = "".to_string();
-    let mut type_id = 0;
-    let mut current_type: Option<Type> = None;
-    let mut entry_id = 0;
-    // path_idx appears to be one-based
-    let mut path_idx = 1;
-    for res in sorted_resources {
-        if res.get_subdirectory() != previous_type {
-            type_id += 1;
-            previous_type = res.get_subdirectory().into();
-
-            if let Some(c_type) = &current_type {
-                res_types.push(c_type.clone());
+// Entries are numbered the same way as in an APK's resources.arsc
+fn construct_types_table(
+    sorted_resources: &[Resource],
+    ids: &ResourceIds,
+    source_path_indices: &[u32]
+) -> Result<Vec<Type>> {
+    let mut res_types: Vec<Type> = ids
+        .types
+        .iter()
+        .enumerate()
+        .map(|(type_idx, (type_name, entries))| {
+            inner_proto! {Type,
+                type_id: proto!{TypeId, id: type_idx as u32 + 1 },
+                name: type_name.clone(),
+                entry: entries
+                    .iter()
+                    .enumerate()
+                    .map(|(entry_idx, entry_name)| inner_proto! {Entry,
+                        entry_id: proto! {EntryId,
+                          id: entry_idx as u32
+                        },
+                        name: entry_name.clone(),
+                        visibility: empty_proto!(Visibility)
+                    })
+                    .collect()
             }
-            current_type = proto! {Type,
-                type_id: proto!{TypeId, id: type_id },
-                name: res.get_subdirectory().into()
-            };
-            entry_id = 0;
-        }
+        })
+        .collect();
 
+    // Each resource is a value for one configuration of its entry
+    for (res_idx, res) in sorted_resources.iter().enumerate() {
         let value = match res {
             Resource::File(file) => {
                 let path = file.get_path();
                 let extension = match res.get_subdirectory() {
-                    "xml" => file_reference::Type::ProtoXml,
+                    _ if file.is_compiled_xml() => file_reference::Type::ProtoXml,
                     "drawable" => file_reference::Type::Png,
                     _ => file_reference::Type::Unknown
                 };
@@ -134,65 +182,129 @@ fn construct_types_table(sorted_resources: &mut Vec<Resource>) -> Result<Vec<Typ
             }
             Resource::String(string) => item::Value::Str(aapt::pb::String {
                 value: string.value.clone()
+            }),
+            Resource::Alias(alias) => item::Value::Ref(Reference {
+                r#type: reference::Type::Reference as i32,
+                id: ids.alias_target(alias)?,
+                // Trim the @
+                name: alias.target.trim()[1..].to_string(),
+                ..Reference::default()
             })
         };
 
-        let c_type = current_type.as_mut().unwrap();
-        c_type.entry.push(inner_proto! {Entry,
-            entry_id: proto! {EntryId,
-              id: entry_id
-            },
-            name: res.get_basename()?,
-            visibility: empty_proto!(Visibility),
-            config_value: vec![ConfigValue {
-                config: empty_proto!(Configuration),
+        let (type_idx, entry_idx) = ids.positions[res_idx];
+        res_types[type_idx].entry[entry_idx]
+            .config_value
+            .push(ConfigValue {
+                config: Some(resource_config_to_proto(res.get_config())),
                 value: proto! {Value,
                     source: proto! {Source,
-                        path_idx: path_idx
+                        path_idx: source_path_indices[res_idx]
                     },
                     value: Some(value::Value::Item(inner_proto! {Item,
                         value: Some(value)
                     }))
                 }
-            }]
-        });
-
-        entry_id += 1;
-        path_idx += 1;
-    }
-    if let Some(c_type) = &current_type {
-        res_types.push(c_type.clone());
+            });
     }
 
     Ok(res_types)
 }
 
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
 fn construct_resource_table(
     package_name: &str,
-    application_label: &Option<String>,
-    resources: &mut Vec<Resource>
+    resources: &[Resource],
+    ids: &ResourceIds,
+    declarations: &ResourceDeclarations,
+    compatibility_mode: bool
 ) -> Result<ResourceTable> {
-    let string_pool = construct_resource_string_pool(resources, application_label)?;
+    let (source_pool, source_path_indices) = construct_source_pool(resources)?;
+    let mut res_types = construct_types_table(resources, ids, &source_path_indices)?;
+    let overlayables = apply_declarations(&mut res_types, ids, declarations)?;
 
     Ok(inner_proto! { ResourceTable,
-        source_pool: proto! {StringPool, data: string_pool },
+        source_pool: proto! {StringPool, data: source_pool },
         package: vec![Package {
-            package_id: proto! {PackageId, id: USER_PACKAGE_ID },
+            package_id: proto! {PackageId, id: ids.package_id },
             package_name: package_name.into(),
-            r#type: construct_types_table(resources)?
+            r#type: res_types
         }],
-        tool_fingerprint: construct_tool_fingerprint()
+        overlayable: overlayables,
+        tool_fingerprint: construct_tool_fingerprint(compatibility_mode)
     })
 }
 
+/// Compiles the files that make up an AAB, ready to be signed and zipped.
+///
+/// `resources` belong to the base module. Any `modules`, such as asset packs,
+/// are added alongside it. `bundle_config` controls how Google Play generates
+/// APKs from the bundle. `declarations` mark base resources as public or
+/// private, and group them into `<overlayable>`s that runtime resource
+/// overlays can replace.
+///
+/// If a [BuildCache] is provided, ProtoXML files which were compiled by a previous
+/// build with identical inputs are reused instead of recompiled.
+///
+/// With `sort_attributes`, the attributes of each ProtoXML element are sorted
+/// by resource ID and then by name, as aapt2 does, instead of being kept in
+/// document order. This makes bundles easier to compare with aapt2's.
+///
+/// `tools:` attributes are stripped, kept or rejected according to
+/// `tools_namespace`, the same as when building an APK.
+///
+/// With `compatibility_mode`, the bundle reproduces what aapt2 and bundletool
+/// write where PACK would otherwise differ: attributes are sorted as with
+/// `sort_attributes`, manifests get the `compileSdkVersion` and
+/// `platformBuildVersion` attributes aapt2 adds, as APK manifests always do,
+/// and each `resources.pb` names aapt2 as the tool that wrote it.
+///
+/// With `inline_string_references`, attributes of the manifest and the base
+/// module's XML files that refer to strings get the default config's strings
+/// instead, as with `XmlOptions::inline_string_references` for APKs.
+///
+/// [AabBuilder] does the same, and can also add base module assets and bundle
+/// metadata.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(skip_all, fields(resources = resources.len(), modules = modules.len()))
+)]
+#[allow(clippy::too_many_arguments)]
 pub fn construct_aab(
     package_name: &str,
-    application_label: &Option<String>,
     android_manifest: String,
-    resources: &mut Vec<Resource>
+    resources: &[Resource],
+    declarations: &ResourceDeclarations,
+    modules: &[AabModule],
+    bundle_config: &BundleConfigOptions,
+    cache: Option<&BuildCache>,
+    sort_attributes: bool,
+    tools_namespace: ToolsNamespace,
+    compatibility_mode: bool,
+    inline_string_references: bool
 ) -> Result<Vec<pack_zip::File>> {
-    let bundle_config = construct_bundle_config();
-    let resource_table = construct_resource_table(package_name, application_label, resources)?;
+    let sort_attributes = sort_attributes || compatibility_mode;
+    let string_values = inline_string_references.then(|| StringValues::new(resources));
+    let bundle_config = construct_bundle_config(bundle_config);
+    let ids = ResourceIds::new(resources)?;
+    let resource_table = construct_resource_table(
+        package_name,
+        resources,
+        &ids,
+        declarations,
+        compatibility_mode
+    )?;
+    let mut manifest = xml_string_to_proto_xml(
+        &mut Cursor::new(android_manifest),
+        std::slice::from_ref(&ids),
+        sort_attributes,
+        tools_namespace,
+        string_values.as_ref()
+    )
+    .map_err(|err| err.in_file("AndroidManifest.xml"))?;
+    if compatibility_mode {
+        add_platform_build_attributes(&mut manifest, std::slice::from_ref(&ids), sort_attributes)?;
+    }
 
     let mut files = vec![
         pack_zip::File {
@@ -200,63 +312,129 @@ pub fn construct_aab(
             data: bundle_config.encode_to_vec()
         },
         pack_zip::File {
-            path: "base/resources.pb".into(),
+            path: format!("{BASE_MODULE_NAME}/resources.pb"),
             data: resource_table.encode_to_vec()
         },
         pack_zip::File {
-            path: "base/manifest/AndroidManifest.xml".into(),
-            data: xml_string_to_proto_xml(&mut Cursor::new(android_manifest), resources)?
-                .encode_to_vec()
+            path: format!("{BASE_MODULE_NAME}/manifest/AndroidManifest.xml"),
+            data: manifest.encode_to_vec()
         },
     ];
+    files.extend(construct_res_files(
+        BASE_MODULE_NAME,
+        resources,
+        std::slice::from_ref(&ids),
+        cache,
+        sort_attributes,
+        tools_namespace,
+        string_values.as_ref()
+    )?);
 
-    let res_clone = resources.clone();
-    for res in resources {
-        if let Resource::File(res_file) = res {
-            let res_bytes = if res_file.subdirectory == "xml" {
-                let xml_node = xml_string_to_proto_xml(
-                    &mut Cursor::new(res_file.contents.clone()),
-                    &res_clone
-                )?;
-                xml_node.encode_to_vec()
-            } else {
-                // Other files can be dumped in verbatim
-                res_file.contents.clone()
-            };
-            files.push(pack_zip::File {
-                path: format!("base/{}", res_file.get_path()),
-                data: res_bytes
-            })
-        }
+    validate_module_names(modules)?;
+    // Feature modules get their own package ID, counting down from the base's
+    let mut next_package_id = ids.package_id;
+    for module in modules {
+        let package_id = match module.kind {
+            ModuleKind::Feature => {
+                next_package_id -= 1;
+                next_package_id
+            }
+            ModuleKind::AssetPack => 0
+        };
+        files.extend(construct_module_files(
+            package_name,
+            module,
+            package_id,
+            &ids,
+            sort_attributes,
+            tools_namespace,
+            compatibility_mode
+        )?);
     }
 
     Ok(files)
 }
 
-/// We have the string that was in the android:label="" attribute, but it might
-/// be a reference to a resource ("@string/blah"), so we have to dereference it.
-fn get_application_label<'a>(label_literal: &'a str, resources: &'a [Resource]) -> Result<&'a str> {
-    if !label_literal.starts_with("@") {
-        return Ok(label_literal);
-    }
-
-    let subdir_and_name: Vec<&str> = label_literal.split("/").collect();
-    if subdir_and_name.len() != 2 {
-        return Err(PackError::ReferenceAttributeParsingFailed(
-            label_literal.to_string()
-        ));
-    }
-    let name = subdir_and_name[1];
-
-    for res in resources {
-        if let Resource::String(str_res) = res {
-            if str_res.name == name {
-                return Ok(&str_res.value);
-            }
-        }
-    }
+/// Compiles a single XML file to ProtoXML, as it's stored in a bundle.
+/// References such as `@drawable/preview` are looked up in `resources`, which
+/// should be all of the base module's resources so that their IDs match a full
+/// build.
+pub fn compile_proto_xml(xml: &[u8], resources: &[Resource]) -> Result<Vec<u8>> {
+    let ids = ResourceIds::new(resources)?;
+    let xml_node = xml_string_to_proto_xml(
+        &mut Cursor::new(xml),
+        std::slice::from_ref(&ids),
+        false,
+        ToolsNamespace::default(),
+        None
+    )?;
+    Ok(xml_node.encode_to_vec())
+}
 
-    Err(PackError::ReferenceAttributeParsingFailed(
-        label_literal.to_string()
-    ))
+// Compiles a module's res/ files, in parallel with the `parallel` feature. XML
+// is converted to ProtoXML, with references looked up in each of `packages` in
+// turn, and string references inlined with `string_values`.
+fn construct_res_files(
+    module_name: &str,
+    resources: &[Resource],
+    packages: &[ResourceIds],
+    cache: Option<&BuildCache>,
+    sort_attributes: bool,
+    tools_namespace: ToolsNamespace,
+    string_values: Option<&StringValues>
+) -> Result<Vec<pack_zip::File>> {
+    // Inlined strings are part of the output, so they're part of the key
+    let stage = format!(
+        "aab-xml{}-{:?}{}",
+        if sort_attributes { "-sorted" } else { "" },
+        tools_namespace,
+        string_values.map_or(String::new(), |values| format!(
+            "-inline-{}",
+            values.digest()
+        ))
+    );
+    let res_files: Vec<&FileResource> = resources
+        .iter()
+        .filter_map(|res| match res {
+            Resource::File(res_file) => Some(res_file),
+            _ => None
+        })
+        .collect();
+    // Files may be compiled on other threads, which don't know which span
+    // they're in
+    #[cfg(feature = "tracing")]
+    let parent = tracing::Span::current();
+    map_in_order(&res_files, |res_file| {
+        #[cfg(feature = "tracing")]
+        let _span =
+            tracing::debug_span!(parent: &parent, "compile_resource", path = %res_file.get_path())
+                .entered();
+        let res_bytes = if res_file.needs_compiling() {
+            let compile = || -> Result<Vec<u8>> {
+                let xml_node = xml_string_to_proto_xml(
+                    &mut Cursor::new(&res_file.contents),
+                    packages,
+                    sort_attributes,
+                    tools_namespace,
+                    string_values
+                )?;
+                Ok(xml_node.encode_to_vec())
+            };
+            let compiled = match cache {
+                Some(cache) => cache.get_or_compile(
+                    BuildCache::key(&stage, resources, &res_file.contents),
+                    compile
+                ),
+                None => compile()
+            };
+            compiled.map_err(|err| err.in_file(res_file.get_path()))?
+        } else {
+            // Other files can be dumped in verbatim
+            res_file.contents.to_vec()
+        };
+        Ok(pack_zip::File {
+            path: format!("{module_name}/{}", res_file.get_path()),
+            data: res_bytes
+        })
+    })
 }
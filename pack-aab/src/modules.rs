@@ -0,0 +1,245 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Bundles can contain modules besides `base`, which Play delivers separately.
+// Watch faces with lots of artwork can move it into asset packs, so that the
+// initial download stays small.
+//
+// Each module is a directory at the root of the bundle, laid out like the base
+// module, with a manifest whose <dist:module> element tells Play what it is.
+use std::io::Cursor;
+
+use pack_asset_compiler::{
+    public_xml_parser::ResourceDeclarations, resource_internal_types::Resource,
+    resource_table::ResourceIds, xml_namespaces::ToolsNamespace
+};
+use pack_common::{ModuleDelivery, ModuleKind, PackError, Result};
+use prost::Message;
+
+use crate::{
+    construct_res_files, construct_resource_table,
+    proto_xml::{add_platform_build_attributes, escape_xml, xml_string_to_proto_xml},
+    targeting::{construct_assets_targeting, construct_native_targeting},
+    BASE_MODULE_NAME
+};
+
+const DIST_NAMESPACE: &str = "http://schemas.android.com/apk/distribution";
+
+/// A module in an App Bundle other than `base`.
+#[derive(Debug, Clone)]
+pub struct AabModule {
+    /// eg. `hires_backgrounds`. Must be unique and only use letters, digits
+    /// and underscores.
+    pub name: String,
+    pub kind: ModuleKind,
+    pub delivery: ModuleDelivery,
+    /// A reference to a string in the base module, eg. `@string/backgrounds_title`,
+    /// that's shown to users while the module downloads. Play requires this
+    /// for feature modules that aren't delivered at install time.
+    pub title: Option<String>,
+    /// Resources from the module's res/ directories. Only feature modules can
+    /// have resources.
+    pub resources: Vec<Resource>,
+    /// Files from the module's assets/ directory, with paths relative to it,
+    /// eg. `backgrounds/night.png`.
+    ///
+    /// Directories can be targeted at particular devices by suffixing their
+    /// names, eg. `textures#tcf_astc/`, `backgrounds#tier_1/` or `voice#lang_de/`,
+    /// the same as with bundletool.
+    pub assets: Vec<pack_zip::File>,
+    /// Native libraries from the module's lib/ directory, with paths relative
+    /// to it, eg. `arm64-v8a/libclock.so`. Only feature modules can have
+    /// native libraries.
+    pub native_libraries: Vec<pack_zip::File>
+}
+
+pub(crate) fn validate_module_names(modules: &[AabModule]) -> Result<()> {
+    for (i, module) in modules.iter().enumerate() {
+        if module.name.is_empty()
+            || !module
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(PackError::ModuleInvalid(format!(
+                "Module name \"{}\" must only contain letters, digits and underscores.",
+                module.name
+            )));
+        }
+        if module.name == BASE_MODULE_NAME
+            || modules[..i].iter().any(|other| other.name == module.name)
+        {
+            return Err(PackError::ModuleInvalid(format!(
+                "There is more than one module called \"{}\".",
+                module.name
+            )));
+        }
+    }
+    Ok(())
+}
+
+// Compiles everything in a module's directory. `package_id` is only used for
+// feature modules, whose resources can also reference the base module's.
+pub(crate) fn construct_module_files(
+    package_name: &str,
+    module: &AabModule,
+    package_id: u32,
+    base_ids: &ResourceIds,
+    sort_attributes: bool,
+    tools_namespace: ToolsNamespace,
+    compatibility_mode: bool
+) -> Result<Vec<pack_zip::File>> {
+    if module.kind == ModuleKind::AssetPack
+        && !(module.resources.is_empty() && module.native_libraries.is_empty())
+    {
+        return Err(PackError::ModuleInvalid(format!(
+            "Asset pack \"{}\" has resources or native libraries, but asset packs can only contain assets.",
+            module.name
+        )));
+    }
+
+    let resources = &module.resources;
+    // Look in the module's own resources first, and then the base module's
+    let packages = [
+        ResourceIds::for_package(resources, package_id)?,
+        base_ids.clone()
+    ];
+
+    let mut manifest = xml_string_to_proto_xml(
+        &mut Cursor::new(module_manifest(package_name, module)?),
+        &packages,
+        sort_attributes,
+        tools_namespace,
+        None
+    )?;
+    if compatibility_mode {
+        add_platform_build_attributes(&mut manifest, &packages, sort_attributes)?;
+    }
+    let mut files = vec![pack_zip::File {
+        path: format!("{}/manifest/AndroidManifest.xml", module.name),
+        data: manifest.encode_to_vec()
+    }];
+
+    if !resources.is_empty() {
+        let resource_table = construct_resource_table(
+            package_name,
+            resources,
+            &packages[0],
+            &ResourceDeclarations::default(),
+            compatibility_mode
+        )?;
+        files.push(pack_zip::File {
+            path: format!("{}/resources.pb", module.name),
+            data: resource_table.encode_to_vec()
+        });
+        // Feature modules are rarely rebuilt on their own, so they aren't cached
+        files.extend(construct_res_files(
+            &module.name,
+            resources,
+            &packages,
+            None,
+            sort_attributes,
+            tools_namespace,
+            None
+        )?);
+    }
+
+    files.extend(construct_asset_files(&module.name, &module.assets)?);
+
+    if !module.native_libraries.is_empty() {
+        let native_targeting = construct_native_targeting(
+            module
+                .native_libraries
+                .iter()
+                .map(|library| &library.path[..])
+        )?;
+        files.push(pack_zip::File {
+            path: format!("{}/native.pb", module.name),
+            data: native_targeting.encode_to_vec()
+        });
+        for library in &module.native_libraries {
+            files.push(pack_zip::File {
+                path: format!("{}/lib/{}", module.name, library.path),
+                data: library.data.clone()
+            });
+        }
+    }
+
+    Ok(files)
+}
+
+// Places assets under `<module>/assets/`, along with an assets.pb if any of
+// their directories are targeted
+pub(crate) fn construct_asset_files(
+    module_name: &str,
+    assets: &[pack_zip::File]
+) -> Result<Vec<pack_zip::File>> {
+    let mut files: Vec<pack_zip::File> = assets
+        .iter()
+        .map(|asset| pack_zip::File {
+            path: format!("{module_name}/assets/{}", asset.path),
+            data: asset.data.clone()
+        })
+        .collect();
+    if let Some(assets_targeting) =
+        construct_assets_targeting(assets.iter().map(|asset| &asset.path[..]))?
+    {
+        files.push(pack_zip::File {
+            path: format!("{module_name}/assets.pb"),
+            data: assets_targeting.encode_to_vec()
+        });
+    }
+    Ok(files)
+}
+
+// Bundletool reads a module's type and delivery from <dist:module> in its manifest.
+// The names and title come from the user, so they're escaped.
+fn module_manifest(package_name: &str, module: &AabModule) -> Result<String> {
+    let delivery = match (module.kind, module.delivery) {
+        (_, ModuleDelivery::InstallTime) => "install-time",
+        (ModuleKind::AssetPack, ModuleDelivery::FastFollow) => "fast-follow",
+        (ModuleKind::Feature, ModuleDelivery::FastFollow) => {
+            return Err(PackError::ModuleInvalid(format!(
+                "Feature module \"{}\" can't use fast-follow delivery, which is only available for asset packs.",
+                module.name
+            )))
+        }
+        (_, ModuleDelivery::OnDemand) => "on-demand"
+    };
+    let module_attrs = match module.kind {
+        ModuleKind::AssetPack => " dist:type=\"asset-pack\"".to_string(),
+        ModuleKind::Feature => match &module.title {
+            Some(title) => format!(" dist:title=\"{}\"", escape_xml(title)),
+            None => String::new()
+        }
+    };
+    // Feature modules are still APKs, so they need an <application />
+    let application = match module.kind {
+        ModuleKind::AssetPack => "",
+        ModuleKind::Feature => "<application android:hasCode=\"false\" />\n"
+    };
+    Ok(format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+        <manifest xmlns:android=\"http://schemas.android.com/apk/res/android\" \
+        xmlns:dist=\"{DIST_NAMESPACE}\" package=\"{}\" split=\"{}\">\n\
+        <dist:module{module_attrs}>\n\
+        <dist:delivery><dist:{delivery} /></dist:delivery>\n\
+        <dist:fusing dist:include=\"true\" />\n\
+        </dist:module>\n\
+        {application}\
+        </manifest>\n",
+        escape_xml(package_name),
+        escape_xml(&module.name)
+    ))
+}
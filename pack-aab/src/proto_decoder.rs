@@ -0,0 +1,231 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Reads a bundle's ProtoXML files and resources.pb into the same types that
+// the asset compiler decodes APKs into, so that code inspecting packages can
+// treat both alike.
+use pack_asset_compiler::decoder::{
+    BinaryValue, DecodedAttribute, DecodedElement, DecodedEntry, DecodedPackage, DecodedTable,
+    DecodedType
+};
+use pack_common::{PackError, Result};
+use prost::Message;
+
+use crate::{
+    aapt::pb::{
+        compound_value, item, plural, primitive, reference, value, xml_node::Node, Item,
+        ResourceTable, XmlElement, XmlNode
+    },
+    resource_config_from_proto
+};
+
+// Res_value types for values BinaryValue doesn't have a variant for
+const TYPE_NULL: u8 = 0x00;
+const TYPE_DIMENSION: u8 = 0x05;
+const TYPE_FRACTION: u8 = 0x06;
+// @empty is a null with a data of 1
+const DATA_NULL_EMPTY: u32 = 1;
+
+// Plurals are bags keyed by these attribute IDs, from ResourceTypes.h
+const ATTR_OTHER: u32 = 0x01000004;
+const ATTR_ZERO: u32 = 0x01000005;
+
+/// Decodes a ProtoXML file, like a module's `manifest/AndroidManifest.xml`,
+/// and returns its root element. Compiled attribute values are used where
+/// there are any, and text nodes are dropped, as in APKs.
+pub fn decode_proto_xml(bytes: &[u8]) -> Result<DecodedElement> {
+    let node = XmlNode::decode(bytes)
+        .map_err(|e| PackError::BundleInvalid(format!("Not a valid ProtoXML file: {e}")))?;
+    match node.node {
+        Some(Node::Element(element)) => Ok(decode_element(element)),
+        _ => Err(PackError::ProtoXmlNodeIsNotAnElement)
+    }
+}
+
+/// Decodes a module's `resources.pb`.
+pub fn decode_proto_resource_table(bytes: &[u8]) -> Result<DecodedTable> {
+    let table = ResourceTable::decode(bytes)
+        .map_err(|e| PackError::BundleInvalid(format!("Not a valid resources.pb: {e}")))?;
+    let packages = table
+        .package
+        .into_iter()
+        .map(|package| DecodedPackage {
+            id: package.package_id.map_or(0, |id| id.id),
+            name: package.package_name,
+            types: package
+                .r#type
+                .into_iter()
+                .map(|res_type| {
+                    let entries: Vec<DecodedEntry> = res_type
+                        .entry
+                        .into_iter()
+                        .map(|entry| DecodedEntry {
+                            id: entry.entry_id.map_or(0, |id| id.id as u16),
+                            name: entry.name,
+                            values: entry
+                                .config_value
+                                .into_iter()
+                                .map(|config_value| {
+                                    (
+                                        resource_config_from_proto(
+                                            &config_value.config.unwrap_or_default()
+                                        ),
+                                        config_value
+                                            .value
+                                            .and_then(|value| value.value)
+                                            .map_or(BinaryValue::Null, decode_value)
+                                    )
+                                })
+                                .collect()
+                        })
+                        .collect();
+                    DecodedType {
+                        id: res_type.type_id.map_or(0, |id| id.id as u8),
+                        name: res_type.name,
+                        entry_count: entries
+                            .iter()
+                            .map(|entry| entry.id as u32 + 1)
+                            .max()
+                            .unwrap_or(0),
+                        entries
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    Ok(DecodedTable { packages })
+}
+
+fn decode_element(element: XmlElement) -> DecodedElement {
+    let optional = |namespace: String| (!namespace.is_empty()).then_some(namespace);
+    DecodedElement {
+        namespace: optional(element.namespace_uri),
+        name: element.name,
+        attributes: element
+            .attribute
+            .into_iter()
+            .map(|attr| DecodedAttribute {
+                namespace: optional(attr.namespace_uri),
+                name: attr.name,
+                resource_id: (attr.resource_id != 0).then_some(attr.resource_id),
+                value: match attr.compiled_item {
+                    Some(item) => decode_item(item),
+                    None => BinaryValue::String(attr.value)
+                }
+            })
+            .collect(),
+        children: element
+            .child
+            .into_iter()
+            .filter_map(|child| match child.node {
+                Some(Node::Element(child)) => Some(decode_element(child)),
+                _ => None
+            })
+            .collect()
+    }
+}
+
+fn decode_value(value: value::Value) -> BinaryValue {
+    let compound = match value {
+        value::Value::Item(item) => return decode_item(item),
+        value::Value::CompoundValue(compound) => compound
+    };
+    let item = |item: Option<Item>| item.map_or(BinaryValue::Null, decode_item);
+    match compound.value {
+        Some(compound_value::Value::Style(style)) => BinaryValue::Bag {
+            parent: style
+                .parent
+                .map(|parent| parent.id)
+                .filter(|&parent| parent != 0),
+            items: style
+                .entry
+                .into_iter()
+                .map(|entry| (entry.key.map_or(0, |key| key.id), item(entry.item)))
+                .collect()
+        },
+        Some(compound_value::Value::Array(array)) => BinaryValue::Bag {
+            parent: None,
+            items: array
+                .element
+                .into_iter()
+                .map(|element| (0, item(element.item)))
+                .collect()
+        },
+        Some(compound_value::Value::Plural(plural)) => BinaryValue::Bag {
+            parent: None,
+            items: plural
+                .entry
+                .into_iter()
+                .map(|entry| {
+                    let key = match plural::Arity::try_from(entry.arity) {
+                        Ok(plural::Arity::Other) | Err(_) => ATTR_OTHER,
+                        Ok(arity) => ATTR_ZERO + arity as u32
+                    };
+                    (key, item(entry.item))
+                })
+                .collect()
+        },
+        // Attributes, styleables and macros don't have values of their own
+        _ => BinaryValue::Bag {
+            parent: None,
+            items: vec![]
+        }
+    }
+}
+
+fn decode_item(item: Item) -> BinaryValue {
+    match item.value {
+        Some(item::Value::Ref(reference)) => {
+            if reference.r#type == reference::Type::Attribute as i32 {
+                BinaryValue::AttributeReference(reference.id)
+            } else {
+                BinaryValue::Reference(reference.id)
+            }
+        }
+        Some(item::Value::Str(string)) => BinaryValue::String(string.value),
+        Some(item::Value::RawStr(string)) => BinaryValue::String(string.value),
+        Some(item::Value::StyledStr(string)) => BinaryValue::String(string.value),
+        Some(item::Value::File(file)) => BinaryValue::String(file.path),
+        // IDs are stored as false in resources.arsc
+        Some(item::Value::Id(_)) => BinaryValue::Boolean(false),
+        Some(item::Value::Prim(primitive)) => match primitive.oneof_value {
+            Some(primitive::OneofValue::NullValue(_)) => BinaryValue::Null,
+            Some(primitive::OneofValue::EmptyValue(_)) => {
+                BinaryValue::Other(TYPE_NULL, DATA_NULL_EMPTY)
+            }
+            Some(primitive::OneofValue::FloatValue(float)) => BinaryValue::Float(float),
+            Some(primitive::OneofValue::DimensionValue(dimension)) => {
+                BinaryValue::Other(TYPE_DIMENSION, dimension)
+            }
+            Some(primitive::OneofValue::FractionValue(fraction)) => {
+                BinaryValue::Other(TYPE_FRACTION, fraction)
+            }
+            Some(primitive::OneofValue::IntDecimalValue(integer)) => BinaryValue::Integer(integer),
+            Some(primitive::OneofValue::IntHexadecimalValue(integer)) => {
+                BinaryValue::Integer(integer as i32)
+            }
+            Some(primitive::OneofValue::BooleanValue(boolean)) => BinaryValue::Boolean(boolean),
+            Some(
+                primitive::OneofValue::ColorArgb8Value(color)
+                | primitive::OneofValue::ColorRgb8Value(color)
+                | primitive::OneofValue::ColorArgb4Value(color)
+                | primitive::OneofValue::ColorRgb4Value(color)
+            ) => BinaryValue::Color(color),
+            // The deprecated float dimensions and fractions are only written by
+            // very old versions of aapt2
+            _ => BinaryValue::Null
+        },
+        None => BinaryValue::Null
+    }
+}
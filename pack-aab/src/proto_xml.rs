@@ -18,13 +18,17 @@
 // In this case, it's ProtoXML, which seems to have been invented for bundletool.
 // This is different to the ResChunkXML which was invented for AAPT.
 
-use std::{collections::HashSet, io::Read};
+use std::{collections::HashMap, io::Read};
 
 use pack_asset_compiler::{
+    inline_strings::StringValues,
     internal_android_attributes::{get_internal_attribute_id, infer_attribute_type},
     resource_external_types::AttributeDataType,
-    resource_internal_types::Resource,
-    xml_file::{lookup_resource_id, ANDROID_INTERNAL_ATTRIBUTE_MAGIC}
+    resource_table::ResourceIds,
+    xml_file::{
+        lookup_resource_id_in_packages, platform_build_attributes, ANDROID_INTERNAL_ATTRIBUTE_MAGIC
+    },
+    xml_namespaces::{NamespaceScopes, ToolsNamespace}
 };
 use pack_common::{PackError, Result};
 use xml::{attribute::OwnedAttribute, common::Position, reader::XmlEvent, EventReader};
@@ -36,15 +40,24 @@ use crate::aapt::pb::{
 
 // NOTE: This is very, VERY similar to xml_to_res_chunk. In future could
 //   generalise this. They are two ways to define very similar data.
-// TODO: Inject compileSdkVersion and friends
+// Unlike xml_to_res_chunk, manifests don't get compileSdkVersion and friends
+// unless they're added with add_platform_build_attributes.
+// References are looked up in each of `packages` in turn. With
+// `sort_attributes`, each element's attributes are sorted like aapt2 sorts
+// them, rather than kept in document order. `tools:` attributes are handled
+// the same way as in binary XML, according to `tools_namespace`. With
+// `string_values`, attributes that refer to strings get the strings instead.
 pub fn xml_string_to_proto_xml<T: Read>(
     byte_source: &mut T,
-    resources: &[Resource]
+    packages: &[ResourceIds],
+    sort_attributes: bool,
+    tools_namespace: ToolsNamespace,
+    string_values: Option<&StringValues>
 ) -> Result<XmlNode> {
     let mut xml_source = EventReader::new(byte_source);
     let mut xml_out = XmlNode::default();
     let mut child_idx_stack: Vec<usize> = vec![];
-    let mut seen_namespaces = HashSet::new();
+    let mut namespace_scopes = NamespaceScopes::new();
 
     loop {
         let event = xml_source.next();
@@ -58,34 +71,36 @@ pub fn xml_string_to_proto_xml<T: Read>(
                 attributes,
                 namespace
             }) => {
-                let mut namespaces_defined_in_this_element = vec![];
-                for ns in namespace.iter() {
-                    // These are kind of fake namespaces, runtime Android doesn't
-                    // care about these.
-                    // NOTE: This is subtly different to the ones used for ResChunk XML,
-                    //   because bundletool *does* care about "tools"
-                    if ns.0.is_empty() || ns.0 == "xml" || ns.0 == "xmlns" {
+                let namespaces_defined_in_this_element = namespace_scopes
+                    .open(&namespace, tools_namespace.ignored_uris())
+                    .into_iter()
+                    .map(|(prefix, uri)| XmlNamespace {
+                        prefix,
+                        uri,
+                        source: source_position
+                    })
+                    .collect();
+
+                let mut proto_attributes = vec![];
+                for mut attr in attributes {
+                    if !tools_namespace.keeps_attribute(&attr)? {
                         continue;
                     }
-                    if seen_namespaces.contains(ns.0) {
-                        continue;
+                    if let Some(values) = string_values {
+                        if let Some(value) = values.resolve(&attr.value)? {
+                            attr.value = value.to_string();
+                        }
                     }
-                    seen_namespaces.insert(ns.0.to_string());
-                    namespaces_defined_in_this_element.push(XmlNamespace {
-                        prefix: ns.0.to_string(),
-                        uri: ns.1.to_string(),
-                        source: source_position
-                    });
+                    proto_attributes.push(parser_attr_to_proto_attr(&attr, packages)?);
+                }
+                if sort_attributes {
+                    proto_attributes.sort_by(|a, b| attribute_order(a).cmp(&attribute_order(b)));
                 }
-
                 let new_element = Node::Element(XmlElement {
                     name: name.local_name,
                     namespace_uri: name.namespace.unwrap_or("".into()),
                     namespace_declaration: namespaces_defined_in_this_element,
-                    attribute: attributes
-                        .iter()
-                        .map(|attr| parser_attr_to_proto_attr(attr, resources))
-                        .collect::<Result<Vec<_>>>()?,
+                    attribute: proto_attributes,
                     child: vec![]
                 });
 
@@ -106,6 +121,7 @@ pub fn xml_string_to_proto_xml<T: Read>(
                 }
             }
             Ok(XmlEvent::EndElement { .. }) => {
+                namespace_scopes.close();
                 child_idx_stack.pop();
             }
             Ok(XmlEvent::EndDocument) => break,
@@ -117,21 +133,40 @@ pub fn xml_string_to_proto_xml<T: Read>(
     Ok(xml_out)
 }
 
+// Adds the attributes aapt2 adds to the manifests it links, eg.
+// android:compileSdkVersion, to a compiled manifest's root element
+pub(crate) fn add_platform_build_attributes(
+    manifest: &mut XmlNode,
+    packages: &[ResourceIds],
+    sort_attributes: bool
+) -> Result<()> {
+    let root = node_to_elem(manifest)?;
+    for attr in platform_build_attributes() {
+        root.attribute
+            .push(parser_attr_to_proto_attr(&attr, packages)?);
+    }
+    if sort_attributes {
+        root.attribute
+            .sort_by(|a, b| attribute_order(a).cmp(&attribute_order(b)));
+    }
+    Ok(())
+}
+
 fn parser_attr_to_proto_attr(
     p_attr: &OwnedAttribute,
-    resources: &[Resource]
+    packages: &[ResourceIds]
 ) -> Result<XmlAttribute> {
     let mut compiled_value: Option<item::Value> = None;
     let resource_id = if p_attr.name.prefix.clone().unwrap_or("".into()) == "android" {
         // This is an internal attribute
         let attr_type = infer_attribute_type(&p_attr.value);
         compiled_value = match attr_type {
-            AttributeDataType::DecimalInteger => Some(item::Value::Prim(Primitive {
+            AttributeDataType::IntDec => Some(item::Value::Prim(Primitive {
                 oneof_value: Some(primitive::OneofValue::IntDecimalValue(
                     p_attr.value.parse::<i32>()?
                 ))
             })),
-            AttributeDataType::BooleanInteger => Some(item::Value::Prim(Primitive {
+            AttributeDataType::IntBoolean => Some(item::Value::Prim(Primitive {
                 oneof_value: Some(primitive::OneofValue::BooleanValue(p_attr.value == "true"))
             })),
             // References will be caught anyway when they begin with @
@@ -147,7 +182,7 @@ fn parser_attr_to_proto_attr(
 
     if p_attr.value.starts_with("@") {
         // This is a reference
-        let res_id = lookup_resource_id(&p_attr.value, resources)?;
+        let res_id = lookup_resource_id_in_packages(&p_attr.value, packages)?;
         compiled_value = Some(item::Value::Ref(Reference {
             r#type: reference::Type::Reference as i32,
             id: res_id,
@@ -172,9 +207,98 @@ fn parser_attr_to_proto_attr(
     })
 }
 
+// aapt2 puts attributes with resource IDs first, by ID, then the rest by
+// namespace and name
+fn attribute_order(attr: &XmlAttribute) -> (bool, u32, &str, &str) {
+    (
+        attr.resource_id == 0,
+        attr.resource_id,
+        &attr.namespace_uri,
+        &attr.name
+    )
+}
+
 fn node_to_elem(node: &mut XmlNode) -> Result<&mut XmlElement> {
     match &mut node.node {
         Some(Node::Element(elem)) => Ok(elem),
         _ => Err(PackError::ProtoXmlNodeIsNotAnElement)
     }
 }
+
+/// Converts a ProtoXML document back into textual XML.
+///
+/// This is the inverse of [xml_string_to_proto_xml], which keeps the original
+/// attribute value strings, so the output can be compiled again by either of
+/// PACK's XML compilers. Source positions are not preserved.
+pub fn proto_xml_to_xml_string(root: &XmlNode) -> Result<String> {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    write_proto_xml_node(root, &mut HashMap::new(), &mut out)?;
+    Ok(out)
+}
+
+fn write_proto_xml_node(
+    node: &XmlNode,
+    // Maps namespace URIs to the prefix they were declared with
+    prefixes: &mut HashMap<String, String>,
+    out: &mut String
+) -> Result<()> {
+    let elem = match &node.node {
+        Some(Node::Element(elem)) => elem,
+        Some(Node::Text(text)) => {
+            out.push_str(&escape_xml(text));
+            return Ok(());
+        }
+        None => return Err(PackError::ProtoXmlNodeIsNotAnElement)
+    };
+
+    // Namespaces are scoped to the element that declares them
+    let outer_prefixes = prefixes.clone();
+    for ns in &elem.namespace_declaration {
+        prefixes.insert(ns.uri.clone(), ns.prefix.clone());
+    }
+
+    let elem_name = qualified_name(&elem.namespace_uri, &elem.name, prefixes);
+    out.push('<');
+    out.push_str(&elem_name);
+    for ns in &elem.namespace_declaration {
+        out.push_str(&format!(" xmlns:{}=\"{}\"", ns.prefix, escape_xml(&ns.uri)));
+    }
+    for attr in &elem.attribute {
+        let attr_name = qualified_name(&attr.namespace_uri, &attr.name, prefixes);
+        out.push_str(&format!(" {}=\"{}\"", attr_name, escape_xml(&attr.value)));
+    }
+
+    if elem.child.is_empty() {
+        out.push_str(" />");
+    } else {
+        out.push('>');
+        for child in &elem.child {
+            write_proto_xml_node(child, prefixes, out)?;
+        }
+        out.push_str(&format!("</{elem_name}>"));
+    }
+
+    *prefixes = outer_prefixes;
+    Ok(())
+}
+
+fn qualified_name(namespace_uri: &str, name: &str, prefixes: &HashMap<String, String>) -> String {
+    match prefixes.get(namespace_uri) {
+        Some(prefix) if !namespace_uri.is_empty() => format!("{prefix}:{name}"),
+        _ => name.to_string()
+    }
+}
+
+pub(crate) fn escape_xml(raw: &str) -> String {
+    let mut escaped = String::with_capacity(raw.len());
+    for c in raw.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c)
+        }
+    }
+    escaped
+}
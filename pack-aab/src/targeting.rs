@@ -0,0 +1,225 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Google Play can deliver different versions of assets and native libraries to
+// different devices. Like `bundletool build-bundle`, the targeting is declared
+// through directory names:
+//
+//  - assets/textures#tcf_astc holds textures for devices that support ASTC
+//  - assets/backgrounds#tier_1 holds backgrounds for device tier 1 and up
+//  - assets/voice#lang_de holds German voice lines
+//  - lib/arm64-v8a holds native libraries for 64-bit ARM devices
+//
+// The targeting is then written out as assets.pb and native.pb, which is what
+// Play actually reads.
+use std::collections::BTreeMap;
+
+use pack_common::{PackError, Result};
+
+use crate::android::bundle::{
+    abi::AbiAlias, texture_compression_format::TextureCompressionFormatAlias, Abi, Assets,
+    AssetsDirectoryTargeting, DeviceTierTargeting, Int32Value, LanguageTargeting,
+    NativeDirectoryTargeting, NativeLibraries, TargetedAssetsDirectory, TargetedNativeDirectory,
+    TextureCompressionFormat, TextureCompressionFormatTargeting
+};
+
+const TEXTURE_COMPRESSION_FORMATS: &[(&str, TextureCompressionFormatAlias)] = &[
+    ("etc1_rgb8", TextureCompressionFormatAlias::Etc1Rgb8),
+    ("paletted", TextureCompressionFormatAlias::Paletted),
+    ("3dc", TextureCompressionFormatAlias::ThreeDc),
+    ("atc", TextureCompressionFormatAlias::Atc),
+    ("latc", TextureCompressionFormatAlias::Latc),
+    ("dxt1", TextureCompressionFormatAlias::Dxt1),
+    ("s3tc", TextureCompressionFormatAlias::S3tc),
+    ("pvrtc", TextureCompressionFormatAlias::Pvrtc),
+    ("astc", TextureCompressionFormatAlias::Astc),
+    ("etc2", TextureCompressionFormatAlias::Etc2)
+];
+
+const ABIS: &[(&str, AbiAlias)] = &[
+    ("armeabi", AbiAlias::Armeabi),
+    ("armeabi-v7a", AbiAlias::ArmeabiV7a),
+    ("arm64-v8a", AbiAlias::Arm64V8a),
+    ("x86", AbiAlias::X86),
+    ("x86_64", AbiAlias::X8664),
+    ("mips", AbiAlias::Mips),
+    ("mips64", AbiAlias::Mips64),
+    ("riscv64", AbiAlias::Riscv64)
+];
+
+// The targeting declared by a single directory's name
+#[derive(Default)]
+struct DirectoryTargeting {
+    texture_compression_format: Option<TextureCompressionFormatAlias>,
+    device_tier: Option<i32>,
+    language: Option<String>
+}
+
+/// Builds the `assets.pb` for a module from the paths of its assets, relative
+/// to `assets/`. Returns `None` if no directories are targeted, in which case
+/// the file isn't needed.
+pub fn construct_assets_targeting<'a>(
+    asset_paths: impl Iterator<Item = &'a str>
+) -> Result<Option<Assets>> {
+    // Every directory that directly contains a file, keyed by its path with
+    // the targeting removed, so that siblings can list each other as alternatives
+    let mut groups: BTreeMap<String, BTreeMap<String, DirectoryTargeting>> = BTreeMap::new();
+    for asset_path in asset_paths {
+        let directory = match asset_path.rsplit_once('/') {
+            Some((directory, _file_name)) => format!("assets/{directory}"),
+            None => "assets".into()
+        };
+        let (untargeted_path, targeting) = parse_directory_targeting(&directory)?;
+        groups
+            .entry(untargeted_path)
+            .or_default()
+            .insert(directory, targeting);
+    }
+
+    let mut any_targeted = false;
+    let mut assets = Assets::default();
+    for siblings in groups.values() {
+        for (path, targeting) in siblings {
+            let others = || {
+                siblings
+                    .iter()
+                    .filter(move |(other_path, _)| *other_path != path)
+                    .map(|(_, other)| other)
+            };
+            let mut proto = AssetsDirectoryTargeting::default();
+            if let Some(format) = targeting.texture_compression_format {
+                proto.texture_compression_format = Some(TextureCompressionFormatTargeting {
+                    value: vec![texture_compression_format(format)],
+                    alternatives: others()
+                        .filter_map(|other| other.texture_compression_format)
+                        .map(texture_compression_format)
+                        .collect()
+                });
+            }
+            if let Some(tier) = targeting.device_tier {
+                proto.device_tier = Some(DeviceTierTargeting {
+                    value: vec![Int32Value { value: tier }],
+                    alternatives: others()
+                        .filter_map(|other| other.device_tier)
+                        .map(|value| Int32Value { value })
+                        .collect()
+                });
+            }
+            if let Some(language) = &targeting.language {
+                proto.language = Some(LanguageTargeting {
+                    value: vec![language.clone()],
+                    alternatives: others()
+                        .filter_map(|other| other.language.clone())
+                        .collect()
+                });
+            }
+            any_targeted |= proto != AssetsDirectoryTargeting::default();
+            assets.directory.push(TargetedAssetsDirectory {
+                path: path.clone(),
+                targeting: Some(proto)
+            });
+        }
+    }
+
+    Ok(any_targeted.then_some(assets))
+}
+
+/// Builds the `native.pb` for a module from the paths of its native libraries,
+/// relative to `lib/`, eg. `arm64-v8a/libclock.so`.
+pub fn construct_native_targeting<'a>(
+    library_paths: impl Iterator<Item = &'a str>
+) -> Result<NativeLibraries> {
+    let mut abis: Vec<&str> = vec![];
+    for library_path in library_paths {
+        let abi = match library_path.split_once('/') {
+            Some((abi, file_name)) if !file_name.contains('/') => abi,
+            _ => {
+                return Err(PackError::DirectoryTargetingInvalid(format!(
+                    "lib/{library_path}"
+                )))
+            }
+        };
+        if !abis.contains(&abi) {
+            abis.push(abi);
+        }
+    }
+    abis.sort();
+
+    let directory = abis
+        .into_iter()
+        .map(|abi| {
+            let alias = ABIS
+                .iter()
+                .find(|(name, _)| *name == abi)
+                .map(|(_, alias)| *alias)
+                .ok_or(PackError::DirectoryTargetingInvalid(format!("lib/{abi}")))?;
+            Ok(TargetedNativeDirectory {
+                path: format!("lib/{abi}"),
+                targeting: Some(NativeDirectoryTargeting {
+                    abi: Some(Abi {
+                        alias: alias as i32
+                    })
+                })
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(NativeLibraries { directory })
+}
+
+fn texture_compression_format(alias: TextureCompressionFormatAlias) -> TextureCompressionFormat {
+    TextureCompressionFormat {
+        alias: alias as i32
+    }
+}
+
+// Splits the targeting suffixes off every segment of a directory path, eg.
+// "assets/textures#tcf_astc" becomes "assets/textures" and ASTC.
+fn parse_directory_targeting(directory: &str) -> Result<(String, DirectoryTargeting)> {
+    let invalid = || PackError::DirectoryTargetingInvalid(directory.into());
+    let mut targeting = DirectoryTargeting::default();
+    let mut untargeted_segments = vec![];
+    for segment in directory.split('/') {
+        let mut parts = segment.split('#');
+        // split() always returns at least one part
+        untargeted_segments.push(parts.next().unwrap_or(segment));
+        for suffix in parts {
+            let (key, value) = suffix.split_once('_').ok_or_else(invalid)?;
+            match key {
+                "tcf" if targeting.texture_compression_format.is_none() => {
+                    targeting.texture_compression_format = Some(
+                        TEXTURE_COMPRESSION_FORMATS
+                            .iter()
+                            .find(|(name, _)| *name == value)
+                            .map(|(_, alias)| *alias)
+                            .ok_or_else(invalid)?
+                    );
+                }
+                "tier" if targeting.device_tier.is_none() => {
+                    targeting.device_tier = Some(
+                        value
+                            .parse::<i32>()
+                            .ok()
+                            .filter(|tier| *tier >= 0)
+                            .ok_or_else(invalid)?
+                    );
+                }
+                "lang" if targeting.language.is_none() => {
+                    targeting.language = Some(value.into());
+                }
+                _ => return Err(invalid())
+            }
+        }
+    }
+    Ok((untargeted_segments.join("/"), targeting))
+}
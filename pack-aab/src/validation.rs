@@ -0,0 +1,332 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// When a bundle breaks one of bundletool's rules, the Play Console rejects it
+// with an error that rarely says which file was at fault. These are the same
+// checks, run locally, with the path of everything that's wrong.
+use std::collections::{BTreeMap, HashSet};
+
+use pack_common::{Diagnostic, ErrorCode};
+use prost::Message;
+
+use crate::{
+    aapt::pb::{item, value, xml_node, ResourceTable, XmlNode},
+    android::bundle::{Assets, BundleConfig, NativeLibraries},
+    bundle_metadata::BUNDLE_METADATA_DIR,
+    targeting::{construct_assets_targeting, construct_native_targeting},
+    BASE_MODULE_NAME
+};
+
+const BUNDLE_CONFIG_PATH: &str = "BundleConfig.pb";
+const MANIFEST_PATH: &str = "manifest/AndroidManifest.xml";
+
+// Everything in a module has to be in one of these directories...
+const MODULE_DIRECTORIES: &[&str] = &["manifest", "dex", "res", "assets", "lib", "root", "apex"];
+// ...or be one of these files
+const MODULE_FILES: &[&str] = &["resources.pb", "assets.pb", "native.pb", "apex.pb"];
+
+// Files in root/ are copied to the root of each APK, so they can't clash with
+// anything the APK generates itself
+const RESERVED_ROOT_DIRECTORIES: &[&str] = &["res", "assets", "lib", "META-INF"];
+const RESERVED_ROOT_FILES: &[&str] = &["AndroidManifest.xml", "resources.arsc"];
+
+/// Checks a bundle's files against the rules bundletool enforces when Google
+/// Play receives it, such as every module having a ProtoXML manifest.
+///
+/// `files` are the contents of the AAB, as returned by [crate::construct_aab]
+/// or read from an existing bundle. Returns every problem found, or nothing if
+/// the bundle is valid.
+pub fn validate(files: &[pack_zip::File]) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    validate_entry_names(files, &mut diagnostics);
+
+    match files.iter().find(|file| file.path == BUNDLE_CONFIG_PATH) {
+        Some(file) => {
+            if let Err(e) = BundleConfig::decode(&file.data[..]) {
+                diagnostics.push(Diagnostic::error(
+                    ErrorCode::BundleConfigInvalid,
+                    Some(BUNDLE_CONFIG_PATH),
+                    format!("Not a valid BundleConfig protobuf: {e}")
+                ));
+            }
+        }
+        None => diagnostics.push(Diagnostic::error(
+            ErrorCode::BundleConfigInvalid,
+            None,
+            format!("Bundles must contain a {BUNDLE_CONFIG_PATH} at their root.")
+        ))
+    }
+
+    // Everything else must be inside a module, apart from signatures and metadata
+    let mut modules: BTreeMap<&str, Vec<(&str, &pack_zip::File)>> = BTreeMap::new();
+    for file in files {
+        match file.path.split_once('/') {
+            Some(("META-INF", _)) => {}
+            Some((directory, _)) if directory == BUNDLE_METADATA_DIR => {}
+            Some((module_name, module_path)) => modules
+                .entry(module_name)
+                .or_default()
+                .push((module_path, file)),
+            None if file.path == BUNDLE_CONFIG_PATH => {}
+            None => diagnostics.push(Diagnostic::error(
+                ErrorCode::BundleFilePathInvalid,
+                Some(&file.path),
+                "Files at the root of a bundle must be inside a module directory, eg. base/."
+            ))
+        }
+    }
+    if !modules.contains_key(BASE_MODULE_NAME) {
+        diagnostics.push(Diagnostic::error(
+            ErrorCode::BundleBaseModuleMissing,
+            None,
+            format!("Bundles must contain a \"{BASE_MODULE_NAME}\" module.")
+        ));
+    }
+    for (module_name, module_files) in &modules {
+        validate_module(module_name, module_files, &mut diagnostics);
+    }
+
+    diagnostics
+}
+
+// Paths that zip tools, or Play, could interpret differently from how PACK does
+fn validate_entry_names(files: &[pack_zip::File], diagnostics: &mut Vec<Diagnostic>) {
+    let mut seen = HashSet::new();
+    for file in files {
+        let path = &file.path;
+        let message = if path.starts_with('/') {
+            Some("Paths must be relative to the root of the bundle.")
+        } else if path.contains('\\') {
+            Some("Paths must use forward slashes.")
+        } else if path
+            .split('/')
+            .any(|segment| segment.is_empty() || segment == "." || segment == "..")
+        {
+            Some("Paths can't contain empty, \".\" or \"..\" segments.")
+        } else if !seen.insert(path) {
+            Some("There is more than one file at this path.")
+        } else {
+            None
+        };
+        if let Some(message) = message {
+            diagnostics.push(Diagnostic::error(
+                ErrorCode::BundleFilePathInvalid,
+                Some(path),
+                message
+            ));
+        }
+    }
+}
+
+fn validate_module(
+    module_name: &str,
+    module_files: &[(&str, &pack_zip::File)],
+    diagnostics: &mut Vec<Diagnostic>
+) {
+    if !module_name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_')
+    {
+        diagnostics.push(Diagnostic::error(
+            ErrorCode::ModuleInvalid,
+            Some(module_name),
+            "Module names must only contain letters, digits and underscores."
+        ));
+    }
+
+    let find = |path: &str| {
+        module_files
+            .iter()
+            .find(|(module_path, _)| *module_path == path)
+            .map(|(_, file)| *file)
+    };
+
+    match find(MANIFEST_PATH) {
+        Some(manifest) => {
+            let is_manifest = XmlNode::decode(&manifest.data[..])
+                .ok()
+                .and_then(|node| node.node)
+                .is_some_and(|node| match node {
+                    xml_node::Node::Element(element) => element.name == "manifest",
+                    _ => false
+                });
+            if !is_manifest {
+                diagnostics.push(Diagnostic::error(
+                    ErrorCode::BundleProtobufInvalid,
+                    Some(&manifest.path),
+                    "Manifests must be compiled to ProtoXML, with a <manifest> root element."
+                ));
+            }
+        }
+        None => diagnostics.push(Diagnostic::error(
+            ErrorCode::ModuleInvalid,
+            Some(module_name),
+            format!("Every module must have a {MANIFEST_PATH}.")
+        ))
+    }
+
+    let mut res_paths = vec![];
+    let mut asset_paths = vec![];
+    let mut library_paths = vec![];
+    for (module_path, file) in module_files {
+        let (directory, path_in_directory) = match module_path.split_once('/') {
+            Some((directory, rest)) if MODULE_DIRECTORIES.contains(&directory) => (directory, rest),
+            _ if MODULE_FILES.contains(module_path) => continue,
+            _ => {
+                diagnostics.push(Diagnostic::error(
+                    ErrorCode::BundleFilePathInvalid,
+                    Some(&file.path),
+                    format!(
+                        "Module files must be in one of {} or be one of {}.",
+                        MODULE_DIRECTORIES.join("/, ") + "/",
+                        MODULE_FILES.join(", ")
+                    )
+                ));
+                continue;
+            }
+        };
+        match directory {
+            "res" => res_paths.push(*module_path),
+            "assets" => asset_paths.push(path_in_directory),
+            "lib" => library_paths.push(path_in_directory),
+            "dex" if !is_dex_file_name(path_in_directory) => diagnostics.push(Diagnostic::error(
+                ErrorCode::BundleFilePathInvalid,
+                Some(&file.path),
+                "Dex files must be named classes.dex, classes2.dex, classes3.dex and so on."
+            )),
+            "root" => {
+                let root_directory = path_in_directory.split('/').next().unwrap_or_default();
+                if RESERVED_ROOT_FILES.contains(&path_in_directory)
+                    || RESERVED_ROOT_DIRECTORIES.contains(&root_directory)
+                    || is_dex_file_name(path_in_directory)
+                {
+                    diagnostics.push(Diagnostic::error(
+                        ErrorCode::BundleFilePathInvalid,
+                        Some(&file.path),
+                        "Files in root/ can't use paths that APKs reserve for themselves."
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    validate_resource_table(find("resources.pb"), &res_paths, module_name, diagnostics);
+
+    if let Some(assets_pb) = find("assets.pb") {
+        if Assets::decode(&assets_pb.data[..]).is_err() {
+            diagnostics.push(Diagnostic::error(
+                ErrorCode::BundleProtobufInvalid,
+                Some(&assets_pb.path),
+                "Not a valid Assets protobuf."
+            ));
+        }
+    } else if let Err(e) = construct_assets_targeting(asset_paths.into_iter()) {
+        diagnostics.push(Diagnostic::error(
+            e.code(),
+            Some(module_name),
+            e.to_string()
+        ));
+    }
+
+    if let Some(native_pb) = find("native.pb") {
+        if NativeLibraries::decode(&native_pb.data[..]).is_err() {
+            diagnostics.push(Diagnostic::error(
+                ErrorCode::BundleProtobufInvalid,
+                Some(&native_pb.path),
+                "Not a valid NativeLibraries protobuf."
+            ));
+        }
+    }
+    if let Err(e) = construct_native_targeting(library_paths.into_iter()) {
+        diagnostics.push(Diagnostic::error(
+            e.code(),
+            Some(module_name),
+            e.to_string()
+        ));
+    }
+}
+
+// Every file in res/ must be in the resource table, and vice versa
+fn validate_resource_table(
+    resources_pb: Option<&pack_zip::File>,
+    res_paths: &[&str],
+    module_name: &str,
+    diagnostics: &mut Vec<Diagnostic>
+) {
+    let Some(resources_pb) = resources_pb else {
+        if !res_paths.is_empty() {
+            diagnostics.push(Diagnostic::error(
+                ErrorCode::BundleResourceTableMismatch,
+                Some(module_name),
+                "Modules with files in res/ must have a resources.pb."
+            ));
+        }
+        return;
+    };
+    let table = match ResourceTable::decode(&resources_pb.data[..]) {
+        Ok(table) => table,
+        Err(e) => {
+            diagnostics.push(Diagnostic::error(
+                ErrorCode::BundleProtobufInvalid,
+                Some(&resources_pb.path),
+                format!("Not a valid ResourceTable protobuf: {e}")
+            ));
+            return;
+        }
+    };
+
+    let referenced_paths: HashSet<&str> = table
+        .package
+        .iter()
+        .flat_map(|package| &package.r#type)
+        .flat_map(|res_type| &res_type.entry)
+        .flat_map(|entry| &entry.config_value)
+        .filter_map(|config_value| match &config_value.value.as_ref()?.value {
+            Some(value::Value::Item(item)) => match &item.value {
+                Some(item::Value::File(file)) => Some(&file.path[..]),
+                _ => None
+            },
+            _ => None
+        })
+        .collect();
+    for path in &referenced_paths {
+        if !res_paths.contains(path) {
+            diagnostics.push(Diagnostic::error(
+                ErrorCode::BundleResourceTableMismatch,
+                Some(&resources_pb.path),
+                format!("The resource table references {path}, which isn't in the module.")
+            ));
+        }
+    }
+    for path in res_paths {
+        if !referenced_paths.contains(path) {
+            diagnostics.push(Diagnostic::error(
+                ErrorCode::BundleResourceTableMismatch,
+                Some(&format!("{module_name}/{path}")),
+                "Files in res/ must be referenced by the module's resource table."
+            ));
+        }
+    }
+}
+
+// classes.dex, classes2.dex, classes3.dex...
+fn is_dex_file_name(name: &str) -> bool {
+    name.strip_prefix("classes")
+        .and_then(|name| name.strip_suffix(".dex"))
+        .is_some_and(|number| {
+            number.is_empty()
+                || (number.parse::<u32>().is_ok_and(|n| n >= 2) && !number.starts_with('0'))
+        })
+}
@@ -0,0 +1,139 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Measures the peak memory a build of a large project takes on top of the
+// project itself, by counting every allocation. Artwork is stored in the zip
+// entries and then the APK, so that's about twice the inputs. Resource
+// contents used to be copied while collecting them too, which made it about
+// three times (203 MiB rather than 139 MiB here). Run with
+// `cargo bench -p pack-api --bench memory`.
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering}
+};
+
+use pack_api::{compile_apk_with_options, BuildOptions, FileResource, Package};
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+// Keeps track of how many bytes are allocated, and the most there have been
+// since PEAK was last reset
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            grew(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            if new_size > layout.size() {
+                grew(new_size - layout.size());
+            } else {
+                CURRENT.fetch_sub(layout.size() - new_size, Ordering::Relaxed);
+            }
+        }
+        new_ptr
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn grew(size: usize) {
+    let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK.fetch_max(current, Ordering::Relaxed);
+}
+
+const MANIFEST: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<manifest xmlns:android="http://schemas.android.com/apk/res/android" package="com.example.large">
+  <application android:label="Large" android:hasCode="false"/>
+</manifest>
+"#;
+
+// Artwork hardly compresses, so this is pseudo-random
+fn drawable(seed: u64, size: usize) -> Vec<u8> {
+    let mut state = seed + 1;
+    (0..size)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state as u8
+        })
+        .collect()
+}
+
+// 64 drawables of 1 MiB each, and a scene that uses them all
+fn large_project() -> Package {
+    let mut resources: Vec<FileResource> = (0..64)
+        .map(|index| {
+            FileResource::new(
+                "drawable".into(),
+                format!("image_{index}.png"),
+                drawable(index, 1024 * 1024)
+            )
+        })
+        .collect();
+    let mut scene = String::from(r#"<WatchFace width="450" height="450"><Scene>"#);
+    for index in 0..64 {
+        scene.push_str(&format!(
+            r#"<PartImage x="0" y="0" width="450" height="450"><Image resource="image_{index}"/></PartImage>"#
+        ));
+    }
+    scene.push_str("</Scene></WatchFace>");
+    resources.push(FileResource::new(
+        "raw".into(),
+        "watchface.xml".into(),
+        scene.into_bytes()
+    ));
+    Package {
+        android_manifest: MANIFEST.as_bytes().to_vec(),
+        resources,
+        modules: vec![]
+    }
+}
+
+fn mib(bytes: usize) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0)
+}
+
+fn main() {
+    let package = large_project();
+    let inputs: usize = package.resources.iter().map(|res| res.contents.len()).sum();
+
+    let before = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(before, Ordering::Relaxed);
+    let apk = compile_apk_with_options(&package, &BuildOptions::default()).unwrap();
+    let peak = PEAK.load(Ordering::Relaxed) - before;
+
+    println!("Inputs:          {:7.1} MiB", mib(inputs));
+    println!("APK:             {:7.1} MiB", mib(apk.len()));
+    println!(
+        "Peak for build:  {:7.1} MiB ({:.2}x the inputs)",
+        mib(peak),
+        peak as f64 / inputs as f64
+    );
+}
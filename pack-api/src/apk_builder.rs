@@ -0,0 +1,150 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// compile_apk always builds from a Package, through the same res/ pipeline.
+// ApkBuilder is the step after that pipeline on its own, for resources that
+// were compiled some other way, or for adding files like licenses that don't
+// belong in res/.
+use std::io::Cursor;
+
+use deku::DekuContainerWrite;
+use pack_asset_compiler::resource_external_types::ResChunk;
+
+use pack_zip::ZipOptions;
+
+use crate::{Compression, Keys, Result};
+
+/// Assembles an APK from files that are already compiled.
+///
+/// ```ignore
+/// let apk = ApkBuilder::new(manifest)
+///     .resource_table(resource_table)
+///     .compiled("res/xml/watchface.xml", watch_face)
+///     .file(pack_zip::File { path: "META-INF/LICENSE.txt".into(), data: license })
+///     .build_and_sign(&keys)?;
+/// ```
+///
+/// The chunks can come from `pack_asset_compiler`, eg. `xml_to_res_chunk` and
+/// `construct_resource_table`. Files are zipped in the order they were added,
+/// after AndroidManifest.xml and resources.arsc, and are 4-byte aligned unless
+/// [ApkBuilder::alignment] says otherwise.
+#[derive(Debug)]
+pub struct ApkBuilder {
+    android_manifest: ResChunk,
+    resource_table: Option<ResChunk>,
+    compiled: Vec<(String, ResChunk)>,
+    files: Vec<pack_zip::File>,
+    zip_options: ZipOptions
+}
+
+impl ApkBuilder {
+    /// Starts an APK with the given compiled AndroidManifest.xml.
+    pub fn new(android_manifest: ResChunk) -> Self {
+        ApkBuilder {
+            android_manifest,
+            resource_table: None,
+            compiled: vec![],
+            files: vec![],
+            zip_options: ZipOptions::default()
+        }
+    }
+
+    /// Sets the resources.arsc. APKs without resources can leave it out.
+    pub fn resource_table(mut self, resource_table: ResChunk) -> Self {
+        self.resource_table = Some(resource_table);
+        self
+    }
+
+    /// Adds a compiled XML file, at its full path in the APK, eg.
+    /// `res/xml/watchface.xml`.
+    pub fn compiled(mut self, path: impl Into<String>, chunk: ResChunk) -> Self {
+        self.compiled.push((path.into(), chunk));
+        self
+    }
+
+    /// Adds a file as-is, eg. an image in `res/drawable/` or a license in
+    /// `META-INF/`.
+    pub fn file(mut self, file: pack_zip::File) -> Self {
+        self.files.push(file);
+        self
+    }
+
+    /// Adds files as-is.
+    pub fn files(mut self, files: impl IntoIterator<Item = pack_zip::File>) -> Self {
+        self.files.extend(files);
+        self
+    }
+
+    /// Leaves files whose paths match any of the globs uncompressed, see
+    /// [crate::BuildOptions::uncompressed_globs].
+    pub fn uncompressed_globs(mut self, globs: impl IntoIterator<Item = String>) -> Self {
+        self.zip_options.uncompressed_globs.extend(globs);
+        self
+    }
+
+    /// Sets whether the file at `path` in the APK is compressed, whatever
+    /// the uncompressed globs say.
+    pub fn compression(mut self, path: impl Into<String>, compression: Compression) -> Self {
+        self.zip_options
+            .compression
+            .insert(path.into(), compression);
+        self
+    }
+
+    /// Aligns the data of every file to `alignment` bytes instead of 4, see
+    /// [ZipOptions::alignment].
+    pub fn alignment(mut self, alignment: u16) -> Self {
+        self.zip_options.alignment = alignment;
+        self
+    }
+
+    /// Aligns the data of uncompressed native libraries to `alignment` bytes
+    /// instead of 16 KB, see [ZipOptions::native_library_alignment].
+    pub fn native_library_alignment(mut self, alignment: u16) -> Self {
+        self.zip_options.native_library_alignment = alignment;
+        self
+    }
+
+    /// Zips everything that was added into an unsigned APK.
+    pub fn build(self) -> Result<Vec<u8>> {
+        let mut files = vec![pack_zip::File {
+            path: "AndroidManifest.xml".into(),
+            data: self.android_manifest.to_bytes()?
+        }];
+        if let Some(resource_table) = self.resource_table {
+            files.push(pack_zip::File {
+                path: "resources.arsc".into(),
+                data: resource_table.to_bytes()?
+            });
+        }
+        for (path, chunk) in self.compiled {
+            files.push(pack_zip::File {
+                data: chunk.to_bytes()?,
+                path
+            });
+        }
+        files.extend(self.files);
+
+        let mut zip_buf = vec![];
+        pack_zip::zip_apk_with_options(&files, Cursor::new(&mut zip_buf), &self.zip_options)?;
+        Ok(zip_buf)
+    }
+
+    /// Zips everything that was added, and signs the APK with APK Signature
+    /// Scheme v2 and v3.
+    pub fn build_and_sign(self, keys: &Keys) -> Result<Vec<u8>> {
+        let mut zip_buf = self.build()?;
+        pack_sign::sign_apk_buffer(&mut zip_buf, keys)
+    }
+}
@@ -0,0 +1,146 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// A record of what went into a package and what came out, so that a package
+// can be traced back to its sources, or a build skipped when nothing changed.
+use sha2::{Digest, Sha256};
+
+use crate::{BuildOptions, Keys, Package};
+
+/// What a package was built from, written as a JSON sidecar with
+/// [BuildManifest::to_json].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BuildManifest {
+    /// The version of PACK that built the package.
+    pub tool_version: String,
+    /// SHA-256 of the package, in lowercase hex.
+    pub sha256: String,
+    /// SHA-256 of the signing certificate, which is the fingerprint Play
+    /// Console shows, or `None` for unsigned packages.
+    pub certificate_sha256: Option<String>,
+    /// Every file the package was built from, sorted by path.
+    pub inputs: Vec<BuildInput>
+}
+
+/// One of the files in [BuildManifest::inputs].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BuildInput {
+    /// Where the file was in the package, eg. `res/xml/watchface.xml` or
+    /// `hires/assets/background.png`.
+    pub path: String,
+    /// SHA-256 of the file, in lowercase hex.
+    pub sha256: String
+}
+
+impl BuildManifest {
+    /// Describes `output`, which was built from `package` and `options`, and
+    /// signed with `keys` if there are any.
+    pub fn new(
+        package: &Package,
+        options: &BuildOptions,
+        output: &[u8],
+        keys: Option<&Keys>
+    ) -> BuildManifest {
+        let mut inputs = vec![input(
+            "AndroidManifest.xml".into(),
+            &package.android_manifest
+        )];
+        for res in &package.resources {
+            inputs.push(input(res.get_path(), &res.contents));
+        }
+        for module in &package.modules {
+            for res in &module.resources {
+                inputs.push(input(
+                    format!("{}/{}", module.name, res.get_path()),
+                    &res.contents
+                ));
+            }
+            for (path, contents) in &module.assets {
+                inputs.push(input(format!("{}/assets/{path}", module.name), contents));
+            }
+        }
+        for (path, contents) in &options.bundle_metadata {
+            inputs.push(input(format!("BUNDLE-METADATA/{path}"), contents));
+        }
+        for (path, contents) in &options.extra_files {
+            inputs.push(input(path.clone(), contents));
+        }
+        inputs.sort_by(|a, b| a.path.cmp(&b.path));
+
+        BuildManifest {
+            tool_version: env!("CARGO_PKG_VERSION").into(),
+            sha256: sha256_hex(output),
+            certificate_sha256: keys.map(|keys| sha256_hex(&keys.certificate)),
+            inputs
+        }
+    }
+
+    /// Formats the manifest as pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        let certificate_sha256 = match &self.certificate_sha256 {
+            Some(sha256) => json_string(sha256),
+            None => "null".into()
+        };
+        let inputs: Vec<String> = self
+            .inputs
+            .iter()
+            .map(|input| {
+                format!(
+                    "    {{ \"path\": {}, \"sha256\": {} }}",
+                    json_string(&input.path),
+                    json_string(&input.sha256)
+                )
+            })
+            .collect();
+        format!(
+            "{{\n  \"tool_version\": {},\n  \"sha256\": {},\n  \"certificate_sha256\": {},\n  \"inputs\": [\n{}\n  ]\n}}\n",
+            json_string(&self.tool_version),
+            json_string(&self.sha256),
+            certificate_sha256,
+            inputs.join(",\n")
+        )
+    }
+}
+
+fn input(path: String, contents: &[u8]) -> BuildInput {
+    BuildInput {
+        path,
+        sha256: sha256_hex(contents)
+    }
+}
+
+pub(crate) fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+pub(crate) fn json_string(value: &str) -> String {
+    let mut json = String::with_capacity(value.len() + 2);
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if c.is_control() => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c)
+        }
+    }
+    json.push('"');
+    json
+}
@@ -0,0 +1,81 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// What a build produced, along with what its manifest declares. The manifest
+// is parsed while compiling anyway, so callers that name output files or show
+// a summary can use this instead of parsing it again.
+use pack_asset_compiler::{resource_internal_types::Resource, xml_file::ManifestInfo};
+
+/// A package from [build_apk](crate::build_apk) or
+/// [build_aab](crate::build_aab), with details from its manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BuildOutput {
+    /// The signed APK or AAB.
+    pub bytes: Vec<u8>,
+    /// eg. `com.example.watchface`
+    pub package_name: String,
+    /// `android:versionCode`, if it's set to a number.
+    pub version_code: Option<u32>,
+    /// `android:versionName`, eg. `1.2.0`.
+    pub version_name: Option<String>,
+    /// `android:minSdkVersion` from `<uses-sdk>`, if it's set to a number.
+    pub min_sdk: Option<u32>,
+    /// The application's label. A string resource is resolved to its value
+    /// without qualifiers, eg. `@string/app_name` to `Analogue`.
+    pub label: Option<String>
+}
+
+impl BuildOutput {
+    pub(crate) fn new(
+        bytes: Vec<u8>,
+        package_name: String,
+        manifest_info: &ManifestInfo,
+        resources: &[Resource]
+    ) -> BuildOutput {
+        BuildOutput {
+            bytes,
+            package_name,
+            version_code: manifest_info
+                .version_code
+                .as_ref()
+                .and_then(|code| code.parse().ok()),
+            version_name: manifest_info.version_name.clone(),
+            min_sdk: manifest_info
+                .min_sdk
+                .as_ref()
+                .and_then(|sdk| sdk.parse().ok()),
+            label: manifest_info
+                .label
+                .as_ref()
+                .map(|label| resolve_string(label, resources))
+        }
+    }
+}
+
+// A reference to a string that doesn't exist is left as it is, since the
+// label is only for showing
+fn resolve_string(value: &str, resources: &[Resource]) -> String {
+    let Some(name) = value.strip_prefix("@string/") else {
+        return value.to_string();
+    };
+    resources
+        .iter()
+        .find_map(|res| match res {
+            Resource::String(string) if string.name == name && string.config.is_default() => {
+                Some(string.value.clone())
+            }
+            _ => None
+        })
+        .unwrap_or_else(|| value.to_string())
+}
@@ -0,0 +1,119 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// A long-lived context for building many packages, eg. on a server that
+// packages watch faces on request. The keys are parsed once, and compiled
+// XML is cached between builds, so each build only pays for what's new.
+use std::sync::Arc;
+
+use pack_common::Result;
+use pack_sign::crypto_keys::Keys;
+
+#[cfg(feature = "aab")]
+use crate::{build_aab, compile_and_sign_aab_with_options};
+use crate::{
+    build_apk, compile_and_sign_apk_with_options, BuildCache, BuildOptions, BuildOutput, Package
+};
+
+// How many builds an engine keeps compiled files for after they were last used
+const ENGINE_CACHE_BUILDS: u64 = 64;
+
+/// Builds and signs packages with the same keys and [BuildOptions], reusing
+/// compiled resources between builds. Compiled files that none of the last
+/// 64 builds used are dropped, so the cache doesn't grow forever.
+///
+/// It's `Send` and `Sync`, and every method takes `&self`, so one engine can
+/// be shared by a pool of threads building different packages at once.
+///
+/// ```ignore
+/// let engine = Arc::new(PackEngine::new(keys, BuildOptions::default()));
+/// let apk_bytes = engine.compile_and_sign_apk(&pkg)?;
+/// ```
+pub struct PackEngine {
+    keys: Keys,
+    options: BuildOptions
+}
+
+// Engines are meant to be shared between threads, so this fails to compile if
+// a field ever stops them being
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<PackEngine>();
+};
+
+impl PackEngine {
+    /// Returns an engine that signs with `keys` and builds with `options`. If
+    /// `options` has no [BuildCache], the engine makes one, so that files
+    /// shared between builds, eg. a template's XML, are only compiled once.
+    pub fn new(keys: Keys, mut options: BuildOptions) -> PackEngine {
+        options
+            .cache
+            .get_or_insert_with(|| Arc::new(BuildCache::new()));
+        PackEngine { keys, options }
+    }
+
+    pub fn keys(&self) -> &Keys {
+        &self.keys
+    }
+
+    pub fn options(&self) -> &BuildOptions {
+        &self.options
+    }
+
+    /// The cache shared by every build.
+    pub fn cache(&self) -> &Arc<BuildCache> {
+        self.options
+            .cache
+            .as_ref()
+            .expect("PackEngine::new always sets a cache")
+    }
+
+    /// Same as [compile_and_sign_apk_with_options](crate::compile_and_sign_apk_with_options)
+    /// with the engine's keys and options.
+    pub fn compile_and_sign_apk(&self, package: &Package) -> Result<Vec<u8>> {
+        self.begin_build();
+        compile_and_sign_apk_with_options(package, &self.keys, &self.options)
+    }
+
+    /// Same as [compile_and_sign_aab_with_options](crate::compile_and_sign_aab_with_options)
+    /// with the engine's keys and options.
+    #[cfg(feature = "aab")]
+    pub fn compile_and_sign_aab(&self, package: &Package) -> Result<Vec<u8>> {
+        self.begin_build();
+        compile_and_sign_aab_with_options(package, &self.keys, &self.options)
+    }
+
+    /// Same as [build_apk](crate::build_apk) with the engine's keys and
+    /// options.
+    pub fn build_apk(&self, package: &Package) -> Result<BuildOutput> {
+        self.begin_build();
+        build_apk(package, &self.keys, &self.options)
+    }
+
+    /// Same as [build_aab](crate::build_aab) with the engine's keys and
+    /// options.
+    #[cfg(feature = "aab")]
+    pub fn build_aab(&self, package: &Package) -> Result<BuildOutput> {
+        self.begin_build();
+        build_aab(package, &self.keys, &self.options)
+    }
+
+    // Counts a build towards the cache's generations, and drops whatever
+    // recent builds haven't used
+    fn begin_build(&self) {
+        let cache = self.cache();
+        cache.begin_build();
+        cache.prune_unused_for(ENGINE_CACHE_BUILDS);
+    }
+}
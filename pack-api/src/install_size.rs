@@ -0,0 +1,121 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Estimates of what an APK costs the people installing it: how much they
+// download, and how much space it takes up once it's installed. These let
+// releases be checked against a size budget before they're uploaded. Google
+// Play's own figures depend on how it compresses downloads and what ART does
+// on each device, so these are estimates rather than exact numbers.
+use pack_zip::EntrySize;
+
+use crate::{build_manifest::json_string, Result, SplitApk};
+
+/// How big an APK is to download and once it's installed, from
+/// [estimate_install_size].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstallSize {
+    /// Name of the split, eg. `config.xxhdpi`, or `None` for a base or
+    /// standalone APK.
+    pub split_name: Option<String>,
+    /// The size of the APK file. Google Play compresses downloads further, so
+    /// this is an upper bound.
+    pub download_size: u64,
+    /// The space the APK takes up on the device. The APK is kept as it is,
+    /// so this is its size, plus native libraries that are extracted from it
+    /// because they're compressed, plus the compiled code ART generates from
+    /// dex files, which is estimated to be the size of the dex files.
+    pub install_size: u64,
+    /// The size of every file in the APK, uncompressed.
+    pub uncompressed_size: u64,
+    /// The uncompressed size of resources.arsc.
+    pub resource_table_size: u64,
+    /// The uncompressed size of the `classes*.dex` files. Watch faces
+    /// usually have no code, so this is 0.
+    pub dex_size: u64,
+    /// The uncompressed size of the native libraries in lib/.
+    pub native_library_size: u64
+}
+
+/// Estimates how big a signed APK is to download and once installed.
+pub fn estimate_install_size(apk: &[u8]) -> Result<InstallSize> {
+    let entries = pack_zip::entry_sizes(apk)?;
+    let total = |filter: fn(&EntrySize) -> bool| -> u64 {
+        entries
+            .iter()
+            .filter(|entry| filter(entry))
+            .map(|entry| entry.uncompressed_size)
+            .sum()
+    };
+    let dex_size = total(|entry| is_dex(&entry.path));
+    let native_library_size = total(|entry| is_native_library(&entry.path));
+    // Stored libraries are loaded straight from the APK, so only compressed
+    // ones are extracted
+    let extracted_size = total(|entry| is_native_library(&entry.path) && !entry.stored);
+    let download_size = apk.len() as u64;
+    Ok(InstallSize {
+        split_name: None,
+        download_size,
+        install_size: download_size + extracted_size + dex_size,
+        uncompressed_size: total(|_| true),
+        resource_table_size: total(|entry| entry.path == "resources.arsc"),
+        dex_size,
+        native_library_size
+    })
+}
+
+/// Estimates the size of each APK from
+/// [compile_and_sign_split_apks](crate::compile_and_sign_split_apks). A
+/// device downloads and installs the base APK plus the splits for its
+/// language and density, so its total is the sum of those.
+pub fn estimate_split_install_sizes(splits: &[SplitApk]) -> Result<Vec<InstallSize>> {
+    splits
+        .iter()
+        .map(|split| {
+            Ok(InstallSize {
+                split_name: split.split_name.clone(),
+                ..estimate_install_size(&split.apk)?
+            })
+        })
+        .collect()
+}
+
+impl InstallSize {
+    /// Formats the estimate as pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        let split_name = match &self.split_name {
+            Some(split_name) => json_string(split_name),
+            None => "null".into()
+        };
+        format!(
+            "{{\n  \"split_name\": {},\n  \"download_size\": {},\n  \"install_size\": {},\n  \"uncompressed_size\": {},\n  \"resource_table_size\": {},\n  \"dex_size\": {},\n  \"native_library_size\": {}\n}}\n",
+            split_name,
+            self.download_size,
+            self.install_size,
+            self.uncompressed_size,
+            self.resource_table_size,
+            self.dex_size,
+            self.native_library_size
+        )
+    }
+}
+
+// eg. classes.dex or classes2.dex
+fn is_dex(path: &str) -> bool {
+    path.starts_with("classes") && path.ends_with(".dex") && !path.contains('/')
+}
+
+// eg. lib/arm64-v8a/libwatchface.so
+fn is_native_library(path: &str) -> bool {
+    path.starts_with("lib/") && path.ends_with(".so")
+}
@@ -0,0 +1,55 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Files from partway through a build, eg. the compiled AndroidManifest.xml and
+// resources.arsc before they're zipped and signed. When a package misbehaves
+// on a device, these show exactly what each stage produced, without having to
+// take the package apart.
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// Collects what each stage of a build produced, when it's set as
+/// [BuildOptions::intermediates](crate::BuildOptions::intermediates).
+///
+/// Files from APK builds are under `apk/`: the compiled
+/// `AndroidManifest.xml`, `resources.arsc` and XML files in `res/`, and
+/// `unsigned.apk`. Files from AAB builds are under `aab/`: `BundleConfig.pb`,
+/// each module's `manifest/AndroidManifest.xml`, `resources.pb` and XML files
+/// in `res/`, and `unsigned.aab`, which only has the v1 signature.
+#[derive(Debug, Default)]
+pub struct Intermediates {
+    files: Mutex<Vec<pack_zip::File>>
+}
+
+impl Intermediates {
+    /// Returns an empty collection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Takes every file collected so far, in the order they were produced.
+    pub fn take(&self) -> Vec<pack_zip::File> {
+        std::mem::take(&mut *self.lock())
+    }
+
+    // Replaces a file from an earlier build with the same options
+    pub(crate) fn add(&self, path: String, data: Vec<u8>) {
+        let mut files = self.lock();
+        files.retain(|file| file.path != path);
+        files.push(pack_zip::File { path, data });
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<pack_zip::File>> {
+        self.files.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
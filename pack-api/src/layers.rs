@@ -0,0 +1,157 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Builds packages from a shared template with layers laid over it, for
+// generating many watch faces that only differ in a few drawables and
+// strings. A layer's file replaces the template's file with the same
+// subdirectory and name, and anything else is added. Replacements that
+// probably aren't what was meant, like preview.xml replacing preview.png, are
+// reported rather than failing, so that a generator can decide what to do.
+use std::{collections::HashMap, io::Cursor};
+
+use pack_asset_compiler::strings_xml_parser::parse_strings_xml;
+use pack_common::{Diagnostic, ErrorCode, Result};
+
+use crate::{FileResource, Package, ResourceConfig};
+
+/// Files to lay over a template with [compose_package], eg. the drawables and
+/// strings for one watch face.
+#[derive(Debug, Clone, Default)]
+pub struct PackageLayer {
+    /// Replaces the manifest, if set.
+    pub android_manifest: Option<Vec<u8>>,
+    /// Files that replace the ones before them with the same subdirectory,
+    /// config and name, or are added if there aren't any.
+    pub resources: Vec<FileResource>
+}
+
+/// A package made by [compose_package].
+pub struct ComposedPackage {
+    pub package: Package,
+    /// Replacements that might break the package, as [ErrorCode::LayerConflict]
+    /// warnings. The package is still complete, with each conflict resolved
+    /// in favour of the later layer.
+    pub conflicts: Vec<Diagnostic>
+}
+
+// A file's place in the package, with qualifiers in the subdirectory moved
+// into the config, so that eg. drawable-hdpi/preview.png is the same file
+// whichever way it's given
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct FileKey {
+    res_type: String,
+    config: ResourceConfig,
+    name: String
+}
+
+/// Lays each layer over the template in turn. Later layers win, so a file in
+/// the last layer replaces any file with the same subdirectory and name before
+/// it, as does its manifest. The template's modules are kept as they are.
+pub fn compose_package(template: &Package, layers: &[PackageLayer]) -> Result<ComposedPackage> {
+    let mut package = template.clone();
+    let mut conflicts = vec![];
+    let mut index_by_key: HashMap<FileKey, usize> = HashMap::new();
+    for (index, resource) in package.resources.iter().enumerate() {
+        index_by_key.insert(file_key(resource)?, index);
+    }
+
+    for (layer_index, layer) in layers.iter().enumerate() {
+        // Layers are numbered from 1, after the template
+        let layer_number = layer_index + 1;
+        if let Some(android_manifest) = &layer.android_manifest {
+            package.android_manifest = android_manifest.clone();
+        }
+
+        for resource in &layer.resources {
+            let key = file_key(resource)?;
+            let resource_path = path(resource, &key);
+            if let Some(&index) = index_by_key.get(&key) {
+                let replaced = &package.resources[index];
+                if key.res_type == "values" && key.name == "strings.xml" {
+                    let missing = missing_strings(&replaced.contents, &resource.contents);
+                    if !missing.is_empty() {
+                        conflicts.push(Diagnostic::warning(
+                            ErrorCode::LayerConflict,
+                            Some(&resource_path),
+                            format!(
+                                "Layer {layer_number} replaces strings.xml without {}, which the template or an earlier layer defines.",
+                                missing.join(", ")
+                            )
+                        ));
+                    }
+                } else if replaced.name != resource.name {
+                    conflicts.push(Diagnostic::warning(
+                        ErrorCode::LayerConflict,
+                        Some(&resource_path),
+                        format!(
+                            "Layer {layer_number} replaces {} with a different kind of file.",
+                            path(replaced, &key)
+                        )
+                    ));
+                }
+                package.resources[index] = resource.clone();
+            } else {
+                index_by_key.insert(key, package.resources.len());
+                package.resources.push(resource.clone());
+            }
+        }
+    }
+    Ok(ComposedPackage { package, conflicts })
+}
+
+fn file_key(resource: &FileResource) -> Result<FileKey> {
+    let (res_type, dir_config) = ResourceConfig::parse_directory(&resource.subdirectory)?;
+    // Qualifiers can either be part of the subdirectory or set on the config
+    let config = if dir_config.is_default() {
+        resource.config.clone()
+    } else {
+        dir_config
+    };
+    // Values files are told apart by their whole names, since their contents
+    // define the resources. Other files are referenced without extensions, so
+    // eg. preview.png and preview.xml are the same resource.
+    let name = if res_type == "values" {
+        resource.name.clone()
+    } else {
+        resource.get_basename()?
+    };
+    Ok(FileKey {
+        res_type: res_type.into(),
+        config,
+        name
+    })
+}
+
+// eg. res/drawable-hdpi/preview.png, like FileResource::get_path
+fn path(resource: &FileResource, key: &FileKey) -> String {
+    format!(
+        "res/{}{}/{}",
+        key.res_type,
+        key.config.directory_suffix(),
+        resource.name
+    )
+}
+
+// The names of strings in the old strings.xml that aren't in the new one
+fn missing_strings(old: &[u8], new: &[u8]) -> Vec<String> {
+    let new_names: Vec<String> = parse_strings_xml(&mut Cursor::new(new))
+        .iter()
+        .map(|string| string.get_name().into())
+        .collect();
+    parse_strings_xml(&mut Cursor::new(old))
+        .iter()
+        .map(|string| string.get_name().to_string())
+        .filter(|name| !new_names.contains(name))
+        .collect()
+}
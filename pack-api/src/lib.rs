@@ -21,13 +21,14 @@
 //!
 //! The following API compiles and signs an APK in memory.
 //!
-//! ```
+//! ```ignore
 //! let pkg = Package {
 //!     android_manifest: "<?xml version...".as_bytes(),
 //!     resources: vec![
 //!         FileResource::new("xml".into(), "strings.xml".into(), "<resource>...".as_bytes()),
 //!         FileResource::new("drawable".into(), "image.png".into(), fs::read(...))
-//!     ]
+//!     ],
+//!     modules: vec![]
 //! }
 //!
 //! // Use placeholder keys for simplicity
@@ -38,31 +39,326 @@
 //! ## Creating an AAB
 //!
 //! The API is exactly the same for the more complex Google Play publishing format.
+//! This needs the `aab` feature, which is on by default. Apps that only build
+//! APKs can turn it off with `default-features = false` to skip compiling the
+//! protobuf and ASN.1 crates that bundles need.
 //!
-//! ```
+//! ```ignore
 //! let aab_bytes = compile_and_sign_aab(pkg, signing_keys)?;
 //! ```
+//!
+//! ## Rebuilding quickly
+//!
+//! When building the same project repeatedly, pass a [BuildCache] through
+//! [BuildOptions] so that unchanged XML files aren't compiled again.
+//!
+//! ```ignore
+//! let options = BuildOptions {
+//!     cache: Some(Arc::new(BuildCache::new())),
+//!     ..Default::default()
+//! };
+//! let apk_bytes = compile_and_sign_apk_with_options(pkg, signing_keys, &options)?;
+//! ```
+//!
+//! A server that builds many packages can share a [PackEngine] between its
+//! threads instead. It holds the keys and options, and always has a cache.
+//!
+//! ```ignore
+//! let engine = Arc::new(PackEngine::new(signing_keys, BuildOptions::default()));
+//! let apk_bytes = engine.compile_and_sign_apk(&pkg)?;
+//! ```
+//!
+//! ## Assembling an APK by hand
+//!
+//! [ApkBuilder] skips the res/ pipeline, and zips, aligns and signs whatever
+//! compiled chunks and files it's given. That suits resources compiled some
+//! other way, or extra files like licenses.
+//!
+//! ```ignore
+//! let apk_bytes = ApkBuilder::new(manifest_chunk)
+//!     .resource_table(resource_table_chunk)
+//!     .file(pack_zip::File { path: "META-INF/LICENSE.txt".into(), data: license })
+//!     .build_and_sign(&signing_keys)?;
+//! ```
+//!
+//! ## Building an overlay
+//!
+//! A runtime resource overlay (RRO) is an APK that replaces another app's
+//! resources, eg. to theme a watch face. Its resources replace the target's
+//! ones with the same type and name, and it needs a package name of its own.
+//!
+//! ```ignore
+//! let options = BuildOptions {
+//!     overlay: Some(OverlayTarget {
+//!         target_package: "com.example.watchface".into(),
+//!         target_name: Some("ThemeResources".into())
+//!     }),
+//!     ..Default::default()
+//! };
+//! let overlay_bytes = compile_and_sign_apk_with_options(overlay_pkg, signing_keys, &options)?;
+//! ```
+//!
+//! ## Profiling
+//!
+//! With the `tracing` feature, each step of a build, like parsing the
+//! manifest, compiling each resource, zipping and signing, runs in a
+//! [tracing](https://docs.rs/tracing) span. Any subscriber can collect them,
+//! eg. to log how long each step took:
+//!
+//! ```ignore
+//! tracing_subscriber::fmt()
+//!     .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
+//!     .init();
+//! ```
 
-use std::io::{BufReader, Cursor};
+#[cfg(feature = "aab")]
+use std::collections::HashMap;
+use std::{
+    io::{BufReader, Cursor},
+    sync::Arc
+};
 
 use deku::DekuContainerWrite;
+#[cfg(feature = "aab")]
+use pack_aab::{AabBuilder, AabModule};
+#[cfg(feature = "aab")]
+use pack_asset_compiler::public_xml_parser::{parse_public_xml, ResourceDeclarations};
 use pack_asset_compiler::{
-    resource_external_types::ResChunk, resource_internal_types::Resource,
-    resource_table::construct_resource_table, strings_xml_parser::parse_strings_xml,
-    xml_file::xml_to_res_chunk
+    locale_config::{
+        locale_config_xml, manifest_locale_config, missing_locales, parse_locale_config,
+        resource_locales, set_manifest_locale_config, LOCALE_CONFIG_FILE_NAME
+    },
+    names::{validate_package_name, validate_resource_name},
+    resource_internal_types::{split_resource_file_name, Resource},
+    resource_table::{
+        construct_resource_table_with_packages, sort_resources, TableEncoding, TablePackage,
+        USER_PACKAGE_MAGIC
+    },
+    strings_xml_parser::parse_values_xml,
+    xml_file::{xml_to_res_chunk_with_options, ManifestInfo, XmlOptions}
 };
+use pack_common::map_in_order;
+#[cfg(feature = "aab")]
 use pack_sign::v1_signing::add_v1_signature_files;
 
+mod apk_builder;
+mod build_manifest;
+mod build_output;
+mod engine;
+mod install_size;
+mod intermediates;
+mod layers;
+mod pack_fs;
+mod package_diff;
+mod project_archive;
+mod res_dir;
+mod size_report;
+mod split_apks;
+
+pub use apk_builder::ApkBuilder;
+pub use build_manifest::{BuildInput, BuildManifest};
+pub use build_output::BuildOutput;
+pub use pack_asset_compiler::build_cache::{BuildCache, CacheKey};
+pub use pack_asset_compiler::decoder::{
+    decode_resource_table, decode_xml, BinaryValue, DecodedAttribute, DecodedElement, DecodedEntry,
+    DecodedPackage, DecodedTable, DecodedType
+};
+pub use pack_asset_compiler::inline_strings::{inline_string_references, StringValues};
+pub use pack_asset_compiler::manifest_overrides::{
+    apply_manifest_overrides, apply_merge_markers, apply_overlay_target, ManifestOverrides,
+    OverlayTarget
+};
+pub use pack_asset_compiler::resource_config::ResourceConfig;
+pub use pack_asset_compiler::table_editor::{encode_resource_table, ResourceTableEditor};
+// Compiled XML and resource tables, for ApkBuilder
+pub use engine::PackEngine;
+pub use install_size::{estimate_install_size, estimate_split_install_sizes, InstallSize};
+pub use intermediates::Intermediates;
+pub use layers::{compose_package, ComposedPackage, PackageLayer};
+pub use pack_asset_compiler::resource_external_types::{AttributeDataType, ChunkType, ResChunk};
+pub use pack_asset_compiler::resource_graph::{ResourceGraph, ResourceName, ResourceNode};
 pub use pack_asset_compiler::resource_internal_types::FileResource;
-pub use pack_common::{PackError, Result};
+pub use pack_asset_compiler::{xml_file::RawValues, xml_namespaces::ToolsNamespace};
+pub use pack_common::{
+    glob_matches, BundleConfigOptions, Compression, Diagnostic, ErrorCode, FileContents,
+    ModuleDelivery, ModuleKind, PackError, Result, Severity, Span
+};
+#[cfg(feature = "mmap")]
+pub use pack_fs::MappedFs;
+pub use pack_fs::{FsMetadata, MemoryFs, PackFs, StdFs};
+#[cfg(feature = "cert-gen")]
+pub use pack_sign::crypto_keys::KeyOptions;
 pub use pack_sign::crypto_keys::Keys;
+pub use pack_sign::verification::{SchemeStatus, SignerCertificate, VerificationReport};
+pub use pack_sign::{DigestAlgorithm, SigningConfig};
+pub use pack_zip::EntrySize;
+pub use package_diff::{diff_packages, ManifestChange, PackageDiff};
+pub use res_dir::{is_excluded, read_ignore_file, read_res_dir, IGNORE_FILE_NAME};
+pub use size_report::{
+    analyze_package, enforce_size_policy, ResourceTypeSize, SizePolicy, SizeReport
+};
+pub use split_apks::{compile_and_sign_split_apks, SplitApk};
 
 /// Represents an Android package before compilation.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Package {
     /// The package's AndroidManifest.xml file as a series of UTF-8 bytes.
+    #[cfg_attr(feature = "serde", serde(with = "pack_common::bytes_or_base64"))]
     pub android_manifest: Vec<u8>,
     /// The package's associated files from the res/ directories.
-    pub resources: Vec<FileResource>
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub resources: Vec<FileResource>,
+    /// Extra modules, such as asset packs. These are only included when
+    /// building an AAB, since APKs can't contain them.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub modules: Vec<Module>
+}
+
+/// A module in an App Bundle besides the base one, for example an asset pack
+/// for Play Asset Delivery.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Module {
+    /// eg. `hires_backgrounds`. Must be unique and only use letters, digits
+    /// and underscores.
+    pub name: String,
+    pub kind: ModuleKind,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub delivery: ModuleDelivery,
+    /// A reference to a string in the base module, eg. `@string/backgrounds_title`.
+    /// Google Play requires this for feature modules that aren't delivered at
+    /// install time.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub title: Option<String>,
+    /// Files from the module's res/ directories. Only feature modules can have
+    /// resources.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub resources: Vec<FileResource>,
+    /// Files from the module's assets/ directory, as pairs of their path
+    /// relative to assets/ and their contents.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, with = "pack_common::bytes_or_base64::files")
+    )]
+    pub assets: Vec<(String, Vec<u8>)>
+}
+
+/// Optional settings which change how a [Package] is built.
+///
+/// The default options produce the same output as [compile_and_sign_apk] and
+/// [compile_and_sign_aab].
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct BuildOptions {
+    /// Reuse compiled resources from previous builds. The same cache can be
+    /// shared between APK and AAB builds, and between threads. Not serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub cache: Option<Arc<BuildCache>>,
+    /// Collects what each stage of the build produced, eg. the compiled
+    /// manifest and the unsigned package, for debugging. Not serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub intermediates: Option<Arc<Intermediates>>,
+    /// How Google Play should generate APKs from the bundle. Only used when
+    /// building an AAB.
+    pub bundle_config: BundleConfigOptions,
+    /// Extra files to store in the AAB's `BUNDLE-METADATA/` directory, as pairs
+    /// of `<namespace>/<name>` paths and contents, eg.
+    /// `com.android.tools.build.obfuscation/proguard.map`. Only used when
+    /// building an AAB.
+    #[cfg_attr(feature = "serde", serde(with = "pack_common::bytes_or_base64::files"))]
+    pub bundle_metadata: Vec<(String, Vec<u8>)>,
+    /// Files to leave uncompressed in APKs, as globs over their path within
+    /// the APK, eg. `res/raw/*.ogg`. For AABs, use
+    /// [BundleConfigOptions::uncompressed_globs] instead.
+    pub uncompressed_globs: Vec<String>,
+    /// What the data of each file in the APK or AAB is aligned to, in bytes,
+    /// for stores that ask for more than Android's 4. Must be a power of two.
+    /// `None` keeps the default, [pack_zip::DEFAULT_ALIGNMENT].
+    pub zip_alignment: Option<u16>,
+    /// What the data of uncompressed native libraries is aligned to instead.
+    /// Must be a power of two. `None` keeps the default,
+    /// [pack_zip::DEFAULT_NATIVE_LIBRARY_ALIGNMENT], which suits 16 KB pages.
+    pub native_library_alignment: Option<u16>,
+    /// Files to add to the APK or AAB as they are, as pairs of their path in
+    /// the package and contents, eg. `META-INF/LICENSE.txt`. They're added
+    /// after resources are compiled and before signing, so the signatures
+    /// cover them. In AABs, files outside `META-INF/` and `BUNDLE-METADATA/`
+    /// must be in a module, eg. `base/root/` for files at the root of the APKs
+    /// Google Play generates.
+    #[cfg_attr(feature = "serde", serde(with = "pack_common::bytes_or_base64::files"))]
+    pub extra_files: Vec<(String, Vec<u8>)>,
+    /// Builds a runtime resource overlay (RRO) for another app instead of a
+    /// standalone app. Its resources replace the target's resources that
+    /// have the same type and name. Only APKs can be overlays.
+    pub overlay: Option<OverlayTarget>,
+    /// Writes resource types that have values for fewer than 60% of their
+    /// resources in a config as sparse types, which makes `resources.arsc`
+    /// smaller for packages with lots of locale or density variants. Only
+    /// Android 8.0 (API 26) and up can read sparse types, so only set this
+    /// when the app's minSdkVersion is at least 26. Only used when building an
+    /// APK.
+    pub sparse_resources: bool,
+    /// Writes `resources.arsc` entries in a compact form that's half the size.
+    /// Only Android 14 (API 34) and up can read it, so only set this when the
+    /// app's minSdkVersion is at least 34. Only used when building an APK.
+    pub compact_resources: bool,
+    /// Sorts the key and value string pools in `resources.arsc` and flags
+    /// them as sorted, so that Android can binary search them. Every version
+    /// of Android can read sorted pools. Only used when building an APK.
+    pub sorted_string_pools: bool,
+    /// Which attributes in compiled XML files, including the manifest, keep
+    /// their original text alongside their typed value. Only used when
+    /// building an APK, since AABs keep every attribute's text anyway.
+    pub raw_values: RawValues,
+    /// Keeps comments in compiled XML files, including the manifest, eg.
+    /// license headers that have to ship with the package. Each is kept with
+    /// the element after it. Only used when building an APK, since ProtoXML
+    /// has nowhere to store comments.
+    pub keep_xml_comments: bool,
+    /// What happens to `tools:` attributes, eg. `tools:ignore`, in compiled
+    /// XML files, including the manifest. They're stripped by default, as
+    /// aapt2 does, and treated the same way in APKs and AABs.
+    pub tools_namespace: ToolsNamespace,
+    /// Sorts the attributes of each element in an AAB's XML files by resource
+    /// ID and then by name, as aapt2 does, so that the output can be compared
+    /// with aapt2's. APKs always sort them this way, since Android relies on
+    /// it when looking up attributes.
+    pub aapt2_attribute_order: bool,
+    /// Reproduces what aapt2 and bundletool write wherever PACK's output
+    /// would otherwise differ, for publishers who want packages identical to
+    /// the ones Android's own tools make before switching. APKs keep raw
+    /// values as with [RawValues::Aapt2], whatever [BuildOptions::raw_values]
+    /// says. AABs sort attributes as with [BuildOptions::aapt2_attribute_order],
+    /// get the `compileSdkVersion` and `platformBuildVersion` manifest
+    /// attributes that APKs always have, and name aapt2 as the tool that wrote
+    /// each `resources.pb`.
+    pub compatibility_mode: bool,
+    /// Replaces attributes that refer to strings, eg. `@string/app_name`, with
+    /// the strings themselves in the manifest and the base module's XML
+    /// files, for on-device parsers that don't resolve references. The
+    /// default config's strings are used, so the text is no longer
+    /// translated. See [StringValues::resolve].
+    pub inline_string_references: bool,
+    /// The language of the resources without a locale qualifier, eg. `en`
+    /// or `en-US`. If it's set and there are resources for other locales,
+    /// `res/xml/locales_config.xml` is generated listing all of them, and
+    /// `android:localeConfig` on `<application>` refers to it, so people can
+    /// choose the app's language in Android 13's settings. A locale config
+    /// the manifest already refers to is checked instead, whether or not
+    /// this is set.
+    pub default_locale: Option<String>,
+    /// Limits on the size of the signed package and its drawables, checked by
+    /// [compile_and_sign_apk_with_options] and
+    /// [compile_and_sign_aab_with_options]. A package over a limit fails to
+    /// build with [PackError::SizePolicyExceeded]. Warning thresholds don't
+    /// stop a build, so front-ends report them with [SizePolicy::check].
+    pub size_policy: SizePolicy,
+    /// The chunk size and digest algorithm that the package's v2 and v3
+    /// signatures are made with. Not serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub signing_config: SigningConfig
 }
 
 /// Performs all the steps in packaging an APK, without signing it.
@@ -76,52 +372,110 @@ pub struct Package {
 ///
 /// The APK is built in-memory without using the local filesystem.
 pub fn compile_apk(package: &Package) -> Result<Vec<u8>> {
-    let mut resources = vec![];
-    // Look for strings.xml and parse it if present
-    for res in &package.resources {
-        if res.subdirectory == "values" && res.name == "strings.xml" {
-            let mut string_cur = Cursor::new(&res.contents);
-            resources.extend(parse_strings_xml(&mut string_cur));
-        } else {
-            resources.push(Resource::File(res.clone()));
-        }
-    }
-    // Sort resources alphabetically so that all sub-types are grouped and binary-searchable
-    resources.sort_by(|a, b| a.get_subdirectory().cmp(b.get_subdirectory()));
+    compile_apk_with_options(package, &BuildOptions::default())
+}
 
-    let (manifest_res_chunk, package_name, _label) =
-        parse_manifest(&package.android_manifest, &resources)?;
-    let mut apk_files: Vec<pack_zip::File> = vec![];
+/// Same as [compile_apk], but with customisable [BuildOptions].
+pub fn compile_apk_with_options(package: &Package, options: &BuildOptions) -> Result<Vec<u8>> {
+    Ok(compile_apk_with_output(package, options)?.bytes)
+}
 
-    apk_files.push(res_to_apk_file(
-        "AndroidManifest.xml".into(),
-        &manifest_res_chunk
-    )?);
+// Builds an unsigned APK, and returns it with what its manifest declares
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(skip_all, fields(resources = package.resources.len()))
+)]
+fn compile_apk_with_output(package: &Package, options: &BuildOptions) -> Result<BuildOutput> {
+    let mut resources = collect_resources(&package.resources)?;
+
+    let android_manifest = apply_manifest_merge_markers(&package.android_manifest)?;
+    let android_manifest = match &options.overlay {
+        Some(target) => apply_overlay_target(&android_manifest, target)?,
+        None => android_manifest
+    };
+    let android_manifest = apply_locale_config(android_manifest, &mut resources, options)?;
+    let (manifest_res_chunk, manifest_info) =
+        parse_manifest(&android_manifest, &resources, options)?;
+    let package_name = manifest_package_name(&manifest_info)?;
+    validate_package_name(&package_name)?;
+    if let Some(target) = &options.overlay {
+        check_overlay_target(target, &package_name)?;
+    }
 
     // Generate the resources.arsc file
-    let resource_table_res_chunk = construct_resource_table(&package_name, &mut resources)?;
-    // Add it to the APK
-    apk_files.push(res_to_apk_file(
-        "resources.arsc".into(),
-        &resource_table_res_chunk
-    )?);
-
-    // Add the resource files themselves to the APK
-    for res in &resources {
-        if let Resource::File(file) = res {
-            let res_bytes = file.as_bytes_for_apk(&resources)?;
-            apk_files.push(pack_zip::File {
-                path: format!("res/{}/{}", file.subdirectory, file.name),
-                data: res_bytes
-            })
+    let resource_table_res_chunk = construct_resource_table_with_packages(
+        &mut [TablePackage {
+            name: &package_name,
+            id: USER_PACKAGE_MAGIC,
+            resources: &mut resources
+        }],
+        &|_config| true,
+        TableEncoding {
+            sparse: options.sparse_resources,
+            compact: options.compact_resources,
+            sorted_pools: options.sorted_string_pools
         }
+    )?;
+    if let Some(intermediates) = &options.intermediates {
+        intermediates.add(
+            "apk/AndroidManifest.xml".into(),
+            manifest_res_chunk.to_bytes()?
+        );
+        intermediates.add(
+            "apk/resources.arsc".into(),
+            resource_table_res_chunk.to_bytes()?
+        );
+    }
+    let mut builder = ApkBuilder::new(manifest_res_chunk)
+        .resource_table(resource_table_res_chunk)
+        .uncompressed_globs(options.uncompressed_globs.iter().cloned());
+    if let Some(alignment) = options.zip_alignment {
+        builder = builder.alignment(alignment);
+    }
+    if let Some(alignment) = options.native_library_alignment {
+        builder = builder.native_library_alignment(alignment);
     }
 
-    let mut zip_buf = vec![];
-    let zip_buf_cursor = Cursor::new(&mut zip_buf);
-    pack_zip::zip_apk(&apk_files, zip_buf_cursor)?;
+    // Add the resource files themselves to the APK. They're compiled in
+    // parallel with the `parallel` feature, but added in the same order.
+    let files: Vec<&FileResource> = resources
+        .iter()
+        .filter_map(|res| match res {
+            Resource::File(file) => Some(file),
+            _ => None
+        })
+        .collect();
+    #[cfg(feature = "tracing")]
+    let parent = tracing::Span::current();
+    let compiled = map_in_order(&files, |file| {
+        #[cfg(feature = "tracing")]
+        let _entered = parent.enter();
+        file_bytes_for_apk(file, &resources, options)
+    })?;
+    for (file, data) in files.into_iter().zip(compiled) {
+        if let Some(intermediates) = &options.intermediates {
+            if file.needs_compiling() {
+                intermediates.add(format!("apk/{}", file.get_path()), data.clone());
+            }
+        }
+        builder = builder
+            .file(pack_zip::File {
+                path: file.get_path(),
+                data
+            })
+            .compression(file.get_path(), file.compression)
+    }
 
-    Ok(zip_buf)
+    let apk = builder.files(extra_files(options)).build()?;
+    if let Some(intermediates) = &options.intermediates {
+        intermediates.add("apk/unsigned.apk".into(), apk.clone());
+    }
+    Ok(BuildOutput::new(
+        apk,
+        package_name,
+        &manifest_info,
+        &resources
+    ))
 }
 
 /// Performs all the steps in packaging an APK.
@@ -137,8 +491,30 @@ pub fn compile_apk(package: &Package) -> Result<Vec<u8>> {
 ///
 /// The APK is built and signed in-memory without using the local filesystem.
 pub fn compile_and_sign_apk(package: &Package, keys: &Keys) -> Result<Vec<u8>> {
-    let mut zip_buf = compile_apk(package)?;
-    pack_sign::sign_apk_buffer(&mut zip_buf, keys)
+    compile_and_sign_apk_with_options(package, keys, &BuildOptions::default())
+}
+
+/// Same as [compile_and_sign_apk], but with customisable [BuildOptions].
+pub fn compile_and_sign_apk_with_options(
+    package: &Package,
+    keys: &Keys,
+    options: &BuildOptions
+) -> Result<Vec<u8>> {
+    Ok(build_apk(package, keys, options)?.bytes)
+}
+
+/// Same as [compile_and_sign_apk_with_options], but also returns the package
+/// name, version and label from the manifest, eg. for naming the APK.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(skip_all, fields(resources = package.resources.len()))
+)]
+pub fn build_apk(package: &Package, keys: &Keys, options: &BuildOptions) -> Result<BuildOutput> {
+    let mut output = compile_apk_with_output(package, options)?;
+    output.bytes =
+        pack_sign::sign_apk_buffer_with_config(&mut output.bytes, keys, &options.signing_config)?;
+    enforce_size_policy(&output.bytes, &options.size_policy)?;
+    Ok(output)
 }
 
 /// Performs all the steps in packaging an AAB (Android App Bundle).
@@ -159,29 +535,109 @@ pub fn compile_and_sign_apk(package: &Package, keys: &Keys) -> Result<Vec<u8>> {
 /// From Android 7 (Nougat) and up, APKs are not required to be signed using Scheme v1.
 /// However, Google Play's backend has not implemented support for signing v2
 /// so bundles intended for publishing must be signed using the old format.
+#[cfg(feature = "aab")]
 pub fn compile_and_sign_aab(package: &Package, keys: &Keys) -> Result<Vec<u8>> {
-    let mut resources = vec![];
-    // Look for strings.xml and parse it if present
-    for res in &package.resources {
-        if res.subdirectory == "values" && res.name == "strings.xml" {
-            let mut string_cur = Cursor::new(&res.contents);
-            resources.extend(parse_strings_xml(&mut string_cur));
-        } else {
-            resources.push(Resource::File(res.clone()));
-        }
-    }
-    // Sort resources alphabetically so that all sub-types are grouped and binary-searchable
-    resources.sort_by(|a, b| a.get_subdirectory().cmp(b.get_subdirectory()));
+    compile_and_sign_aab_with_options(package, keys, &BuildOptions::default())
+}
 
-    let (_, package_name, label) = parse_manifest(&package.android_manifest, &resources)?;
+/// Same as [compile_and_sign_aab], but with customisable [BuildOptions].
+#[cfg(feature = "aab")]
+pub fn compile_and_sign_aab_with_options(
+    package: &Package,
+    keys: &Keys,
+    options: &BuildOptions
+) -> Result<Vec<u8>> {
+    Ok(build_aab(package, keys, options)?.bytes)
+}
 
-    let mut aab_files = pack_aab::construct_aab(
-        &package_name,
-        &label,
-        String::from_utf8(package.android_manifest.clone())
-            .map_err(|_e| PackError::ManifestIsNotUTF8)?,
-        &mut resources
+/// Same as [compile_and_sign_aab_with_options], but also returns the package
+/// name, version and label from the manifest, eg. for naming the AAB.
+#[cfg(feature = "aab")]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(skip_all, fields(resources = package.resources.len(), modules = package.modules.len()))
+)]
+pub fn build_aab(package: &Package, keys: &Keys, options: &BuildOptions) -> Result<BuildOutput> {
+    if options.overlay.is_some() {
+        return Err(PackError::OverlayInvalid(
+            "Overlays can only be built as APKs, not AABs.".into()
+        ));
+    }
+    let mut resources = collect_resources(&package.resources)?;
+    let android_manifest = apply_locale_config(
+        apply_manifest_merge_markers(&package.android_manifest)?,
+        &mut resources,
+        options
     )?;
+    let (_, manifest_info) = parse_manifest(&android_manifest, &resources, options)?;
+    let package_name = manifest_package_name(&manifest_info)?;
+    validate_package_name(&package_name)?;
+    let android_manifest =
+        String::from_utf8(android_manifest).map_err(|_e| PackError::ManifestIsNotUTF8)?;
+    // The builder takes the resources, so the output is described up front
+    let output = BuildOutput::new(vec![], package_name.clone(), &manifest_info, &resources);
+
+    // Files that should be stored stay that way in the APKs Play generates too
+    let mut bundle_config = options.bundle_config.clone();
+    let mut compression = HashMap::new();
+    let modules = package
+        .modules
+        .iter()
+        .map(|module| (&module.name[..], &module.resources));
+    for (module_name, files) in [("base", &package.resources)].into_iter().chain(modules) {
+        for file in files {
+            if file.compression == Compression::Store {
+                bundle_config.uncompressed_globs.push(file.get_path());
+            }
+            compression.insert(
+                format!("{module_name}/{}", file.get_path()),
+                file.compression
+            );
+        }
+    }
+
+    let mut builder = AabBuilder::new(package_name, android_manifest)
+        .resources(resources)
+        .declarations(collect_declarations(&package.resources)?)
+        .bundle_config(bundle_config)
+        .sort_attributes(options.aapt2_attribute_order)
+        .compatibility_mode(options.compatibility_mode)
+        .inline_string_references(options.inline_string_references)
+        .tools_namespace(options.tools_namespace);
+    for module in &package.modules {
+        builder = builder.module(AabModule {
+            name: module.name.clone(),
+            kind: module.kind,
+            delivery: module.delivery,
+            title: module.title.clone(),
+            resources: collect_resources(&module.resources)?,
+            assets: module
+                .assets
+                .iter()
+                .map(|(path, data)| pack_zip::File {
+                    path: path.clone(),
+                    data: data.clone()
+                })
+                .collect(),
+            native_libraries: vec![]
+        });
+    }
+    for (path, contents) in &options.bundle_metadata {
+        builder = builder.bundle_metadata(path, contents.clone());
+    }
+    if let Some(cache) = &options.cache {
+        builder = builder.cache(cache.clone());
+    }
+    let mut aab_files = builder.build()?;
+    aab_files.extend(extra_files(options));
+    if let Some(intermediates) = &options.intermediates {
+        for file in aab_files
+            .iter()
+            .filter(|file| is_compiled_aab_file(&file.path))
+        {
+            intermediates.add(format!("aab/{}", file.path), file.data.clone());
+        }
+    }
 
     // Sign the AAB with Scheme v1 (pre-zip)
     add_v1_signature_files(&mut aab_files, keys)?;
@@ -189,26 +645,496 @@ pub fn compile_and_sign_aab(package: &Package, keys: &Keys) -> Result<Vec<u8>> {
     // Zip up the AAB
     let mut aab_buf = vec![];
     let aab_buf_cursor = Cursor::new(&mut aab_buf);
-    pack_zip::zip_apk(&aab_files, aab_buf_cursor)?;
+    pack_zip::zip_apk_with_options(
+        &aab_files,
+        aab_buf_cursor,
+        &zip_options(options, compression)
+    )?;
+
+    if let Some(intermediates) = &options.intermediates {
+        intermediates.add("aab/unsigned.aab".into(), aab_buf.clone());
+    }
 
     // Sign the AAB with Scheme v2 and v3 (post-zip)
+    let aab = pack_sign::sign_apk_buffer_with_config(&mut aab_buf, keys, &options.signing_config)?;
+    enforce_size_policy(&aab, &options.size_policy)?;
+    Ok(BuildOutput {
+        bytes: aab,
+        ..output
+    })
+}
+
+/// Returns the package name declared in the package's AndroidManifest.xml, eg.
+/// `com.example.watchface`.
+pub fn package_name(package: &Package) -> Result<String> {
+    let resources = collect_resources(&package.resources)?;
+    let (_, manifest_info) = parse_manifest(
+        &package.android_manifest,
+        &resources,
+        &BuildOptions::default()
+    )?;
+    manifest_package_name(&manifest_info)
+}
+
+/// Finds which resources the package's manifest and resources refer to, eg.
+/// to report resources that nothing uses with [ResourceGraph::diagnostics],
+/// or to leave them out of a build. Only covers the base module.
+pub fn resource_graph(package: &Package) -> Result<ResourceGraph> {
+    ResourceGraph::new(
+        &package.android_manifest,
+        &collect_resources(&package.resources)?
+    )
+}
+
+/// Compiles a single XML file, eg. `res/xml/watch_face_info.xml`, to the binary
+/// XML format used in APKs, without building the rest of the package.
+///
+/// References such as `@drawable/preview` are resolved against `resources`,
+/// which should be all of the package's resources in the same order as
+/// [Package::resources], so that the IDs match a full build.
+pub fn compile_xml_to_axml(xml: &[u8], resources: &[FileResource]) -> Result<Vec<u8>> {
+    let resources = collect_resources(resources)?;
+    let (xml_res_chunk, _) =
+        xml_to_res_chunk_with_options(&mut Cursor::new(xml), &resources, &XmlOptions::default())?;
+    Ok(xml_res_chunk.to_bytes()?)
+}
+
+/// Same as [compile_xml_to_axml], but compiles to the ProtoXML format used in
+/// AABs.
+#[cfg(feature = "aab")]
+pub fn compile_xml_to_protoxml(xml: &[u8], resources: &[FileResource]) -> Result<Vec<u8>> {
+    pack_aab::compile_proto_xml(xml, &collect_resources(resources)?)
+}
+
+/// Signs an existing, unsigned APK with APK Signature Scheme v2 and v3.
+pub fn sign_apk(apk: &[u8], keys: &Keys) -> Result<Vec<u8>> {
+    sign_apk_with_config(apk, keys, &SigningConfig::default())
+}
+
+/// Same as [sign_apk], but the signatures are made with `config`.
+pub fn sign_apk_with_config(apk: &[u8], keys: &Keys, config: &SigningConfig) -> Result<Vec<u8>> {
+    if has_signing_block(apk) {
+        return Err(PackError::PackageAlreadySigned);
+    }
+    pack_sign::sign_apk_buffer_with_config(&mut apk.to_vec(), keys, config)
+}
+
+/// Signs an existing AAB the same way as [compile_and_sign_aab]. Any previous
+/// signatures are replaced.
+#[cfg(feature = "aab")]
+pub fn sign_aab(aab: &[u8], keys: &Keys) -> Result<Vec<u8>> {
+    let mut aab_files: Vec<pack_zip::File> = pack_zip::unzip(aab)?
+        .into_iter()
+        .filter(|file| !file.path.starts_with("META-INF/"))
+        .collect();
+    add_v1_signature_files(&mut aab_files, keys)?;
+
+    let mut aab_buf = vec![];
+    pack_zip::zip_apk(&aab_files, Cursor::new(&mut aab_buf))?;
     pack_sign::sign_apk_buffer(&mut aab_buf, keys)
 }
 
+/// Returns whether an APK or AAB has an APK Signing Block, which holds its
+/// Scheme v2 and v3 signatures.
+pub fn has_signing_block(package: &[u8]) -> bool {
+    const SIGNING_BLOCK_MAGIC: &[u8] = b"APK Sig Block 42";
+    package
+        .windows(SIGNING_BLOCK_MAGIC.len())
+        .any(|window| window == SIGNING_BLOCK_MAGIC)
+}
+
+/// Checks an existing APK or AAB's signatures, alignment and zip integrity.
+/// See [VerificationReport::is_valid] for whether it passed.
+pub fn verify_package(package: &[u8]) -> Result<VerificationReport> {
+    pack_sign::verification::verify_package(package)
+}
+
+/// The manifest and resource table of an existing APK.
+#[derive(Debug, Clone)]
+pub struct DecodedApk {
+    pub manifest: DecodedElement,
+    /// Empty if the APK has no `resources.arsc`.
+    pub resources: DecodedTable
+}
+
+/// Decodes an existing APK's binary `AndroidManifest.xml` and
+/// `resources.arsc`, eg. to inspect what it declares.
+pub fn decode_apk(apk: &[u8]) -> Result<DecodedApk> {
+    let files = pack_zip::unzip(apk)?;
+    let find = |path: &str| files.iter().find(|file| file.path == path);
+    let manifest = find("AndroidManifest.xml").ok_or(PackError::BinaryResourceInvalid(
+        "AndroidManifest.xml is missing, so this isn't an APK.".into()
+    ))?;
+    Ok(DecodedApk {
+        manifest: decode_xml(&manifest.data)?,
+        resources: match find("resources.arsc") {
+            Some(table) => decode_resource_table(&table.data)?,
+            None => DecodedTable::default()
+        }
+    })
+}
+
+/// Checks that the package is a watch face that will work on a watch:
+///
+///  - Every Watch Face Format file, ie. XML in res/raw/ or res/xml/ with a
+///    `<WatchFace>` root element, matches the schema for the format version
+///    declared in the manifest
+///  - The manifest declares the watch feature and has no code
+///  - The preview in res/xml/watch_face_info.xml is a drawable in the package
+///
+/// Returns every problem found, or nothing if the watch face is valid. Only
+/// available with the `wff` feature.
+#[cfg(feature = "wff")]
+pub fn validate_watch_face(package: &Package) -> Result<Vec<Diagnostic>> {
+    validate_watch_face_with_options(package, &WatchFaceValidationOptions::default())
+}
+
+/// Optional checks for [validate_watch_face_with_options].
+#[cfg(feature = "wff")]
+#[derive(Debug, Clone, Default)]
+pub struct WatchFaceValidationOptions {
+    /// Also parse expressions, like `[HOUR_0_23] * 30`, and check that the
+    /// data sources and functions they use exist in the declared format
+    /// version.
+    pub check_expressions: bool
+}
+
+/// Same as [validate_watch_face], but with extra [WatchFaceValidationOptions].
+#[cfg(feature = "wff")]
+pub fn validate_watch_face_with_options(
+    package: &Package,
+    options: &WatchFaceValidationOptions
+) -> Result<Vec<Diagnostic>> {
+    // An unsupported version is reported along with the rest of the schema
+    let format_version = pack_wff::declared_format_version(&package.android_manifest).ok();
+    let mut diagnostics = pack_wff::validate_manifest(&package.android_manifest);
+    let mut drawables = vec![];
+    let mut watch_face_info = None;
+    for res in &package.resources {
+        let (res_type, _) = ResourceConfig::parse_directory(&res.subdirectory)?;
+        if res_type == "drawable" {
+            drawables.push((res.get_basename()?, &res.contents[..]));
+        } else if res.get_path() == pack_wff::WATCH_FACE_INFO_PATH {
+            watch_face_info = Some(res);
+        }
+        if (res_type == "raw" || res_type == "xml") && pack_wff::is_watch_face(&res.contents) {
+            diagnostics.extend(pack_wff::validate_watch_face(
+                &package.android_manifest,
+                &res.contents,
+                &res.get_path()
+            ));
+            if let (true, Some(format_version)) = (options.check_expressions, format_version) {
+                diagnostics.extend(pack_wff::validate_expressions(
+                    &res.contents,
+                    &res.get_path(),
+                    format_version
+                ));
+            }
+        }
+    }
+
+    let drawables: Vec<(&str, &[u8])> = drawables
+        .iter()
+        .map(|(name, contents)| (&name[..], *contents))
+        .collect();
+    match watch_face_info {
+        Some(info) => diagnostics.extend(pack_wff::validate_watch_face_info(
+            &info.contents,
+            pack_wff::WATCH_FACE_INFO_PATH,
+            &drawables
+        )),
+        None => diagnostics.push(Diagnostic::warning(
+            ErrorCode::WatchFaceInfoMissing,
+            Some(pack_wff::WATCH_FACE_INFO_PATH),
+            "Missing, so the watch face picker won't have a preview to show."
+        ))
+    }
+    Ok(diagnostics)
+}
+
+// Parses values files into string and alias resources, and splits qualifiers like
+// `-xxhdpi` off subdirectory names into each resource's config.
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+fn collect_resources(files: &[FileResource]) -> Result<Vec<Resource>> {
+    let mut resources = vec![];
+    for res in files {
+        let (res_type, dir_config) = ResourceConfig::parse_directory(&res.subdirectory)?;
+        // Qualifiers can either be part of the subdirectory or set on the config
+        let config = if dir_config.is_default() {
+            res.config.clone()
+        } else {
+            dir_config
+        };
+        // Declarations don't define resources, see collect_declarations()
+        if is_declarations_file(res_type, &res.name) {
+            continue;
+        }
+        // Values files like strings.xml define resources rather than being one
+        if res_type == "values" && res.get_extension() == "xml" {
+            let mut values_cur = Cursor::new(&res.contents);
+            for mut value in parse_values_xml(&mut values_cur) {
+                value.set_config(config.clone());
+                validate_resource_name(&value)?;
+                resources.push(value);
+            }
+        } else {
+            let file = Resource::File(FileResource {
+                subdirectory: res_type.into(),
+                config,
+                ..res.clone()
+            });
+            validate_resource_name(&file)?;
+            resources.push(file);
+        }
+    }
+    // Sort resources the way their IDs are assigned, so that all sub-types are
+    // grouped and the package doesn't depend on the order files were read in
+    sort_resources(&mut resources);
+    Ok(resources)
+}
+
+// Only bundles record visibility and overlayables, so APKs ignore these files
+fn is_declarations_file(res_type: &str, name: &str) -> bool {
+    res_type == "values" && (name == "public.xml" || name == "overlayable.xml")
+}
+
+#[cfg(feature = "aab")]
+fn collect_declarations(files: &[FileResource]) -> Result<ResourceDeclarations> {
+    let mut declarations = ResourceDeclarations::default();
+    for res in files {
+        let (res_type, config) = ResourceConfig::parse_directory(&res.subdirectory)?;
+        if is_declarations_file(res_type, &res.name) {
+            if !config.is_default() || !res.config.is_default() {
+                return Err(PackError::ResourceDeclarationInvalid(format!(
+                    "{} can only be in res/values/, without qualifiers.",
+                    res.name
+                )));
+            }
+            declarations.extend(
+                parse_public_xml(&mut Cursor::new(&res.contents))
+                    .map_err(|err| err.in_file(res.get_path()))?
+            );
+        }
+    }
+    Ok(declarations)
+}
+
+#[cfg_attr(feature = "tracing", tracing::instrument(skip_all))]
 fn parse_manifest(
     manifest: &[u8],
-    resources: &[Resource]
-) -> Result<(ResChunk, String, Option<String>)> {
+    resources: &[Resource],
+    options: &BuildOptions
+) -> Result<(ResChunk, ManifestInfo)> {
     let manifest_cursor = Cursor::new(manifest);
     let mut reader = BufReader::new(manifest_cursor);
-    let (manifest_res_chunk, manifest_info) = xml_to_res_chunk(&mut reader, resources)?;
-    Ok((
-        manifest_res_chunk,
-        manifest_info
-            .package_name
-            .ok_or(PackError::ManifestDoesNotHavePackageName)?,
-        manifest_info.label
-    ))
+    xml_to_res_chunk_with_options(&mut reader, resources, &xml_options(options))
+        .map_err(|err| err.in_file("AndroidManifest.xml"))
+}
+
+fn apply_manifest_merge_markers(manifest: &[u8]) -> Result<Vec<u8>> {
+    apply_merge_markers(manifest).map_err(|err| err.in_file("AndroidManifest.xml"))
+}
+
+// Checks that the manifest's locale config lists every locale there are
+// resources for. If it doesn't have one, and the options say what language the
+// unqualified resources are in, one is written.
+fn apply_locale_config(
+    manifest: Vec<u8>,
+    resources: &mut Vec<Resource>,
+    options: &BuildOptions
+) -> Result<Vec<u8>> {
+    let mut locales = resource_locales(resources);
+    if let Some(default_locale) = &options.default_locale {
+        locales.push(default_locale.clone());
+    }
+    if let Some(reference) = manifest_locale_config(&manifest)? {
+        check_locale_config(&reference, resources, &locales)?;
+        return Ok(manifest);
+    }
+    // With only the default locale, there's nothing to choose between
+    let Some(default_locale) = &options.default_locale else {
+        return Ok(manifest);
+    };
+    if locales.len() < 2 {
+        return Ok(manifest);
+    }
+    let (basename, _) = split_resource_file_name(LOCALE_CONFIG_FILE_NAME);
+    let reference = format!("@xml/{basename}");
+    // A locales_config.xml that isn't referenced yet is used as it is
+    if find_xml_file(resources, basename).is_some() {
+        check_locale_config(&reference, resources, &locales)?;
+    } else {
+        let contents = locale_config_xml(default_locale, &resource_locales(resources))?;
+        resources.push(Resource::File(FileResource::new(
+            "xml".into(),
+            LOCALE_CONFIG_FILE_NAME.into(),
+            contents
+        )));
+        sort_resources(resources);
+    }
+    set_manifest_locale_config(&manifest, &reference)
+}
+
+fn check_locale_config(reference: &str, resources: &[Resource], locales: &[String]) -> Result<()> {
+    // A reference to anything else fails when the manifest is compiled
+    let Some(file) = reference
+        .strip_prefix("@xml/")
+        .and_then(|basename| find_xml_file(resources, basename))
+    else {
+        return Ok(());
+    };
+    let listed = parse_locale_config(&file.contents).map_err(|err| err.in_file(file.get_path()))?;
+    let missing = missing_locales(&listed, locales);
+    if !missing.is_empty() {
+        return Err(PackError::LocaleConfigInvalid(format!(
+            "{} doesn't list {}, which the package has resources for, so people can't choose them in the system settings.",
+            file.get_path(),
+            missing.join(", ")
+        )));
+    }
+    Ok(())
+}
+
+// An uncompiled file in res/xml/ without qualifiers, eg. locales_config.xml
+// for `locales_config`
+fn find_xml_file<'a>(resources: &'a [Resource], basename: &str) -> Option<&'a FileResource> {
+    resources.iter().find_map(|res| match res {
+        Resource::File(file)
+            if file.subdirectory == "xml"
+                && file.config.is_default()
+                && !file.precompiled
+                && split_resource_file_name(&file.name).0 == basename =>
+        {
+            Some(file)
+        }
+        _ => None
+    })
+}
+
+fn xml_options(options: &BuildOptions) -> XmlOptions {
+    XmlOptions {
+        raw_values: if options.compatibility_mode {
+            RawValues::Aapt2
+        } else {
+            options.raw_values
+        },
+        keep_comments: options.keep_xml_comments,
+        tools_namespace: options.tools_namespace,
+        inline_string_references: options.inline_string_references
+    }
+}
+
+fn manifest_package_name(manifest_info: &ManifestInfo) -> Result<String> {
+    manifest_info
+        .package_name
+        .clone()
+        .ok_or(PackError::ManifestDoesNotHavePackageName)
+}
+
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        name = "compile_resource",
+        level = "debug",
+        skip_all,
+        fields(path = %file.get_path())
+    )
+)]
+fn file_bytes_for_apk(
+    file: &FileResource,
+    resources: &[Resource],
+    options: &BuildOptions
+) -> Result<Vec<u8>> {
+    let xml_options = xml_options(options);
+    let bytes = match &options.cache {
+        // Only XML files are compiled, other files would just be copied into the cache
+        Some(cache) if file.needs_compiling() => cache.get_or_compile(
+            BuildCache::key(
+                &format!(
+                    "apk-xml-{:?}{}-{:?}{}",
+                    xml_options.raw_values,
+                    if options.keep_xml_comments {
+                        "-comments"
+                    } else {
+                        ""
+                    },
+                    options.tools_namespace,
+                    // Inlined strings are part of the output, so they're
+                    // part of the key
+                    if options.inline_string_references {
+                        format!("-inline-{}", StringValues::new(resources).digest())
+                    } else {
+                        String::new()
+                    }
+                ),
+                resources,
+                &file.contents
+            ),
+            || file.as_bytes_for_apk_with_options(resources, &xml_options)
+        ),
+        _ => file.as_bytes_for_apk_with_options(resources, &xml_options)
+    };
+    bytes.map_err(|err| err.in_file(file.get_path()))
+}
+
+// Files in an AAB that PACK generated rather than copied, eg.
+// `base/resources.pb` or `base/res/xml/watchface.xml`
+#[cfg(feature = "aab")]
+fn is_compiled_aab_file(path: &str) -> bool {
+    let Some((_module, path)) = path.split_once('/') else {
+        return path.ends_with(".pb");
+    };
+    path.ends_with(".pb")
+        || path == "manifest/AndroidManifest.xml"
+        || (path.starts_with("res/") && !path.starts_with("res/raw") && path.ends_with(".xml"))
+}
+
+fn check_overlay_target(target: &OverlayTarget, package_name: &str) -> Result<()> {
+    if target.target_package.is_empty() {
+        return Err(PackError::OverlayInvalid(
+            "The target package name is empty.".into()
+        ));
+    }
+    if target.target_package == package_name {
+        return Err(PackError::OverlayInvalid(format!(
+            "An overlay can't target its own package, {package_name}."
+        )));
+    }
+    Ok(())
+}
+
+fn extra_files(options: &BuildOptions) -> impl Iterator<Item = pack_zip::File> + '_ {
+    options
+        .extra_files
+        .iter()
+        .map(|(path, data)| pack_zip::File {
+            path: path.clone(),
+            data: data.clone()
+        })
+}
+
+// How an AAB is zipped. APKs are zipped by ApkBuilder instead.
+#[cfg(feature = "aab")]
+fn zip_options(
+    options: &BuildOptions,
+    compression: HashMap<String, Compression>
+) -> pack_zip::ZipOptions {
+    let defaults = pack_zip::ZipOptions::default();
+    pack_zip::ZipOptions {
+        compression,
+        alignment: options.zip_alignment.unwrap_or(defaults.alignment),
+        native_library_alignment: options
+            .native_library_alignment
+            .unwrap_or(defaults.native_library_alignment),
+        ..defaults
+    }
+}
+
+fn zip_files(files: &[pack_zip::File]) -> Result<Vec<u8>> {
+    let mut zip_buf = vec![];
+    let zip_buf_cursor = Cursor::new(&mut zip_buf);
+    pack_zip::zip_apk(files, zip_buf_cursor)?;
+    Ok(zip_buf)
 }
 
 fn res_to_apk_file(path: String, chunk: &ResChunk) -> Result<pack_zip::File> {
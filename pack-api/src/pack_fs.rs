@@ -0,0 +1,286 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Where projects are read from. pack-cli reads from disk, but the same
+// directory-walking rules apply to projects held in memory, eg. a template
+// with a user's files laid over it, or files uploaded to a server.
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    ffi::OsString,
+    fs, io,
+    path::{Component, Path, PathBuf}
+};
+
+use pack_common::{FileContents, PackError, Result};
+
+// Windows can't open paths longer than this unless they're in the extended
+// `\\?\` form, which deep res/ trees can need
+#[cfg(windows)]
+const MAX_PATH: usize = 260;
+
+// Mapping a file has a cost of its own, so it's only worth it for large ones
+#[cfg(feature = "mmap")]
+const MMAP_MIN_LEN: u64 = 1 << 20;
+
+/// What [PackFs::metadata] knows about a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FsMetadata {
+    pub is_dir: bool,
+    /// The size of a file in bytes, or 0 for directories.
+    pub len: u64
+}
+
+/// A file system that projects can be read from, eg. with
+/// [Package::from_fs](crate::Package::from_fs). [StdFs] reads from disk, and
+/// [MemoryFs] from files held in memory. With the `mmap` feature, `MappedFs`
+/// reads from disk too, but maps large files instead.
+pub trait PackFs {
+    /// Lists the names of the files and directories directly inside `path`,
+    /// in any order.
+    fn read_dir(&self, path: &Path) -> Result<Vec<String>>;
+
+    fn read_file(&self, path: &Path) -> Result<Vec<u8>>;
+
+    /// Reads a file for a [FileResource](crate::FileResource). By default
+    /// it's the same as [PackFs::read_file], but file systems can share or
+    /// map files rather than reading them into memory.
+    fn read_file_contents(&self, path: &Path) -> Result<FileContents> {
+        Ok(self.read_file(path)?.into())
+    }
+
+    /// Fails if there's nothing at `path`.
+    fn metadata(&self, path: &Path) -> Result<FsMetadata>;
+}
+
+/// Reads from disk with [std::fs].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdFs;
+
+impl PackFs for StdFs {
+    fn read_dir(&self, path: &Path) -> Result<Vec<String>> {
+        let mut names = vec![];
+        for entry in fs::read_dir(long_path(path))? {
+            names.push(utf8_file_name(path, entry?.file_name())?);
+        }
+        Ok(names)
+    }
+
+    fn read_file(&self, path: &Path) -> Result<Vec<u8>> {
+        Ok(fs::read(long_path(path))?)
+    }
+
+    fn metadata(&self, path: &Path) -> Result<FsMetadata> {
+        let metadata = fs::metadata(long_path(path))?;
+        Ok(FsMetadata {
+            is_dir: metadata.is_dir(),
+            len: if metadata.is_dir() { 0 } else { metadata.len() }
+        })
+    }
+}
+
+/// Reads from disk like [StdFs], but memory-maps files of 1 MiB or more, so
+/// they're only paged in while they're being compressed or hashed.
+#[cfg(feature = "mmap")]
+#[derive(Debug, Clone, Copy)]
+pub struct MappedFs {
+    // Only MappedFs::new can make one
+    _private: ()
+}
+
+#[cfg(feature = "mmap")]
+impl MappedFs {
+    /// # Safety
+    ///
+    /// Files read through it mustn't be written to, truncated or replaced in
+    /// place while any [FileContents] read from them are still around, ie.
+    /// until the [Package](crate::Package) and anything built from it without
+    /// copying are dropped. Otherwise reading the contents is undefined
+    /// behaviour, and on most platforms a file that was made shorter crashes
+    /// the process with SIGBUS. Use [StdFs] for projects that may be edited
+    /// during a build, eg. when rebuilding on every change.
+    pub unsafe fn new() -> MappedFs {
+        MappedFs { _private: () }
+    }
+}
+
+#[cfg(feature = "mmap")]
+impl PackFs for MappedFs {
+    fn read_dir(&self, path: &Path) -> Result<Vec<String>> {
+        StdFs.read_dir(path)
+    }
+
+    fn read_file(&self, path: &Path) -> Result<Vec<u8>> {
+        StdFs.read_file(path)
+    }
+
+    fn read_file_contents(&self, path: &Path) -> Result<FileContents> {
+        let file = fs::File::open(long_path(path))?;
+        if file.metadata()?.len() < MMAP_MIN_LEN {
+            return Ok(self.read_file(path)?.into());
+        }
+        // Safety: whoever made this MappedFs promised not to change the file
+        // while the map is alive, and the map is only ever read
+        let map = unsafe { memmap2::Mmap::map(&file)? };
+        Ok(FileContents::new(map))
+    }
+
+    fn metadata(&self, path: &Path) -> Result<FsMetadata> {
+        StdFs.metadata(path)
+    }
+}
+
+/// Files held in memory, by path. Directories aren't stored, but exist
+/// wherever there's a file inside them, and the root directory is `""` or
+/// `"."`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryFs {
+    files: BTreeMap<PathBuf, Vec<u8>>
+}
+
+impl MemoryFs {
+    pub fn new() -> MemoryFs {
+        MemoryFs::default()
+    }
+
+    /// Adds a file, or replaces it if there's one at `path` already. Paths
+    /// are relative, eg. `res/drawable/preview.png`.
+    pub fn insert(&mut self, path: impl AsRef<Path>, contents: Vec<u8>) {
+        self.files.insert(normalize(path.as_ref()), contents);
+    }
+
+    /// Removes a file, returning its contents if there was one.
+    pub fn remove(&mut self, path: impl AsRef<Path>) -> Option<Vec<u8>> {
+        self.files.remove(&normalize(path.as_ref()))
+    }
+
+    /// Every file's path and contents, ordered by path.
+    pub fn files(&self) -> impl Iterator<Item = (&Path, &[u8])> {
+        self.files
+            .iter()
+            .map(|(path, contents)| (path.as_path(), contents.as_slice()))
+    }
+
+    // The paths of files anywhere under a directory
+    fn paths_under<'a>(&'a self, dir: &'a Path) -> impl Iterator<Item = &'a Path> {
+        self.files
+            .keys()
+            .filter(move |path| path.starts_with(dir) && path.as_path() != dir)
+            .map(|path| path.as_path())
+    }
+}
+
+impl PackFs for MemoryFs {
+    fn read_dir(&self, path: &Path) -> Result<Vec<String>> {
+        let dir = normalize(path);
+        if self.metadata(&dir)?.is_dir {
+            let mut names: Vec<String> = self
+                .paths_under(&dir)
+                .filter_map(|path| path.strip_prefix(&dir).ok()?.components().next())
+                .map(|name| utf8_file_name(&dir, name.as_os_str().to_owned()))
+                .collect::<Result<Vec<String>>>()?;
+            names.dedup();
+            Ok(names)
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::NotADirectory,
+                format!("{} is a file, not a directory", path.display())
+            )
+            .into())
+        }
+    }
+
+    fn read_file(&self, path: &Path) -> Result<Vec<u8>> {
+        if let Some(contents) = self.files.get(&normalize(path)) {
+            return Ok(contents.clone());
+        }
+        // Fails if there's nothing there either
+        self.metadata(path)?;
+        Err(io::Error::new(
+            io::ErrorKind::IsADirectory,
+            format!("{} is a directory, not a file", path.display())
+        )
+        .into())
+    }
+
+    fn metadata(&self, path: &Path) -> Result<FsMetadata> {
+        let path = normalize(path);
+        if let Some(contents) = self.files.get(&path) {
+            return Ok(FsMetadata {
+                is_dir: false,
+                len: contents.len() as u64
+            });
+        }
+        if path.as_os_str().is_empty() || self.paths_under(&path).next().is_some() {
+            return Ok(FsMetadata {
+                is_dir: true,
+                len: 0
+            });
+        }
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("There's no file or directory at {}", path.display())
+        )
+        .into())
+    }
+}
+
+// Drops `.` and leading `/`s, so that eg. `./res/raw` and `res/raw/` are the
+// same path
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| matches!(component, Component::Normal(_) | Component::ParentDir))
+        .collect()
+}
+
+// Names that aren't UTF-8 can't be referenced or stored in a package, so
+// they're an error rather than being mangled into a name that can't be used
+fn utf8_file_name(dir: &Path, name: OsString) -> Result<String> {
+    name.into_string().map_err(|name| {
+        PackError::ResourceFileNameInvalid(format!(
+            "{} doesn't have a valid UTF-8 name, so it can't be packaged. Rename it.",
+            dir.join(name).display()
+        ))
+    })
+}
+
+// Turns a long path into the extended form on Windows, eg.
+// `\\?\C:\watchface\res\...` or `\\?\UNC\server\share\...`
+#[cfg(windows)]
+fn long_path(path: &Path) -> Cow<'_, Path> {
+    if path.as_os_str().len() < MAX_PATH {
+        return Cow::Borrowed(path);
+    }
+    // Extended paths aren't normalised by Windows, so they have to be
+    // absolute, with no `.` or `..`, and only use backslashes
+    let Some(absolute) = std::path::absolute(path)
+        .ok()
+        .and_then(|absolute| absolute.to_str().map(String::from))
+    else {
+        return Cow::Borrowed(path);
+    };
+    let long = if absolute.starts_with(r"\\?\") {
+        absolute
+    } else if let Some(unc) = absolute.strip_prefix(r"\\") {
+        format!(r"\\?\UNC\{unc}")
+    } else {
+        format!(r"\\?\{absolute}")
+    };
+    Cow::Owned(PathBuf::from(long))
+}
+
+#[cfg(not(windows))]
+fn long_path(path: &Path) -> Cow<'_, Path> {
+    Cow::Borrowed(path)
+}
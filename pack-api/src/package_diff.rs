@@ -0,0 +1,356 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Compares two builds of a package, eg. the last release and the next one out
+// of a generator pipeline, so that a reviewer can see what actually changed
+// without unzipping both. Manifests and resource tables are decoded and
+// compared by name rather than by resource ID, since adding one resource
+// shifts the IDs of all the others.
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    build_manifest::{json_string, sha256_hex},
+    Result
+};
+use pack_asset_compiler::{
+    decoder::{decode_resource_table, decode_xml, BinaryValue, DecodedElement, DecodedTable},
+    resource_external_types::AttributeDataType
+};
+use pack_common::PackError;
+
+const ANDROID_NAMESPACE: &str = "http://schemas.android.com/apk/res/android";
+
+/// The differences between two packages, from [diff_packages]. Every list is
+/// sorted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageDiff {
+    /// Files only in the new package.
+    pub added_entries: Vec<String>,
+    /// Files only in the old package.
+    pub removed_entries: Vec<String>,
+    /// Files in both packages, with different contents.
+    pub changed_entries: Vec<String>,
+    /// Resources only in the new package, eg. `@drawable/preview`.
+    pub added_resources: Vec<String>,
+    /// Resources only in the old package.
+    pub removed_resources: Vec<String>,
+    /// Resources in both packages with a different value in any config, or
+    /// whose file has different contents.
+    pub changed_resources: Vec<String>,
+    pub manifest_changes: Vec<ManifestChange>
+}
+
+/// A manifest attribute that was added, removed or changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestChange {
+    /// The path to the element, eg. `manifest/application/meta-data[name]`.
+    /// Elements are told apart by their `android:name`, or by their position
+    /// among elements with the same tag if they don't have one.
+    pub element: String,
+    /// eg. `android:versionCode`
+    pub attribute: String,
+    /// The value in the old package, or `None` if it was added.
+    pub old: Option<String>,
+    /// The value in the new package, or `None` if it was removed.
+    pub new: Option<String>
+}
+
+impl PackageDiff {
+    /// Whether the packages have exactly the same files.
+    pub fn is_empty(&self) -> bool {
+        self.added_entries.is_empty()
+            && self.removed_entries.is_empty()
+            && self.changed_entries.is_empty()
+    }
+
+    /// Formats the differences as pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        let strings = |values: &[String]| -> String {
+            let values: Vec<String> = values.iter().map(|value| json_string(value)).collect();
+            values.join(", ")
+        };
+        let optional = |value: &Option<String>| match value {
+            Some(value) => json_string(value),
+            None => "null".into()
+        };
+        let manifest_changes: Vec<String> = self
+            .manifest_changes
+            .iter()
+            .map(|change| {
+                format!(
+                    "    {{ \"element\": {}, \"attribute\": {}, \"old\": {}, \"new\": {} }}",
+                    json_string(&change.element),
+                    json_string(&change.attribute),
+                    optional(&change.old),
+                    optional(&change.new)
+                )
+            })
+            .collect();
+        format!(
+            "{{\n  \"added_entries\": [{}],\n  \"removed_entries\": [{}],\n  \"changed_entries\": [{}],\n  \"added_resources\": [{}],\n  \"removed_resources\": [{}],\n  \"changed_resources\": [{}],\n  \"manifest_changes\": [\n{}\n  ]\n}}\n",
+            strings(&self.added_entries),
+            strings(&self.removed_entries),
+            strings(&self.changed_entries),
+            strings(&self.added_resources),
+            strings(&self.removed_resources),
+            strings(&self.changed_resources),
+            manifest_changes.join(",\n")
+        )
+    }
+}
+
+/// Compares two APKs or two AABs. Files are compared by their SHA-256 hashes,
+/// and the manifests and resource tables are decoded so that changes can be
+/// reported by resource name and attribute. For bundles, the manifest and
+/// resources of the base module are compared, and other modules only show up
+/// as changed files.
+pub fn diff_packages(old: &[u8], new: &[u8]) -> Result<PackageDiff> {
+    let old = PackageContents::read(old)?;
+    let new = PackageContents::read(new)?;
+
+    let (added_entries, removed_entries, changed_entries) = compare(&old.entries, &new.entries);
+    let (added_resources, removed_resources, changed_resources) =
+        compare(&old.resources(), &new.resources());
+
+    let old_attributes = old.manifest_attributes();
+    let new_attributes = new.manifest_attributes();
+    let keys: BTreeSet<_> = old_attributes.keys().chain(new_attributes.keys()).collect();
+    let mut manifest_changes = vec![];
+    for key in keys {
+        let (old, new) = (old_attributes.get(key), new_attributes.get(key));
+        if old != new {
+            let (element, attribute) = key.clone();
+            manifest_changes.push(ManifestChange {
+                element,
+                attribute,
+                old: old.cloned(),
+                new: new.cloned()
+            });
+        }
+    }
+
+    Ok(PackageDiff {
+        added_entries,
+        removed_entries,
+        changed_entries,
+        added_resources,
+        removed_resources,
+        changed_resources,
+        manifest_changes
+    })
+}
+
+// Returns the keys that were added, removed and changed
+fn compare<K: Ord + Clone, V: PartialEq>(
+    old: &BTreeMap<K, V>,
+    new: &BTreeMap<K, V>
+) -> (Vec<K>, Vec<K>, Vec<K>) {
+    let added = new
+        .keys()
+        .filter(|key| !old.contains_key(key))
+        .cloned()
+        .collect();
+    let mut removed = vec![];
+    let mut changed = vec![];
+    for (key, value) in old {
+        match new.get(key) {
+            None => removed.push(key.clone()),
+            Some(new_value) if new_value != value => changed.push(key.clone()),
+            Some(_) => {}
+        }
+    }
+    (added, removed, changed)
+}
+
+struct PackageContents {
+    // SHA-256 of every file, by path
+    entries: BTreeMap<String, String>,
+    manifest: DecodedElement,
+    table: DecodedTable,
+    // Where the resource table's file paths are relative to, eg. `base/`
+    module_directory: &'static str
+}
+
+impl PackageContents {
+    fn read(package: &[u8]) -> Result<PackageContents> {
+        let files = pack_zip::unzip(package)?;
+        let find = |path: &str| files.iter().find(|file| file.path == path);
+        let entries = files
+            .iter()
+            .map(|file| (file.path.clone(), sha256_hex(&file.data)))
+            .collect();
+
+        if let Some(manifest) = find("AndroidManifest.xml") {
+            let table = match find("resources.arsc") {
+                Some(table) => decode_resource_table(&table.data)?,
+                None => DecodedTable::default()
+            };
+            return Ok(PackageContents {
+                entries,
+                manifest: decode_xml(&manifest.data)?,
+                table,
+                module_directory: ""
+            });
+        }
+        let Some(manifest) = find("base/manifest/AndroidManifest.xml") else {
+            return Err(PackError::BinaryResourceInvalid(
+                "The package has neither an AndroidManifest.xml nor a base/manifest/AndroidManifest.xml, so it isn't an APK or AAB".into()
+            ));
+        };
+        Self::read_bundle(entries, &manifest.data, find("base/resources.pb"))
+    }
+
+    #[cfg(feature = "aab")]
+    fn read_bundle(
+        entries: BTreeMap<String, String>,
+        manifest: &[u8],
+        table: Option<&pack_zip::File>
+    ) -> Result<PackageContents> {
+        let table = match table {
+            Some(table) => pack_aab::decode_proto_resource_table(&table.data)?,
+            None => DecodedTable::default()
+        };
+        Ok(PackageContents {
+            entries,
+            manifest: pack_aab::decode_proto_xml(manifest)?,
+            table,
+            module_directory: "base/"
+        })
+    }
+
+    #[cfg(not(feature = "aab"))]
+    fn read_bundle(
+        _entries: BTreeMap<String, String>,
+        _manifest: &[u8],
+        _table: Option<&pack_zip::File>
+    ) -> Result<PackageContents> {
+        Err(PackError::BundleInvalid(
+            "Comparing App Bundles needs pack-api's aab feature".into()
+        ))
+    }
+
+    // Every resource's value in each config, by name
+    fn resources(&self) -> BTreeMap<String, Vec<String>> {
+        let mut resources = BTreeMap::new();
+        for package in &self.table.packages {
+            for res_type in &package.types {
+                for entry in &res_type.entries {
+                    let values = entry
+                        .values
+                        .iter()
+                        .map(|(config, value)| {
+                            format!(
+                                "({}) {}",
+                                config.qualifiers().join("-"),
+                                self.format_resource_value(value)
+                            )
+                        })
+                        .collect();
+                    resources.insert(format!("@{}/{}", res_type.name, entry.name), values);
+                }
+            }
+        }
+        resources
+    }
+
+    // Files are stored as their paths, so their hashes are included to catch
+    // eg. a redrawn preview
+    fn format_resource_value(&self, value: &BinaryValue) -> String {
+        if let BinaryValue::String(path) = value {
+            let entry_path = format!("{}{path}", self.module_directory);
+            if let Some(hash) = self.entries.get(&entry_path) {
+                return format!("(file) {path} {hash}");
+            }
+        }
+        format_value(value, &self.table)
+    }
+
+    // Every attribute in the manifest, keyed by element path and attribute
+    fn manifest_attributes(&self) -> BTreeMap<(String, String), String> {
+        let mut attributes = BTreeMap::new();
+        self.add_attributes(&self.manifest, self.manifest.name.clone(), &mut attributes);
+        attributes
+    }
+
+    fn add_attributes(
+        &self,
+        element: &DecodedElement,
+        path: String,
+        attributes: &mut BTreeMap<(String, String), String>
+    ) {
+        for attribute in &element.attributes {
+            let name = match attribute.namespace.as_deref() {
+                Some(ANDROID_NAMESPACE) => format!("android:{}", attribute.name),
+                _ => attribute.name.clone()
+            };
+            attributes.insert(
+                (path.clone(), name),
+                format_value(&attribute.value, &self.table)
+            );
+        }
+
+        for (index, child) in element.children.iter().enumerate() {
+            let siblings = element.children_named(&child.name).count();
+            let key = match child.attribute("name") {
+                Some(BinaryValue::String(name)) => Some(name.clone()),
+                Some(name) => Some(format_value(name, &self.table)),
+                None if siblings > 1 => Some(
+                    element.children[..index]
+                        .iter()
+                        .filter(|sibling| sibling.name == child.name)
+                        .count()
+                        .to_string()
+                ),
+                None => None
+            };
+            let child_path = match key {
+                Some(key) => format!("{path}/{}[{key}]", child.name),
+                None => format!("{path}/{}", child.name)
+            };
+            self.add_attributes(child, child_path, attributes);
+        }
+    }
+}
+
+// References are formatted by name, so that they only count as changed if
+// they point somewhere else
+fn format_value(value: &BinaryValue, table: &DecodedTable) -> String {
+    match value {
+        BinaryValue::Null => "@null".into(),
+        BinaryValue::Reference(id) => table.reference_name(*id).unwrap_or(format!("@{id:#010x}")),
+        BinaryValue::AttributeReference(id) => match table.reference_name(*id) {
+            Some(name) => name.replacen('@', "?", 1),
+            None => format!("?{id:#010x}")
+        },
+        BinaryValue::String(string) => string.clone(),
+        BinaryValue::Integer(integer) => integer.to_string(),
+        BinaryValue::Boolean(boolean) => boolean.to_string(),
+        BinaryValue::Float(float) => float.to_string(),
+        BinaryValue::Color(color) => format!("#{color:08x}"),
+        BinaryValue::Other(data_type, data) => match AttributeDataType::from_id(*data_type) {
+            Some(data_type) => format!("({data_type:?}) {data:#010x}"),
+            None => format!("(type {data_type:#04x}) {data:#010x}")
+        },
+        BinaryValue::Bag { items, .. } => {
+            let items: Vec<String> = items
+                .iter()
+                .map(|(key, value)| {
+                    let key = table.reference_name(*key).unwrap_or(format!("{key:#010x}"));
+                    format!("{key}={}", format_value(value, table))
+                })
+                .collect();
+            format!("[{}]", items.join(", "))
+        }
+    }
+}
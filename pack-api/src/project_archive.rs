@@ -0,0 +1,213 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Reads a whole project from a single zip or tar, so that front-ends and
+// servers can take one upload instead of every resource separately. Only the
+// manifest and res/ are read, the same as `pack build` reads from a directory.
+use pack_common::{PackError, Result};
+
+use crate::{FileResource, Package};
+
+const MANIFEST_NAME: &str = "AndroidManifest.xml";
+// Tars are made of 512-byte blocks. Each file has a header block, followed by
+// its contents padded out to a whole block.
+const TAR_BLOCK_SIZE: usize = 512;
+const TAR_CHECKSUM: std::ops::Range<usize> = 148..156;
+
+impl Package {
+    /// Reads a package from a zip or uncompressed tar of a project, which has
+    /// an `AndroidManifest.xml` with a `res/` directory beside it, eg.
+    /// `res/drawable-xxhdpi/preview.png`. They can also be inside a single
+    /// directory, as when zipping a folder. Other files are ignored.
+    pub fn from_project_archive(archive: &[u8]) -> Result<Package> {
+        let files = if archive.starts_with(b"PK") {
+            pack_zip::unzip(archive)?
+        } else {
+            untar(archive)?
+        };
+        let files: Vec<(&str, &[u8])> = files
+            .iter()
+            .map(|file| (file.path.trim_start_matches("./"), &file.data[..]))
+            .collect();
+
+        // The manifest closest to the top is the project's, in case eg. a build
+        // directory with its own manifest was archived too
+        let (manifest_path, android_manifest) = files
+            .iter()
+            .filter(|(path, _)| *path == MANIFEST_NAME || path.ends_with("/AndroidManifest.xml"))
+            .min_by_key(|(path, _)| path.len())
+            .ok_or(invalid(format!(
+                "There's no {MANIFEST_NAME} in the archive."
+            )))?;
+        let res_dir = format!("{}res/", manifest_path.strip_suffix(MANIFEST_NAME).unwrap());
+
+        let mut resources = vec![];
+        for (path, data) in &files {
+            let Some(res_path) = path.strip_prefix(&res_dir) else {
+                continue;
+            };
+            let parts: Vec<&str> = res_path.split('/').collect();
+            // Skip files like .DS_Store, which aren't part of the project
+            if parts.last().is_some_and(|name| name.starts_with('.')) {
+                continue;
+            }
+            match parts[..] {
+                [dir, name] => resources.push(FileResource::new(
+                    dir.into(),
+                    name.into(),
+                    data.to_vec()
+                )),
+                [name] => {
+                    return Err(PackError::ResourceDirectoryInvalid(format!(
+                        "res/{name} needs to be in a resource type directory, eg. res/drawable/{name}."
+                    )))
+                }
+                [dir, name, ..] => {
+                    return Err(PackError::ResourceDirectoryInvalid(format!(
+                        "res/{dir}/{name}/ is a directory, but resource directories can't have subdirectories."
+                    )))
+                }
+                [] => {}
+            }
+        }
+        // Resource IDs depend on the order, so it shouldn't depend on how the
+        // archive was made
+        resources.sort_by(|a, b| (&a.subdirectory, &a.name).cmp(&(&b.subdirectory, &b.name)));
+
+        Ok(Package {
+            android_manifest: android_manifest.to_vec(),
+            resources,
+            modules: vec![]
+        })
+    }
+}
+
+// Reads the regular files in a ustar, GNU or pax tar
+fn untar(archive: &[u8]) -> Result<Vec<pack_zip::File>> {
+    if archive.starts_with(&[0x1F, 0x8B]) {
+        return Err(invalid(
+            "Compressed tars aren't supported, decompress it first.".into()
+        ));
+    }
+
+    if archive.len() < TAR_BLOCK_SIZE {
+        return Err(invalid("Expected a zip or tar archive.".into()));
+    }
+
+    let mut files = vec![];
+    // Paths too long for the header are given in an entry before the file
+    let mut long_path: Option<String> = None;
+    let mut offset = 0;
+    while offset + TAR_BLOCK_SIZE <= archive.len() {
+        let header = &archive[offset..offset + TAR_BLOCK_SIZE];
+        // The archive ends with empty blocks
+        if header.iter().all(|&byte| byte == 0) {
+            break;
+        }
+        if !checksum_matches(header) {
+            return Err(invalid(if offset == 0 {
+                "Expected a zip or tar archive.".into()
+            } else {
+                format!("The tar header at offset {offset} is corrupt.")
+            }));
+        }
+
+        let size = parse_octal(&header[124..136])?;
+        let data_start = offset + TAR_BLOCK_SIZE;
+        let data = archive
+            .get(data_start..data_start + size)
+            .ok_or(invalid("A file runs past the end of the archive.".into()))?;
+        offset = data_start + size.next_multiple_of(TAR_BLOCK_SIZE);
+
+        match header[156] {
+            // Regular files
+            b'0' | b'\0' | b'7' => files.push(pack_zip::File {
+                path: long_path.take().unwrap_or_else(|| header_path(header)),
+                data: data.to_vec()
+            }),
+            // GNU long names
+            b'L' => long_path = Some(nul_terminated(data)),
+            // pax extended headers, which might not set the path
+            b'x' => long_path = pax_path(data).or(long_path),
+            // Directories, links and the like
+            _ => long_path = None
+        }
+    }
+    Ok(files)
+}
+
+// The checksum is the sum of the header's bytes, counting the checksum field
+// itself as spaces
+fn checksum_matches(header: &[u8]) -> bool {
+    let sum: usize = header
+        .iter()
+        .enumerate()
+        .map(|(i, &byte)| {
+            if TAR_CHECKSUM.contains(&i) {
+                b' ' as usize
+            } else {
+                byte as usize
+            }
+        })
+        .sum();
+    parse_octal(&header[TAR_CHECKSUM]).is_ok_and(|checksum| checksum == sum)
+}
+
+// ustar splits long paths into a prefix and a name
+fn header_path(header: &[u8]) -> String {
+    let name = nul_terminated(&header[0..100]);
+    let prefix = if &header[257..262] == b"ustar" {
+        nul_terminated(&header[345..500])
+    } else {
+        String::new()
+    };
+    if prefix.is_empty() {
+        name
+    } else {
+        format!("{prefix}/{name}")
+    }
+}
+
+// pax headers are records like "30 path=res/drawable/preview.png\n", each
+// starting with its own length
+fn pax_path(data: &[u8]) -> Option<String> {
+    let mut rest = std::str::from_utf8(data).ok()?;
+    while let Some((length, _)) = rest.split_once(' ') {
+        let record = rest.get(..length.parse().ok()?)?;
+        let (_, field) = record.split_once(' ')?;
+        if let Some(path) = field.strip_prefix("path=") {
+            return Some(path.trim_end_matches('\n').into());
+        }
+        rest = &rest[record.len()..];
+    }
+    None
+}
+
+fn parse_octal(field: &[u8]) -> Result<usize> {
+    let digits = nul_terminated(field);
+    usize::from_str_radix(digits.trim(), 8)
+        .map_err(|_| invalid(format!("\"{digits}\" isn't a valid tar number.")))
+}
+
+fn nul_terminated(bytes: &[u8]) -> String {
+    let end = bytes
+        .iter()
+        .position(|&byte| byte == 0)
+        .unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+fn invalid(reason: String) -> PackError {
+    PackError::ProjectArchiveInvalid(reason)
+}
@@ -0,0 +1,195 @@
+// Copyright 2024 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Reads a watch face's res/ directory, from disk or any other PackFs. Android
+// only allows one level of subdirectories, named after a resource type and
+// its qualifiers, eg. res/drawable-xxhdpi/preview.png. Anything else is an
+// error rather than being quietly left out of the package.
+//
+// Files that shouldn't be packaged, like design sources, can be excluded
+// with globs from a .packignore file, or eg. pack-cli's --exclude.
+use std::{collections::HashMap, path::Path};
+
+use pack_asset_compiler::{
+    names::validate_file_resource_name, resource_internal_types::split_resource_file_name
+};
+use pack_common::{glob_matches, Compression, PackError, Result};
+
+use crate::{FileResource, PackFs, Package, ResourceConfig};
+
+/// Lists globs of files to leave out, one per line, in the project directory.
+pub const IGNORE_FILE_NAME: &str = ".packignore";
+
+// Resource types that can have a res/ subdirectory
+const RES_TYPES: &[&str] = &[
+    "anim",
+    "animator",
+    "color",
+    "drawable",
+    "font",
+    "interpolator",
+    "layout",
+    "menu",
+    "mipmap",
+    "navigation",
+    "raw",
+    "transition",
+    "values",
+    "xml"
+];
+
+impl Package {
+    /// Reads a project from `project_dir`, which has an `AndroidManifest.xml`
+    /// and a `res/` directory, the same way as `pack build`. Files matching
+    /// globs in a `.packignore` are left out.
+    pub fn from_fs(fs: &impl PackFs, project_dir: &Path) -> Result<Package> {
+        let exclude_globs = read_ignore_file(fs, project_dir)?;
+        Ok(Package {
+            android_manifest: fs.read_file(&project_dir.join("AndroidManifest.xml"))?,
+            resources: read_res_dir(fs, &project_dir.join("res"), &exclude_globs)?,
+            modules: vec![]
+        })
+    }
+}
+
+/// Reads every resource in `res_path`, except those matching `exclude_globs`
+/// (see [is_excluded]).
+pub fn read_res_dir(
+    fs: &impl PackFs,
+    res_path: &Path,
+    exclude_globs: &[String]
+) -> Result<Vec<FileResource>> {
+    let mut resources = vec![];
+    // Each file-based resource's directory and file name, by type, config and
+    // name, to catch eg. drawable/preview.png and drawable/preview.xml
+    let mut seen: HashMap<(String, ResourceConfig, String), (String, String)> = HashMap::new();
+    // Each path by its lowercase form, to catch files that only differ in
+    // case, which can't both be extracted on macOS or Windows
+    let mut folded_paths: HashMap<String, String> = HashMap::new();
+
+    for dir_name in sorted_entries(fs, res_path)? {
+        if is_excluded(exclude_globs, &format!("res/{dir_name}")) {
+            continue;
+        }
+        let dir_path = res_path.join(&dir_name);
+        if !fs.metadata(&dir_path)?.is_dir {
+            return Err(PackError::ResourceDirectoryInvalid(format!(
+                "res/{dir_name} needs to be in a resource type directory, eg. res/drawable/{dir_name}."
+            )));
+        }
+        let (res_type, config) = ResourceConfig::parse_directory(&dir_name)?;
+        if !RES_TYPES.contains(&res_type) {
+            return Err(PackError::ResourceDirectoryInvalid(format!(
+                "res/{dir_name}/ isn't a resource type. Use one of {}.",
+                RES_TYPES.join(", ")
+            )));
+        }
+
+        for name in sorted_entries(fs, &dir_path)? {
+            if is_excluded(exclude_globs, &format!("res/{dir_name}/{name}")) {
+                continue;
+            }
+            let path = format!("res/{dir_name}/{name}");
+            if path.contains('\\') {
+                return Err(PackError::ResourceFileNameInvalid(format!(
+                    "{path} has a backslash in its name. Paths in a package only use forward slashes, so rename it."
+                )));
+            }
+            if let Some(other) = folded_paths.insert(path.to_lowercase(), path.clone()) {
+                return Err(PackError::ResourceFileNameInvalid(format!(
+                    "{other} and {path} only differ in case, so rename one of them."
+                )));
+            }
+            let file_path = dir_path.join(&name);
+            if fs.metadata(&file_path)?.is_dir {
+                return Err(PackError::ResourceDirectoryInvalid(format!(
+                    "res/{dir_name}/{name}/ is a directory, but resource directories can't have subdirectories."
+                )));
+            }
+            let file = FileResource {
+                subdirectory: res_type.into(),
+                name,
+                config: config.clone(),
+                resource_id: 0,
+                contents: fs.read_file_contents(&file_path)?,
+                precompiled: false,
+                compression: Compression::Auto
+            };
+            // Values files can be called anything, since it's their contents
+            // that define resources
+            if res_type != "values" {
+                // Resources are referenced without extensions, eg. @drawable/frame
+                // for frame.9.png, or @xml/clock.face for clock.face.xml
+                validate_file_resource_name(&file)?;
+                let (res_name, _) = split_resource_file_name(&file.name);
+                let key = (res_type.to_string(), config.clone(), res_name.to_string());
+                if let Some((other_dir, other_name)) =
+                    seen.insert(key, (dir_name.clone(), file.name.clone()))
+                {
+                    if other_name == file.name {
+                        return Err(PackError::DuplicateResource(format!(
+                            "{other_dir}/{res_name}"
+                        )));
+                    }
+                    return Err(PackError::ResourceFileNameInvalid(format!(
+                        "res/{other_dir}/{other_name} and res/{dir_name}/{} are both @{res_type}/{res_name}. Resources are referenced without their extensions, so rename one of them.",
+                        file.name
+                    )));
+                }
+            }
+            resources.push(file);
+        }
+    }
+    Ok(resources)
+}
+
+/// Reads the globs from a project's .packignore, if it has one. Blank lines
+/// and lines starting with `#` are skipped.
+pub fn read_ignore_file(fs: &impl PackFs, project_dir: &Path) -> Result<Vec<String>> {
+    let path = project_dir.join(IGNORE_FILE_NAME);
+    if !fs.metadata(&path).is_ok_and(|metadata| !metadata.is_dir) {
+        return Ok(vec![]);
+    }
+    Ok(String::from_utf8_lossy(&fs.read_file(&path)?)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect())
+}
+
+/// Whether a path relative to the project directory, eg. `res/raw/intro.psd`,
+/// matches any of the globs. Like in .gitignore, globs without a `/`, eg.
+/// `*.psd`, match file and directory names anywhere.
+pub fn is_excluded(globs: &[String], path: &str) -> bool {
+    let name = path.rsplit('/').next().unwrap_or(path);
+    globs.iter().any(|glob| {
+        // A trailing slash just marks a directory
+        let glob = glob.trim_end_matches('/');
+        if glob.contains('/') {
+            glob_matches(glob, path)
+        } else {
+            glob_matches(glob, name)
+        }
+    })
+}
+
+// Lists a directory in a stable order, without hidden files like .DS_Store,
+// which aapt2 also skips
+fn sorted_entries(fs: &impl PackFs, dir: &Path) -> Result<Vec<String>> {
+    let mut names = fs.read_dir(dir)?;
+    names.retain(|name| !name.starts_with('.'));
+    names.sort();
+    Ok(names)
+}
@@ -0,0 +1,305 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Where the space in an APK or AAB goes, like apkanalyzer's file sizes, so
+// that it's clear what to shrink when a package is too big for a watch.
+//
+// A SizePolicy sets limits on that space, so that a package that's too big
+// fails to build, rather than being rejected when it's uploaded or installed.
+use pack_common::{Diagnostic, ErrorCode, PackError};
+use pack_sign::verification::{is_signature_related, signing_block_size};
+use pack_zip::EntrySize;
+
+use crate::{build_manifest::json_string, Result};
+
+const LARGEST_DRAWABLE_COUNT: usize = 10;
+// How many of the largest files a SizePolicyExceeded error lists
+const BREAKDOWN_ENTRY_COUNT: usize = 5;
+
+/// Where the space in a package goes, from [analyze_package].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SizeReport {
+    /// The size of the whole package file.
+    pub total_size: u64,
+    /// Every file in the package, largest first by compressed size.
+    pub entries: Vec<EntrySize>,
+    /// The files in res/, totalled by resource type, largest first.
+    pub resource_types: Vec<ResourceTypeSize>,
+    /// The largest files in res/drawable*/, at most 10 of them.
+    pub largest_drawables: Vec<EntrySize>,
+    /// The compressed size of resources.arsc, or for bundles, of every
+    /// module's resources.pb.
+    pub resource_table_size: u64,
+    /// The APK Signing Block, plus the JAR signature files in META-INF/.
+    pub signing_size: u64
+}
+
+/// The files of one resource type in a [SizeReport], eg. `drawable`,
+/// whatever their qualifiers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceTypeSize {
+    pub res_type: String,
+    pub file_count: usize,
+    pub compressed_size: u64,
+    pub uncompressed_size: u64
+}
+
+/// Works out how much space each file, resource type and signature takes up
+/// in an existing APK or AAB.
+pub fn analyze_package(package: &[u8]) -> Result<SizeReport> {
+    let mut entries = pack_zip::entry_sizes(package)?;
+    entries.sort_by(|a, b| {
+        b.compressed_size
+            .cmp(&a.compressed_size)
+            .then_with(|| a.path.cmp(&b.path))
+    });
+
+    let mut resource_types: Vec<ResourceTypeSize> = vec![];
+    let mut resource_table_size = 0;
+    let mut signing_size = signing_block_size(package)? as u64;
+    for entry in &entries {
+        if entry.path == "resources.arsc" || entry.path.ends_with("/resources.pb") {
+            resource_table_size += entry.compressed_size;
+        }
+        if is_signature_related(&entry.path) {
+            signing_size += entry.compressed_size;
+        }
+        let Some(res_type) = resource_type(&entry.path) else {
+            continue;
+        };
+        let type_size = match resource_types
+            .iter()
+            .position(|type_size| type_size.res_type == res_type)
+        {
+            Some(index) => &mut resource_types[index],
+            None => {
+                resource_types.push(ResourceTypeSize {
+                    res_type: res_type.into(),
+                    file_count: 0,
+                    compressed_size: 0,
+                    uncompressed_size: 0
+                });
+                resource_types.last_mut().unwrap()
+            }
+        };
+        type_size.file_count += 1;
+        type_size.compressed_size += entry.compressed_size;
+        type_size.uncompressed_size += entry.uncompressed_size;
+    }
+    resource_types.sort_by(|a, b| {
+        b.compressed_size
+            .cmp(&a.compressed_size)
+            .then_with(|| a.res_type.cmp(&b.res_type))
+    });
+
+    // Entries are already sorted largest first
+    let largest_drawables = entries
+        .iter()
+        .filter(|entry| resource_type(&entry.path) == Some("drawable"))
+        .take(LARGEST_DRAWABLE_COUNT)
+        .cloned()
+        .collect();
+
+    Ok(SizeReport {
+        total_size: package.len() as u64,
+        entries,
+        resource_types,
+        largest_drawables,
+        resource_table_size,
+        signing_size
+    })
+}
+
+impl SizeReport {
+    /// Formats the report as pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        let entries = |entries: &[EntrySize]| -> String {
+            let entries: Vec<String> = entries
+                .iter()
+                .map(|entry| {
+                    format!(
+                        "    {{ \"path\": {}, \"compressed_size\": {}, \"uncompressed_size\": {}, \"stored\": {} }}",
+                        json_string(&entry.path),
+                        entry.compressed_size,
+                        entry.uncompressed_size,
+                        entry.stored
+                    )
+                })
+                .collect();
+            entries.join(",\n")
+        };
+        let resource_types: Vec<String> = self
+            .resource_types
+            .iter()
+            .map(|type_size| {
+                format!(
+                    "    {{ \"type\": {}, \"file_count\": {}, \"compressed_size\": {}, \"uncompressed_size\": {} }}",
+                    json_string(&type_size.res_type),
+                    type_size.file_count,
+                    type_size.compressed_size,
+                    type_size.uncompressed_size
+                )
+            })
+            .collect();
+        format!(
+            "{{\n  \"total_size\": {},\n  \"resource_table_size\": {},\n  \"signing_size\": {},\n  \"entries\": [\n{}\n  ],\n  \"resource_types\": [\n{}\n  ],\n  \"largest_drawables\": [\n{}\n  ]\n}}\n",
+            self.total_size,
+            self.resource_table_size,
+            self.signing_size,
+            entries(&self.entries),
+            resource_types.join(",\n"),
+            entries(&self.largest_drawables)
+        )
+    }
+}
+
+// eg. `drawable` for res/drawable-xxhdpi/preview.png, or for
+// base/res/drawable/preview.png in a bundle
+fn resource_type(path: &str) -> Option<&str> {
+    let segments: Vec<&str> = path.split('/').collect();
+    let directory = match segments[..] {
+        ["res", directory, _] | [_, "res", directory, _] => directory,
+        _ => return None
+    };
+    directory.split('-').next()
+}
+
+/// Limits on how big a package and each of its drawables can be, in bytes.
+/// Going over a `max_` limit fails the build, and going over a `warn_`
+/// threshold is reported as a warning. Anything left as `None` isn't checked,
+/// so the default policy allows any size.
+///
+/// Drawables are measured by their uncompressed size, ie. the size of the
+/// file in res/. For AABs, the package size is the size of the bundle.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct SizePolicy {
+    pub max_package_size: Option<u64>,
+    pub warn_package_size: Option<u64>,
+    pub max_drawable_size: Option<u64>,
+    pub warn_drawable_size: Option<u64>
+}
+
+impl SizePolicy {
+    /// Whether the policy doesn't check anything.
+    pub fn is_unlimited(&self) -> bool {
+        *self == SizePolicy::default()
+    }
+
+    /// Everything in `report` that goes over a limit, as errors, or over a
+    /// warning threshold, as warnings.
+    pub fn check(&self, report: &SizeReport) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+        if let Some(diagnostic) = size_diagnostic(
+            report.total_size,
+            self.max_package_size,
+            self.warn_package_size,
+            ErrorCode::PackageTooLarge,
+            None
+        ) {
+            diagnostics.push(diagnostic);
+        }
+        for entry in &report.entries {
+            if resource_type(&entry.path) != Some("drawable") {
+                continue;
+            }
+            if let Some(diagnostic) = size_diagnostic(
+                entry.uncompressed_size,
+                self.max_drawable_size,
+                self.warn_drawable_size,
+                ErrorCode::DrawableTooLarge,
+                Some(&entry.path)
+            ) {
+                diagnostics.push(diagnostic);
+            }
+        }
+        diagnostics
+    }
+}
+
+// An error if `size` is over `max`, or else a warning if it's over `warn`.
+// Drawables have a path, and the package as a whole doesn't.
+fn size_diagnostic(
+    size: u64,
+    max: Option<u64>,
+    warn: Option<u64>,
+    code: ErrorCode,
+    path: Option<&str>
+) -> Option<Diagnostic> {
+    let what = if path.is_some() {
+        "The drawable"
+    } else {
+        "The package"
+    };
+    match (max, warn) {
+        (Some(max), _) if size > max => Some(Diagnostic::error(
+            code,
+            path,
+            format!("{what} is {size} bytes, over the limit of {max} bytes.")
+        )),
+        (_, Some(warn)) if size > warn => Some(Diagnostic::warning(
+            code,
+            path,
+            format!("{what} is {size} bytes, over the warning threshold of {warn} bytes.")
+        )),
+        _ => None
+    }
+}
+
+/// Fails with [PackError::SizePolicyExceeded] if `package` goes over any of
+/// `policy`'s limits. The error lists what was too big, and where the space
+/// in the package went.
+pub fn enforce_size_policy(package: &[u8], policy: &SizePolicy) -> Result<()> {
+    if policy.max_package_size.is_none() && policy.max_drawable_size.is_none() {
+        return Ok(());
+    }
+    let report = analyze_package(package)?;
+    let errors: Vec<String> = policy
+        .check(&report)
+        .iter()
+        .filter(|diagnostic| diagnostic.is_error())
+        .map(|diagnostic| match &diagnostic.path {
+            Some(path) => format!("{path}: {}", diagnostic.message),
+            None => diagnostic.message.clone()
+        })
+        .collect();
+    if errors.is_empty() {
+        return Ok(());
+    }
+
+    let mut breakdown = errors.join("\n");
+    breakdown.push_str("\nLargest files, by compressed size:");
+    for entry in report.entries.iter().take(BREAKDOWN_ENTRY_COUNT) {
+        breakdown.push_str(&format!(
+            "\n  {:>10}  {}",
+            entry.compressed_size, entry.path
+        ));
+    }
+    breakdown.push_str("\nResource types:");
+    for type_size in &report.resource_types {
+        breakdown.push_str(&format!(
+            "\n  {:>10}  {} ({} {})",
+            type_size.compressed_size,
+            type_size.res_type,
+            type_size.file_count,
+            if type_size.file_count == 1 {
+                "file"
+            } else {
+                "files"
+            }
+        ));
+    }
+    Err(PackError::SizePolicyExceeded(breakdown))
+}
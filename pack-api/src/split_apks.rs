@@ -0,0 +1,144 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Config splits let a device download only the densities and languages it
+// needs, which is what Google Play does with the APKs it generates from a
+// bundle. Building them directly lets you test the same layout locally with
+// `adb install-multiple`.
+use pack_asset_compiler::{
+    resource_config::ResourceConfig, resource_external_types::ResChunk,
+    resource_internal_types::Resource, resource_table::construct_resource_table_with_configs,
+    xml_file::ManifestInfo
+};
+
+use crate::{
+    collect_resources, file_bytes_for_apk, manifest_package_name, parse_manifest, res_to_apk_file,
+    zip_files, BuildOptions, Keys, Package, Result
+};
+
+/// One of the signed APKs produced by [compile_and_sign_split_apks].
+#[derive(Debug, Clone)]
+pub struct SplitApk {
+    /// Name of the split, eg. `config.xxhdpi`, or `None` for the base APK.
+    pub split_name: Option<String>,
+    /// The signed APK as a series of bytes.
+    pub apk: Vec<u8>
+}
+
+/// Builds a base APK plus a config split for each language and screen density.
+///
+/// Resources with a language qualifier go into a `config.<language>` split, eg.
+/// `config.de`. Other resources with a density qualifier go into a
+/// `config.<density>` split, eg. `config.xxhdpi`. Everything else goes into the
+/// base APK, which is always returned first.
+///
+/// Every APK is signed with the same keys. They must be installed together,
+/// for example with `adb install-multiple`.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(skip_all, fields(resources = package.resources.len()))
+)]
+pub fn compile_and_sign_split_apks(package: &Package, keys: &Keys) -> Result<Vec<SplitApk>> {
+    let mut resources = collect_resources(&package.resources)?;
+    let (manifest_res_chunk, manifest_info) = parse_manifest(
+        &package.android_manifest,
+        &resources,
+        &BuildOptions::default()
+    )?;
+    let package_name = manifest_package_name(&manifest_info)?;
+
+    let mut split_names: Vec<String> = resources
+        .iter()
+        .filter_map(|res| split_name(res.get_config()))
+        .collect();
+    split_names.sort();
+    split_names.dedup();
+
+    let base_files = split_files(None, &manifest_res_chunk, &package_name, &mut resources)?;
+    let mut apks = vec![SplitApk {
+        split_name: None,
+        apk: pack_sign::sign_apk_buffer(&mut zip_files(&base_files)?, keys)?
+    }];
+
+    for name in split_names {
+        let manifest = split_manifest(&package_name, &manifest_info, &name);
+        let (manifest_res_chunk, _) =
+            parse_manifest(manifest.as_bytes(), &resources, &BuildOptions::default())?;
+        let files = split_files(
+            Some(&name),
+            &manifest_res_chunk,
+            &package_name,
+            &mut resources
+        )?;
+        apks.push(SplitApk {
+            apk: pack_sign::sign_apk_buffer(&mut zip_files(&files)?, keys)?,
+            split_name: Some(name)
+        });
+    }
+
+    Ok(apks)
+}
+
+// Returns which split resources with this config belong in, or None for the base
+fn split_name(config: &ResourceConfig) -> Option<String> {
+    if let Some(language) = &config.language {
+        Some(format!("config.{language}"))
+    } else {
+        config
+            .density_qualifier()
+            .map(|density| format!("config.{density}"))
+    }
+}
+
+fn split_files(
+    split: Option<&str>,
+    manifest: &ResChunk,
+    package_name: &str,
+    resources: &mut [Resource]
+) -> Result<Vec<pack_zip::File>> {
+    let in_split = |config: &ResourceConfig| split_name(config).as_deref() == split;
+
+    let table = construct_resource_table_with_configs(package_name, resources, &in_split)?;
+    let mut files = vec![
+        res_to_apk_file("AndroidManifest.xml".into(), manifest)?,
+        res_to_apk_file("resources.arsc".into(), &table)?,
+    ];
+    for res in resources.iter() {
+        if let Resource::File(file) = res {
+            if in_split(&file.config) {
+                files.push(pack_zip::File {
+                    path: file.get_path(),
+                    data: file_bytes_for_apk(file, resources, &BuildOptions::default())?
+                });
+            }
+        }
+    }
+    Ok(files)
+}
+
+// Config splits only need enough of a manifest to say which app they belong to
+fn split_manifest(package_name: &str, manifest_info: &ManifestInfo, split_name: &str) -> String {
+    let version_code = manifest_info
+        .version_code
+        .as_ref()
+        .map(|version_code| format!(" android:versionCode=\"{version_code}\""))
+        .unwrap_or_default();
+    format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+        <manifest xmlns:android=\"http://schemas.android.com/apk/res/android\" \
+        package=\"{package_name}\" split=\"{split_name}\"{version_code}>\n\
+        <application android:hasCode=\"false\" />\n\
+        </manifest>\n"
+    )
+}
@@ -0,0 +1,78 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Builds with BuildOptions::inline_string_references, which must only touch
+// the XML files that get compiled.
+use pack_api::{
+    compile_apk_with_options, decode_xml, BinaryValue, BuildOptions, FileResource, Package
+};
+
+const MANIFEST: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<manifest xmlns:android="http://schemas.android.com/apk/res/android" package="com.example.inline">
+  <application android:label="@string/app_name" android:hasCode="false"/>
+</manifest>
+"#;
+
+const STRINGS: &str = r#"<resources>
+  <string name="app_name">Inline</string>
+</resources>
+"#;
+
+// Laid out the way an on-device parser might expect it, which rewriting it
+// would lose
+const RAW_XML: &str = r#"<?xml version='1.0'?>
+<!-- Shipped as it is -->
+<face   title = "@string/app_name" ></face>
+"#;
+
+const COMPILED_XML: &str = r#"<face xmlns:android="http://schemas.android.com/apk/res/android" android:label="@string/app_name"/>
+"#;
+
+fn build() -> Vec<pack_zip::File> {
+    let package = Package {
+        android_manifest: MANIFEST.as_bytes().to_vec(),
+        resources: vec![
+            FileResource::new("values".into(), "strings.xml".into(), STRINGS.into()),
+            FileResource::new("raw".into(), "face.xml".into(), RAW_XML.into()),
+            FileResource::new("xml".into(), "face.xml".into(), COMPILED_XML.into()),
+        ],
+        modules: vec![]
+    };
+    let options = BuildOptions {
+        inline_string_references: true,
+        ..Default::default()
+    };
+    let apk = compile_apk_with_options(&package, &options).unwrap();
+    pack_zip::unzip(&apk).unwrap()
+}
+
+fn file<'a>(files: &'a [pack_zip::File], path: &str) -> &'a [u8] {
+    &files.iter().find(|file| file.path == path).unwrap().data
+}
+
+#[test]
+fn raw_xml_is_unchanged() {
+    let files = build();
+    assert_eq!(file(&files, "res/raw/face.xml"), RAW_XML.as_bytes());
+}
+
+#[test]
+fn compiled_xml_is_inlined() {
+    let files = build();
+    let face = decode_xml(file(&files, "res/xml/face.xml")).unwrap();
+    assert_eq!(
+        face.attributes[0].value,
+        BinaryValue::String("Inline".into())
+    );
+}
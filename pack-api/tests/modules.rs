@@ -0,0 +1,58 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Builds an AAB with a feature module whose title has characters that are
+// special in XML, which must reach the module's manifest as they were.
+#![cfg(feature = "aab")]
+
+use pack_api::{
+    compile_and_sign_aab, BinaryValue, Keys, Module, ModuleDelivery, ModuleKind, Package
+};
+
+const MANIFEST: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<manifest xmlns:android="http://schemas.android.com/apk/res/android" package="com.example.modules">
+  <application android:hasCode="false"/>
+</manifest>
+"#;
+
+const TITLE: &str = r#"Faces & "more" <HD>"#;
+
+#[test]
+fn module_titles_are_escaped() {
+    let package = Package {
+        android_manifest: MANIFEST.as_bytes().to_vec(),
+        resources: vec![],
+        modules: vec![Module {
+            name: "extras".into(),
+            kind: ModuleKind::Feature,
+            delivery: ModuleDelivery::InstallTime,
+            title: Some(TITLE.into()),
+            resources: vec![],
+            assets: vec![]
+        }]
+    };
+    let keys = Keys::from_combined_pem_string(include_str!("testing-keys.pem")).unwrap();
+    let aab = compile_and_sign_aab(&package, &keys).unwrap();
+    let manifest = pack_zip::unzip(&aab)
+        .unwrap()
+        .into_iter()
+        .find(|file| file.path == "extras/manifest/AndroidManifest.xml")
+        .unwrap();
+    let manifest = pack_aab::decode_proto_xml(&manifest.data).unwrap();
+    let module = manifest.children_named("module").next().unwrap();
+    assert_eq!(
+        module.attribute("title"),
+        Some(&BinaryValue::String(TITLE.into()))
+    );
+}
@@ -0,0 +1,84 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Resource IDs have to be the same however the package lists its files, and
+// whatever order strings.xml defines strings in, so that references resolve
+// the same way on every machine.
+use pack_api::{compile_apk, decode_resource_table, DecodedTable, FileResource, Package};
+
+const MANIFEST: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<manifest xmlns:android="http://schemas.android.com/apk/res/android" package="com.example.order">
+  <application android:label="@string/app_name" android:hasCode="false"/>
+</manifest>
+"#;
+
+const STRINGS: &str = r#"<resources>
+  <string name="zebra">Zebra</string>
+  <string name="app_name">Order</string>
+</resources>
+"#;
+
+fn table(resources: Vec<FileResource>) -> DecodedTable {
+    let package = Package {
+        android_manifest: MANIFEST.as_bytes().to_vec(),
+        resources,
+        modules: vec![]
+    };
+    let apk = compile_apk(&package).unwrap();
+    let arsc = pack_zip::unzip(&apk)
+        .unwrap()
+        .into_iter()
+        .find(|file| file.path == "resources.arsc")
+        .unwrap();
+    decode_resource_table(&arsc.data).unwrap()
+}
+
+fn resources() -> Vec<FileResource> {
+    vec![
+        FileResource::new("raw".into(), "zeta.txt".into(), b"z".to_vec()),
+        FileResource::new("values".into(), "strings.xml".into(), STRINGS.into()),
+        FileResource::new("raw".into(), "alpha.txt".into(), b"a".to_vec()),
+    ]
+}
+
+#[test]
+fn entries_are_numbered_by_name() {
+    let table = table(resources());
+    let names: Vec<(&str, Vec<(u16, &str)>)> = table.packages[0]
+        .types
+        .iter()
+        .map(|res_type| {
+            let entries = res_type
+                .entries
+                .iter()
+                .map(|entry| (entry.id, entry.name.as_str()))
+                .collect();
+            (res_type.name.as_str(), entries)
+        })
+        .collect();
+    assert_eq!(
+        names,
+        [
+            ("raw", vec![(0, "alpha"), (1, "zeta")]),
+            ("string", vec![(0, "app_name"), (1, "zebra")])
+        ]
+    );
+}
+
+#[test]
+fn ids_dont_depend_on_file_order() {
+    let mut reversed = resources();
+    reversed.reverse();
+    assert_eq!(table(reversed), table(resources()));
+}
@@ -0,0 +1,74 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Signs with a SigningConfig other than the default, and checks that the
+// signatures it makes are the ones Android would verify.
+use pack_api::{
+    compile_and_sign_apk_with_options, verify_package, BuildOptions, DigestAlgorithm, FileResource,
+    Keys, PackError, Package, SchemeStatus, SigningConfig
+};
+
+const MANIFEST: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<manifest xmlns:android="http://schemas.android.com/apk/res/android" package="com.example.signing">
+  <application android:label="@string/app_name" android:hasCode="false"/>
+</manifest>
+"#;
+
+const STRINGS: &str = r#"<resources>
+  <string name="app_name">Signing</string>
+</resources>
+"#;
+
+fn build(signing_config: SigningConfig) -> Vec<u8> {
+    let package = Package {
+        android_manifest: MANIFEST.as_bytes().to_vec(),
+        resources: vec![FileResource::new(
+            "values".into(),
+            "strings.xml".into(),
+            STRINGS.into()
+        )],
+        modules: vec![]
+    };
+    let keys = Keys::from_combined_pem_string(include_str!("testing-keys.pem")).unwrap();
+    let options = BuildOptions {
+        signing_config,
+        ..Default::default()
+    };
+    compile_and_sign_apk_with_options(&package, &keys, &options).unwrap()
+}
+
+#[test]
+fn zero_chunk_size_is_rejected() {
+    assert!(matches!(
+        SigningConfig::new(0, DigestAlgorithm::Sha256),
+        Err(PackError::SigningConfigInvalid(_))
+    ));
+}
+
+#[test]
+fn sha512_signatures_verify() {
+    let config = SigningConfig::new(1024 * 1024, DigestAlgorithm::Sha512).unwrap();
+    let report = verify_package(&build(config)).unwrap();
+    assert_eq!(report.v2, SchemeStatus::Verified);
+    assert_eq!(report.v3, SchemeStatus::Verified);
+}
+
+#[test]
+fn chunk_size_is_used() {
+    // Android always digests 1MB chunks, so any other size can't verify
+    let config = SigningConfig::new(16, DigestAlgorithm::Sha256).unwrap();
+    let report = verify_package(&build(config)).unwrap();
+    assert!(matches!(report.v2, SchemeStatus::Invalid(_)));
+    assert!(matches!(report.v3, SchemeStatus::Invalid(_)));
+}
@@ -0,0 +1,99 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Builds with BuildOptions::sorted_string_pools, which must change the order of
+// the strings in resources.arsc but not what any resource resolves to.
+use pack_api::{
+    compile_apk_with_options, decode_resource_table, BuildOptions, FileResource, Package
+};
+
+const MANIFEST: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<manifest xmlns:android="http://schemas.android.com/apk/res/android" package="com.example.sorted">
+  <application android:label="@string/a" android:hasCode="false"/>
+</manifest>
+"#;
+
+// Resources are ordered by name, which puts the values out of order, and two
+// values are the same
+const STRINGS: &str = r#"<resources>
+  <string name="a">Zebra</string>
+  <string name="b">Apple</string>
+  <string name="c">Mango</string>
+  <string name="d">Apple</string>
+</resources>
+"#;
+
+const STRING_POOL_SORTED_FLAG: u32 = 1;
+
+fn build_table(sorted_string_pools: bool) -> Vec<u8> {
+    let package = Package {
+        android_manifest: MANIFEST.as_bytes().to_vec(),
+        resources: vec![FileResource::new(
+            "values".into(),
+            "strings.xml".into(),
+            STRINGS.into()
+        )],
+        modules: vec![]
+    };
+    let options = BuildOptions {
+        sorted_string_pools,
+        ..Default::default()
+    };
+    let apk = compile_apk_with_options(&package, &options).unwrap();
+    pack_zip::unzip(&apk)
+        .unwrap()
+        .into_iter()
+        .find(|file| file.path == "resources.arsc")
+        .unwrap()
+        .data
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+// The value pool comes straight after the table's header. Returns its flags
+// and strings, which are all short enough for one-byte lengths.
+fn value_pool(table: &[u8]) -> (u32, Vec<String>) {
+    let pool = &table[12..];
+    let count = u32_at(pool, 8) as usize;
+    let strings_start = u32_at(pool, 20) as usize;
+    let strings = (0..count)
+        .map(|i| {
+            let start = strings_start + u32_at(pool, 28 + i * 4) as usize;
+            let len = pool[start + 1] as usize;
+            String::from_utf8(pool[start + 2..start + 2 + len].to_vec()).unwrap()
+        })
+        .collect();
+    (u32_at(pool, 16), strings)
+}
+
+#[test]
+fn pools_are_only_sorted_when_asked() {
+    let (flags, strings) = value_pool(&build_table(false));
+    assert_eq!(flags & STRING_POOL_SORTED_FLAG, 0);
+    assert_eq!(strings, ["Zebra", "Apple", "Mango"]);
+
+    let (flags, strings) = value_pool(&build_table(true));
+    assert_ne!(flags & STRING_POOL_SORTED_FLAG, 0);
+    assert_eq!(strings, ["Apple", "Mango", "Zebra"]);
+}
+
+#[test]
+fn sorting_keeps_every_value() {
+    assert_eq!(
+        decode_resource_table(&build_table(true)).unwrap(),
+        decode_resource_table(&build_table(false)).unwrap()
+    );
+}
@@ -0,0 +1,69 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Benchmarks for compiling resources: building string pools, and compiling a
+// watch face's XML to binary XML. Run with `cargo bench -p pack-asset-compiler`.
+use std::{hint::black_box, io::Cursor};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use pack_asset_compiler::{string_pool::construct_string_pool, xml_file::xml_to_res_chunk};
+
+// Watch faces repeat the same handful of elements many times, so this builds
+// one with a few hundred of them
+fn watch_face_xml(parts: usize) -> String {
+    let mut xml = String::from(
+        r##"<?xml version="1.0" encoding="utf-8"?>
+<WatchFace width="450" height="450">
+  <Metadata key="CLOCK_TYPE" value="DIGITAL"/>
+  <Scene backgroundColor="#ff000000">
+"##
+    );
+    for index in 0..parts {
+        xml.push_str(&format!(
+            r##"    <PartImage x="{x}" y="{y}" width="48" height="48" alpha="255">
+      <Image resource="icon_{index}"/>
+      <Variant mode="AMBIENT" target="alpha" value="0"/>
+    </PartImage>
+    <PartText x="{x}" y="{y}" width="120" height="40">
+      <Text align="CENTER">
+        <Font family="SYNC_TO_DEVICE" size="24" color="#ffffffff">
+          <Template>%s<Parameter expression="[HOUR_0_23]"/></Template>
+        </Font>
+      </Text>
+    </PartText>
+"##,
+            x = index % 400,
+            y = (index * 7) % 400
+        ));
+    }
+    xml.push_str("  </Scene>\n</WatchFace>\n");
+    xml
+}
+
+fn string_pool(c: &mut Criterion) {
+    let strings: Vec<String> = (0..5000).map(|index| format!("string_{index}")).collect();
+    c.bench_function("construct_string_pool", |b| {
+        b.iter(|| construct_string_pool(black_box(&strings)).unwrap())
+    });
+}
+
+fn compile_xml(c: &mut Criterion) {
+    let xml = watch_face_xml(300);
+    c.bench_function("xml_to_res_chunk", |b| {
+        b.iter(|| xml_to_res_chunk(&mut Cursor::new(black_box(xml.as_bytes())), &[]).unwrap())
+    });
+}
+
+criterion_group!(benches, string_pool, compile_xml);
+criterion_main!(benches);
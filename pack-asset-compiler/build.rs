@@ -21,9 +21,14 @@ use std::path::Path;
 // Builds a large, compile-time list of Android Internal Attributes and their
 // IDs for faster runtime lookup.
 // See get_internal_attribute_id
+//
+// Also generates the chunk and value type enums from the copy of
+// ResourceTypes.h in src/aosp, see write_resource_types
 
 fn main() -> Result<(), Box<dyn Error>> {
     let out_dir = env::var("OUT_DIR")?;
+    write_resource_types(&out_dir)?;
+
     let dest_path = Path::new(&out_dir).join("internal_attributes_map.rs");
     let mut f = File::create(&dest_path)?;
 
@@ -47,6 +52,126 @@ fn main() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+const RESOURCE_TYPES_HEADER: &str = "src/aosp/ResourceTypes.h";
+
+// Writes resource_types.rs, with a constant for every RES_*_TYPE and Res_value
+// TYPE_* in the header, and the ChunkType and AttributeDataType enums. The
+// FIRST and LAST values mark ranges rather than types, so they're only
+// constants. See resource_external_types
+fn write_resource_types(out_dir: &str) -> Result<(), Box<dyn Error>> {
+    let header = std::fs::read_to_string(RESOURCE_TYPES_HEADER)?;
+    let mut chunk_types = vec![];
+    let mut data_types = vec![];
+    for line in header.lines() {
+        let Some((name, value)) = line.trim().split_once('=') else {
+            continue;
+        };
+        let name = name.trim();
+        let value = value.trim().trim_end_matches(',');
+        let Some(hex) = value.strip_prefix("0x") else {
+            continue;
+        };
+        let value = u16::from_str_radix(hex, 16)?;
+        if let Some(variant) = name
+            .strip_prefix("RES_")
+            .and_then(|name| name.strip_suffix("_TYPE"))
+        {
+            chunk_types.push((name, variant, value));
+        } else if let Some(variant) = name.strip_prefix("TYPE_") {
+            data_types.push((name, variant, value));
+        }
+    }
+    if chunk_types.is_empty() || data_types.is_empty() {
+        return Err(format!("No types were found in {RESOURCE_TYPES_HEADER}.").into());
+    }
+
+    let dest_path = Path::new(out_dir).join("resource_types.rs");
+    let mut f = File::create(&dest_path)?;
+    write_type_enum(&mut f, "ChunkType", "u16", &chunk_types)?;
+    write_type_enum(&mut f, "AttributeDataType", "u8", &data_types)?;
+
+    println!("cargo:rerun-if-changed={RESOURCE_TYPES_HEADER}");
+
+    Ok(())
+}
+
+fn write_type_enum(
+    f: &mut File,
+    enum_name: &str,
+    id_type: &str,
+    types: &[(&str, &str, u16)]
+) -> Result<(), Box<dyn Error>> {
+    let width = if id_type == "u8" { 4 } else { 6 };
+    for (name, _, value) in types {
+        writeln!(f, "pub const {name}: {id_type} = {value:#0width$x};")?;
+    }
+    // Ranges like RES_XML_FIRST_CHUNK_TYPE share their value with a real type
+    let types: Vec<_> = types
+        .iter()
+        .filter(|(_, variant, _)| !variant.contains("FIRST_") && !variant.contains("LAST_"))
+        .collect();
+
+    writeln!(f)?;
+    writeln!(f, "/// See `{RESOURCE_TYPES_HEADER}`.")?;
+    writeln!(f, "#[derive(Debug, Clone, Copy, PartialEq, Eq, DekuWrite)]")?;
+    writeln!(f, "#[deku(id_type = \"{id_type}\")]")?;
+    writeln!(f, "pub enum {enum_name} {{")?;
+    for (name, variant, value) in &types {
+        writeln!(f, "    /// `{name}`")?;
+        writeln!(f, "    #[deku(id = {value:#0width$x})]")?;
+        writeln!(f, "    {},", camel_case(variant))?;
+    }
+    writeln!(f, "}}")?;
+
+    writeln!(f)?;
+    writeln!(f, "impl {enum_name} {{")?;
+    writeln!(
+        f,
+        "    /// The type with this ID, if it's one Android knows about."
+    )?;
+    writeln!(f, "    pub fn from_id(id: {id_type}) -> Option<Self> {{")?;
+    writeln!(f, "        match id {{")?;
+    for (_, variant, value) in &types {
+        writeln!(
+            f,
+            "            {value:#0width$x} => Some(Self::{}),",
+            camel_case(variant)
+        )?;
+    }
+    writeln!(f, "            _ => None")?;
+    writeln!(f, "        }}")?;
+    writeln!(f, "    }}")?;
+    writeln!(f)?;
+    writeln!(f, "    pub fn id(self) -> {id_type} {{")?;
+    writeln!(f, "        match self {{")?;
+    for (_, variant, value) in &types {
+        writeln!(
+            f,
+            "            Self::{} => {value:#0width$x},",
+            camel_case(variant)
+        )?;
+    }
+    writeln!(f, "        }}")?;
+    writeln!(f, "    }}")?;
+    writeln!(f, "}}")?;
+    writeln!(f)?;
+
+    Ok(())
+}
+
+// INT_COLOR_ARGB8 -> IntColorArgb8
+fn camel_case(name: &str) -> String {
+    name.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_string() + &chars.as_str().to_lowercase(),
+                None => String::new()
+            }
+        })
+        .collect()
+}
+
 const INTERNAL_ANDROID_ATTRIBUTES: &[&str] = &[
     "theme",
     "label",
@@ -1443,5 +1568,237 @@ const INTERNAL_ANDROID_ATTRIBUTES: &[&str] = &[
     "UNKNOWN",
     "UNKNOWN",
     "compileSdkVersion",
-    "compileSdkCodename"
+    "compileSdkCodename",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "UNKNOWN",
+    "localeConfig"
 ];
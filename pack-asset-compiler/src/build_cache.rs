@@ -0,0 +1,246 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Compiling XML (to either ResChunk XML or ProtoXML) is the slowest part of
+// packaging after signing. When the same project is built over and over, most
+// files haven't changed, so we keep their compiled bytes around keyed by a hash
+// of everything that could affect the output.
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, MutexGuard, PoisonError
+    }
+};
+
+use pack_common::*;
+use sha2::{Digest, Sha256};
+
+use crate::resource_internal_types::Resource;
+
+/// SHA-256 of a compilation's inputs.
+pub type CacheKey = [u8; 32];
+
+// Bumped whenever the on-disk format, or the compiler output for the same
+// input, changes. Old caches are then silently discarded.
+const CACHE_FILE_MAGIC: &[u8; 8] = b"PACKBC01";
+
+/// Holds compiled resources between builds, keyed by content hash.
+///
+/// A compiled XML file depends not only on its own bytes but also on the IDs
+/// of any resources it references, which in turn depend on every resource in
+/// the package. [BuildCache::key] takes all of this into account, so a cached
+/// entry is only reused when the output would be byte-for-byte identical.
+///
+/// The cache can be shared between threads and persisted with
+/// [BuildCache::to_bytes] / [BuildCache::from_bytes]. A process that keeps
+/// the same cache for many builds, eg. one watching a project, should call
+/// [BuildCache::begin_build] before each of them, so that pruning only keeps
+/// what recent builds used.
+#[derive(Debug, Default)]
+pub struct BuildCache {
+    entries: Mutex<HashMap<CacheKey, CacheEntry>>,
+    // Counts calls to begin_build
+    generation: AtomicU64
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    data: Vec<u8>,
+    // The generation this entry was last looked up or inserted in
+    last_used: u64
+}
+
+impl BuildCache {
+    /// Returns an empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Computes the key for compiling `contents` in the context of `resources`.
+    ///
+    /// `stage` distinguishes between different compilers run on the same input,
+    /// for example `"apk-xml"` and `"aab-xml"`.
+    pub fn key(stage: &str, resources: &[Resource], contents: &[u8]) -> CacheKey {
+        let mut hasher = Sha256::new();
+        hasher.update(env!("CARGO_PKG_VERSION"));
+        hasher.update([0u8]);
+        hasher.update(stage);
+        hasher.update([0u8]);
+        // Resource IDs are assigned from the (sorted) order of resources, so
+        // the names and order of every resource affect reference lookups.
+        for res in resources {
+            hasher.update(res.get_subdirectory());
+            hasher.update([0u8]);
+            hasher.update(res.get_name());
+            hasher.update([0u8]);
+            hasher.update(res.get_config().directory_suffix());
+            hasher.update([0u8]);
+        }
+        hasher.update((contents.len() as u64).to_le_bytes());
+        hasher.update(contents);
+        hasher.finalize().into()
+    }
+
+    /// Returns the cached bytes for `key`, or runs `compile` and caches its
+    /// output if there's no entry yet. Errors are not cached.
+    pub fn get_or_compile<F: FnOnce() -> Result<Vec<u8>>>(
+        &self,
+        key: CacheKey,
+        compile: F
+    ) -> Result<Vec<u8>> {
+        if let Some(hit) = self.lock().get_mut(&key) {
+            hit.last_used = self.generation();
+            return Ok(hit.data.clone());
+        }
+        // Don't hold the lock while compiling, so other threads can carry on
+        let compiled = compile()?;
+        self.lock().insert(
+            key,
+            CacheEntry {
+                data: compiled.clone(),
+                last_used: self.generation()
+            }
+        );
+        Ok(compiled)
+    }
+
+    /// Returns the cached bytes for `key`, if there's an entry for it.
+    pub fn get(&self, key: &CacheKey) -> Option<Vec<u8>> {
+        let mut entries = self.lock();
+        let hit = entries.get_mut(key)?;
+        hit.last_used = self.generation();
+        Some(hit.data.clone())
+    }
+
+    /// Caches `data` for `key`, replacing any entry there was. Along with
+    /// [BuildCache::get], this lets callers cache things that aren't
+    /// compiled, eg. what a whole package was built from.
+    pub fn insert(&self, key: CacheKey, data: Vec<u8>) {
+        let mut entries = self.lock();
+        let last_used = self.generation();
+        entries.insert(key, CacheEntry { data, last_used });
+    }
+
+    /// Number of compiled files currently held.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Removes every entry.
+    pub fn clear(&self) {
+        self.lock().clear()
+    }
+
+    /// Starts a new build, after which entries only count as used once they're
+    /// looked up again. Without this, everything a long-lived cache ever held
+    /// counts as used, and pruning never removes anything.
+    pub fn begin_build(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Removes entries which haven't been used since the last
+    /// [BuildCache::begin_build], or since the cache was created or loaded.
+    /// Call this after a build, before persisting the cache, so that outdated
+    /// versions of files don't accumulate forever.
+    pub fn prune_unused(&self) {
+        self.prune_unused_for(1)
+    }
+
+    /// Removes entries which none of the last `builds` builds used, counting
+    /// the current one. This suits a cache shared by builds of different
+    /// packages, where the last build alone doesn't show what's still needed.
+    pub fn prune_unused_for(&self, builds: u64) {
+        let mut entries = self.lock();
+        // Read under the lock, so that it's at least as new as any entry, but
+        // don't rely on that: a build can start at any moment
+        let generation = self.generation();
+        entries.retain(|_key, entry| generation.saturating_sub(entry.last_used) < builds)
+    }
+
+    /// Serialises the cache so that it can be written to disk.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let entries = self.lock();
+        let mut out = CACHE_FILE_MAGIC.to_vec();
+        out.extend((entries.len() as u32).to_le_bytes());
+        for (key, entry) in entries.iter() {
+            out.extend(key);
+            out.extend((entry.data.len() as u32).to_le_bytes());
+            out.extend(&entry.data);
+        }
+        out
+    }
+
+    /// Reads back a cache written by [BuildCache::to_bytes].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut reader = CacheReader { bytes, pos: 0 };
+        if reader.take(CACHE_FILE_MAGIC.len())? != CACHE_FILE_MAGIC {
+            return Err(PackError::BuildCacheInvalid);
+        }
+        let entry_count = reader.take_u32()?;
+        let mut entries = HashMap::new();
+        for _ in 0..entry_count {
+            let key: CacheKey = reader
+                .take(32)?
+                .try_into()
+                .map_err(|_e| PackError::BuildCacheInvalid)?;
+            let data_len = reader.take_u32()? as usize;
+            let data = reader.take(data_len)?.to_vec();
+            entries.insert(key, CacheEntry { data, last_used: 0 });
+        }
+        // Loaded entries belong to an earlier generation, so they only count
+        // as used once they're looked up
+        Ok(BuildCache {
+            entries: Mutex::new(entries),
+            generation: AtomicU64::new(1)
+        })
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Relaxed)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<CacheKey, CacheEntry>> {
+        // A panic while holding the lock can't leave the map half-written, so
+        // carry on with whatever is in it.
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+struct CacheReader<'a> {
+    bytes: &'a [u8],
+    pos: usize
+}
+
+impl<'a> CacheReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or(PackError::BuildCacheInvalid)?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn take_u32(&mut self) -> Result<u32> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+}
@@ -0,0 +1,643 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Reads binary XML files and resources.arsc back out of an APK, so that
+// packages can be inspected. This isn't only for what PACK writes: APKs built
+// by aapt2 use more of the format, like UTF-16 string pools, sparse types and
+// styles, so those are understood too.
+//
+// Everything is bounds-checked, since the APK being inspected could be anything.
+use pack_common::*;
+
+use crate::{
+    resource_config::ResourceConfig,
+    resource_external_types::{
+        RES_STRING_POOL_TYPE, RES_TABLE_PACKAGE_TYPE, RES_TABLE_TYPE, RES_TABLE_TYPE_TYPE,
+        RES_XML_END_ELEMENT_TYPE, RES_XML_RESOURCE_MAP_TYPE, RES_XML_START_ELEMENT_TYPE,
+        RES_XML_TYPE, TYPE_ATTRIBUTE, TYPE_DYNAMIC_ATTRIBUTE, TYPE_DYNAMIC_REFERENCE,
+        TYPE_FIRST_COLOR_INT, TYPE_FLOAT, TYPE_INT_BOOLEAN, TYPE_INT_DEC, TYPE_INT_HEX,
+        TYPE_LAST_COLOR_INT, TYPE_NULL, TYPE_REFERENCE, TYPE_STRING
+    }
+};
+
+const STRING_POOL_UTF8_FLAG: u32 = 1 << 8;
+const NO_INDEX: u32 = 0xFFFF_FFFF;
+
+// ResTable_type flags
+const TYPE_FLAG_SPARSE: u8 = 0x01;
+const TYPE_FLAG_OFFSET16: u8 = 0x02;
+// ResTable_entry flags
+const ENTRY_FLAG_COMPLEX: u16 = 0x0001;
+const ENTRY_FLAG_COMPACT: u16 = 0x0008;
+
+/// A value from a binary XML attribute or a resource table entry.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BinaryValue {
+    /// An empty value, eg. `@null`.
+    Null,
+    /// The ID of another resource, eg. `@drawable/preview`.
+    Reference(u32),
+    /// The ID of a theme attribute, eg. `?android:colorAccent`.
+    AttributeReference(u32),
+    String(String),
+    /// Decimal or hexadecimal integers.
+    Integer(i32),
+    Boolean(bool),
+    Float(f32),
+    /// An ARGB colour, eg. `0xFF00FF00` for `#00FF00`.
+    Color(u32),
+    /// Anything else, like dimensions and fractions, as the raw `Res_value`
+    /// type and data.
+    Other(u8, u32),
+    /// A complex resource, like a style or plural, with the attribute IDs and
+    /// values of its items. Styles can have a parent style to inherit from.
+    Bag {
+        parent: Option<u32>,
+        items: Vec<(u32, BinaryValue)>
+    }
+}
+
+/// An element from a binary XML file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedElement {
+    /// eg. `http://schemas.android.com/apk/res/android`
+    pub namespace: Option<String>,
+    /// eg. `application`
+    pub name: String,
+    pub attributes: Vec<DecodedAttribute>,
+    pub children: Vec<DecodedElement>
+}
+
+/// An attribute of an element from a binary XML file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedAttribute {
+    pub namespace: Option<String>,
+    /// eg. `label`
+    pub name: String,
+    /// The ID of the attribute from the resource map, for `android:` attributes.
+    pub resource_id: Option<u32>,
+    pub value: BinaryValue
+}
+
+impl DecodedElement {
+    /// Returns the value of the attribute with this name, eg. `label` for
+    /// `android:label`, in any namespace.
+    pub fn attribute(&self, name: &str) -> Option<&BinaryValue> {
+        self.attributes
+            .iter()
+            .find(|attr| attr.name == name)
+            .map(|attr| &attr.value)
+    }
+
+    /// Returns the direct children with this name.
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a DecodedElement> {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+}
+
+/// The contents of a `resources.arsc`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DecodedTable {
+    pub packages: Vec<DecodedPackage>
+}
+
+/// A package in a resource table, usually the app's own with ID `0x7F`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedPackage {
+    pub id: u32,
+    /// eg. `com.example.watchface`
+    pub name: String,
+    pub types: Vec<DecodedType>
+}
+
+/// A resource type in a package, eg. `drawable`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedType {
+    /// 1-based, the second byte of every resource ID of this type.
+    pub id: u8,
+    pub name: String,
+    /// How many entry IDs the type has, including ones without any values.
+    pub entry_count: u32,
+    /// The entries that have values, ordered by ID.
+    pub entries: Vec<DecodedEntry>
+}
+
+/// A resource, with its value in each config it's defined for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecodedEntry {
+    /// The last two bytes of the resource's ID.
+    pub id: u16,
+    /// eg. `preview`
+    pub name: String,
+    pub values: Vec<(ResourceConfig, BinaryValue)>
+}
+
+impl DecodedTable {
+    /// Finds a resource by its ID, eg. `0x7F010000`.
+    pub fn entry(
+        &self,
+        resource_id: u32
+    ) -> Option<(&DecodedPackage, &DecodedType, &DecodedEntry)> {
+        let package = self
+            .packages
+            .iter()
+            .find(|package| package.id == resource_id >> 24)?;
+        let res_type = package
+            .types
+            .iter()
+            .find(|res_type| res_type.id as u32 == (resource_id >> 16) & 0xFF)?;
+        let entry = res_type
+            .entries
+            .iter()
+            .find(|entry| entry.id as u32 == resource_id & 0xFFFF)?;
+        Some((package, res_type, entry))
+    }
+
+    /// Returns the name of a resource, eg. `@drawable/preview`.
+    pub fn reference_name(&self, resource_id: u32) -> Option<String> {
+        let (_, res_type, entry) = self.entry(resource_id)?;
+        Some(format!("@{}/{}", res_type.name, entry.name))
+    }
+
+    /// Returns a resource's values, following references to other resources.
+    pub fn resolve(&self, resource_id: u32) -> Vec<(ResourceConfig, BinaryValue)> {
+        self.resolve_with_depth(resource_id, 0)
+    }
+
+    fn resolve_with_depth(
+        &self,
+        resource_id: u32,
+        depth: usize
+    ) -> Vec<(ResourceConfig, BinaryValue)> {
+        // References can form loops in a malformed table
+        const MAX_DEPTH: usize = 16;
+        let Some((_, _, entry)) = self.entry(resource_id) else {
+            return vec![];
+        };
+        let mut values = vec![];
+        for (config, value) in &entry.values {
+            match value {
+                BinaryValue::Reference(id) if depth < MAX_DEPTH => {
+                    // The referenced resource's configs only matter if this one
+                    // applies to every device
+                    for (resolved_config, resolved) in self.resolve_with_depth(*id, depth + 1) {
+                        let config = if config.is_default() {
+                            resolved_config
+                        } else {
+                            config.clone()
+                        };
+                        values.push((config, resolved));
+                    }
+                }
+                _ => values.push((config.clone(), value.clone()))
+            }
+        }
+        values
+    }
+}
+
+/// Decodes a binary XML file, like an APK's `AndroidManifest.xml`, and returns
+/// its root element.
+pub fn decode_xml(bytes: &[u8]) -> Result<DecodedElement> {
+    let file = Chunk::read(bytes, 0)?;
+    if file.chunk_type != RES_XML_TYPE {
+        return Err(invalid("Not a binary XML file."));
+    }
+
+    let mut strings = vec![];
+    let mut resource_map = vec![];
+    // The elements that are still open, innermost last
+    let mut stack: Vec<DecodedElement> = vec![];
+    let mut root = None;
+    for chunk in file.children(bytes)? {
+        match chunk.chunk_type {
+            RES_STRING_POOL_TYPE => strings = read_string_pool(bytes, &chunk)?,
+            RES_XML_RESOURCE_MAP_TYPE => {
+                resource_map = (chunk.body_start()..chunk.end)
+                    .step_by(4)
+                    .map(|offset| read_u32(bytes, offset))
+                    .collect::<Result<Vec<_>>>()?;
+            }
+            RES_XML_START_ELEMENT_TYPE => {
+                stack.push(read_element(bytes, &chunk, &strings, &resource_map)?);
+            }
+            RES_XML_END_ELEMENT_TYPE => {
+                let element = stack
+                    .pop()
+                    .ok_or(invalid("An element was closed before it was opened."))?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(element),
+                    None if root.is_none() => root = Some(element),
+                    None => return Err(invalid("There is more than one root element."))
+                }
+            }
+            // Namespaces are already resolved on each element and attribute,
+            // and text isn't meaningful in the files that are inspected
+            _ => {}
+        }
+    }
+
+    if !stack.is_empty() {
+        return Err(invalid("An element was never closed."));
+    }
+    root.ok_or(invalid("There is no root element."))
+}
+
+/// Decodes a `resources.arsc`.
+pub fn decode_resource_table(bytes: &[u8]) -> Result<DecodedTable> {
+    let table = Chunk::read(bytes, 0)?;
+    if table.chunk_type != RES_TABLE_TYPE {
+        return Err(invalid("Not a resource table."));
+    }
+
+    let mut values = vec![];
+    let mut decoded = DecodedTable::default();
+    for chunk in table.children(bytes)? {
+        match chunk.chunk_type {
+            RES_STRING_POOL_TYPE => values = read_string_pool(bytes, &chunk)?,
+            RES_TABLE_PACKAGE_TYPE => decoded.packages.push(read_package(bytes, &chunk, &values)?),
+            _ => {}
+        }
+    }
+    Ok(decoded)
+}
+
+// Where a chunk is in the file, from its ResChunk_header
+struct Chunk {
+    chunk_type: u16,
+    start: usize,
+    header_size: usize,
+    end: usize
+}
+
+impl Chunk {
+    fn read(bytes: &[u8], start: usize) -> Result<Chunk> {
+        let chunk = Chunk {
+            chunk_type: read_u16(bytes, start)?,
+            start,
+            header_size: read_u16(bytes, start + 2)? as usize,
+            end: start + read_u32(bytes, start + 4)? as usize
+        };
+        if chunk.header_size < 8
+            || chunk.start + chunk.header_size > chunk.end
+            || chunk.end > bytes.len()
+        {
+            return Err(invalid(format!(
+                "The chunk at {start:#x} has an invalid size."
+            )));
+        }
+        Ok(chunk)
+    }
+
+    fn body_start(&self) -> usize {
+        self.start + self.header_size
+    }
+
+    // Chunks that come after this one's header, up to its end
+    fn children(&self, bytes: &[u8]) -> Result<Vec<Chunk>> {
+        let mut chunks = vec![];
+        let mut offset = self.body_start();
+        while offset < self.end {
+            let chunk = Chunk::read(bytes, offset)?;
+            if chunk.end > self.end || chunk.end == offset {
+                return Err(invalid(format!(
+                    "The chunk at {offset:#x} has an invalid size."
+                )));
+            }
+            offset = chunk.end;
+            chunks.push(chunk);
+        }
+        Ok(chunks)
+    }
+}
+
+fn read_element(
+    bytes: &[u8],
+    chunk: &Chunk,
+    strings: &[String],
+    resource_map: &[u32]
+) -> Result<DecodedElement> {
+    // ResXMLTree_attrExt comes straight after the ResXMLTree_node header
+    let ext = chunk.body_start();
+    let attribute_start = read_u16(bytes, ext + 8)? as usize;
+    let attribute_size = read_u16(bytes, ext + 10)? as usize;
+    let attribute_count = read_u16(bytes, ext + 12)? as usize;
+
+    let mut attributes = vec![];
+    for i in 0..attribute_count {
+        let offset = ext + attribute_start + i * attribute_size;
+        if offset + 20 > chunk.end {
+            return Err(invalid("An element's attributes run past its end."));
+        }
+        let name_index = read_u32(bytes, offset + 4)?;
+        let raw_value = read_u32(bytes, offset + 8)?;
+        let mut value = read_value(bytes, offset + 12, strings)?;
+        // aapt2 keeps the original text of attributes that it couldn't compile
+        if value == BinaryValue::Null && raw_value != NO_INDEX {
+            value = BinaryValue::String(string(strings, raw_value)?);
+        }
+        attributes.push(DecodedAttribute {
+            namespace: optional_string(strings, read_u32(bytes, offset)?)?,
+            name: string(strings, name_index)?,
+            resource_id: resource_map.get(name_index as usize).copied(),
+            value
+        });
+    }
+
+    Ok(DecodedElement {
+        namespace: optional_string(strings, read_u32(bytes, ext)?)?,
+        name: string(strings, read_u32(bytes, ext + 4)?)?,
+        attributes,
+        children: vec![]
+    })
+}
+
+fn read_package(bytes: &[u8], chunk: &Chunk, values: &[String]) -> Result<DecodedPackage> {
+    let id = read_u32(bytes, chunk.start + 8)?;
+    let name_units = (0..128)
+        .map(|i| read_u16(bytes, chunk.start + 12 + i * 2))
+        .collect::<Result<Vec<u16>>>()?;
+    let name_len = name_units.iter().position(|unit| *unit == 0).unwrap_or(128);
+    let name = String::from_utf16_lossy(&name_units[..name_len]);
+    let type_strings_start = chunk.start + read_u32(bytes, chunk.start + 268)? as usize;
+    let key_strings_start = chunk.start + read_u32(bytes, chunk.start + 276)? as usize;
+
+    let children = chunk.children(bytes)?;
+    let pool_at = |start: usize| -> Result<Vec<String>> {
+        let pool = children
+            .iter()
+            .find(|child| child.start == start && child.chunk_type == RES_STRING_POOL_TYPE)
+            .ok_or(invalid(format!("Package {name} is missing a string pool.")))?;
+        read_string_pool(bytes, pool)
+    };
+    let type_names = pool_at(type_strings_start)?;
+    let keys = pool_at(key_strings_start)?;
+
+    let mut types: Vec<DecodedType> = vec![];
+    for child in children
+        .iter()
+        .filter(|child| child.chunk_type == RES_TABLE_TYPE_TYPE)
+    {
+        let type_id = read_u8(bytes, child.start + 8)?;
+        let flags = read_u8(bytes, child.start + 9)?;
+        let entry_count = read_u32(bytes, child.start + 12)?;
+        let entries_start = child.start + read_u32(bytes, child.start + 16)? as usize;
+        let config_size = read_u32(bytes, child.start + 20)? as usize;
+        let config_data = bytes
+            .get(child.start + 24..child.start + 20 + config_size.max(4))
+            .ok_or(invalid("A type's config runs past the end of the table."))?;
+        let config = ResourceConfig::from_res_table_config(config_data);
+
+        let res_type = match types.iter().position(|res_type| res_type.id == type_id) {
+            Some(i) => &mut types[i],
+            None => {
+                types.push(DecodedType {
+                    id: type_id,
+                    name: string(
+                        &type_names,
+                        (type_id as u32)
+                            .checked_sub(1)
+                            .ok_or(invalid("A type has an ID of 0."))?
+                    )?,
+                    entry_count,
+                    entries: vec![]
+                });
+                types.last_mut().unwrap()
+            }
+        };
+
+        for (entry_id, offset) in entry_offsets(bytes, child, flags, entry_count)? {
+            let (key, value) = read_entry(bytes, entries_start + offset, child.end, values)?;
+            let entry = match res_type
+                .entries
+                .iter()
+                .position(|entry| entry.id == entry_id)
+            {
+                Some(i) => &mut res_type.entries[i],
+                None => {
+                    res_type.entries.push(DecodedEntry {
+                        id: entry_id,
+                        name: string(&keys, key)?,
+                        values: vec![]
+                    });
+                    res_type.entries.last_mut().unwrap()
+                }
+            };
+            entry.values.push((config.clone(), value));
+        }
+    }
+
+    for res_type in &mut types {
+        res_type.entries.sort_by_key(|entry| entry.id);
+    }
+    types.sort_by_key(|res_type| res_type.id);
+    Ok(DecodedPackage { id, name, types })
+}
+
+// Returns the ID and offset (from entriesStart) of each entry that has a value
+fn entry_offsets(
+    bytes: &[u8],
+    chunk: &Chunk,
+    flags: u8,
+    entry_count: u32
+) -> Result<Vec<(u16, usize)>> {
+    let start = chunk.body_start();
+    let mut offsets = vec![];
+    for i in 0..entry_count as usize {
+        if flags & TYPE_FLAG_SPARSE != 0 {
+            // Pairs of entry ID and offset / 4
+            let id = read_u16(bytes, start + i * 4)?;
+            offsets.push((id, read_u16(bytes, start + i * 4 + 2)? as usize * 4));
+        } else if flags & TYPE_FLAG_OFFSET16 != 0 {
+            let offset = read_u16(bytes, start + i * 2)?;
+            if offset != 0xFFFF {
+                offsets.push((i as u16, offset as usize * 4));
+            }
+        } else {
+            let offset = read_u32(bytes, start + i * 4)?;
+            if offset != NO_INDEX {
+                offsets.push((i as u16, offset as usize));
+            }
+        }
+    }
+    Ok(offsets)
+}
+
+// Returns the key string index and value of the ResTable_entry at `offset`
+fn read_entry(
+    bytes: &[u8],
+    offset: usize,
+    end: usize,
+    values: &[String]
+) -> Result<(u32, BinaryValue)> {
+    if offset + 8 > end {
+        return Err(invalid("An entry runs past the end of its type."));
+    }
+    let size = read_u16(bytes, offset)?;
+    let flags = read_u16(bytes, offset + 2)?;
+
+    if flags & ENTRY_FLAG_COMPACT != 0 {
+        // The key is where the size would be, and the data type is in the
+        // high byte of the flags
+        let value = typed_value((flags >> 8) as u8, read_u32(bytes, offset + 4)?, values)?;
+        return Ok((size as u32, value));
+    }
+
+    let key = read_u32(bytes, offset + 4)?;
+    if flags & ENTRY_FLAG_COMPLEX == 0 {
+        return Ok((key, read_value(bytes, offset + size as usize, values)?));
+    }
+
+    // ResTable_map_entry is followed by `count` ResTable_maps
+    let parent = read_u32(bytes, offset + 8)?;
+    let count = read_u32(bytes, offset + 12)? as usize;
+    let mut items = vec![];
+    for i in 0..count {
+        let map = offset + size as usize + i * 12;
+        if map + 12 > end {
+            return Err(invalid("A complex entry runs past the end of its type."));
+        }
+        items.push((read_u32(bytes, map)?, read_value(bytes, map + 4, values)?));
+    }
+    Ok((
+        key,
+        BinaryValue::Bag {
+            parent: (parent != 0).then_some(parent),
+            items
+        }
+    ))
+}
+
+// Reads a Res_value
+fn read_value(bytes: &[u8], offset: usize, strings: &[String]) -> Result<BinaryValue> {
+    typed_value(
+        read_u8(bytes, offset + 3)?,
+        read_u32(bytes, offset + 4)?,
+        strings
+    )
+}
+
+fn typed_value(data_type: u8, data: u32, strings: &[String]) -> Result<BinaryValue> {
+    Ok(match data_type {
+        TYPE_NULL => BinaryValue::Null,
+        // Dynamic references point into shared libraries, whose package IDs
+        // are only known at runtime, but the ID is still read the same way
+        TYPE_REFERENCE | TYPE_DYNAMIC_REFERENCE => BinaryValue::Reference(data),
+        TYPE_ATTRIBUTE | TYPE_DYNAMIC_ATTRIBUTE => BinaryValue::AttributeReference(data),
+        TYPE_STRING => BinaryValue::String(string(strings, data)?),
+        TYPE_FLOAT => BinaryValue::Float(f32::from_bits(data)),
+        TYPE_INT_DEC | TYPE_INT_HEX => BinaryValue::Integer(data as i32),
+        TYPE_INT_BOOLEAN => BinaryValue::Boolean(data != 0),
+        TYPE_FIRST_COLOR_INT..=TYPE_LAST_COLOR_INT => BinaryValue::Color(data),
+        _ => BinaryValue::Other(data_type, data)
+    })
+}
+
+fn read_string_pool(bytes: &[u8], chunk: &Chunk) -> Result<Vec<String>> {
+    let string_count = read_u32(bytes, chunk.start + 8)? as usize;
+    let flags = read_u32(bytes, chunk.start + 16)?;
+    let strings_start = chunk.start + read_u32(bytes, chunk.start + 20)? as usize;
+    let is_utf8 = flags & STRING_POOL_UTF8_FLAG != 0;
+
+    (0..string_count)
+        .map(|i| {
+            let offset = strings_start + read_u32(bytes, chunk.body_start() + i * 4)? as usize;
+            if offset >= chunk.end {
+                return Err(invalid("A string runs past the end of its pool."));
+            }
+            if is_utf8 {
+                read_utf8_string(&bytes[..chunk.end], offset)
+            } else {
+                read_utf16_string(&bytes[..chunk.end], offset)
+            }
+        })
+        .collect()
+}
+
+// UTF-8 strings are prefixed with their length in characters and then in
+// bytes, each of which take two bytes if the first has its high bit set
+fn read_utf8_string(bytes: &[u8], offset: usize) -> Result<String> {
+    let read_length = |offset: usize| -> Result<(usize, usize)> {
+        let first = read_u8(bytes, offset)? as usize;
+        if first & 0x80 == 0 {
+            Ok((first, 1))
+        } else {
+            Ok((
+                ((first & 0x7F) << 8) | read_u8(bytes, offset + 1)? as usize,
+                2
+            ))
+        }
+    };
+    let (_, char_length_size) = read_length(offset)?;
+    let (byte_length, byte_length_size) = read_length(offset + char_length_size)?;
+    let start = offset + char_length_size + byte_length_size;
+    let string_bytes = bytes
+        .get(start..start + byte_length)
+        .ok_or(invalid("A string runs past the end of its pool."))?;
+    Ok(String::from_utf8_lossy(string_bytes).into_owned())
+}
+
+// UTF-16 strings are prefixed with their length in code units, which takes
+// two u16s if the first has its high bit set
+fn read_utf16_string(bytes: &[u8], offset: usize) -> Result<String> {
+    let first = read_u16(bytes, offset)? as usize;
+    let (length, start) = if first & 0x8000 == 0 {
+        (first, offset + 2)
+    } else {
+        (
+            ((first & 0x7FFF) << 16) | read_u16(bytes, offset + 2)? as usize,
+            offset + 4
+        )
+    };
+    let units = (0..length)
+        .map(|i| read_u16(bytes, start + i * 2))
+        .collect::<Result<Vec<u16>>>()?;
+    Ok(String::from_utf16_lossy(&units))
+}
+
+fn string(strings: &[String], index: u32) -> Result<String> {
+    strings.get(index as usize).cloned().ok_or(invalid(format!(
+        "String {index} is missing from the string pool."
+    )))
+}
+
+fn optional_string(strings: &[String], index: u32) -> Result<Option<String>> {
+    if index == NO_INDEX {
+        Ok(None)
+    } else {
+        string(strings, index).map(Some)
+    }
+}
+
+fn read_u8(bytes: &[u8], offset: usize) -> Result<u8> {
+    bytes.get(offset).copied().ok_or_else(truncated)
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> Result<u16> {
+    let slice = bytes.get(offset..offset + 2).ok_or_else(truncated)?;
+    Ok(u16::from_le_bytes([slice[0], slice[1]]))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32> {
+    let slice = bytes.get(offset..offset + 4).ok_or_else(truncated)?;
+    Ok(u32::from_le_bytes([slice[0], slice[1], slice[2], slice[3]]))
+}
+
+fn truncated() -> PackError {
+    invalid("The data ends unexpectedly.")
+}
+
+fn invalid(reason: impl Into<String>) -> PackError {
+    PackError::BinaryResourceInvalid(reason.into())
+}
@@ -0,0 +1,162 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// aapt2 keeps an attribute like android:label="@string/app_name" as a
+// reference for Android to resolve, but some on-device parsers read compiled
+// XML without resolving anything, so all they'd see is a resource ID. For
+// them, string references in attributes can be replaced with the strings
+// themselves. The compilers do this as they read each attribute, with
+// XmlOptions::inline_string_references, so errors still point at the user's
+// file.
+use std::{borrow::Cow, collections::HashMap, io::Cursor, sync::Arc};
+
+use pack_common::*;
+use sha2::{Digest, Sha256};
+use xml::{
+    attribute::OwnedAttribute,
+    reader::{self, EventReader, ParserConfig},
+    writer::{self, EmitterConfig}
+};
+
+use crate::resource_internal_types::Resource;
+
+const STRING_REFERENCE_PREFIX: &str = "@string/";
+
+/// What each string in the default config stands for, for replacing
+/// references to strings with the strings themselves.
+#[derive(Debug, Default)]
+pub struct StringValues<'a> {
+    // What each string's name stands for, which might be another reference
+    values: HashMap<&'a str, &'a str>
+}
+
+impl<'a> StringValues<'a> {
+    /// Collects the strings, and [aliases](crate::resource_internal_types::AliasResource)
+    /// of strings, in the default config of `resources`.
+    pub fn new(resources: &'a [Resource]) -> Self {
+        let values = resources
+            .iter()
+            .filter(|res| res.get_config().is_default())
+            .filter_map(|res| match res {
+                Resource::String(string) => Some((&string.name[..], &string.value[..])),
+                Resource::Alias(alias) if alias.res_type == "string" => {
+                    Some((&alias.name[..], &alias.target[..]))
+                }
+                _ => None
+            })
+            .collect();
+        StringValues { values }
+    }
+
+    /// Returns the string an attribute value like `@string/app_name` refers
+    /// to, or `None` if it isn't a string reference. A string whose value
+    /// refers to another string is followed to the end, and fails with
+    /// [PackError::ReferenceCycle] if it leads back on itself.
+    pub fn resolve(&self, value: &str) -> Result<Option<&'a str>> {
+        let Some(mut name) = value.trim().strip_prefix(STRING_REFERENCE_PREFIX) else {
+            return Ok(None);
+        };
+        let mut seen: Vec<&str> = vec![];
+        loop {
+            if seen.contains(&name) {
+                let chain: Vec<String> = seen
+                    .iter()
+                    .chain([&name])
+                    .map(|name| format!("{STRING_REFERENCE_PREFIX}{name}"))
+                    .collect();
+                return Err(PackError::ReferenceCycle(chain.join(" -> ")));
+            }
+            seen.push(name);
+            let value = *self.values.get(name).ok_or_else(|| {
+                PackError::ReferenceAttributeLookupFailed(format!(
+                    "{STRING_REFERENCE_PREFIX}{name}"
+                ))
+            })?;
+            match value.trim().strip_prefix(STRING_REFERENCE_PREFIX) {
+                Some(next) => name = next,
+                None => return Ok(Some(value))
+            }
+        }
+    }
+
+    /// A hash of every string, for cache keys. XML compiled with inlined
+    /// strings has to be compiled again when any of them changes.
+    pub fn digest(&self) -> String {
+        let mut values: Vec<(&str, &str)> = self.values.iter().map(|(k, v)| (*k, *v)).collect();
+        values.sort();
+        let mut hasher = Sha256::new();
+        for (name, value) in values {
+            hasher.update(name);
+            hasher.update([0u8]);
+            hasher.update(value);
+            hasher.update([0u8]);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+/// Returns a copy of the XML file `xml` with each attribute that refers to a
+/// string, eg. `@string/app_name`, replaced by the string's value in the
+/// default config, as resolved by [StringValues::resolve]. Other references
+/// and text are left as they are.
+///
+/// The copy is written out again rather than edited in place, so its layout
+/// differs from `xml`. Builds don't use this, see
+/// [XmlOptions::inline_string_references](crate::xml_file::XmlOptions::inline_string_references).
+pub fn inline_string_references(xml: &[u8], resources: &[Resource]) -> Result<Vec<u8>> {
+    let values = StringValues::new(resources);
+
+    let mut output = vec![];
+    let mut writer = EmitterConfig::new()
+        .perform_indent(false)
+        .create_writer(&mut output);
+    let write_err = |e| PackError::XmlWritingFailed(Arc::new(e));
+    let reader =
+        EventReader::new_with_config(Cursor::new(xml), ParserConfig::new().ignore_comments(false));
+
+    for event in reader {
+        let event = event.map_err(PackError::XmlParsingFailed)?;
+        match &event {
+            reader::XmlEvent::StartElement {
+                name,
+                attributes,
+                namespace
+            } => {
+                let attributes = attributes
+                    .iter()
+                    .map(|attr| {
+                        Ok(OwnedAttribute::new(
+                            attr.name.clone(),
+                            values.resolve(&attr.value)?.unwrap_or(&attr.value)
+                        ))
+                    })
+                    .collect::<Result<Vec<OwnedAttribute>>>()?;
+                writer
+                    .write(writer::XmlEvent::StartElement {
+                        name: name.borrow(),
+                        attributes: Cow::Owned(attributes.iter().map(|a| a.borrow()).collect()),
+                        namespace: Cow::Borrowed(namespace)
+                    })
+                    .map_err(write_err)?;
+            }
+            _ => {
+                if let Some(event) = event.as_writer_event() {
+                    writer.write(event).map_err(write_err)?;
+                }
+            }
+        }
+    }
+
+    Ok(output)
+}
@@ -21,9 +21,9 @@ include!(concat!(env!("OUT_DIR"), "/internal_attributes_map.rs"));
 
 pub fn infer_attribute_type(value: &String) -> AttributeDataType {
     if value.parse::<u32>().is_ok() {
-        AttributeDataType::DecimalInteger
+        AttributeDataType::IntDec
     } else if value == "true" || value == "false" {
-        AttributeDataType::BooleanInteger
+        AttributeDataType::IntBoolean
     } else if value.starts_with("@") {
         AttributeDataType::Reference
     } else {
@@ -16,14 +16,25 @@ use deku::DekuContainerWrite;
 use pack_common::*;
 use resource_external_types::{ChunkType, ResChunk, ResChunkHeader};
 
+pub mod build_cache;
+pub mod decoder;
+pub mod inline_strings;
 pub mod internal_android_attributes;
+pub mod locale_config;
+pub mod manifest_overrides;
+pub mod names;
+pub mod public_xml_parser;
+pub mod resource_config;
 pub mod resource_external_types;
+pub mod resource_graph;
 pub mod resource_internal_types;
 pub mod resource_table;
 pub mod string_pool;
 pub mod strings_xml_parser;
+pub mod table_editor;
 pub mod xml_file;
 pub mod xml_first_pass;
+pub mod xml_namespaces;
 
 pub fn generate_res_chunk<T: DekuContainerWrite>(
     chunk_type: ChunkType,
@@ -31,17 +42,20 @@ pub fn generate_res_chunk<T: DekuContainerWrite>(
     extra_header_size: u16,
     extra_chunk_size: u16
 ) -> Result<ResChunk> {
-    let data_bytes = data.to_bytes()?;
-    let data = ResChunk {
+    let mut data_bytes = data.to_bytes()?;
+    // Android requires every chunk to fall on a 32-bit boundary, so chunks
+    // are padded out to a multiple of 4 bytes with zeros
+    let unpadded_size = 0x08 + extra_chunk_size as usize + data_bytes.len();
+    data_bytes.resize(
+        data_bytes.len() + unpadded_size.next_multiple_of(4) - unpadded_size,
+        0
+    );
+    Ok(ResChunk {
         header: ResChunkHeader {
             chunk_type,
             header_size: 0x08 + extra_header_size,
             chunk_size: 0x08 + extra_chunk_size as u32 + data_bytes.len() as u32
         },
         data: data_bytes
-    };
-    if !data.header.chunk_size.is_multiple_of(4) {
-        unimplemented!("Generic chunk alignment ({:?})", data);
-    }
-    Ok(data)
+    })
 }
@@ -0,0 +1,227 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// From Android 13, people can pick a language for each app in the system
+// settings, but only from the locales the app lists in a locale config: an
+// XML resource like res/xml/locales_config.xml, referred to by
+// android:localeConfig on <application>. PACK can write one listing every
+// locale a package has resources for, and checks that a hand-written one
+// doesn't leave any out.
+use std::{borrow::Cow, io::Cursor, sync::Arc};
+
+use pack_common::*;
+use xml::{
+    attribute::OwnedAttribute,
+    name::OwnedName,
+    reader::{self, EventReader, ParserConfig},
+    writer::{self, EmitterConfig}
+};
+
+use crate::resource_internal_types::Resource;
+
+/// The name PACK gives a locale config it writes, in res/xml/.
+pub const LOCALE_CONFIG_FILE_NAME: &str = "locales_config.xml";
+
+const ANDROID_NAMESPACE: &str = "http://schemas.android.com/apk/res/android";
+const ANDROID_PREFIX: &str = "android";
+
+/// Every locale that `resources` have values for, as BCP-47 tags like `de`
+/// or `fr-CA`, sorted and without duplicates.
+pub fn resource_locales(resources: &[Resource]) -> Vec<String> {
+    let mut locales: Vec<String> = resources
+        .iter()
+        .filter_map(|res| res.get_config().locale_tag())
+        .collect();
+    locales.sort();
+    locales.dedup();
+    locales
+}
+
+/// Writes a locale config listing `default_locale`, the language of the
+/// resources without a locale qualifier, followed by `locales`.
+pub fn locale_config_xml(default_locale: &str, locales: &[String]) -> Result<Vec<u8>> {
+    check_locale_tag(default_locale)?;
+    let mut xml = format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<locale-config xmlns:android=\"{ANDROID_NAMESPACE}\">\n    <locale android:name=\"{default_locale}\"/>\n"
+    );
+    for locale in locales {
+        if !locale.eq_ignore_ascii_case(default_locale) {
+            xml.push_str(&format!("    <locale android:name=\"{locale}\"/>\n"));
+        }
+    }
+    xml.push_str("</locale-config>\n");
+    Ok(xml.into_bytes())
+}
+
+/// Returns the locales a locale config lists, in order.
+pub fn parse_locale_config(xml: &[u8]) -> Result<Vec<String>> {
+    let mut locales = vec![];
+    let mut depth = 0;
+    for event in EventReader::new(Cursor::new(xml)) {
+        match event.map_err(PackError::XmlParsingFailed)? {
+            reader::XmlEvent::StartElement {
+                name, attributes, ..
+            } => {
+                depth += 1;
+                match (depth, &name.local_name[..]) {
+                    (1, "locale-config") | (2, "locale") => {}
+                    (1, other) => {
+                        return Err(PackError::LocaleConfigInvalid(format!(
+                            "Its root element is <{other}>, but needs to be <locale-config>."
+                        )))
+                    }
+                    (_, other) => {
+                        return Err(PackError::LocaleConfigInvalid(format!(
+                            "<{other}> isn't allowed there. It can only have <locale> elements."
+                        )))
+                    }
+                }
+                if depth == 2 {
+                    let locale = android_attribute(&attributes, "name").ok_or(
+                        PackError::LocaleConfigInvalid("A <locale> has no android:name.".into())
+                    )?;
+                    check_locale_tag(locale)?;
+                    locales.push(locale.to_string());
+                }
+            }
+            reader::XmlEvent::EndElement { .. } => depth -= 1,
+            _ => {}
+        }
+    }
+    Ok(locales)
+}
+
+/// Returns which locales in `locales` a locale config listing `listed`
+/// leaves out. Tags are compared ignoring case, as Android does.
+pub fn missing_locales<'a>(listed: &[String], locales: &'a [String]) -> Vec<&'a str> {
+    locales
+        .iter()
+        .filter(|locale| {
+            !listed
+                .iter()
+                .any(|listed| listed.eq_ignore_ascii_case(locale))
+        })
+        .map(|locale| &locale[..])
+        .collect()
+}
+
+/// Returns the value of `android:localeConfig` on the manifest's
+/// `<application>`, eg. `@xml/locales_config`, if it has one.
+pub fn manifest_locale_config(manifest: &[u8]) -> Result<Option<String>> {
+    let mut depth = 0;
+    for event in EventReader::new(Cursor::new(manifest)) {
+        match event.map_err(PackError::XmlParsingFailed)? {
+            reader::XmlEvent::StartElement {
+                name, attributes, ..
+            } => {
+                depth += 1;
+                if depth == 2 && name.local_name == "application" {
+                    return Ok(android_attribute(&attributes, "localeConfig").map(String::from));
+                }
+            }
+            reader::XmlEvent::EndElement { .. } => depth -= 1,
+            _ => {}
+        }
+    }
+    Ok(None)
+}
+
+/// Returns a copy of `manifest` with `android:localeConfig` on its
+/// `<application>` set to `reference`, eg. `@xml/locales_config`. Comments
+/// are kept, so they can still be compiled into the manifest.
+pub fn set_manifest_locale_config(manifest: &[u8], reference: &str) -> Result<Vec<u8>> {
+    let mut output = vec![];
+    let mut writer = EmitterConfig::new()
+        .perform_indent(false)
+        .create_writer(&mut output);
+    let write_err = |e| PackError::XmlWritingFailed(Arc::new(e));
+    let mut depth = 0;
+    let mut found_application = false;
+    let reader = EventReader::new_with_config(
+        Cursor::new(manifest),
+        ParserConfig::new().ignore_comments(false)
+    );
+
+    for event in reader {
+        let event = event.map_err(PackError::XmlParsingFailed)?;
+        match &event {
+            reader::XmlEvent::StartElement {
+                name,
+                attributes,
+                namespace
+            } => {
+                depth += 1;
+                if depth == 2 && name.local_name == "application" {
+                    found_application = true;
+                    let mut attributes = attributes.clone();
+                    attributes.retain(|attr| !is_android_attribute(attr, "localeConfig"));
+                    attributes.push(OwnedAttribute::new(
+                        OwnedName::qualified(
+                            "localeConfig",
+                            ANDROID_NAMESPACE,
+                            Some(ANDROID_PREFIX)
+                        ),
+                        reference
+                    ));
+                    writer
+                        .write(writer::XmlEvent::StartElement {
+                            name: name.borrow(),
+                            attributes: Cow::Owned(attributes.iter().map(|a| a.borrow()).collect()),
+                            namespace: Cow::Borrowed(namespace)
+                        })
+                        .map_err(write_err)?;
+                    continue;
+                }
+            }
+            reader::XmlEvent::EndElement { .. } => depth -= 1,
+            _ => {}
+        }
+        if let Some(event) = event.as_writer_event() {
+            writer.write(event).map_err(write_err)?;
+        }
+    }
+
+    if !found_application {
+        return Err(PackError::LocaleConfigInvalid(
+            "AndroidManifest.xml has no <application> to set android:localeConfig on.".into()
+        ));
+    }
+    Ok(output)
+}
+
+fn android_attribute<'a>(attributes: &'a [OwnedAttribute], local_name: &str) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|attr| is_android_attribute(attr, local_name))
+        .map(|attr| &attr.value[..])
+}
+
+fn is_android_attribute(attr: &OwnedAttribute, local_name: &str) -> bool {
+    attr.name.local_name == local_name && attr.name.namespace.as_deref() == Some(ANDROID_NAMESPACE)
+}
+
+// Only checks the shape of a tag, eg. `en` or `zh-Hant-TW`, not whether its
+// parts are real languages or regions
+fn check_locale_tag(tag: &str) -> Result<()> {
+    let valid = !tag.is_empty()
+        && tag.split('-').all(|part| {
+            (1..=8).contains(&part.len()) && part.chars().all(|c| c.is_ascii_alphanumeric())
+        });
+    if !valid {
+        return Err(PackError::LocaleConfigInvalid(format!(
+            "\"{tag}\" isn't a locale tag like en or pt-BR."
+        )));
+    }
+    Ok(())
+}
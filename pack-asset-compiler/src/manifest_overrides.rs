@@ -0,0 +1,376 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// The same project is often built in slightly different ways, eg. a debug build
+// with a `.debug` package suffix so it can be installed next to the release,
+// or a CI build with its own version code. Rather than keeping several copies
+// of AndroidManifest.xml, these are patched into the <manifest> element before
+// it's compiled. Overlays get their <overlay> element the same way.
+//
+// Template manifests are also written for Gradle's manifest merger, whose
+// tools:node and tools:remove markers drop parts of a manifest. PACK doesn't
+// merge manifests, but it applies those markers the same way.
+use std::{borrow::Cow, io::Cursor, sync::Arc};
+
+use pack_common::*;
+use xml::{
+    attribute::OwnedAttribute,
+    name::OwnedName,
+    reader::{self, EventReader},
+    writer::{self, EmitterConfig}
+};
+
+use crate::xml_namespaces::TOOLS_NAMESPACE;
+
+const ANDROID_NAMESPACE: &str = "http://schemas.android.com/apk/res/android";
+const ANDROID_PREFIX: &str = "android";
+
+// The tools: attributes that control the manifest merger
+const MERGE_MARKERS: &[&str] = &[
+    "node",
+    "remove",
+    "replace",
+    "strict",
+    "selector",
+    "overrideLibrary"
+];
+// The values tools:node can have
+const NODE_MODES: &[&str] = &[
+    "merge",
+    "merge-only-attributes",
+    "replace",
+    "strict",
+    "remove",
+    "removeAll"
+];
+
+/// Changes to make to an AndroidManifest.xml before building it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct ManifestOverrides {
+    /// Replaces `android:versionCode`.
+    pub version_code: Option<u32>,
+    /// Replaces `android:versionName`.
+    pub version_name: Option<String>,
+    /// Appended to the `package` attribute, eg. `.debug`.
+    pub package_suffix: Option<String>
+}
+
+impl ManifestOverrides {
+    pub fn is_empty(&self) -> bool {
+        *self == ManifestOverrides::default()
+    }
+}
+
+/// The app whose resources a runtime resource overlay (RRO) replaces.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OverlayTarget {
+    /// The target's package name, eg. `com.example.watchface`.
+    pub target_package: String,
+    /// The `<overlayable>` group in the target whose resources are replaced,
+    /// eg. `ThemeResources`. From Android 11, overlays that aren't
+    /// preinstalled can only replace overlayable resources.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub target_name: Option<String>
+}
+
+/// Returns a copy of `manifest` with the overrides applied to its root
+/// `<manifest>` element. Comments aren't kept.
+pub fn apply_manifest_overrides(manifest: &[u8], overrides: &ManifestOverrides) -> Result<Vec<u8>> {
+    if overrides.is_empty() {
+        return Ok(manifest.to_vec());
+    }
+
+    let mut output = vec![];
+    let mut writer = EmitterConfig::new()
+        .perform_indent(false)
+        .create_writer(&mut output);
+    let write_err = |e| PackError::XmlWritingFailed(Arc::new(e));
+    let mut is_root = true;
+
+    for event in EventReader::new(Cursor::new(manifest)) {
+        let event = event.map_err(PackError::XmlParsingFailed)?;
+        match &event {
+            reader::XmlEvent::StartElement {
+                name,
+                attributes,
+                namespace
+            } if is_root => {
+                is_root = false;
+                let attributes = override_attributes(attributes, overrides)?;
+                writer
+                    .write(writer::XmlEvent::StartElement {
+                        name: name.borrow(),
+                        attributes: Cow::Owned(attributes.iter().map(|a| a.borrow()).collect()),
+                        namespace: Cow::Borrowed(namespace)
+                    })
+                    .map_err(write_err)?;
+            }
+            _ => {
+                if let Some(event) = event.as_writer_event() {
+                    writer.write(event).map_err(write_err)?;
+                }
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Returns a copy of `manifest` with an `<overlay>` element for `target` in its
+/// root `<manifest>` element, in place of any `<overlay>` it already had.
+/// Comments aren't kept.
+pub fn apply_overlay_target(manifest: &[u8], target: &OverlayTarget) -> Result<Vec<u8>> {
+    let mut output = vec![];
+    let mut writer = EmitterConfig::new()
+        .perform_indent(false)
+        .create_writer(&mut output);
+    let write_err = |e| PackError::XmlWritingFailed(Arc::new(e));
+    let mut depth = 0;
+    // The depth of an existing <overlay> while it's being left out
+    let mut skipping: Option<usize> = None;
+
+    for event in EventReader::new(Cursor::new(manifest)) {
+        let event = event.map_err(PackError::XmlParsingFailed)?;
+        let skip = match &event {
+            reader::XmlEvent::StartElement { name, .. } => {
+                depth += 1;
+                if depth == 2 && name.local_name == "overlay" && name.namespace.is_none() {
+                    skipping = Some(depth);
+                }
+                skipping.is_some()
+            }
+            reader::XmlEvent::EndElement { .. } => {
+                let skip = skipping.is_some();
+                if skipping == Some(depth) {
+                    skipping = None;
+                }
+                depth -= 1;
+                skip
+            }
+            _ => skipping.is_some()
+        };
+        if skip {
+            continue;
+        }
+        if let Some(writer_event) = event.as_writer_event() {
+            writer.write(writer_event).map_err(write_err)?;
+        }
+
+        // The <overlay> goes first, straight after <manifest> opens
+        if depth == 1 && matches!(event, reader::XmlEvent::StartElement { .. }) {
+            let mut overlay = writer::XmlEvent::start_element("overlay")
+                .ns(ANDROID_PREFIX, ANDROID_NAMESPACE)
+                .attr("android:targetPackage", &target.target_package);
+            if let Some(target_name) = &target.target_name {
+                overlay = overlay.attr("android:targetName", target_name);
+            }
+            writer.write(overlay).map_err(write_err)?;
+            writer
+                .write(writer::XmlEvent::end_element())
+                .map_err(write_err)?;
+        }
+    }
+
+    Ok(output)
+}
+
+/// Returns a copy of `manifest` with the manifest merger's markers applied,
+/// as far as they can be without other manifests to merge with:
+///
+///  - An element with `tools:node="remove"` is left out, along with
+///    everything inside it. `tools:node="removeAll"` leaves out every element
+///    with the same name in the same parent.
+///  - The attributes listed in `tools:remove`, eg. `android:required`, are
+///    left out of the element.
+///  - The other `tools:node` modes and markers, like `tools:replace`, have
+///    nothing to merge with, so they're only removed.
+///
+/// The markers never make it into the compiled manifest, whatever
+/// [ToolsNamespace](crate::xml_namespaces::ToolsNamespace) says about other
+/// `tools:` attributes. A manifest without any is returned unchanged,
+/// otherwise comments aren't kept.
+pub fn apply_merge_markers(manifest: &[u8]) -> Result<Vec<u8>> {
+    let events = EventReader::new(Cursor::new(manifest))
+        .into_iter()
+        .collect::<std::result::Result<Vec<reader::XmlEvent>, _>>()
+        .map_err(PackError::XmlParsingFailed)?;
+    let has_markers = events.iter().any(|event| match event {
+        reader::XmlEvent::StartElement { attributes, .. } => attributes.iter().any(is_merge_marker),
+        _ => false
+    });
+    if !has_markers {
+        return Ok(manifest.to_vec());
+    }
+
+    // Elements are numbered in the order they start. Each removeAll is noted
+    // as its parent's number and its own name, the elements it removes.
+    let mut remove_all: Vec<(Option<usize>, &OwnedName)> = vec![];
+    let mut parents: Vec<usize> = vec![];
+    let mut next_index = 0;
+    for event in &events {
+        match event {
+            reader::XmlEvent::StartElement {
+                name, attributes, ..
+            } => {
+                if let Some(mode) = tools_attribute(attributes, "node") {
+                    if !NODE_MODES.contains(&mode) {
+                        return Err(PackError::MergeMarkerInvalid(format!(
+                            "<{}> has tools:node=\"{mode}\", which isn't one of {}.",
+                            name.borrow().to_repr(),
+                            NODE_MODES.join(", ")
+                        )));
+                    }
+                    if mode == "removeAll" {
+                        remove_all.push((parents.last().copied(), name));
+                    }
+                }
+                parents.push(next_index);
+                next_index += 1;
+            }
+            reader::XmlEvent::EndElement { .. } => {
+                parents.pop();
+            }
+            _ => {}
+        }
+    }
+
+    let mut output = vec![];
+    let mut writer = EmitterConfig::new()
+        .perform_indent(false)
+        .create_writer(&mut output);
+    let write_err = |e| PackError::XmlWritingFailed(Arc::new(e));
+    let mut parents: Vec<usize> = vec![];
+    let mut next_index = 0;
+    // The depth of an element while it's being left out
+    let mut skipping: Option<usize> = None;
+
+    for event in &events {
+        match event {
+            reader::XmlEvent::StartElement {
+                name,
+                attributes,
+                namespace
+            } => {
+                let removed = tools_attribute(attributes, "node") == Some("remove")
+                    || remove_all.contains(&(parents.last().copied(), name));
+                parents.push(next_index);
+                next_index += 1;
+                if skipping.is_none() && removed {
+                    skipping = Some(parents.len());
+                }
+                if skipping.is_some() {
+                    continue;
+                }
+                let attributes = unmarked_attributes(attributes);
+                writer
+                    .write(writer::XmlEvent::StartElement {
+                        name: name.borrow(),
+                        attributes: Cow::Owned(attributes.iter().map(|a| a.borrow()).collect()),
+                        namespace: Cow::Borrowed(namespace)
+                    })
+                    .map_err(write_err)?;
+            }
+            reader::XmlEvent::EndElement { .. } => {
+                let skip = skipping.is_some();
+                if skipping == Some(parents.len()) {
+                    skipping = None;
+                }
+                parents.pop();
+                if !skip {
+                    writer
+                        .write(writer::XmlEvent::end_element())
+                        .map_err(write_err)?;
+                }
+            }
+            _ if skipping.is_some() => {}
+            _ => {
+                if let Some(event) = event.as_writer_event() {
+                    writer.write(event).map_err(write_err)?;
+                }
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+fn is_merge_marker(attr: &OwnedAttribute) -> bool {
+    attr.name.namespace.as_deref() == Some(TOOLS_NAMESPACE)
+        && MERGE_MARKERS.contains(&&attr.name.local_name[..])
+}
+
+fn tools_attribute<'a>(attributes: &'a [OwnedAttribute], local_name: &str) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|attr| {
+            attr.name.namespace.as_deref() == Some(TOOLS_NAMESPACE)
+                && attr.name.local_name == local_name
+        })
+        .map(|attr| attr.value.trim())
+}
+
+// An element's attributes without the merge markers or the attributes its
+// tools:remove lists, which are written with their prefix, eg. android:required
+fn unmarked_attributes(attributes: &[OwnedAttribute]) -> Vec<OwnedAttribute> {
+    let removed: Vec<&str> = tools_attribute(attributes, "remove")
+        .map(|names| names.split(',').map(str::trim).collect())
+        .unwrap_or_default();
+    attributes
+        .iter()
+        .filter(|attr| !is_merge_marker(attr))
+        .filter(|attr| !removed.contains(&&attr.name.borrow().to_repr()[..]))
+        .cloned()
+        .collect()
+}
+
+fn override_attributes(
+    attributes: &[OwnedAttribute],
+    overrides: &ManifestOverrides
+) -> Result<Vec<OwnedAttribute>> {
+    let mut attributes = attributes.to_vec();
+
+    if let Some(suffix) = &overrides.package_suffix {
+        let package = attributes
+            .iter_mut()
+            .find(|attr| attr.name.local_name == "package" && attr.name.namespace.is_none())
+            .ok_or(PackError::ManifestDoesNotHavePackageName)?;
+        package.value.push_str(suffix);
+    }
+
+    let android_values = [
+        (
+            "versionCode",
+            overrides.version_code.map(|code| code.to_string())
+        ),
+        ("versionName", overrides.version_name.clone())
+    ];
+    for (local_name, value) in android_values {
+        let Some(value) = value else {
+            continue;
+        };
+        attributes.retain(|attr| {
+            !(attr.name.local_name == local_name
+                && attr.name.namespace.as_deref() == Some(ANDROID_NAMESPACE))
+        });
+        attributes.push(OwnedAttribute::new(
+            OwnedName::qualified(local_name, ANDROID_NAMESPACE, Some(ANDROID_PREFIX)),
+            value
+        ));
+    }
+
+    Ok(attributes)
+}
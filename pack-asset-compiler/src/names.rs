@@ -0,0 +1,157 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Android won't install a package whose name, or whose resources' names,
+// break its rules, but nothing says so until then. These checks run while
+// building instead, and point at the first character that's wrong.
+use pack_common::{PackError, Result};
+
+use crate::resource_internal_types::{FileResource, Resource};
+
+/// Checks that `name` is a package name Android can install, eg.
+/// `com.example.watchface`. It needs at least two parts separated by periods,
+/// each starting with a letter and then only using letters, digits and
+/// underscores.
+pub fn validate_package_name(name: &str) -> Result<()> {
+    let invalid = |reason: String| {
+        Err(PackError::PackageNameInvalid(format!(
+            "\"{name}\" {reason}"
+        )))
+    };
+    if !name.contains('.') {
+        return invalid(format!(
+            "needs at least two parts separated by periods, eg. \"com.example.{name}\"."
+        ));
+    }
+
+    let mut part_start = true;
+    for (index, c) in name.chars().enumerate() {
+        let position = index + 1;
+        if c == '.' {
+            if part_start {
+                return invalid(format!(
+                    "has a period at position {position} with nothing before it."
+                ));
+            }
+            part_start = true;
+        } else if !(c.is_ascii_alphanumeric() || c == '_') {
+            let suggestion: String = name
+                .chars()
+                .map(|c| {
+                    if c.is_ascii_alphanumeric() || c == '_' || c == '.' {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect();
+            return invalid(format!(
+                "has \"{c}\" at position {position}, which package names can't contain. They can only use letters, digits and underscores between periods. Try \"{suggestion}\"."
+            ));
+        } else if part_start && !c.is_ascii_alphabetic() {
+            return invalid(format!(
+                "has \"{c}\" at position {position}, but each part has to start with a letter."
+            ));
+        } else {
+            part_start = false;
+        }
+    }
+    if part_start {
+        return invalid("ends with a period.".into());
+    }
+    Ok(())
+}
+
+/// Checks that a resource's name can be referenced, eg. as `@string/name`.
+/// Names start with a letter or underscore, and then only use letters,
+/// digits, underscores and periods. See [validate_file_resource_name] for
+/// files, which are stricter.
+pub fn validate_resource_name(resource: &Resource) -> Result<()> {
+    match resource {
+        Resource::File(file) => validate_file_resource_name(file),
+        Resource::String(string) => {
+            check_name(&format!("@string/{}", string.name), &string.name, false)
+        }
+        Resource::Alias(alias) => check_name(
+            &format!("@{}/{}", alias.res_type, alias.name),
+            &alias.name,
+            false
+        )
+    }
+}
+
+/// Checks the name of a file-based resource, ie. its file name without the
+/// extension. Like aapt2, this only allows lowercase letters.
+pub fn validate_file_resource_name(file: &FileResource) -> Result<()> {
+    check_name(&file.get_path(), &file.get_basename()?, true)
+}
+
+// `context` says where the name came from, eg. the path of its file
+fn check_name(context: &str, name: &str, lowercase: bool) -> Result<()> {
+    if name.is_empty() {
+        return Err(PackError::ResourceNameInvalid(format!(
+            "{context} has an empty name."
+        )));
+    }
+    let allowed = |c: char| {
+        c.is_ascii_lowercase()
+            || (c.is_ascii_uppercase() && !lowercase)
+            || c.is_ascii_digit()
+            || c == '_'
+            || c == '.'
+    };
+    let suggestion = || {
+        let mut suggestion: String = name
+            .chars()
+            .map(|c| match c {
+                c if allowed(c) => c,
+                c if c.is_ascii_uppercase() => c.to_ascii_lowercase(),
+                _ => '_'
+            })
+            .collect();
+        if suggestion.starts_with(|c: char| !(c.is_ascii_alphabetic() || c == '_')) {
+            suggestion.insert(0, '_');
+        }
+        suggestion.trim_end_matches('.').to_string()
+    };
+    let invalid = |reason: String| {
+        Err(PackError::ResourceNameInvalid(format!(
+            "{context} {reason} Try \"{}\".",
+            suggestion()
+        )))
+    };
+
+    for (index, c) in name.chars().enumerate() {
+        let position = index + 1;
+        if c.is_ascii_uppercase() && lowercase {
+            return invalid(format!(
+                "has \"{c}\" at position {position} of its name, but file-based resource names have to be lowercase."
+            ));
+        }
+        if !allowed(c) {
+            return invalid(format!(
+                "has \"{c}\" at position {position} of its name, which resource names can't contain. They can only use letters, digits, underscores and periods."
+            ));
+        }
+        if index == 0 && !(c.is_ascii_alphabetic() || c == '_') {
+            return invalid(format!(
+                "has \"{c}\" at position {position} of its name, but resource names have to start with a letter or underscore."
+            ));
+        }
+    }
+    if name.ends_with('.') {
+        return invalid("has a name that ends with a period.".into());
+    }
+    Ok(())
+}
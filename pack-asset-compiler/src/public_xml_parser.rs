@@ -0,0 +1,189 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// res/values/public.xml (and overlayable.xml) don't define any resources, they
+// declare things *about* other resources: whether they're public, and whether
+// runtime resource overlays (RROs) are allowed to replace them. Watch faces
+// that can be customised with RROs need these declarations.
+//
+// <resources>
+//     <public type="string" name="primary_color" />
+//     <java-symbol type="drawable" name="internal_texture" />
+//     <overlayable name="WatchFaceColors">
+//         <policy type="public|signature">
+//             <item type="string" name="primary_color" />
+//         </policy>
+//     </overlayable>
+// </resources>
+use std::io::Read;
+
+use pack_common::*;
+use xml::{attribute::OwnedAttribute, reader::XmlEvent, EventReader};
+
+/// Whether a resource can be accessed from outside its package.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceVisibility {
+    /// Declared with `<public>`.
+    Public,
+    /// Declared with `<java-symbol>` or `<symbol>`.
+    Private
+}
+
+/// Who is allowed to overlay a resource, from `<policy type="...">`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverlayablePolicy {
+    Public,
+    System,
+    Vendor,
+    Product,
+    Signature,
+    Odm,
+    Oem,
+    Actor,
+    ConfigSignature
+}
+
+const OVERLAYABLE_POLICIES: &[(&str, OverlayablePolicy)] = &[
+    ("public", OverlayablePolicy::Public),
+    ("system", OverlayablePolicy::System),
+    ("vendor", OverlayablePolicy::Vendor),
+    ("product", OverlayablePolicy::Product),
+    ("signature", OverlayablePolicy::Signature),
+    ("odm", OverlayablePolicy::Odm),
+    ("oem", OverlayablePolicy::Oem),
+    ("actor", OverlayablePolicy::Actor),
+    ("config_signature", OverlayablePolicy::ConfigSignature)
+];
+
+/// A group of resources that can be replaced by runtime resource overlays.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overlayable {
+    /// eg. `WatchFaceColors`
+    pub name: String,
+    /// The component that enables and disables overlays, eg. `overlay://theme`.
+    pub actor: Option<String>,
+    pub items: Vec<OverlayableItem>
+}
+
+/// A resource inside an `<overlayable>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OverlayableItem {
+    /// eg. `string`
+    pub res_type: String,
+    /// eg. `primary_color`
+    pub name: String,
+    pub policies: Vec<OverlayablePolicy>
+}
+
+/// Everything declared about resources in `public.xml`-style files.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResourceDeclarations {
+    /// Resources with an explicit visibility, as (type, name, visibility).
+    pub visibility: Vec<(String, String, ResourceVisibility)>,
+    pub overlayables: Vec<Overlayable>
+}
+
+impl ResourceDeclarations {
+    /// Adds the declarations from another file.
+    pub fn extend(&mut self, other: ResourceDeclarations) {
+        self.visibility.extend(other.visibility);
+        self.overlayables.extend(other.overlayables);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.visibility.is_empty() && self.overlayables.is_empty()
+    }
+}
+
+pub fn parse_public_xml<T: Read>(byte_source: &mut T) -> Result<ResourceDeclarations> {
+    let xml_source = EventReader::new(byte_source);
+    let mut declarations = ResourceDeclarations::default();
+    // The policies of the <policy> element we're inside, if any
+    let mut policies: Option<Vec<OverlayablePolicy>> = None;
+
+    for event in xml_source {
+        match event.map_err(PackError::XmlParsingFailed)? {
+            XmlEvent::StartElement {
+                name, attributes, ..
+            } => match &name.local_name[..] {
+                "public" | "java-symbol" | "symbol" => {
+                    let visibility = if name.local_name == "public" {
+                        ResourceVisibility::Public
+                    } else {
+                        ResourceVisibility::Private
+                    };
+                    declarations.visibility.push((
+                        required_attribute(&attributes, &name.local_name, "type")?,
+                        required_attribute(&attributes, &name.local_name, "name")?,
+                        visibility
+                    ));
+                }
+                "overlayable" => declarations.overlayables.push(Overlayable {
+                    name: required_attribute(&attributes, "overlayable", "name")?,
+                    actor: attribute(&attributes, "actor"),
+                    items: vec![]
+                }),
+                "policy" => {
+                    let policy_types = required_attribute(&attributes, "policy", "type")?;
+                    policies = Some(
+                        policy_types
+                            .split('|')
+                            .map(|policy_type| {
+                                OVERLAYABLE_POLICIES
+                                    .iter()
+                                    .find(|(name, _)| *name == policy_type.trim())
+                                    .map(|(_, policy)| *policy)
+                                    .ok_or(PackError::ResourceDeclarationInvalid(format!(
+                                        "Unknown overlayable policy \"{policy_type}\"."
+                                    )))
+                            })
+                            .collect::<Result<Vec<_>>>()?
+                    );
+                }
+                "item" => {
+                    // <item> is only meaningful inside <overlayable><policy>
+                    let (Some(overlayable), Some(policies)) =
+                        (declarations.overlayables.last_mut(), &policies)
+                    else {
+                        continue;
+                    };
+                    overlayable.items.push(OverlayableItem {
+                        res_type: required_attribute(&attributes, "item", "type")?,
+                        name: required_attribute(&attributes, "item", "name")?,
+                        policies: policies.clone()
+                    });
+                }
+                // Don't care about most structural elements
+                _ => {}
+            },
+            XmlEvent::EndElement { name } if name.local_name == "policy" => policies = None,
+            _ => {}
+        }
+    }
+
+    Ok(declarations)
+}
+
+fn attribute(attributes: &[OwnedAttribute], key: &str) -> Option<String> {
+    attributes
+        .iter()
+        .find(|attr| attr.name.local_name == key && attr.name.namespace.is_none())
+        .map(|attr| attr.value.clone())
+}
+
+fn required_attribute(attributes: &[OwnedAttribute], element: &str, key: &str) -> Result<String> {
+    attribute(attributes, key).ok_or(PackError::ResourceDeclarationInvalid(format!(
+        "<{element}> is missing its \"{key}\" attribute."
+    )))
+}
@@ -0,0 +1,291 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Resources can have alternative versions for different device configurations,
+// which are expressed as qualifiers on their res/ subdirectory, for example
+// `drawable-xxhdpi` or `values-de-rAT`.
+//
+// Only the qualifiers that are useful for watch faces are supported.
+use pack_common::*;
+
+/// Density value for `nodpi` resources, which are never scaled.
+pub const DENSITY_NONE: u16 = 0xFFFF;
+/// Density value for `anydpi` resources, which suit every density.
+pub const DENSITY_ANY: u16 = 0xFFFE;
+
+const DENSITY_QUALIFIERS: &[(&str, u16)] = &[
+    ("ldpi", 120),
+    ("mdpi", 160),
+    ("tvdpi", 213),
+    ("hdpi", 240),
+    ("xhdpi", 320),
+    ("xxhdpi", 480),
+    ("xxxhdpi", 640),
+    ("nodpi", DENSITY_NONE),
+    ("anydpi", DENSITY_ANY)
+];
+
+// Bits of ResTable_typeSpec's configuration change flags, from ActivityInfo.java
+pub const CONFIG_CHANGE_LOCALE: u32 = 0x0004;
+pub const CONFIG_CHANGE_DENSITY: u32 = 0x0100;
+pub const CONFIG_CHANGE_VERSION: u32 = 0x0400;
+pub const CONFIG_CHANGE_SCREEN_ROUND: u32 = 0x8000;
+
+// Values of ResTable_config.screenLayout2
+const SCREEN_ROUND_NO: u8 = 0x01;
+const SCREEN_ROUND_YES: u8 = 0x02;
+
+/// The device configuration a resource applies to.
+///
+/// A default (all `None`) config applies to every device. The ordering of
+/// configs is only used to produce deterministic output.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ResourceConfig {
+    /// ISO-639 language code, eg. `de` for `values-de`.
+    pub language: Option<String>,
+    /// ISO-3166 region code, eg. `AT` for `values-de-rAT`. Requires a language.
+    pub region: Option<String>,
+    /// `Some(true)` for `-round`, `Some(false)` for `-notround`.
+    pub round: Option<bool>,
+    /// Screen density in dots-per-inch, eg. `480` for `drawable-xxhdpi`.
+    pub density: Option<u16>,
+    /// Minimum platform version, eg. `26` for `drawable-v26`.
+    pub sdk_version: Option<u16>
+}
+
+impl ResourceConfig {
+    /// Splits a res/ subdirectory name like `drawable-round-xxhdpi` into its
+    /// resource type (`drawable`) and config. Like aapt2, qualifiers have to
+    /// be in the order [ResourceConfig::qualifiers] returns them, and each
+    /// can only be given once.
+    pub fn parse_directory(directory: &str) -> Result<(&str, ResourceConfig)> {
+        let mut parts = directory.split('-');
+        // split() always returns at least one part
+        let res_type = parts.next().unwrap_or(directory);
+        let mut config = ResourceConfig::default();
+        let mut previous_rank = None;
+        for qualifier in parts {
+            match config.add_qualifier(qualifier) {
+                Some(rank) if previous_rank < Some(rank) => previous_rank = Some(rank),
+                _ => return Err(PackError::UnknownResourceQualifier(directory.into()))
+            }
+        }
+        if config.region.is_some() && config.language.is_none() {
+            return Err(PackError::UnknownResourceQualifier(directory.into()));
+        }
+        Ok((res_type, config))
+    }
+
+    // Returns where the qualifier goes in a directory name relative to the
+    // others, or None if it isn't one PACK supports
+    fn add_qualifier(&mut self, qualifier: &str) -> Option<u8> {
+        if let Some((_, dpi)) = DENSITY_QUALIFIERS.iter().find(|(q, _)| *q == qualifier) {
+            self.density = Some(*dpi);
+            Some(3)
+        } else if qualifier == "round" {
+            self.round = Some(true);
+            Some(2)
+        } else if qualifier == "notround" {
+            self.round = Some(false);
+            Some(2)
+        } else if let Some(version) = qualifier
+            .strip_prefix('v')
+            .and_then(|v| v.parse::<u16>().ok())
+        {
+            self.sdk_version = Some(version);
+            Some(4)
+        } else if self.language.is_none()
+            && (2..=3).contains(&qualifier.len())
+            && qualifier.chars().all(|c| c.is_ascii_lowercase())
+        {
+            self.language = Some(qualifier.into());
+            Some(0)
+        } else if let Some(region) = qualifier.strip_prefix('r').filter(|r| {
+            self.language.is_some()
+                && self.region.is_none()
+                && r.len() == 2
+                && r.chars().all(|c| c.is_ascii_uppercase())
+        }) {
+            self.region = Some(region.into());
+            Some(1)
+        } else {
+            None
+        }
+    }
+
+    /// Whether this config applies to every device.
+    pub fn is_default(&self) -> bool {
+        *self == ResourceConfig::default()
+    }
+
+    /// Returns the qualifiers in the order Android requires them, eg.
+    /// `["de", "rAT", "round", "xxhdpi"]`.
+    pub fn qualifiers(&self) -> Vec<String> {
+        let mut qualifiers = vec![];
+        if let Some(language) = &self.language {
+            qualifiers.push(language.clone());
+        }
+        if let Some(region) = &self.region {
+            qualifiers.push(format!("r{region}"));
+        }
+        match self.round {
+            Some(true) => qualifiers.push("round".into()),
+            Some(false) => qualifiers.push("notround".into()),
+            None => {}
+        }
+        if let Some(density) = self.density_qualifier() {
+            qualifiers.push(density);
+        }
+        if let Some(version) = self.sdk_version {
+            qualifiers.push(format!("v{version}"));
+        }
+        qualifiers
+    }
+
+    /// Returns what's appended to a res/ subdirectory for this config, eg.
+    /// `-de-xxhdpi`, or an empty string for the default config.
+    pub fn directory_suffix(&self) -> String {
+        self.qualifiers()
+            .iter()
+            .map(|qualifier| format!("-{qualifier}"))
+            .collect()
+    }
+
+    /// Returns the density's qualifier, eg. `xxhdpi`, or `480dpi` for
+    /// non-standard densities.
+    pub fn density_qualifier(&self) -> Option<String> {
+        let density = self.density?;
+        Some(
+            DENSITY_QUALIFIERS
+                .iter()
+                .find(|(_, dpi)| *dpi == density)
+                .map(|(qualifier, _)| qualifier.to_string())
+                .unwrap_or(format!("{density}dpi"))
+        )
+    }
+
+    /// Returns the BCP-47 locale tag, eg. `de-AT`.
+    pub fn locale_tag(&self) -> Option<String> {
+        let language = self.language.as_ref()?;
+        Some(match &self.region {
+            Some(region) => format!("{language}-{region}"),
+            None => language.clone()
+        })
+    }
+
+    /// Returns the `ResTable_typeSpec` flags for the dimensions this config sets.
+    pub fn change_flags(&self) -> u32 {
+        let mut flags = 0;
+        if self.language.is_some() {
+            flags |= CONFIG_CHANGE_LOCALE;
+        }
+        if self.round.is_some() {
+            flags |= CONFIG_CHANGE_SCREEN_ROUND;
+        }
+        if self.density.is_some() {
+            flags |= CONFIG_CHANGE_DENSITY;
+        }
+        if self.sdk_version.is_some() {
+            flags |= CONFIG_CHANGE_VERSION;
+        }
+        flags
+    }
+
+    /// Encodes this config as the body of a `ResTable_config` (everything after
+    /// its `size` field).
+    pub fn to_res_table_config(&self) -> [u8; 60] {
+        // Offsets are relative to the end of the 4-byte size field
+        let mut data = [0; 60];
+        if let Some(language) = &self.language {
+            data[0x04..0x06].copy_from_slice(&pack_language(language));
+        }
+        if let Some(region) = &self.region {
+            data[0x06..0x08].copy_from_slice(&region.as_bytes()[..2]);
+        }
+        if let Some(density) = self.density {
+            data[0x0A..0x0C].copy_from_slice(&density.to_le_bytes());
+        }
+        if let Some(version) = self.sdk_version {
+            data[0x14..0x16].copy_from_slice(&version.to_le_bytes());
+        }
+        match self.round {
+            Some(true) => data[0x2C] = SCREEN_ROUND_YES,
+            Some(false) => data[0x2C] = SCREEN_ROUND_NO,
+            None => {}
+        }
+        data
+    }
+
+    /// Decodes the body of a `ResTable_config`, the reverse of
+    /// [ResourceConfig::to_res_table_config]. Dimensions that PACK doesn't
+    /// support, like orientation or night mode, are ignored.
+    pub fn from_res_table_config(data: &[u8]) -> ResourceConfig {
+        // Older tables have shorter configs, where the missing fields are 0
+        let mut padded = [0; 60];
+        let len = data.len().min(padded.len());
+        padded[..len].copy_from_slice(&data[..len]);
+        let data = padded;
+
+        let read_u16 = |offset: usize| u16::from_le_bytes([data[offset], data[offset + 1]]);
+        let language = unpack_language([data[0x04], data[0x05]]);
+        // Numeric regions like r419 are packed the same way, but aren't supported
+        let region = (language.is_some() && data[0x06] & 0x80 == 0 && data[0x06] != 0)
+            .then(|| String::from_utf8_lossy(&data[0x06..0x08]).into_owned());
+        ResourceConfig {
+            language,
+            region,
+            round: match data[0x2C] & 0x03 {
+                SCREEN_ROUND_YES => Some(true),
+                SCREEN_ROUND_NO => Some(false),
+                _ => None
+            },
+            density: Some(read_u16(0x0A)).filter(|density| *density != 0),
+            sdk_version: Some(read_u16(0x14)).filter(|version| *version != 0)
+        }
+    }
+}
+
+// Two-letter languages are stored as-is, three-letter languages are packed
+// into 5-bit characters. See ResTable_config::packLanguage.
+fn pack_language(language: &str) -> [u8; 2] {
+    let bytes = language.as_bytes();
+    if bytes.len() == 2 {
+        [bytes[0], bytes[1]]
+    } else {
+        let first = (bytes[0] - b'a') & 0x7F;
+        let second = (bytes[1] - b'a') & 0x7F;
+        let third = (bytes[2] - b'a') & 0x7F;
+        [0x80 | (third << 2) | (second >> 3), (second << 5) | first]
+    }
+}
+
+fn unpack_language(packed: [u8; 2]) -> Option<String> {
+    if packed[0] == 0 {
+        None
+    } else if packed[0] & 0x80 == 0 {
+        Some(String::from_utf8_lossy(&packed).into_owned())
+    } else {
+        let first = packed[1] & 0x1F;
+        let second = ((packed[1] >> 5) | (packed[0] << 3)) & 0x1F;
+        let third = (packed[0] >> 2) & 0x1F;
+        Some(
+            [first, second, third]
+                .iter()
+                .map(|c| (b'a' + c) as char)
+                .collect()
+        )
+    }
+}
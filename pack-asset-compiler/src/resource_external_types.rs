@@ -104,18 +104,24 @@ pub struct XmlAttributeDataChunk {
     pub data: u32
 }
 
-#[derive(Debug, PartialEq, DekuWrite, Clone)]
-#[deku(id_type = "u8")]
-pub enum AttributeDataType {
-    // Others ommitted
-    #[deku(id = 0x01)]
-    Reference,
-    #[deku(id = 0x03)]
-    String,
-    #[deku(id = 0x10)]
-    DecimalInteger,
-    #[deku(id = 0x12)]
-    BooleanInteger
+// ChunkType and AttributeDataType, along with constants like RES_XML_TYPE and
+// TYPE_INT_DEC, are generated from ResourceTypes.h by build.rs
+include!(concat!(env!("OUT_DIR"), "/resource_types.rs"));
+
+// The names that were in use before the enums were generated, so code written
+// against them keeps compiling
+#[allow(non_upper_case_globals)]
+impl ChunkType {
+    #[deprecated(note = "Renamed to `Xml`, after `RES_XML_TYPE`")]
+    pub const XmlFile: ChunkType = ChunkType::Xml;
+}
+
+#[allow(non_upper_case_globals)]
+impl AttributeDataType {
+    #[deprecated(note = "Renamed to `IntDec`, after `TYPE_INT_DEC`")]
+    pub const DecimalInteger: AttributeDataType = AttributeDataType::IntDec;
+    #[deprecated(note = "Renamed to `IntBoolean`, after `TYPE_INT_BOOLEAN`")]
+    pub const BooleanInteger: AttributeDataType = AttributeDataType::IntBoolean;
 }
 
 #[derive(Debug, PartialEq, DekuWrite)]
@@ -151,6 +157,21 @@ pub struct TablePackageChunk {
     pub type_id_offset: u32
 }
 
+// Maps the package IDs of shared libraries to their names, so Android can
+// assign them real IDs when they're loaded
+#[derive(Debug, PartialEq, DekuWrite)]
+pub struct TableLibraryChunk {
+    pub entry_count: u32,
+    pub entries: Vec<TableLibraryEntry>
+}
+
+#[derive(Debug, PartialEq, DekuWrite)]
+pub struct TableLibraryEntry {
+    pub package_id: u32,
+    // This is always 128 u16s (256 bytes) long, like TablePackageChunk.name
+    pub package_name: Vec<u16>
+}
+
 #[derive(Debug, PartialEq, DekuWrite)]
 pub struct TableTypeSpecChunk {
     pub id: u8,
@@ -181,49 +202,25 @@ pub struct TableEntry {
     pub value: XmlAttributeDataChunk
 }
 
-// This struct is the number 64 followed by 60 zeroes
-// Luckily, we don't care about any of the data for watch faces.
-// TODO: Can we report size as 4 and not include any zeroes?
-//   Would save disk space for resources.arsc
+// A ResTable_entry with FLAG_COMPACT, for simple values. The key takes the
+// place of the size, and the value's type is the high byte of the flags.
+#[derive(Debug, PartialEq, DekuWrite)]
+pub struct TableCompactEntry {
+    pub key: u16,
+    pub flags: u8,
+    pub data_type: AttributeDataType,
+    pub data: u32
+}
+
+// A ResTable_config. `size` includes itself, and Android zero-fills anything
+// past it, so the default config can be written as just the number 4.
 #[derive(Debug, PartialEq, DekuWrite)]
 pub struct TableConfigChunk {
     pub size: u32,
-    pub data: [u8; 60]
-}
-
-#[derive(Debug, PartialEq, DekuWrite)]
-#[deku(id_type = "u16")]
-pub enum ChunkType {
-    #[deku(id = 0x0000)]
-    Null,
-    #[deku(id = 0x0001)]
-    StringPool,
-    #[deku(id = 0x0002)]
-    Table,
-    #[deku(id = 0x0003)]
-    XmlFile,
-
-    // Types within an XmlFile
-    #[deku(id = 0x0100)]
-    XmlStartNamespace,
-    #[deku(id = 0x0101)]
-    XmlEndNamespace,
-    #[deku(id = 0x0102)]
-    XmlStartElement,
-    #[deku(id = 0x0103)]
-    XmlEndElement,
-    #[deku(id = 0x180)]
-    XmlResourceMap,
-
-    // Types within a Table
-    #[deku(id = 0x0200)]
-    TablePackage,
-    #[deku(id = 0x0201)]
-    TableType,
-    #[deku(id = 0x0202)]
-    TableTypeSpec
+    pub data: Vec<u8>
 }
 
+pub const STRING_POOL_SORTED_FLAG: u32 = 1 << 0;
 pub const STRING_POOL_UTF8_FLAG: u32 = 1 << 8;
 #[derive(Debug, PartialEq, DekuWrite)]
 pub struct StringPoolHeader {
@@ -0,0 +1,271 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Which resources refer to which, eg. the manifest to @string/app_name, or a
+// watch face to @drawable/preview. Anything the manifest can't reach, directly
+// or through other resources, is probably left over and can be dropped, unless
+// code refers to it.
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt
+};
+
+use pack_common::*;
+use xml::{reader::XmlEvent, EventReader};
+
+use crate::resource_internal_types::Resource;
+
+// Watch faces are found by their file name rather than through a reference
+const ROOT_RESOURCES: &[(&str, &str)] = &[("raw", "watchface"), ("xml", "watch_face_info")];
+
+/// A resource's type and name, eg. `@drawable/preview`. Every config of a
+/// resource has the same name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ResourceName {
+    /// eg. `drawable`
+    pub res_type: String,
+    /// eg. `preview`, without any file extension
+    pub name: String
+}
+
+impl ResourceName {
+    pub fn new(res_type: impl Into<String>, name: impl Into<String>) -> Self {
+        ResourceName {
+            res_type: res_type.into(),
+            name: name.into()
+        }
+    }
+
+    /// Parses a reference like `@drawable/preview`. Returns None for anything
+    /// that doesn't refer to one of the package's own resources, like plain
+    /// text, `@null`, `@android:color/black` or new IDs like `@+id/hands`.
+    pub fn from_reference(reference: &str) -> Option<Self> {
+        let (res_type, name) = reference.trim().strip_prefix('@')?.split_once('/')?;
+        if res_type.is_empty() || res_type.contains([':', '+']) || name.is_empty() {
+            return None;
+        }
+        Some(ResourceName::new(res_type, name))
+    }
+}
+
+impl fmt::Display for ResourceName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "@{}/{}", self.res_type, self.name)
+    }
+}
+
+/// Something that can refer to resources.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ResourceNode {
+    Manifest,
+    Resource(ResourceName)
+}
+
+impl fmt::Display for ResourceNode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResourceNode::Manifest => write!(f, "AndroidManifest.xml"),
+            ResourceNode::Resource(name) => write!(f, "{name}")
+        }
+    }
+}
+
+/// The references between a package's manifest and resources.
+#[derive(Debug, Clone, Default)]
+pub struct ResourceGraph {
+    /// Every resource in the package, and the files it's defined in
+    definitions: BTreeMap<ResourceName, BTreeSet<String>>,
+    references: BTreeMap<ResourceNode, BTreeSet<ResourceName>>,
+    /// Resources that are used without being referenced, eg. by code
+    roots: BTreeSet<ResourceName>
+}
+
+impl ResourceGraph {
+    /// Finds the references in a manifest and its resources, which are split
+    /// into strings and files as when compiling. References are read from the
+    /// attributes and text of XML files and from string values. Precompiled
+    /// files can't be read, so they aren't treated as referring to anything.
+    pub fn new(manifest: &[u8], resources: &[Resource]) -> Result<Self> {
+        let mut graph = ResourceGraph::default();
+        graph.references.insert(
+            ResourceNode::Manifest,
+            xml_references(manifest).map_err(|err| err.in_file("AndroidManifest.xml"))?
+        );
+        for res in resources {
+            let name = ResourceName::new(res.get_subdirectory(), res.get_basename()?);
+            let (path, references) = match res {
+                Resource::File(file) => {
+                    let references = if file.get_extension() == "xml" && !file.precompiled {
+                        xml_references(&file.contents)
+                            .map_err(|err| err.in_file(file.get_path()))?
+                    } else {
+                        BTreeSet::new()
+                    };
+                    (file.get_path(), references)
+                }
+                Resource::String(string) => (
+                    format!("res/values{}/strings.xml", string.config.directory_suffix()),
+                    ResourceName::from_reference(&string.value)
+                        .into_iter()
+                        .collect()
+                ),
+                // Aliases can be in any values file, which isn't kept track of
+                Resource::Alias(alias) => (
+                    format!("res/values{}", alias.config.directory_suffix()),
+                    ResourceName::from_reference(&alias.target)
+                        .into_iter()
+                        .collect()
+                )
+            };
+            graph
+                .definitions
+                .entry(name.clone())
+                .or_default()
+                .insert(path);
+            graph
+                .references
+                .entry(ResourceNode::Resource(name))
+                .or_default()
+                .extend(references);
+        }
+        for (res_type, name) in ROOT_RESOURCES {
+            let name = ResourceName::new(*res_type, *name);
+            if graph.definitions.contains_key(&name) {
+                graph.roots.insert(name);
+            }
+        }
+        Ok(graph)
+    }
+
+    /// Marks a resource as used even if nothing refers to it, eg. because
+    /// code loads it.
+    pub fn add_root(&mut self, name: ResourceName) {
+        self.roots.insert(name);
+    }
+
+    /// Every resource in the package.
+    pub fn resources(&self) -> impl Iterator<Item = &ResourceName> {
+        self.definitions.keys()
+    }
+
+    /// The files that define a resource, eg. `res/drawable-hdpi/preview.png`
+    /// and `res/drawable-xhdpi/preview.png`.
+    pub fn paths(&self, name: &ResourceName) -> impl Iterator<Item = &String> {
+        self.definitions.get(name).into_iter().flatten()
+    }
+
+    /// The resources `node` refers to, whether or not they exist.
+    pub fn references(&self, node: &ResourceNode) -> impl Iterator<Item = &ResourceName> {
+        self.references.get(node).into_iter().flatten()
+    }
+
+    /// The manifest and resources that refer to `name`.
+    pub fn referenced_by<'a>(
+        &'a self,
+        name: &'a ResourceName
+    ) -> impl Iterator<Item = &'a ResourceNode> + 'a {
+        self.references
+            .iter()
+            .filter(move |(_, references)| references.contains(name))
+            .map(|(node, _)| node)
+    }
+
+    /// Resources that can't be reached from the manifest or a root by
+    /// following references, so aren't used unless code refers to them.
+    pub fn unreachable(&self) -> Vec<&ResourceName> {
+        let mut reached = BTreeSet::new();
+        let mut pending: Vec<&ResourceName> = self
+            .references(&ResourceNode::Manifest)
+            .chain(&self.roots)
+            .collect();
+        while let Some(name) = pending.pop() {
+            if reached.insert(name) {
+                pending.extend(self.references(&ResourceNode::Resource(name.clone())));
+            }
+        }
+        self.resources()
+            .filter(|name| !reached.contains(name))
+            .collect()
+    }
+
+    /// References to resources that don't exist, as pairs of what refers to
+    /// them and what they refer to.
+    pub fn missing_targets(&self) -> Vec<(&ResourceNode, &ResourceName)> {
+        self.references
+            .iter()
+            .flat_map(|(node, references)| references.iter().map(move |name| (node, name)))
+            .filter(|(_, name)| !self.definitions.contains_key(name))
+            .collect()
+    }
+
+    /// Warnings for every unreachable resource and missing target, for
+    /// showing alongside a build.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+        for name in self.unreachable() {
+            for path in self.paths(name) {
+                let message = match name.res_type.as_str() {
+                    "string" => format!("The string \"{}\" is never referenced.", name.name),
+                    _ => format!("{name} is never referenced.")
+                };
+                diagnostics.push(Diagnostic::warning(
+                    ErrorCode::ResourceUnreferenced,
+                    Some(path),
+                    message
+                ));
+            }
+        }
+        for (node, name) in self.missing_targets() {
+            let path = match node {
+                ResourceNode::Manifest => Some("AndroidManifest.xml"),
+                ResourceNode::Resource(source) => self.paths(source).next().map(String::as_str)
+            };
+            diagnostics.push(Diagnostic::warning(
+                ErrorCode::ReferenceAttributeLookupFailed,
+                path,
+                format!("{node} refers to {name}, which doesn't exist.")
+            ));
+        }
+        diagnostics
+    }
+}
+
+// References can be in attributes, eg. android:label="@string/app_name", or
+// in text, eg. <item>@color/accent</item>. Watch Face Format elements refer
+// to drawables by name, eg. <Image resource="hands" />.
+fn xml_references(xml: &[u8]) -> Result<BTreeSet<ResourceName>> {
+    let mut references = BTreeSet::new();
+    for event in EventReader::new(xml) {
+        match event.map_err(PackError::XmlParsingFailed)? {
+            XmlEvent::StartElement { attributes, .. } => {
+                for attr in attributes {
+                    if let Some(name) = ResourceName::from_reference(&attr.value) {
+                        references.insert(name);
+                    } else if attr.name.local_name == "resource"
+                        && attr.name.prefix.is_none()
+                        && !attr.value.is_empty()
+                        && !attr.value.starts_with('@')
+                    {
+                        references.insert(ResourceName::new("drawable", attr.value));
+                    }
+                }
+            }
+            XmlEvent::Characters(text) => {
+                references.extend(ResourceName::from_reference(&text));
+            }
+            _ => {}
+        }
+    }
+    Ok(references)
+}
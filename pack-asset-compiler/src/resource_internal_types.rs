@@ -17,7 +17,13 @@ use deku::DekuContainerWrite;
 use pack_common::*;
 use std::io::Cursor;
 
-use crate::xml_file::xml_to_res_chunk;
+use crate::{
+    resource_config::ResourceConfig,
+    xml_file::{xml_to_res_chunk_with_options, XmlOptions}
+};
+
+// Nine-patches are the one kind of file whose extension has a period in it
+const NINE_PATCH_EXTENSION: &str = "9.png";
 
 // TODO: Factor common values like name and resource_id into a parent struct with an
 //   enum for just the value
@@ -25,31 +31,73 @@ use crate::xml_file::xml_to_res_chunk;
 #[derive(Debug, Clone)]
 pub enum Resource {
     File(FileResource),
-    String(StringResource)
+    String(StringResource),
+    Alias(AliasResource)
 }
 
 /// Represents any non-string resource file
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FileResource {
     /// eg. `drawable`
     pub subdirectory: String,
     /// eg. `preview.png`
     pub name: String,
+    /// The device configuration this file is for, eg. `xxhdpi` for
+    /// `drawable-xxhdpi`. The default config applies to every device.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub config: ResourceConfig,
     /// Starts as 0, populated by the asset complier
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub resource_id: u32,
-    /// Contents of the file in bytes.
-    pub contents: Vec<u8>
+    /// Contents of the file in bytes. They're shared rather than copied as
+    /// the file moves through the build, since drawables can be large.
+    #[cfg_attr(feature = "serde", serde(with = "pack_common::bytes_or_base64"))]
+    pub contents: FileContents,
+    /// The contents were already compiled by another tool, eg. aapt2, so
+    /// they're packaged as they are. They must be binary XML when building an
+    /// APK, or ProtoXML when building an AAB. Doesn't apply to
+    /// `values/strings.xml`, whose strings always go in the resource table.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub precompiled: bool,
+    /// Whether the file is compressed in the APK or AAB, eg.
+    /// [Compression::Store] for audio that's played straight from the
+    /// package. In AABs, stored files are also left uncompressed in the APKs
+    /// Google Play generates, but Play decides for itself whether to deflate
+    /// the rest.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub compression: Compression
 }
 
 impl FileResource {
-    /// Returns the path to this file within an APK or AAB Module, for example `res/drawable/preview.png`.
+    /// Returns the path to this file within an APK or AAB Module, for example `res/drawable/preview.png`
+    /// or `res/drawable-xxhdpi/preview.png`.
     pub fn get_path(&self) -> String {
-        format!("res/{}/{}", self.subdirectory, self.name)
+        format!(
+            "res/{}{}/{}",
+            self.subdirectory,
+            self.config.directory_suffix(),
+            self.name
+        )
     }
 
-    /// Returns the name of the resource file without its file extension.
+    /// Returns the name of the resource file without its file extension, eg.
+    /// `clock.face` for `clock.face.xml`. See [split_resource_file_name].
     pub fn get_basename(&self) -> Result<String> {
-        Ok(self.name.split('.').next().unwrap_or("").to_string())
+        let (basename, _) = split_resource_file_name(&self.name);
+        if basename.is_empty() {
+            return Err(PackError::ResourceFileNameInvalid(format!(
+                "{} has nothing before its extension, so it can't be referenced.",
+                self.get_path()
+            )));
+        }
+        Ok(basename.to_string())
+    }
+
+    /// Returns the file extension without its leading period, eg. `png`, or
+    /// `9.png` for a nine-patch. Empty if the name doesn't have one.
+    pub fn get_extension(&self) -> &str {
+        split_resource_file_name(&self.name).1
     }
 
     /// Returns a [FileResource] representing the file in question
@@ -60,29 +108,73 @@ impl FileResource {
         FileResource {
             subdirectory,
             name,
+            config: ResourceConfig::default(),
             resource_id: 0,
-            contents
+            contents: contents.into(),
+            precompiled: false,
+            compression: Compression::Auto
         }
     }
 
+    /// Whether the file is stored compiled in packages, which is the case for
+    /// XML files in every directory but `raw/`, eg. drawables and color state
+    /// lists as well as `xml/`. APKs store them as binary XML, and AABs as
+    /// ProtoXML.
+    pub fn is_compiled_xml(&self) -> bool {
+        self.subdirectory != "raw" && self.get_extension() == "xml"
+    }
+
+    /// Whether the contents are compiled when packaging, which is the case for
+    /// [compiled XML](FileResource::is_compiled_xml) files unless they were
+    /// [precompiled](FileResource::precompiled).
+    pub fn needs_compiling(&self) -> bool {
+        self.is_compiled_xml() && !self.precompiled
+    }
+
     /// Returns the `Vec<u8>` to be placed into an APK to represent this file. For most
-    /// files, that's just the contents. For files in the XML directory, they are compiled
+    /// files, that's just the contents. XML files outside `raw/` are compiled
     /// to a [special format](https://cs.android.com/android/platform/superproject/main/+/main:frameworks/base/libs/androidfw/include/androidfw/ResourceTypes.h;l=244)
-    /// unique to AAPT.
+    /// unique to AAPT, unless they're [precompiled](FileResource::precompiled).
     pub fn as_bytes_for_apk(&self, resources: &[Resource]) -> Result<Vec<u8>> {
-        if self.subdirectory == "xml" {
-            let (parsed_xml_res_chunk, _) =
-                xml_to_res_chunk(&mut Cursor::new(self.contents.clone()), resources)?;
+        self.as_bytes_for_apk_with_options(resources, &XmlOptions::default())
+    }
+
+    /// Same as [FileResource::as_bytes_for_apk], but with customisable
+    /// [XmlOptions] for compiling XML files.
+    pub fn as_bytes_for_apk_with_options(
+        &self,
+        resources: &[Resource],
+        options: &XmlOptions
+    ) -> Result<Vec<u8>> {
+        if self.needs_compiling() {
+            let (parsed_xml_res_chunk, _) = xml_to_res_chunk_with_options(
+                &mut Cursor::new(&self.contents[..]),
+                resources,
+                options
+            )?;
             Ok(parsed_xml_res_chunk.to_bytes()?)
         } else {
             // Other files can be dumped in verbatim
-            // TODO: Can we just consume this? Cloning is wasteful for large resources
             // TODO: res/drawable resources can be PNG-crushed. AAPT2 does. libimagequant seems perfect.
-            Ok(self.contents.clone())
+            Ok(self.contents.to_vec())
         }
     }
 }
 
+/// Splits a resource file name into the resource's name and the file's
+/// extension, eg. `clock.face.xml` into `clock.face` and `xml`. Only the last
+/// extension is split off, except for nine-patches, where `frame.9.png` is
+/// `frame` and `9.png`. Names without an extension have an empty one.
+pub fn split_resource_file_name(name: &str) -> (&str, &str) {
+    if let Some(basename) = name
+        .strip_suffix(NINE_PATCH_EXTENSION)
+        .and_then(|name| name.strip_suffix('.'))
+    {
+        return (basename, NINE_PATCH_EXTENSION);
+    }
+    name.rsplit_once('.').unwrap_or((name, ""))
+}
+
 /// Represents a key-value pair from `strings.xml`.
 #[derive(Debug, Clone)]
 pub struct StringResource {
@@ -90,6 +182,32 @@ pub struct StringResource {
     pub name: String,
     /// eg. "Analogue"
     pub value: String,
+    /// The device configuration this string is for, eg. `de` for `values-de/strings.xml`
+    pub config: ResourceConfig,
+    /// Can start as 0, construct_resource_table fills it in
+    pub resource_id: u32,
+    /// False for strings that shouldn't be translated, eg. a brand name, from
+    /// `translatable="false"`. These are left out of translation passes like
+    /// pseudo-localisation and checks for missing translations.
+    pub translatable: bool,
+    /// False for strings that aren't format strings even though they contain
+    /// `%`, from `formatted="false"`.
+    pub formatted: bool
+}
+
+/// Represents a resource whose value is a reference to another resource, eg.
+/// `<drawable name="preview_round">@drawable/preview</drawable>` in a values
+/// file, so that one file can be used under several names.
+#[derive(Debug, Clone)]
+pub struct AliasResource {
+    /// eg. `drawable`. Usually the same as the target's type.
+    pub res_type: String,
+    /// eg. "preview_round"
+    pub name: String,
+    /// The resource it refers to, eg. `@drawable/preview`
+    pub target: String,
+    /// The device configuration this alias is for, eg. `night` for `values-night/drawables.xml`
+    pub config: ResourceConfig,
     /// Can start as 0, construct_resource_table fills it in
     pub resource_id: u32
 }
@@ -101,16 +219,20 @@ impl Resource {
             Resource::File(file) => &file.subdirectory[..],
             // String resources live in values/strings.xml
             // But they get reported in the APK as "string"
-            Resource::String(_) => "string"
+            Resource::String(_) => "string",
+            // Aliases get reported as their own type, eg. "drawable"
+            Resource::Alias(alias) => &alias.res_type[..]
         }
     }
 
     /// Returns the value that needs to be put into the string pool for this resource. For [files](FileResource)
-    /// that's relative paths, for [strings](StringResource) that's their actual values.
+    /// that's relative paths, for [strings](StringResource) that's their actual values, and for
+    /// [aliases](AliasResource) that's the reference, eg. `@drawable/preview`.
     pub fn get_string_pool_string(&self) -> String {
         match self {
             Resource::File(file) => file.get_path(),
-            Resource::String(sres) => sres.value.clone()
+            Resource::String(sres) => sres.value.clone(),
+            Resource::Alias(alias) => alias.target.clone()
         }
     }
 
@@ -119,16 +241,37 @@ impl Resource {
     pub fn get_name(&self) -> &str {
         match self {
             Resource::File(file) => &file.name[..],
-            Resource::String(sres) => &sres.name[..]
+            Resource::String(sres) => &sres.name[..],
+            Resource::Alias(alias) => &alias.name[..]
         }
     }
 
     /// Returns the name of the resource without its file extension. For [String Resources](StringResource),
-    /// this is equivalent to [get_name](Resource::get_name).
+    /// and [aliases](AliasResource), this is equivalent to [get_name](Resource::get_name).
     pub fn get_basename(&self) -> Result<String> {
         match self {
             Resource::File(file) => file.get_basename(),
-            Resource::String(sres) => Ok(sres.name.to_string())
+            Resource::String(sres) => Ok(sres.name.to_string()),
+            Resource::Alias(alias) => Ok(alias.name.to_string())
+        }
+    }
+
+    /// Returns the device configuration this resource is for.
+    pub fn get_config(&self) -> &ResourceConfig {
+        match self {
+            Resource::File(file) => &file.config,
+            Resource::String(sres) => &sres.config,
+            Resource::Alias(alias) => &alias.config
+        }
+    }
+
+    /// Helper for setting the `config` field regardless of whether you know this is a [FileResource] or
+    /// [StringResource].
+    pub fn set_config(&mut self, config: ResourceConfig) {
+        match self {
+            Resource::File(file) => file.config = config,
+            Resource::String(sres) => sres.config = config,
+            Resource::Alias(alias) => alias.config = config
         }
     }
 
@@ -140,7 +283,8 @@ impl Resource {
     pub fn get_resource_id(&self) -> u32 {
         match self {
             Resource::File(file) => file.resource_id,
-            Resource::String(sres) => sres.resource_id
+            Resource::String(sres) => sres.resource_id,
+            Resource::Alias(alias) => alias.resource_id
         }
     }
 
@@ -149,7 +293,8 @@ impl Resource {
     pub fn set_resource_id(&mut self, res_id: u32) {
         match self {
             Resource::File(file) => file.resource_id = res_id,
-            Resource::String(sres) => sres.resource_id = res_id
+            Resource::String(sres) => sres.resource_id = res_id,
+            Resource::Alias(alias) => alias.resource_id = res_id
         }
     }
 }
@@ -14,53 +14,362 @@
 
 use deku::prelude::*;
 use pack_common::*;
-use std::collections::HashMap;
+use std::{cmp::Ordering, collections::HashMap};
 
 use crate::{
     generate_res_chunk,
+    resource_config::ResourceConfig,
     resource_external_types::{
-        AttributeDataType, ChunkType, RawBytes, ResChunk, TableConfigChunk, TableEntry,
-        TableHeaderChunk, TablePackageChunk, TableTypeChunk, TableTypeSpecChunk,
-        XmlAttributeDataChunk
+        AttributeDataType, ChunkType, RawBytes, ResChunk, TableCompactEntry, TableConfigChunk,
+        TableEntry, TableHeaderChunk, TableLibraryChunk, TableLibraryEntry, TablePackageChunk,
+        TableTypeChunk, TableTypeSpecChunk, XmlAttributeDataChunk
     },
-    resource_internal_types::Resource,
-    string_pool::construct_string_pool
+    resource_graph::ResourceName,
+    resource_internal_types::{split_resource_file_name, AliasResource, FileResource, Resource},
+    string_pool::{construct_deduplicated_string_pool, construct_string_pool}
 };
 
-const USER_PACKAGE_MAGIC: u32 = 0x7F;
+/// Package ID of the app's own resources. Feature modules count down from here.
+pub const USER_PACKAGE_MAGIC: u32 = 0x7F;
+/// Package ID that shared libraries are built with. Android replaces it with
+/// a real ID when the library is loaded.
+pub const SHARED_LIBRARY_PACKAGE_ID: u32 = 0x00;
+// Package ID of the Android framework's resources, eg. `android:attr/label`
+const FRAMEWORK_PACKAGE_ID: u32 = 0x01;
+// Offset of a resource that has no value in a particular configuration
+const NO_ENTRY: u32 = 0xFFFF_FFFF;
+// ResTable_type flag for types that only list the entries with values
+const TYPE_FLAG_SPARSE: u8 = 0x01;
+// ResTable_entry flag for 8-byte entries holding a simple value
+const ENTRY_FLAG_COMPACT: u8 = 0x08;
+// Size of a ResTable_type header, not counting its config
+const TYPE_HEADER_SIZE: u32 = 20;
+// aapt2 only makes a type sparse if less than this percentage of its entries
+// have values, since sparse offsets are twice the size
+const SPARSE_ENCODING_THRESHOLD: usize = 60;
+
+/// Assigns resource IDs to a list of resources.
+///
+/// Types are numbered in alphabetical order, and so are the entries within
+/// each type, regardless of the order the resources are listed in (see
+/// [compare_resources]). Resources with the same type and name but different
+/// [configs](ResourceConfig) are alternatives for the same entry, so they
+/// share an ID.
+#[derive(Debug, Clone)]
+pub struct ResourceIds {
+    /// The first byte of every ID, eg. `0x7F`.
+    pub package_id: u32,
+    /// Each type's name followed by the names of its entries.
+    pub types: Vec<(String, Vec<String>)>,
+    /// The (type index, entry index) of each resource, in the same order as
+    /// the resources that were passed to [ResourceIds::new], even though
+    /// that isn't the order IDs are assigned in.
+    pub positions: Vec<(usize, usize)>
+}
+
+impl ResourceIds {
+    pub fn new(resources: &[Resource]) -> Result<ResourceIds> {
+        ResourceIds::for_package(resources, USER_PACKAGE_MAGIC)
+    }
+
+    /// Like [ResourceIds::new], but for a package other than the app's own,
+    /// eg. `0x7E` for the resources of a feature module.
+    pub fn for_package(resources: &[Resource], package_id: u32) -> Result<ResourceIds> {
+        let mut types: Vec<(String, Vec<String>)> = vec![];
+        let mut entry_indices: HashMap<(usize, String), usize> = HashMap::new();
+        // The file behind each (type, entry, config), to catch files that
+        // only differ by extension, eg. clock.png and clock.xml
+        let mut files: HashMap<(usize, usize, &ResourceConfig), &FileResource> = HashMap::new();
+        let mut positions = vec![(0, 0); resources.len()];
+        let mut order: Vec<usize> = (0..resources.len()).collect();
+        order.sort_by(|&a, &b| compare_resources(&resources[a], &resources[b]));
+        for index in order {
+            let res = &resources[index];
+            let type_idx = match types
+                .iter()
+                .position(|(name, _)| name == res.get_subdirectory())
+            {
+                Some(type_idx) => type_idx,
+                None => {
+                    types.push((res.get_subdirectory().to_string(), vec![]));
+                    types.len() - 1
+                }
+            };
+            let basename = res.get_basename()?;
+            let entries = &mut types[type_idx].1;
+            let entry_idx = *entry_indices
+                .entry((type_idx, basename.clone()))
+                .or_insert_with(|| {
+                    entries.push(basename);
+                    entries.len() - 1
+                });
+            if let Resource::File(file) = res {
+                if let Some(other) = files.insert((type_idx, entry_idx, &file.config), file) {
+                    if other.name != file.name {
+                        return Err(PackError::ResourceFileNameInvalid(format!(
+                            "{} and {} are both @{}/{}. Resources are referenced without their extensions, so rename one of them.",
+                            other.get_path(),
+                            file.get_path(),
+                            file.subdirectory,
+                            types[type_idx].1[entry_idx]
+                        )));
+                    }
+                }
+            }
+            positions[index] = (type_idx, entry_idx);
+        }
+        Ok(ResourceIds {
+            package_id,
+            types,
+            positions
+        })
+    }
+
+    /// Returns the ID of the resource at `index` in the list of resources.
+    pub fn resource_id(&self, index: usize) -> u32 {
+        let (type_idx, entry_idx) = self.positions[index];
+        self.to_resource_id(type_idx, entry_idx)
+    }
+
+    /// Returns the ID of the resource with the given type and name (without
+    /// its file extension), eg. `drawable` and `preview`.
+    pub fn lookup(&self, res_type: &str, name: &str) -> Option<u32> {
+        let type_idx = self.types.iter().position(|(t, _)| t == res_type)?;
+        let entry_idx = self.types[type_idx].1.iter().position(|e| e == name)?;
+        Some(self.to_resource_id(type_idx, entry_idx))
+    }
+
+    /// Returns the ID of the resource an [alias](AliasResource) refers to,
+    /// which must be in the same package.
+    pub fn alias_target(&self, alias: &AliasResource) -> Result<u32> {
+        let target = ResourceName::from_reference(&alias.target)
+            .ok_or_else(|| PackError::ReferenceAttributeParsingFailed(alias.target.clone()))?;
+        self.lookup(&target.res_type, &target.name)
+            .ok_or_else(|| PackError::ReferenceAttributeLookupFailed(alias.target.clone()))
+    }
+
+    fn to_resource_id(&self, type_idx: usize, entry_idx: usize) -> u32 {
+        // Type IDs are 1-based
+        (self.package_id << 24) | ((type_idx as u32 + 1) << 16) | entry_idx as u32
+    }
+}
+
+/// The order resource IDs are assigned in: by type, then name, then config,
+/// then file name, eg. to tell `preview.png` and `preview.xml` apart. Names are
+/// compared byte by byte, so the order doesn't depend on the locale or
+/// platform.
+pub fn compare_resources(a: &Resource, b: &Resource) -> Ordering {
+    sort_key(a).cmp(&sort_key(b))
+}
+
+fn sort_key(res: &Resource) -> (&str, &str, &ResourceConfig, &str) {
+    let name = match res {
+        Resource::File(file) => split_resource_file_name(&file.name).0,
+        Resource::String(string) => &string.name,
+        Resource::Alias(alias) => &alias.name
+    };
+    (
+        res.get_subdirectory(),
+        name,
+        res.get_config(),
+        res.get_name()
+    )
+}
+
+/// Sorts resources into the order [ResourceIds] assigns IDs in, which groups
+/// each type's resources together.
+pub fn sort_resources(resources: &mut [Resource]) {
+    resources.sort_by(compare_resources);
+}
+
+/// How [construct_resource_table_with_packages] lays out the table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TableEncoding {
+    /// Writes types where fewer than 60% of entries have a value in a config
+    /// as sparse types, which only list the entries that do. Only Android 8.0
+    /// (API 26) and up can read them.
+    pub sparse: bool,
+    /// Writes values as 8-byte compact entries instead of 16-byte ones. Only
+    /// Android 14 (API 34) and up can read them.
+    pub compact: bool,
+    /// Sorts the value and key string pools, and flags them as sorted so that
+    /// Android can binary search them. Every version of Android can read them.
+    pub sorted_pools: bool
+}
+
+/// One package in a resource table built by
+/// [construct_resource_table_with_packages].
+#[derive(Debug)]
+pub struct TablePackage<'a> {
+    /// eg. `com.example.watchface`
+    pub name: &'a str,
+    /// The first byte of the package's resource IDs, eg. [USER_PACKAGE_MAGIC],
+    /// or [SHARED_LIBRARY_PACKAGE_ID] for a shared library.
+    pub id: u32,
+    pub resources: &'a mut [Resource]
+}
 
 pub fn construct_resource_table(
     package_name: &str,
     resources: &mut [Resource]
 ) -> Result<ResChunk> {
-    let res_types = get_unique_res_types(resources);
-    let res_buckets = get_res_type_buckets(resources);
-    let res_basenames: Vec<String> = resources
-        .iter()
-        .map(|res| res.get_basename())
-        .collect::<Result<Vec<String>>>()?;
+    construct_resource_table_with_configs(package_name, resources, &|_config| true)
+}
 
+/// Like [construct_resource_table], but only includes values for the configs
+/// that `include_config` accepts. This is used to build the tables for split
+/// APKs, which each only contain some configs.
+///
+/// Resource IDs are always assigned from the full list of resources, so that
+/// they're the same in every split.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        name = "construct_resource_table",
+        skip_all,
+        fields(%package_name, resources = resources.len())
+    )
+)]
+pub fn construct_resource_table_with_configs(
+    package_name: &str,
+    resources: &mut [Resource],
+    include_config: &dyn Fn(&ResourceConfig) -> bool
+) -> Result<ResChunk> {
+    construct_resource_table_with_packages(
+        &mut [TablePackage {
+            name: package_name,
+            id: USER_PACKAGE_MAGIC,
+            resources
+        }],
+        include_config,
+        TableEncoding::default()
+    )
+}
+
+/// Like [construct_resource_table_with_configs], but with any number of
+/// packages, each with its own package ID. Fails with
+/// [PackError::DuplicatePackageId] if two packages have the same ID.
+///
+/// Packages with IDs other than the app's and the framework's are listed in a
+/// library chunk, as aapt2 does, so that Android can assign shared libraries
+/// their real ID when they're loaded.
+pub fn construct_resource_table_with_packages(
+    packages: &mut [TablePackage],
+    include_config: &dyn Fn(&ResourceConfig) -> bool,
+    encoding: TableEncoding
+) -> Result<ResChunk> {
+    for (i, package) in packages.iter().enumerate() {
+        if packages[..i].iter().any(|other| other.id == package.id) {
+            return Err(PackError::DuplicatePackageId(package.id));
+        }
+    }
     let mut data: Vec<u8> = vec![];
 
     // Add a header for the table we're about to construct
-    data.extend(TableHeaderChunk { package_count: 1 }.to_bytes()?);
+    data.extend(
+        TableHeaderChunk {
+            package_count: packages.len() as u32
+        }
+        .to_bytes()?
+    );
 
-    let path_strings: Vec<String> = resources
+    // Values are shared between every package, and resources with the same
+    // value share a string
+    let path_strings: Vec<String> = packages
         .iter()
+        .flat_map(|package| package.resources.iter())
         .map(|res| res.get_string_pool_string())
         .collect();
-    let path_string_pool = construct_string_pool(&path_strings)?.to_bytes()?;
-    data.extend(path_string_pool);
+    let (path_string_pool, value_indices) =
+        construct_deduplicated_string_pool(&path_strings, encoding.sorted_pools)?;
+    data.extend(path_string_pool.to_bytes()?);
+
+    let libraries: Vec<(u32, &str)> = packages
+        .iter()
+        .filter(|package| package.id != FRAMEWORK_PACKAGE_ID && package.id != USER_PACKAGE_MAGIC)
+        .map(|package| (package.id, package.name))
+        .collect();
+    let mut first_value = 0;
+    for package in packages.iter_mut() {
+        let package_values = &value_indices[first_value..first_value + package.resources.len()];
+        data.extend(construct_package(
+            package,
+            package_values,
+            &libraries,
+            include_config,
+            encoding
+        )?);
+        first_value += package.resources.len();
+    }
+
+    generate_res_chunk(ChunkType::Table, RawBytes { data }, 4, 0)
+}
+
+// Builds one package's chunk. `value_indices` holds the index of each resource's value
+// in the table's string pool.
+fn construct_package(
+    package: &mut TablePackage,
+    value_indices: &[u32],
+    libraries: &[(u32, &str)],
+    include_config: &dyn Fn(&ResourceConfig) -> bool,
+    encoding: TableEncoding
+) -> Result<Vec<u8>> {
+    let ids = ResourceIds::for_package(package.resources, package.id)?;
+    for (i, res) in package.resources.iter_mut().enumerate() {
+        res.set_resource_id(ids.resource_id(i));
+    }
+    let resources: &[Resource] = package.resources;
+
+    let res_types: Vec<String> = ids.types.iter().map(|(name, _)| name.clone()).collect();
+    let res_basenames: Vec<String> = resources
+        .iter()
+        .map(|res| res.get_basename())
+        .collect::<Result<Vec<String>>>()?;
+
+    // Find the resource for each (type, entry, config)
+    let mut values: HashMap<(usize, usize, &ResourceConfig), usize> = HashMap::new();
+    for (i, res) in resources.iter().enumerate() {
+        let (type_idx, entry_idx) = ids.positions[i];
+        if values
+            .insert((type_idx, entry_idx, res.get_config()), i)
+            .is_some()
+        {
+            return Err(PackError::DuplicateResource(format!(
+                "{}/{}{}",
+                res.get_subdirectory(),
+                res_basenames[i],
+                res.get_config().directory_suffix()
+            )));
+        }
+    }
 
     let res_types_string_pool = construct_string_pool(&res_types)?.to_bytes()?;
-    let res_basenames_string_pool = construct_string_pool(&res_basenames)?.to_bytes()?;
+    // Every config of a resource has the same key, so they share a string
+    let (res_basenames_string_pool, key_indices) =
+        construct_deduplicated_string_pool(&res_basenames, encoding.sorted_pools)?;
+    let res_basenames_string_pool = res_basenames_string_pool.to_bytes()?;
 
     let mut res_type_data: Vec<u8> = vec![];
-    let mut absolute_entry = 0;
-    for (i, res_type) in res_types.iter().enumerate() {
+    for (type_idx, (_, entries)) in ids.types.iter().enumerate() {
         // This is 1-based
-        let res_type_id = i as u8 + 1;
-        let entry_count = res_buckets.get(res_type).unwrap().len() as u32;
+        let res_type_id = type_idx as u8 + 1;
+        let entry_count = entries.len() as u32;
+
+        // Each entry is flagged with every dimension that any of its values
+        // varies by. This is based on all configs, not just the included ones,
+        // so that the flags agree between splits.
+        let mut configuration_change_flags = vec![0; entries.len()];
+        let mut configs: Vec<&ResourceConfig> = vec![];
+        for &(value_type_idx, entry_idx, config) in values.keys() {
+            if value_type_idx == type_idx {
+                configuration_change_flags[entry_idx] |= config.change_flags();
+                if include_config(config) && !configs.contains(&config) {
+                    configs.push(config);
+                }
+            }
+        }
+        configs.sort();
+
         // Generate a TableTypeSpec for each resouce type
         let type_spec = TableTypeSpecChunk {
             id: res_type_id,
@@ -68,62 +377,108 @@ pub fn construct_resource_table(
             // Reserved 0
             types_count: 0,
             entry_count,
-            configuration_change_flags: vec![0; entry_count as usize]
+            configuration_change_flags
         };
         res_type_data
             .extend(generate_res_chunk(ChunkType::TableTypeSpec, type_spec, 8, 0)?.to_bytes()?);
 
-        // Generate a TableType for each resource type
-        let mut entry_data: Vec<u8> = vec![];
-        let mut offsets: Vec<u32> = vec![];
-        for j in 0..entry_count {
-            offsets.push(16 * j);
-            resources[absolute_entry as usize]
-                .set_resource_id(0x7F00_0000 | ((res_type_id as u32) << 16) | j);
-            let entry = TableEntry {
-                size: 8,
-                flags: 0,
-                key: absolute_entry,
-                value: XmlAttributeDataChunk {
-                    size: 8,
-                    res0: 0,
-                    data_type: AttributeDataType::String,
-                    // TODO: Not sure if this is right
-                    data: absolute_entry
+        // Generate a TableType for each config of each resource type
+        for config in configs {
+            let mut entry_data: Vec<u8> = vec![];
+            let mut offsets: Vec<u32> = vec![];
+            for entry_idx in 0..entries.len() {
+                let Some(&res_idx) = values.get(&(type_idx, entry_idx, config)) else {
+                    offsets.push(NO_ENTRY);
+                    continue;
+                };
+                offsets.push(entry_data.len() as u32);
+                // TODO: Not sure if this is right
+                let (data_type, data) = match &resources[res_idx] {
+                    Resource::Alias(alias) => {
+                        (AttributeDataType::Reference, ids.alias_target(alias)?)
+                    }
+                    _ => (AttributeDataType::String, value_indices[res_idx])
+                };
+                let key = key_indices[res_idx];
+                // Compact entries only have room for 16-bit keys
+                match u16::try_from(key) {
+                    Ok(key) if encoding.compact => {
+                        let entry = TableCompactEntry {
+                            key,
+                            flags: ENTRY_FLAG_COMPACT,
+                            data_type,
+                            data
+                        };
+                        entry_data.extend(entry.to_bytes()?);
+                    }
+                    _ => {
+                        let entry = TableEntry {
+                            size: 8,
+                            flags: 0,
+                            key,
+                            value: XmlAttributeDataChunk {
+                                size: 8,
+                                res0: 0,
+                                data_type,
+                                data
+                            }
+                        };
+                        entry_data.extend(entry.to_bytes()?);
+                    }
                 }
+            }
+            let (flags, offsets) = if encoding.sparse {
+                sparse_offsets(&offsets)
+            } else {
+                (0, offsets)
+            };
+            let config = table_config(config);
+            let header_size = TYPE_HEADER_SIZE + config.size;
+            let type_chunk = TableTypeChunk {
+                id: res_type_id,
+                flags,
+                reserved: 0,
+                entry_count: offsets.len() as u32,
+                entries_start: header_size + offsets.len() as u32 * 4,
+                config,
+                offsets
             };
-            entry_data.extend(entry.to_bytes()?);
-            absolute_entry += 1;
+            res_type_data.extend(
+                generate_res_chunk(
+                    ChunkType::TableType,
+                    type_chunk,
+                    header_size as u16 - 8,
+                    entry_data.len() as u16
+                )?
+                .to_bytes()?
+            );
+            res_type_data.extend(entry_data);
         }
-        let type_chunk = TableTypeChunk {
-            id: res_type_id,
-            flags: 0,
-            reserved: 0,
-            entry_count,
-            entries_start: 0x54 + offsets.len() as u32 * 4,
-            config: TableConfigChunk {
-                size: 64,
-                data: [0; 60]
-            },
-            offsets
+    }
+
+    // aapt2 puts the library chunk after the types
+    if !libraries.is_empty() {
+        let library_chunk = TableLibraryChunk {
+            entry_count: libraries.len() as u32,
+            entries: libraries
+                .iter()
+                .map(|&(package_id, package_name)| {
+                    Ok(TableLibraryEntry {
+                        package_id,
+                        package_name: get_padded_package_name(package_name)?
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?
         };
-        res_type_data.extend(
-            generate_res_chunk(
-                ChunkType::TableType,
-                type_chunk,
-                0x54 - 8,
-                entry_data.len() as u16
-            )?
-            .to_bytes()?
-        );
-        res_type_data.extend(entry_data);
+        res_type_data
+            .extend(generate_res_chunk(ChunkType::TableLibrary, library_chunk, 4, 0)?.to_bytes()?);
     }
 
     let table_package_chunk = generate_res_chunk(
         ChunkType::TablePackage,
         TablePackageChunk {
-            id: USER_PACKAGE_MAGIC,
-            name: get_padded_package_name(package_name)?,
+            id: package.id,
+            name: get_padded_package_name(package.name)?,
             // This is the same as the header size, means type_strings begins immediately
             type_string_offset: 0x120,
             last_public_type: 0,
@@ -136,13 +491,50 @@ pub fn construct_resource_table(
         (res_types_string_pool.len() + res_basenames_string_pool.len() + res_type_data.len())
             as u16
     )?;
-    data.extend(table_package_chunk.to_bytes()?);
+    let mut data = table_package_chunk.to_bytes()?;
     data.extend(res_types_string_pool);
     data.extend(res_basenames_string_pool);
-
     data.extend(res_type_data);
+    Ok(data)
+}
 
-    generate_res_chunk(ChunkType::Table, RawBytes { data }, 4, 0)
+// The default config is written with only its size, since Android fills in
+// the rest with zeros anyway
+fn table_config(config: &ResourceConfig) -> TableConfigChunk {
+    if config.is_default() {
+        return TableConfigChunk {
+            size: 4,
+            data: vec![]
+        };
+    }
+    let data = config.to_res_table_config().to_vec();
+    TableConfigChunk {
+        size: 4 + data.len() as u32,
+        data
+    }
+}
+
+// Converts a type's offsets to ResTable_sparseTypeEntry pairs of entry index
+// and offset / 4, if that's smaller and every offset fits
+fn sparse_offsets(offsets: &[u32]) -> (u8, Vec<u32>) {
+    let present: Vec<(usize, u32)> = offsets
+        .iter()
+        .enumerate()
+        .filter(|&(_, &offset)| offset != NO_ENTRY)
+        .map(|(index, &offset)| (index, offset))
+        .collect();
+    let fits = offsets.len() <= u16::MAX as usize + 1
+        && present
+            .iter()
+            .all(|&(_, offset)| offset / 4 <= u16::MAX as u32);
+    if !fits || present.len() * 100 >= offsets.len() * SPARSE_ENCODING_THRESHOLD {
+        return (0, offsets.to_vec());
+    }
+    let sparse = present
+        .into_iter()
+        .map(|(index, offset)| index as u32 | ((offset / 4) << 16))
+        .collect();
+    (TYPE_FLAG_SPARSE, sparse)
 }
 
 // Returns the package name in zero-padded 128 UTF-16 characters
@@ -166,17 +558,3 @@ pub fn get_unique_res_types(resources: &[Resource]) -> Vec<String> {
     }
     unique_vec
 }
-
-fn get_res_type_buckets(resources: &[Resource]) -> HashMap<String, Vec<String>> {
-    let mut map = HashMap::new();
-    for res in resources {
-        let subdir = res.get_subdirectory().to_string();
-        if !map.contains_key(&subdir) {
-            map.insert(subdir.clone(), vec![]);
-        }
-        map.get_mut(&subdir)
-            .unwrap()
-            .push(res.get_name().to_string());
-    }
-    map
-}
@@ -12,15 +12,64 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::{HashMap, HashSet};
+
 use crate::{generate_res_chunk, resource_external_types::*};
 use pack_common::*;
 
-pub fn construct_string_pool(strings: &Vec<String>) -> Result<ResChunk> {
+pub fn construct_string_pool(strings: &[String]) -> Result<ResChunk> {
+    construct_string_pool_with_flags(strings, STRING_POOL_UTF8_FLAG)
+}
+
+/// Builds a pool that stores each distinct string in `strings` once, and
+/// returns it along with the index in the pool of each of `strings`.
+///
+/// With `sorted`, the pool is in UTF-16 order and flagged as sorted, which lets
+/// Android binary search it.
+pub fn construct_deduplicated_string_pool(
+    strings: &[String],
+    sorted: bool
+) -> Result<(ResChunk, Vec<u32>)> {
+    let mut unique: Vec<String> = strings.to_vec();
+    if sorted {
+        // Android compares strings by their UTF-16 code units
+        unique.sort_by(|a, b| a.encode_utf16().cmp(b.encode_utf16()));
+        unique.dedup();
+    } else {
+        let mut seen = HashSet::new();
+        unique.retain(|string| seen.insert(string.clone()));
+    }
+
+    let positions: HashMap<&String, u32> = unique
+        .iter()
+        .enumerate()
+        .map(|(i, string)| (string, i as u32))
+        .collect();
+    let indices = strings.iter().map(|string| positions[string]).collect();
+
+    let flags = if sorted {
+        STRING_POOL_UTF8_FLAG | STRING_POOL_SORTED_FLAG
+    } else {
+        STRING_POOL_UTF8_FLAG
+    };
+    Ok((construct_string_pool_with_flags(&unique, flags)?, indices))
+}
+
+// Strings that are in the pool more than once, eg. the placeholders for
+// android: attributes that compiled XML doesn't end up using, point at the
+// same data rather than each having a copy.
+fn construct_string_pool_with_flags(strings: &[String], flags: u32) -> Result<ResChunk> {
     let mut string_indicies: Vec<u32> = vec![];
     let mut string_data: Vec<u8> = vec![];
+    let mut offsets: HashMap<&str, u32> = HashMap::new();
     for string in strings {
+        if let Some(&offset) = offsets.get(string.as_str()) {
+            string_indicies.push(offset);
+            continue;
+        }
         let index = string_data.len() as u32;
         string_indicies.push(index);
+        offsets.insert(string, index);
 
         if string.len() > 0x7FFF {
             // I think normal AAPT2 would fall back to UTF-16 encoding here, since
@@ -30,33 +79,23 @@ pub fn construct_string_pool(strings: &Vec<String>) -> Result<ResChunk> {
             return Err(PackError::StringPoolStringTooLong(string.clone()));
         }
 
-        let char_count = string.chars().count();
-        let byte_count = string.len();
-        if string.len() < 128 {
-            string_data.push(char_count as u8);
-            string_data.push(byte_count as u8);
-        } else {
-            string_data.push(0x80 | ((char_count >> 8) & 0xFF) as u8);
-            string_data.push((char_count & 0b11111111) as u8);
-            string_data.push(0x80 | ((byte_count >> 8) & 0xFF) as u8);
-            string_data.push((byte_count & 0b11111111) as u8);
-        }
+        // The first length is in UTF-16 code units rather than characters, eg.
+        // 2 for an emoji, which is what Android allocates when it decodes it.
+        // Each length is encoded on its own, since they can differ widely.
+        push_length(&mut string_data, string.encode_utf16().count());
+        push_length(&mut string_data, string.len());
 
         string_data.extend(string.bytes());
         string_data.push(0);
     }
 
-    // String data is a u8 array, but AAPT requires all chunks to fall on
-    // 32-bit boundaries. So we need to padd out to an even 4-bytes.
-    // TODO: Move this to the generate_res_chunk function, it should apply to all chunks
-    let padding = 4 - (string_data.len() % 4);
-    string_data.resize(string_data.len() + padding, 0);
-
+    // String data is a u8 array, so it's usually not 4-byte aligned.
+    // generate_res_chunk pads it out.
     let string_indicies_size_bytes = 4 * strings.len() as u32;
     let string_pool_header = StringPoolHeader {
         string_count: strings.len() as u32,
         style_count: 0,
-        flags: STRING_POOL_UTF8_FLAG,
+        flags,
         strings_start: 0x1C + string_indicies_size_bytes,
         styles_start: 0
     };
@@ -68,3 +107,104 @@ pub fn construct_string_pool(strings: &Vec<String>) -> Result<ResChunk> {
 
     generate_res_chunk(ChunkType::StringPool, string_pool_chunk, 0x1C - 0x08, 0)
 }
+
+// Lengths under 128 take one byte. Longer ones take two, big-endian, with the
+// high bit of the first set.
+fn push_length(data: &mut Vec<u8>, length: usize) {
+    if length < 0x80 {
+        data.push(length as u8);
+    } else {
+        data.push(0x80 | (length >> 8) as u8);
+        data.push(length as u8);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        decoder::{decode_xml, BinaryValue},
+        xml_file::xml_to_res_chunk
+    };
+    use deku::DekuContainerWrite;
+    use std::io::Cursor;
+
+    // Compiles the string as an attribute value and reads it back the way
+    // Android would
+    fn round_trip(string: &str) -> Result<String> {
+        let xml = format!("<face title=\"{string}\"/>");
+        let (chunk, _) = xml_to_res_chunk(&mut Cursor::new(xml), &[])?;
+        match decode_xml(&chunk.to_bytes()?)?.attribute("title") {
+            Some(BinaryValue::String(value)) => Ok(value.clone()),
+            value => panic!("Expected a string, not {value:?}")
+        }
+    }
+
+    #[test]
+    fn lengths_switch_to_two_bytes_at_0x80() {
+        for (length, expected) in [
+            (0, vec![0x00]),
+            (0x7F, vec![0x7F]),
+            (0x80, vec![0x80, 0x80]),
+            (0x7FFF, vec![0xFF, 0xFF])
+        ] {
+            let mut data = vec![];
+            push_length(&mut data, length);
+            assert_eq!(data, expected, "{length:#x}");
+        }
+    }
+
+    #[test]
+    fn byte_lengths_round_trip_at_0x80() {
+        // CJK characters are 3 bytes but 1 UTF-16 code unit, so only the byte
+        // length crosses the boundary
+        for string in ["字".repeat(42) + "a", "字".repeat(42) + "ab"] {
+            assert_eq!(round_trip(&string).unwrap(), string);
+        }
+    }
+
+    #[test]
+    fn utf16_lengths_round_trip_at_0x80() {
+        // Emoji are 2 UTF-16 code units each, so these are 127 and 128 units
+        for string in ["😀".repeat(63) + "a", "😀".repeat(64)] {
+            assert_eq!(round_trip(&string).unwrap(), string);
+        }
+    }
+
+    #[test]
+    fn lengths_round_trip_at_0x7fff() {
+        // Both lengths are 0x7FFF for ASCII. Any string that long in UTF-16
+        // code units is at least that long in bytes too.
+        for string in ["a".repeat(0x7FFF), "字".repeat(10920) + "😀abc"] {
+            assert_eq!(round_trip(&string).unwrap(), string);
+        }
+    }
+
+    #[test]
+    fn strings_over_0x7fff_bytes_are_rejected() {
+        let string = "字".repeat(10920) + "😀abcd";
+        assert_eq!(string.len(), 0x8000);
+        assert!(matches!(
+            construct_string_pool(&[string]),
+            Err(PackError::StringPoolStringTooLong(_))
+        ));
+    }
+
+    fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    // The offset of each string's data from the start of the pool's data
+    fn string_offsets(pool: &[u8]) -> Vec<u32> {
+        let count = u32_at(pool, 8) as usize;
+        (0..count).map(|i| u32_at(pool, 0x1C + i * 4)).collect()
+    }
+
+    #[test]
+    fn identical_strings_share_data() {
+        let strings = ["TMP", "label", "TMP"].map(String::from);
+        let pool = construct_string_pool(&strings).unwrap().to_bytes().unwrap();
+        // Each string's data is its two lengths, the string and a terminator
+        assert_eq!(string_offsets(&pool), [0, 6, 0]);
+    }
+}
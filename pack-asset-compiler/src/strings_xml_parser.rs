@@ -15,16 +15,44 @@
 // The res/values/strings.xml file is parsed separately and specially.
 // It's not a path-referenced resource like drawables, the strings all
 // go *directly* into resources.arsc
+//
+// Other values files are parsed the same way, for aliases: elements named
+// after a resource type whose value refers to another resource, eg.
+// <drawable name="preview_round">@drawable/preview</drawable>, or the same
+// with <item type="drawable">. They let one file be used under several names.
 use std::io::Read;
 
-use xml::{reader::XmlEvent, EventReader};
+use xml::{attribute::OwnedAttribute, reader::XmlEvent, EventReader};
+
+use crate::{
+    resource_graph::ResourceName,
+    resource_internal_types::{AliasResource, Resource, StringResource}
+};
 
-use crate::resource_internal_types::{Resource, StringResource};
+// The types that can be aliased by an element named after them. Any type can
+// be with <item type="...">.
+const ALIAS_ELEMENTS: &[&str] = &[
+    "bool", "color", "dimen", "drawable", "integer", "layout", "mipmap", "raw", "xml"
+];
 
+/// Parses the `<string>`s in a strings.xml file. Aliases are left out, see
+/// [parse_values_xml].
 pub fn parse_strings_xml<T: Read>(byte_source: &mut T) -> Vec<Resource> {
+    parse_values_xml(byte_source)
+        .into_iter()
+        .filter(|res| matches!(res, Resource::String(_)))
+        .collect()
+}
+
+/// Parses the `<string>`s and aliases in a values file. Anything else, like
+/// a `<color>` with a literal value, is ignored.
+pub fn parse_values_xml<T: Read>(byte_source: &mut T) -> Vec<Resource> {
     let xml_source = EventReader::new(byte_source);
-    let mut strings = vec![];
-    let mut next_string_name: Option<String> = None;
+    let mut resources = vec![];
+    // The <string> being read, whose value is built up from its text
+    let mut next_string: Option<StringResource> = None;
+    // Same for an alias, whose value is the reference
+    let mut next_alias: Option<AliasResource> = None;
 
     for event in xml_source {
         match event {
@@ -34,28 +62,81 @@ pub fn parse_strings_xml<T: Read>(byte_source: &mut T) -> Vec<Resource> {
                 namespace: _namespace
             }) => {
                 if name.local_name == "string" {
+                    let mut string = StringResource {
+                        resource_id: 0,
+                        name: String::new(),
+                        value: String::new(),
+                        config: Default::default(),
+                        translatable: true,
+                        formatted: true
+                    };
+                    let mut has_name = false;
                     for attr in attributes {
-                        if attr.name.local_name == "name" {
-                            next_string_name = Some(attr.value);
+                        match &attr.name.local_name[..] {
+                            "name" => {
+                                string.name = attr.value;
+                                has_name = true;
+                            }
+                            "translatable" => string.translatable = attr.value != "false",
+                            "formatted" => string.formatted = attr.value != "false",
+                            _ => {}
                         }
                     }
+                    next_string = has_name.then_some(string);
+                } else {
+                    next_alias = alias_start(&name.local_name, &attributes);
                 }
             }
-            Ok(XmlEvent::Characters(chars)) => {
-                if let Some(string_name) = &next_string_name {
-                    strings.push(Resource::String(StringResource {
-                        resource_id: 0,
-                        name: string_name.clone(),
-                        value: chars
-                    }))
+            Ok(XmlEvent::Characters(chars)) | Ok(XmlEvent::CData(chars)) => {
+                if let Some(string) = &mut next_string {
+                    string.value.push_str(&chars);
+                } else if let Some(alias) = &mut next_alias {
+                    alias.target.push_str(&chars);
                 }
                 // Else this was some other random text in the file, not in a <string /> tag
                 // Ignore this for resilience
             }
+            // Strings are added when they end, so that empty ones like
+            // <string name="x"/>, which have no text, are still added
+            Ok(XmlEvent::EndElement { name }) => {
+                if name.local_name == "string" {
+                    if let Some(string) = next_string.take() {
+                        resources.push(Resource::String(string));
+                    }
+                } else if let Some(mut alias) = next_alias.take() {
+                    alias.target = alias.target.trim().to_string();
+                    if ResourceName::from_reference(&alias.target).is_some() {
+                        resources.push(Resource::Alias(alias));
+                    }
+                }
+            }
             // Don't care about most structural elements
             _ => {}
         }
     }
 
-    strings
+    resources
+}
+
+// Starts an alias for eg. <drawable name="x"> or <item type="drawable"
+// name="x">, which becomes one if its value turns out to be a reference
+fn alias_start(element: &str, attributes: &[OwnedAttribute]) -> Option<AliasResource> {
+    let attribute = |name: &str| {
+        attributes
+            .iter()
+            .find(|attr| attr.name.local_name == name && attr.name.namespace.is_none())
+            .map(|attr| attr.value.clone())
+    };
+    let res_type = match element {
+        "item" => attribute("type")?,
+        _ if ALIAS_ELEMENTS.contains(&element) => element.to_string(),
+        _ => return None
+    };
+    Some(AliasResource {
+        res_type,
+        name: attribute("name")?,
+        target: String::new(),
+        config: Default::default(),
+        resource_id: 0
+    })
 }
@@ -0,0 +1,388 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Patches the resources.arsc of an existing APK. The table is decoded, edited
+// and written out again, rather than rebuilt from resources, so that every
+// resource that isn't touched keeps its ID, and the compiled XML and code that
+// refer to it by ID still work.
+//
+// Only what the decoder reads survives a round trip. That's every value, but
+// not string styling, public flags, overlayables or shared library chunks.
+use std::collections::HashMap;
+
+use deku::DekuContainerWrite;
+use pack_common::{PackError, Result};
+
+use crate::{
+    decoder::{
+        decode_resource_table, BinaryValue, DecodedEntry, DecodedPackage, DecodedTable, DecodedType
+    },
+    resource_config::ResourceConfig,
+    resource_external_types::{
+        RES_TABLE_PACKAGE_TYPE, RES_TABLE_TYPE, RES_TABLE_TYPE_SPEC_TYPE, RES_TABLE_TYPE_TYPE,
+        TYPE_ATTRIBUTE, TYPE_FLOAT, TYPE_INT_BOOLEAN, TYPE_INT_COLOR_ARGB8, TYPE_INT_DEC,
+        TYPE_NULL, TYPE_REFERENCE, TYPE_STRING
+    },
+    resource_table::USER_PACKAGE_MAGIC,
+    string_pool::{construct_deduplicated_string_pool, construct_string_pool}
+};
+
+const NO_ENTRY: u32 = 0xFFFF_FFFF;
+const ENTRY_FLAG_COMPLEX: u16 = 0x0001;
+
+/// Adds, replaces and removes resources in a decoded `resources.arsc`.
+///
+/// ```ignore
+/// let mut editor = ResourceTableEditor::decode(&arsc)?;
+/// editor.replace("string", "app_name", vec![(ResourceConfig::default(), BinaryValue::String("Patched".into()))])?;
+/// let id = editor.add("drawable", "badge", vec![(ResourceConfig::default(), BinaryValue::String("res/drawable/badge.png".into()))])?;
+/// let arsc = editor.encode()?;
+/// ```
+///
+/// Edits apply to the app's own package, `0x7F`. Existing resources keep
+/// their IDs, and removed resources leave a gap rather than shifting the
+/// ones after them. Files that file-based resources point to, like
+/// `res/drawable/badge.png`, have to be added to the APK separately.
+#[derive(Debug, Clone)]
+pub struct ResourceTableEditor {
+    table: DecodedTable
+}
+
+impl ResourceTableEditor {
+    pub fn new(table: DecodedTable) -> Self {
+        ResourceTableEditor { table }
+    }
+
+    /// Decodes a `resources.arsc` to edit.
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        Ok(ResourceTableEditor::new(decode_resource_table(bytes)?))
+    }
+
+    /// The table with every edit so far.
+    pub fn table(&self) -> &DecodedTable {
+        &self.table
+    }
+
+    /// Adds a resource with a value for each config, eg. a string with its
+    /// translations. It gets the next free ID of its type, or the type gets
+    /// the next free type ID if the table doesn't have it yet. Returns the new
+    /// resource's ID.
+    pub fn add(
+        &mut self,
+        res_type: &str,
+        name: &str,
+        values: Vec<(ResourceConfig, BinaryValue)>
+    ) -> Result<u32> {
+        if self.find(res_type, name).is_some() {
+            return Err(invalid(format!("@{res_type}/{name} already exists.")));
+        }
+        let package = self
+            .table
+            .packages
+            .iter_mut()
+            .find(|package| package.id == USER_PACKAGE_MAGIC)
+            .ok_or(invalid("The table doesn't have an app package."))?;
+        let type_index = match package.types.iter().position(|t| t.name == res_type) {
+            Some(type_index) => type_index,
+            None => {
+                let id = package.types.iter().map(|t| t.id).max().unwrap_or(0);
+                let id = id
+                    .checked_add(1)
+                    .ok_or(invalid("The table already has 255 types."))?;
+                package.types.push(DecodedType {
+                    id,
+                    name: res_type.into(),
+                    entry_count: 0,
+                    entries: vec![]
+                });
+                package.types.len() - 1
+            }
+        };
+        let decoded_type = &mut package.types[type_index];
+        let next_id = decoded_type
+            .entries
+            .iter()
+            .map(|entry| entry.id as u32 + 1)
+            .max()
+            .unwrap_or(0)
+            .max(decoded_type.entry_count);
+        let id = u16::try_from(next_id)
+            .map_err(|_| invalid(format!("@{res_type} has no free resource IDs left.")))?;
+        decoded_type.entries.push(DecodedEntry {
+            id,
+            name: name.into(),
+            values
+        });
+        decoded_type.entry_count = next_id + 1;
+        Ok(resource_id(package.id, decoded_type.id, id))
+    }
+
+    /// Replaces all of a resource's values, keeping its ID, which is returned.
+    pub fn replace(
+        &mut self,
+        res_type: &str,
+        name: &str,
+        values: Vec<(ResourceConfig, BinaryValue)>
+    ) -> Result<u32> {
+        let (package_id, type_id, entry) = self
+            .find(res_type, name)
+            .ok_or(invalid(format!("@{res_type}/{name} doesn't exist.")))?;
+        entry.values = values;
+        Ok(resource_id(package_id, type_id, entry.id))
+    }
+
+    /// Removes a resource, and returns the ID it had. No other resource's ID
+    /// changes, so anything still referring to it will fail to resolve.
+    pub fn remove(&mut self, res_type: &str, name: &str) -> Result<u32> {
+        let (package_id, type_id, entry) = self
+            .find(res_type, name)
+            .ok_or(invalid(format!("@{res_type}/{name} doesn't exist.")))?;
+        let id = resource_id(package_id, type_id, entry.id);
+        for package in &mut self.table.packages {
+            for decoded_type in &mut package.types {
+                decoded_type
+                    .entries
+                    .retain(|entry| resource_id(package.id, decoded_type.id, entry.id) != id);
+            }
+        }
+        Ok(id)
+    }
+
+    /// Encodes the edited table as a `resources.arsc`.
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        encode_resource_table(&self.table)
+    }
+
+    fn find(&mut self, res_type: &str, name: &str) -> Option<(u32, u8, &mut DecodedEntry)> {
+        let package = self
+            .table
+            .packages
+            .iter_mut()
+            .find(|package| package.id == USER_PACKAGE_MAGIC)?;
+        let decoded_type = package.types.iter_mut().find(|t| t.name == res_type)?;
+        let entry = decoded_type
+            .entries
+            .iter_mut()
+            .find(|entry| entry.name == name)?;
+        Some((package.id, decoded_type.id, entry))
+    }
+}
+
+/// Encodes a [DecodedTable] as a `resources.arsc`, with every resource at the
+/// ID it was decoded with.
+pub fn encode_resource_table(table: &DecodedTable) -> Result<Vec<u8>> {
+    // Every string value in every package shares the table's pool
+    let mut strings = vec![];
+    for package in &table.packages {
+        for decoded_type in &package.types {
+            for entry in &decoded_type.entries {
+                for (_, value) in &entry.values {
+                    collect_strings(value, &mut strings);
+                }
+            }
+        }
+    }
+    let (pool, indices) = construct_deduplicated_string_pool(&strings, false)?;
+    let string_ids: HashMap<&str, u32> = strings.iter().map(String::as_str).zip(indices).collect();
+
+    let mut body = pool.to_bytes()?;
+    for package in &table.packages {
+        body.extend(encode_package(package, &string_ids)?);
+    }
+    Ok(chunk(
+        RES_TABLE_TYPE,
+        &(table.packages.len() as u32).to_le_bytes(),
+        &body
+    ))
+}
+
+fn encode_package(package: &DecodedPackage, string_ids: &HashMap<&str, u32>) -> Result<Vec<u8>> {
+    // Type names are looked up by type ID - 1, so gaps need a placeholder
+    let type_count = package.types.iter().map(|t| t.id).max().unwrap_or(0) as usize;
+    let mut type_names = vec![String::new(); type_count];
+    for decoded_type in &package.types {
+        if decoded_type.id == 0 {
+            return Err(invalid(format!(
+                "@{} has a type ID of 0.",
+                decoded_type.name
+            )));
+        }
+        type_names[decoded_type.id as usize - 1] = decoded_type.name.clone();
+    }
+    let keys: Vec<String> = package
+        .types
+        .iter()
+        .flat_map(|t| t.entries.iter().map(|entry| entry.name.clone()))
+        .collect();
+    let type_pool = construct_string_pool(&type_names)?.to_bytes()?;
+    let (key_pool, key_indices) = construct_deduplicated_string_pool(&keys, false)?;
+    let key_pool = key_pool.to_bytes()?;
+
+    let mut types_data = vec![];
+    let mut key_indices = key_indices.into_iter();
+    for decoded_type in &package.types {
+        let entry_count = decoded_type
+            .entries
+            .iter()
+            .map(|entry| entry.id as u32 + 1)
+            .max()
+            .unwrap_or(0)
+            .max(decoded_type.entry_count);
+        let mut change_flags = vec![0u32; entry_count as usize];
+        let mut configs: Vec<&ResourceConfig> = vec![];
+        // The entry, key and value for each config
+        let mut by_config: HashMap<&ResourceConfig, Vec<(u16, u32, &BinaryValue)>> = HashMap::new();
+        for entry in &decoded_type.entries {
+            let key = key_indices.next().unwrap_or_default();
+            for (config, value) in &entry.values {
+                change_flags[entry.id as usize] |= config.change_flags();
+                if !configs.contains(&config) {
+                    configs.push(config);
+                }
+                by_config
+                    .entry(config)
+                    .or_default()
+                    .push((entry.id, key, value));
+            }
+        }
+        configs.sort();
+
+        let mut spec_header = vec![decoded_type.id, 0, 0, 0];
+        spec_header.extend(entry_count.to_le_bytes());
+        let spec_body: Vec<u8> = change_flags.iter().flat_map(|f| f.to_le_bytes()).collect();
+        types_data.extend(chunk(RES_TABLE_TYPE_SPEC_TYPE, &spec_header, &spec_body));
+
+        for config in configs {
+            let mut offsets = vec![NO_ENTRY; entry_count as usize];
+            let mut entries = vec![];
+            for &(id, key, value) in &by_config[config] {
+                offsets[id as usize] = entries.len() as u32;
+                entries.extend(encode_entry(key, value, string_ids)?);
+            }
+            // The default config is only its size, as the rest would be zeros
+            let config_data = if config.is_default() {
+                vec![]
+            } else {
+                config.to_res_table_config().to_vec()
+            };
+            let header_size = 8 + 12 + 4 + config_data.len() as u32;
+            let mut header = vec![decoded_type.id, 0, 0, 0];
+            header.extend(entry_count.to_le_bytes());
+            header.extend((header_size + entry_count * 4).to_le_bytes());
+            header.extend((4 + config_data.len() as u32).to_le_bytes());
+            header.extend(config_data);
+            let mut body: Vec<u8> = offsets.iter().flat_map(|o| o.to_le_bytes()).collect();
+            body.extend(entries);
+            types_data.extend(chunk(RES_TABLE_TYPE_TYPE, &header, &body));
+        }
+    }
+
+    // ResTable_package, whose string pools come straight after its header
+    let header_size: u32 = 8 + 4 + 256 + 20;
+    let mut header = package.id.to_le_bytes().to_vec();
+    let mut name: Vec<u16> = package.name.encode_utf16().collect();
+    if name.len() > 128 {
+        return Err(PackError::PackageNameTooLong(package.name.clone()));
+    }
+    name.resize(128, 0);
+    header.extend(name.iter().flat_map(|unit| unit.to_le_bytes()));
+    header.extend(header_size.to_le_bytes());
+    header.extend(0u32.to_le_bytes());
+    header.extend((header_size + type_pool.len() as u32).to_le_bytes());
+    header.extend(0u32.to_le_bytes());
+    header.extend(0u32.to_le_bytes());
+    let mut body = type_pool;
+    body.extend(key_pool);
+    body.extend(types_data);
+    Ok(chunk(RES_TABLE_PACKAGE_TYPE, &header, &body))
+}
+
+// A ResTable_entry, or a ResTable_map_entry and its maps for bags
+fn encode_entry(key: u32, value: &BinaryValue, string_ids: &HashMap<&str, u32>) -> Result<Vec<u8>> {
+    let mut bytes = vec![];
+    match value {
+        BinaryValue::Bag { parent, items } => {
+            bytes.extend(16u16.to_le_bytes());
+            bytes.extend(ENTRY_FLAG_COMPLEX.to_le_bytes());
+            bytes.extend(key.to_le_bytes());
+            bytes.extend(parent.unwrap_or(0).to_le_bytes());
+            bytes.extend((items.len() as u32).to_le_bytes());
+            for (name, item) in items {
+                bytes.extend(name.to_le_bytes());
+                bytes.extend(encode_value(item, string_ids)?);
+            }
+        }
+        _ => {
+            bytes.extend(8u16.to_le_bytes());
+            bytes.extend(0u16.to_le_bytes());
+            bytes.extend(key.to_le_bytes());
+            bytes.extend(encode_value(value, string_ids)?);
+        }
+    }
+    Ok(bytes)
+}
+
+// A Res_value
+fn encode_value(value: &BinaryValue, string_ids: &HashMap<&str, u32>) -> Result<[u8; 8]> {
+    let (data_type, data) = match value {
+        BinaryValue::Null => (TYPE_NULL, 0),
+        BinaryValue::Reference(id) => (TYPE_REFERENCE, *id),
+        BinaryValue::AttributeReference(id) => (TYPE_ATTRIBUTE, *id),
+        BinaryValue::String(string) => (TYPE_STRING, string_ids[string.as_str()]),
+        BinaryValue::Float(float) => (TYPE_FLOAT, float.to_bits()),
+        BinaryValue::Integer(integer) => (TYPE_INT_DEC, *integer as u32),
+        // Android reads any non-zero value as true, but writes all ones
+        BinaryValue::Boolean(boolean) => (TYPE_INT_BOOLEAN, if *boolean { u32::MAX } else { 0 }),
+        BinaryValue::Color(color) => (TYPE_INT_COLOR_ARGB8, *color),
+        BinaryValue::Other(data_type, data) => (*data_type, *data),
+        BinaryValue::Bag { .. } => return Err(invalid("A bag can't be an item of another bag."))
+    };
+    let mut bytes = [0; 8];
+    bytes[..2].copy_from_slice(&8u16.to_le_bytes());
+    bytes[3] = data_type;
+    bytes[4..].copy_from_slice(&data.to_le_bytes());
+    Ok(bytes)
+}
+
+fn collect_strings(value: &BinaryValue, strings: &mut Vec<String>) {
+    match value {
+        BinaryValue::String(string) => strings.push(string.clone()),
+        BinaryValue::Bag { items, .. } => {
+            for (_, item) in items {
+                collect_strings(item, strings);
+            }
+        }
+        _ => {}
+    }
+}
+
+// A chunk with a ResChunk_header, followed by the rest of its header and its body
+fn chunk(chunk_type: u16, header: &[u8], body: &[u8]) -> Vec<u8> {
+    let header_size = 8 + header.len();
+    let mut bytes = Vec::with_capacity(header_size + body.len());
+    bytes.extend(chunk_type.to_le_bytes());
+    bytes.extend((header_size as u16).to_le_bytes());
+    bytes.extend(((header_size + body.len()) as u32).to_le_bytes());
+    bytes.extend(header);
+    bytes.extend(body);
+    bytes
+}
+
+fn resource_id(package_id: u32, type_id: u8, entry_id: u16) -> u32 {
+    (package_id << 24) | ((type_id as u32) << 16) | entry_id as u32
+}
+
+fn invalid(reason: impl Into<String>) -> PackError {
+    PackError::ResourceTableEditInvalid(reason.into())
+}
@@ -14,24 +14,27 @@
 
 use pack_common::*;
 use std::{
-    collections::{HashMap, HashSet},
-    io::{Read, Seek, SeekFrom},
-    path::PathBuf
+    collections::HashMap,
+    io::{Read, Seek, SeekFrom}
 };
 
 use crate::{
     generate_res_chunk,
+    inline_strings::StringValues,
     internal_android_attributes::{get_internal_attribute_id, infer_attribute_type},
     resource_external_types::*,
     resource_internal_types::Resource,
+    resource_table::ResourceIds,
     string_pool::construct_string_pool,
-    xml_first_pass::count_unique_android_internal_attributes
+    xml_first_pass::count_unique_android_internal_attributes,
+    xml_namespaces::{NamespaceScopes, ToolsNamespace}
 };
 use deku::DekuContainerWrite;
 use xml::{
     attribute::OwnedAttribute,
+    common::{Position, TextPosition},
     name::OwnedName,
-    reader::{EventReader, XmlEvent}
+    reader::{EventReader, ParserConfig, XmlEvent}
 };
 
 const ANDROID_NAMESPACE: &str = "http://schemas.android.com/apk/res/android";
@@ -45,11 +48,48 @@ pub const ANDROID_INTERNAL_ATTRIBUTE_MAGIC: u32 = 0x0101_0000;
 // we add ourselves.
 const ANDROID_UNIQUE_ATTR_PADDING: usize = 2;
 
+/// The attributes aapt2 adds to the `<manifest>` element of every app it
+/// links, recording the platform it was compiled against. Binary XML
+/// manifests always get them.
+pub fn platform_build_attributes() -> [OwnedAttribute; 4] {
+    [
+        OwnedAttribute::new(
+            OwnedName::qualified("compileSdkVersion", ANDROID_NAMESPACE, Some(ANDROID_PREFIX)),
+            ANDROID_COMPILE_VERSION
+        ),
+        OwnedAttribute::new(
+            OwnedName::qualified(
+                "compileSdkCodename",
+                ANDROID_NAMESPACE,
+                Some(ANDROID_PREFIX)
+            ),
+            ANDROID_COMPILE_CODENAME
+        ),
+        OwnedAttribute::new(
+            OwnedName::local("platformBuildVersionCode"),
+            ANDROID_COMPILE_VERSION
+        ),
+        OwnedAttribute::new(
+            OwnedName::local("platformBuildVersionName"),
+            ANDROID_COMPILE_CODENAME
+        )
+    ]
+}
+
 fn generate_xml_chunk<T: DekuContainerWrite>(chunk_type: ChunkType, chunk: T) -> Result<Vec<u8>> {
+    generate_xml_chunk_with_comment(chunk_type, chunk, UINT32_MINUS_ONE)
+}
+
+// `comment` is the comment's index in the string pool
+fn generate_xml_chunk_with_comment<T: DekuContainerWrite>(
+    chunk_type: ChunkType,
+    chunk: T,
+    comment: u32
+) -> Result<Vec<u8>> {
     let chunk_bytes = chunk.to_bytes()?;
     let node_header = XmlNodeChunk {
         line_number: 1,
-        comment: UINT32_MINUS_ONE,
+        comment,
         node_data: chunk_bytes
     };
     Ok(generate_res_chunk(chunk_type, node_header, 8, 0)?.to_bytes()?)
@@ -66,12 +106,65 @@ fn generate_namspace_chunk(start: bool, prefix: u32, uri: u32) -> Result<Vec<u8>
     )
 }
 
+/// Which attributes keep their original text as a raw value in compiled XML,
+/// alongside their typed value. Android itself only reads the typed values, but
+/// some tools and on-device parsers read `rawValue` directly.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum RawValues {
+    /// Only string attributes, whose raw and typed values are the same string.
+    #[default]
+    Strings,
+    /// Strings, plus attributes outside the `android` namespace, which have no
+    /// resource ID to compile them against. This matches aapt2's output, eg.
+    /// `platformBuildVersionCode` keeps `"34"`.
+    Aapt2,
+    /// Every attribute, eg. `"@drawable/preview"` for references, like aapt2's
+    /// `--keep-raw-values`.
+    All
+}
+
+// aapt2 puts android: attributes first, by resource ID, then the rest by
+// namespace and name
+// xml-rs already rejects most of what this is used for, but it allows a few
+// things through that binary XML can't hold
+fn malformed_xml(position: TextPosition, message: String) -> PackError {
+    PackError::XmlParsingFailed((&position, message).into())
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum AttributeOrder {
+    Android(u32),
+    Other(String, String)
+}
+
+/// Options for [xml_to_res_chunk_with_options].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct XmlOptions {
+    pub raw_values: RawValues,
+    /// Keeps comments, eg. license headers, as the comment of the element
+    /// that follows them, like aapt2 does. Comments with no element after
+    /// them are dropped.
+    pub keep_comments: bool,
+    /// What to do with `tools:` attributes. See [ToolsNamespace].
+    pub tools_namespace: ToolsNamespace,
+    /// Replaces attributes that refer to strings, eg. `@string/app_name`,
+    /// with the default config's strings. See [StringValues::resolve].
+    pub inline_string_references: bool
+}
+
 // If the XML file was a manifest, we can bubble some useful information up to the caller,
 // such as the package name
 pub struct ManifestInfo {
     pub package_name: Option<String>,
     // This is only required for AAB packaging
-    pub label: Option<String>
+    pub label: Option<String>,
+    // Split APKs have to declare the same version code as the base
+    pub version_code: Option<String>,
+    pub version_name: Option<String>,
+    // From <uses-sdk android:minSdkVersion>
+    pub min_sdk: Option<String>
 }
 
 // Encodes an XML file into an XmlFileType ResChunk
@@ -80,17 +173,26 @@ pub struct ManifestInfo {
 pub fn xml_to_res_chunk<T: Read + Seek>(
     byte_source: &mut T,
     resources: &[Resource]
+) -> Result<(ResChunk, ManifestInfo)> {
+    xml_to_res_chunk_with_options(byte_source, resources, &XmlOptions::default())
+}
+
+/// Same as [xml_to_res_chunk], but with customisable [XmlOptions].
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+pub fn xml_to_res_chunk_with_options<T: Read + Seek>(
+    byte_source: &mut T,
+    resources: &[Resource],
+    options: &XmlOptions
 ) -> Result<(ResChunk, ManifestInfo)> {
     let mut strings: Vec<String> = vec![];
     let mut string_ids: HashMap<String, u32> = HashMap::new();
-    let mut seen_namespaces = HashSet::new();
-    let mut namespace_stack: Vec<Vec<usize>> = vec![];
+    let mut namespace_scopes = NamespaceScopes::new();
     let mut xml_resource_map: Vec<u32> = vec![];
 
     let unique_android_attrs =
         count_unique_android_internal_attributes(byte_source) + ANDROID_UNIQUE_ATTR_PADDING;
     // Send ptr back to the start for second pass over XML
-    byte_source.seek(SeekFrom::Start(0)).unwrap();
+    byte_source.seek(SeekFrom::Start(0))?;
 
     // These will all get replaced
     for _ in 0..unique_android_attrs {
@@ -136,13 +238,29 @@ pub fn xml_to_res_chunk<T: Read + Seek>(
         }};
     }
 
+    let android_id = ANDROID_INTERNAL_ATTRIBUTE_MAGIC | get_internal_attribute_id("id")?;
+    let string_values = options
+        .inline_string_references
+        .then(|| StringValues::new(resources));
     let mut manifest_info = ManifestInfo {
         package_name: None,
-        label: None
+        label: None,
+        version_code: None,
+        version_name: None,
+        min_sdk: None
     };
-    let xml_source = EventReader::new(byte_source);
+    let mut xml_source = EventReader::new_with_config(
+        byte_source,
+        ParserConfig::new().ignore_comments(!options.keep_comments)
+    );
     let mut chunks: Vec<u8> = vec![];
-    for event in xml_source {
+    // Comments since the last element, for the next one to start
+    let mut pending_comments: Vec<String> = vec![];
+    // Each element that's open, and the IDs of its name and namespace for it
+    // to end with
+    let mut open_elements: Vec<(OwnedName, u32, u32)> = vec![];
+    loop {
+        let event = xml_source.next();
         match event {
             // No Binary XML representation for this
             Ok(XmlEvent::StartDocument {
@@ -155,24 +273,13 @@ pub fn xml_to_res_chunk<T: Read + Seek>(
                 attributes: imm_attributes,
                 namespace
             }) => {
-                let mut namespaces_defined_this_element = vec![];
-                for ns in namespace.iter() {
-                    // These are kind of fake namespaces, runtime Android doesn't
-                    // care about these.
-                    if ns.0.is_empty() || ns.0 == "tools" || ns.0 == "xml" || ns.0 == "xmlns" {
-                        continue;
-                    }
-                    if seen_namespaces.contains(ns.0) {
-                        continue;
-                    }
-                    seen_namespaces.insert(ns.0.to_string());
-                    let prefix_id = add_or_use_string!(ns.0.to_string());
-                    let uri_id = add_or_use_string!(ns.1.to_string());
+                for (prefix, uri) in
+                    namespace_scopes.open(&namespace, options.tools_namespace.ignored_uris())
+                {
+                    let prefix_id = add_or_use_string!(prefix.clone());
+                    let uri_id = add_or_use_string!(uri.clone());
                     chunks.extend(generate_namspace_chunk(true, prefix_id, uri_id)?);
-                    namespaces_defined_this_element.push(prefix_id as usize);
-                    namespaces_defined_this_element.push(uri_id as usize);
                 }
-                namespace_stack.push(namespaces_defined_this_element);
 
                 let elem_name = name.local_name.to_string();
                 let name_id = add_or_use_string!(elem_name.clone());
@@ -189,44 +296,24 @@ pub fn xml_to_res_chunk<T: Read + Seek>(
                     style_index: 0,
                     attribute_data: vec![]
                 };
-                if let Some(ns) = name.namespace {
+                if let Some(ns) = &name.namespace {
                     elem.namespace = add_or_use_string!(ns.to_string());
                 }
 
                 let mut attributes = imm_attributes.to_vec();
                 if elem_name == "manifest" {
-                    // Inject some values that AAPT itself injects
-                    attributes.push(OwnedAttribute::new(
-                        OwnedName::qualified(
-                            "compileSdkVersion",
-                            ANDROID_NAMESPACE,
-                            Some(ANDROID_PREFIX)
-                        ),
-                        ANDROID_COMPILE_VERSION
-                    ));
-                    attributes.push(OwnedAttribute::new(
-                        OwnedName::qualified(
-                            "compileSdkCodename",
-                            ANDROID_NAMESPACE,
-                            Some(ANDROID_PREFIX)
-                        ),
-                        ANDROID_COMPILE_CODENAME
-                    ));
-                    attributes.push(OwnedAttribute::new(
-                        OwnedName::local("platformBuildVersionCode"),
-                        ANDROID_COMPILE_VERSION
-                    ));
-                    attributes.push(OwnedAttribute::new(
-                        OwnedName::local("platformBuildVersionName"),
-                        ANDROID_COMPILE_CODENAME
-                    ));
+                    attributes.extend(platform_build_attributes());
                 }
 
-                for attr in attributes {
-                    if let Some(ns) = &attr.name.prefix {
-                        if ns == "tools" {
-                            // Not a runtime-visible attribute
-                            continue;
+                let mut attr_chunks = vec![];
+                for mut attr in attributes {
+                    if !options.tools_namespace.keeps_attribute(&attr)? {
+                        // Not a runtime-visible attribute
+                        continue;
+                    }
+                    if let Some(values) = &string_values {
+                        if let Some(value) = values.resolve(&attr.value)? {
+                            attr.value = value.to_string();
                         }
                     }
 
@@ -236,6 +323,24 @@ pub fn xml_to_res_chunk<T: Read + Seek>(
                     {
                         manifest_info.package_name = Some(attr.value.clone());
                     }
+                    if elem_name == "manifest"
+                        && attr.name.local_name == "versionCode"
+                        && attr.name.namespace == Some(ANDROID_NAMESPACE.into())
+                    {
+                        manifest_info.version_code = Some(attr.value.clone());
+                    }
+                    if elem_name == "manifest"
+                        && attr.name.local_name == "versionName"
+                        && attr.name.namespace == Some(ANDROID_NAMESPACE.into())
+                    {
+                        manifest_info.version_name = Some(attr.value.clone());
+                    }
+                    if elem_name == "uses-sdk"
+                        && attr.name.local_name == "minSdkVersion"
+                        && attr.name.namespace == Some(ANDROID_NAMESPACE.into())
+                    {
+                        manifest_info.min_sdk = Some(attr.value.clone());
+                    }
                     if elem_name == "application"
                         && attr.name.local_name == "label"
                         && attr.name.namespace == Some(ANDROID_NAMESPACE.into())
@@ -244,6 +349,7 @@ pub fn xml_to_res_chunk<T: Read + Seek>(
                     }
 
                     let attr_type = infer_attribute_type(&attr.value);
+                    let is_android_attr = attr.name.prefix.as_deref() == Some(ANDROID_PREFIX);
                     let name_id = if let Some(prefix) = &attr.name.prefix {
                         if prefix == "android" {
                             add_or_use_android_string!(attr.name.local_name.clone())
@@ -253,34 +359,40 @@ pub fn xml_to_res_chunk<T: Read + Seek>(
                     } else {
                         add_or_use_string!(attr.name.local_name.clone())
                     };
-                    let namespace_id = if let Some(ns) = attr.name.namespace {
+                    let namespace_id = if let Some(ns) = &attr.name.namespace {
                         add_or_use_string!(ns.clone())
                     } else {
                         UINT32_MINUS_ONE
                     };
 
-                    let value_id = if attr_type == AttributeDataType::String {
-                        add_or_use_string!(attr.value.clone())
-                    } else {
-                        0xFFFFFFFF
+                    let value_id = match options.raw_values {
+                        _ if attr_type == AttributeDataType::String => {
+                            add_or_use_string!(attr.value.clone())
+                        }
+                        RawValues::All => add_or_use_string!(attr.value.clone()),
+                        RawValues::Aapt2 if !is_android_attr => {
+                            add_or_use_string!(attr.value.clone())
+                        }
+                        _ => UINT32_MINUS_ONE
                     };
                     let typed_value = XmlAttributeDataChunk {
                         size: 8,
                         res0: 0,
-                        data_type: attr_type.clone(),
+                        data_type: attr_type,
                         data: match attr_type {
                             AttributeDataType::Reference => {
                                 lookup_resource_id(&attr.value, resources)?
                             }
-                            AttributeDataType::String => value_id,
-                            AttributeDataType::DecimalInteger => attr.value.parse::<u32>()?,
-                            AttributeDataType::BooleanInteger => {
+                            AttributeDataType::IntDec => attr.value.parse::<u32>()?,
+                            AttributeDataType::IntBoolean => {
                                 if attr.value == "true" {
                                     1
                                 } else {
                                     0
                                 }
                             }
+                            // Strings, the only other type infer_attribute_type gives
+                            _ => value_id
                         }
                     };
 
@@ -290,35 +402,99 @@ pub fn xml_to_res_chunk<T: Read + Seek>(
                         raw_value: value_id,
                         typed_value
                     };
+                    let order = if is_android_attr {
+                        AttributeOrder::Android(
+                            xml_resource_map
+                                .get(name_id as usize)
+                                .copied()
+                                .unwrap_or(UINT32_MINUS_ONE)
+                        )
+                    } else {
+                        AttributeOrder::Other(
+                            attr.name.namespace.unwrap_or_default(),
+                            attr.name.local_name
+                        )
+                    };
+                    attr_chunks.push((order, attr_chunk));
+                }
+
+                // Android binary searches an element's attributes by resource
+                // ID, so they're sorted the way aapt2 sorts them
+                attr_chunks.sort_by(|(a, _), (b, _)| a.cmp(b));
+                for (index, (order, attr_chunk)) in attr_chunks.into_iter().enumerate() {
+                    // These are 1-based, so that 0 means there isn't one
+                    let position = u16::try_from(index + 1).map_err(|_| {
+                        malformed_xml(
+                            xml_source.position(),
+                            format!("<{}> has too many attributes.", name.borrow().to_repr())
+                        )
+                    })?;
+                    match order {
+                        AttributeOrder::Android(id) if id == android_id => elem.id_index = position,
+                        AttributeOrder::Other(namespace, name) if namespace.is_empty() => {
+                            match name.as_str() {
+                                "class" => elem.class_index = position,
+                                "style" => elem.style_index = position,
+                                _ => {}
+                            }
+                        }
+                        _ => {}
+                    }
                     elem.attribute_data.extend(attr_chunk.to_bytes()?);
                     elem.attribute_count += 1;
                 }
 
-                chunks.extend(generate_xml_chunk(ChunkType::XmlStartElement, elem)?);
+                let comment = if pending_comments.is_empty() {
+                    UINT32_MINUS_ONE
+                } else {
+                    add_or_use_string!(pending_comments.join("\n"))
+                };
+                pending_comments.clear();
+                open_elements.push((name, elem.name, elem.namespace));
+                chunks.extend(generate_xml_chunk_with_comment(
+                    ChunkType::XmlStartElement,
+                    elem,
+                    comment
+                )?);
             }
+            Ok(XmlEvent::Comment(comment)) => pending_comments.push(comment.trim().to_string()),
             Ok(XmlEvent::Whitespace(_)) => {}
-            Ok(XmlEvent::EndElement { name }) => {
-                let mut elem = XmlEndElementChunk {
-                    name: *string_ids.get(&name.local_name.to_string()).unwrap(),
-                    namespace: UINT32_MINUS_ONE
-                };
-                if let Some(ns) = &name.namespace {
-                    elem.namespace = *string_ids.get(&ns.to_string()).unwrap();
+            Ok(XmlEvent::EndElement { name: end_name }) => {
+                pending_comments.clear();
+                let end_repr = end_name.borrow().to_repr();
+                let (start_name, name, namespace) = open_elements.pop().ok_or_else(|| {
+                    malformed_xml(
+                        xml_source.position(),
+                        format!("</{end_repr}> doesn't close any element.")
+                    )
+                })?;
+                if start_name != end_name {
+                    return Err(malformed_xml(
+                        xml_source.position(),
+                        format!(
+                            "</{end_repr}> doesn't match <{}>.",
+                            start_name.borrow().to_repr()
+                        )
+                    ));
                 }
+                let elem = XmlEndElementChunk { name, namespace };
                 chunks.extend(generate_xml_chunk(ChunkType::XmlEndElement, elem)?);
-                let namepsaces_to_close = namespace_stack.pop().unwrap();
-                for i in (0..namepsaces_to_close.len()).step_by(2) {
-                    chunks.extend(generate_namspace_chunk(
-                        false,
-                        namepsaces_to_close[i] as u32,
-                        namepsaces_to_close[i + 1] as u32
-                    )?);
+                for (prefix, uri) in namespace_scopes.close() {
+                    let prefix_id = add_or_use_string!(prefix.clone());
+                    let uri_id = add_or_use_string!(uri.clone());
+                    chunks.extend(generate_namspace_chunk(false, prefix_id, uri_id)?);
                 }
             }
-            Ok(XmlEvent::EndDocument) => {}
+            Ok(XmlEvent::EndDocument) => break,
             Err(e) => return Err(PackError::XmlParsingFailed(e)),
-            // TODO: Don't println from within this library crate, consumers might not want that
-            _ => eprintln!("Warning: Unknown XML part: {:?}", event.unwrap())
+            // TODO: Don't println from within this library crate without the
+            //   tracing feature, consumers might not want that
+            Ok(part) => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!("Unknown XML part: {:?}", part);
+                #[cfg(not(feature = "tracing"))]
+                eprintln!("Warning: Unknown XML part: {:?}", part)
+            }
         }
     }
 
@@ -343,7 +519,7 @@ pub fn xml_to_res_chunk<T: Read + Seek>(
 
     Ok((
         generate_res_chunk(
-            ChunkType::XmlFile,
+            ChunkType::Xml,
             RawBytes {
                 data: string_pool_bytes
             },
@@ -355,9 +531,15 @@ pub fn xml_to_res_chunk<T: Read + Seek>(
 }
 
 pub fn lookup_resource_id(reference: &str, resources: &[Resource]) -> Result<u32> {
+    lookup_resource_id_in_packages(reference, &[ResourceIds::new(resources)?])
+}
+
+/// Like [lookup_resource_id], but searches several packages in order, eg. a
+/// feature module's own resources and then those of the base module.
+pub fn lookup_resource_id_in_packages(reference: &str, packages: &[ResourceIds]) -> Result<u32> {
     // Reference format: "@drawable/preview"
     // Trim @ and split
-    let trimmed = String::from(&reference[1..]);
+    let trimmed = String::from(reference.strip_prefix('@').unwrap_or(reference));
     let subdir_and_name: Vec<&str> = trimmed.split("/").collect();
     if subdir_and_name.len() != 2 {
         return Err(PackError::ReferenceAttributeParsingFailed(
@@ -365,32 +547,15 @@ pub fn lookup_resource_id(reference: &str, resources: &[Resource]) -> Result<u32
         ));
     }
 
-    let mut res_type = 0;
-    let mut res_id = 0;
-    let mut subdir = String::new();
-    for res in resources {
-        if res.get_subdirectory() != subdir {
-            subdir = res.get_subdirectory().into();
-            res_type += 1;
-            res_id = 0;
-        }
-
-        let res_name_path = PathBuf::from(res.get_name());
-        let res_stem = res_name_path.file_stem();
-        if res_stem.is_none() {
-            res_id += 1;
-            continue;
-        }
-        if res.get_subdirectory() == subdir_and_name[0] && res_stem.unwrap() == subdir_and_name[1] {
-            // At this stage, we may be parsing an AndroidManifest.xml, in which case
-            // we may not have built the resource table yet and we hit a chicken-and-egg
-            // problem.
-            // To avoid a circular dependency, we *predict* which ID the resource table
-            // code will assign to the referenced resource.
-            let predicted_res_id = 0x7F00_0000 | (res_type << 16) | res_id;
+    // At this stage, we may be parsing an AndroidManifest.xml, in which case
+    // we may not have built the resource table yet and we hit a chicken-and-egg
+    // problem.
+    // To avoid a circular dependency, we *predict* which ID the resource table
+    // code will assign to the referenced resource.
+    for ids in packages {
+        if let Some(predicted_res_id) = ids.lookup(subdir_and_name[0], subdir_and_name[1]) {
             return Ok(predicted_res_id);
         }
-        res_id += 1;
     }
 
     Err(PackError::ReferenceAttributeLookupFailed(
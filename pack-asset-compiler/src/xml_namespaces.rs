@@ -0,0 +1,125 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// xml-rs reports every namespace in scope at each element, rather than the
+// ones it declares. Both binary XML and ProtoXML need each element's own
+// declarations, so that a namespace starts at the element that declares it
+// and ends with that element, even when a prefix is declared again in a
+// sibling or shadowed by a different URI further down.
+//
+// It's also where the `tools` namespace is dealt with, so that binary XML and
+// ProtoXML treat it the same way.
+use pack_common::{PackError, Result};
+use xml::{attribute::OwnedAttribute, namespace::Namespace};
+
+/// The namespace of attributes like `tools:ignore` and `tools:targetApi`, which
+/// only Android Studio and lint read.
+pub const TOOLS_NAMESPACE: &str = "http://schemas.android.com/tools";
+
+/// What compiled XML does with the `tools` namespace. It's matched by URI, so
+/// it doesn't matter which prefix a file binds it to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum ToolsNamespace {
+    /// Drops `tools:` attributes and the `xmlns:tools` declaration, like
+    /// aapt2 does.
+    #[default]
+    Strip,
+    /// Compiles `tools:` attributes and the declaration like any other
+    /// namespace.
+    Keep,
+    /// Fails on any `tools:` attribute. The declaration on its own is still
+    /// dropped, since templates often declare it without using it.
+    Error
+}
+
+impl ToolsNamespace {
+    /// Whether `attr` should be compiled. Errors if it's a `tools:`
+    /// attribute and they aren't allowed.
+    pub fn keeps_attribute(self, attr: &OwnedAttribute) -> Result<bool> {
+        if attr.name.namespace.as_deref() != Some(TOOLS_NAMESPACE) {
+            return Ok(true);
+        }
+        match self {
+            ToolsNamespace::Strip => Ok(false),
+            ToolsNamespace::Keep => Ok(true),
+            ToolsNamespace::Error => Err(PackError::ToolsAttributeNotAllowed(format!(
+                "{}:{}",
+                attr.name.prefix.as_deref().unwrap_or_default(),
+                attr.name.local_name
+            )))
+        }
+    }
+
+    /// The namespace URIs that elements shouldn't declare, for
+    /// [NamespaceScopes::open].
+    pub fn ignored_uris(self) -> &'static [&'static str] {
+        match self {
+            ToolsNamespace::Keep => &[],
+            ToolsNamespace::Strip | ToolsNamespace::Error => &[TOOLS_NAMESPACE]
+        }
+    }
+}
+
+/// The namespaces declared by each element that's currently open.
+#[derive(Debug, Default)]
+pub struct NamespaceScopes {
+    scopes: Vec<Vec<(String, String)>>
+}
+
+impl NamespaceScopes {
+    pub fn new() -> Self {
+        NamespaceScopes::default()
+    }
+
+    /// Opens an element, given the namespaces in scope at it. Returns the
+    /// `(prefix, uri)` pairs the element declares, ie. prefixes that weren't
+    /// in scope before, or now point to a different URI. Prefixes bound to a
+    /// URI in `ignored_uris` are never declared, and neither are the built-in
+    /// `xml` and `xmlns` prefixes or the default namespace.
+    pub fn open(&mut self, namespace: &Namespace, ignored_uris: &[&str]) -> Vec<(String, String)> {
+        let declared: Vec<(String, String)> = namespace
+            .iter()
+            .filter(|(prefix, uri)| {
+                !prefix.is_empty()
+                    && *prefix != "xml"
+                    && *prefix != "xmlns"
+                    && !ignored_uris.contains(uri)
+            })
+            .filter(|(prefix, uri)| self.uri(prefix) != Some(uri))
+            .map(|(prefix, uri)| (prefix.to_string(), uri.to_string()))
+            .collect();
+        self.scopes.push(declared.clone());
+        declared
+    }
+
+    /// Closes the innermost element. Returns the namespaces it declared, in
+    /// reverse order, which is the order they end in.
+    pub fn close(&mut self) -> Vec<(String, String)> {
+        let mut declared = self.scopes.pop().unwrap_or_default();
+        declared.reverse();
+        declared
+    }
+
+    // The URI a prefix points to in the innermost scope that declares it
+    fn uri(&self, prefix: &str) -> Option<&str> {
+        self.scopes
+            .iter()
+            .rev()
+            .flatten()
+            .find(|(declared, _)| declared == prefix)
+            .map(|(_, uri)| uri.as_str())
+    }
+}
@@ -0,0 +1,63 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Prunes a shared cache while another thread keeps starting builds and
+// inserting into it, the way PackEngine builds overlap.
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc
+    },
+    thread
+};
+
+use pack_asset_compiler::build_cache::{BuildCache, CacheKey};
+
+const BUILDS: u64 = 100_000;
+
+fn key(build: u64) -> CacheKey {
+    let mut key = [0; 32];
+    key[..8].copy_from_slice(&build.to_le_bytes());
+    key
+}
+
+#[test]
+fn pruning_keeps_entries_inserted_during_it() {
+    let cache = Arc::new(BuildCache::new());
+    let done = Arc::new(AtomicBool::new(false));
+    // Several of them, so that one is usually waiting for the lock with a
+    // generation it read before the builder moved on
+    let pruners: Vec<_> = (0..4)
+        .map(|_| {
+            let (cache, done) = (Arc::clone(&cache), Arc::clone(&done));
+            thread::spawn(move || {
+                while !done.load(Ordering::Relaxed) {
+                    cache.prune_unused_for(64);
+                }
+            })
+        })
+        .collect();
+    for build in 0..BUILDS {
+        cache.begin_build();
+        cache.insert(key(build), vec![1]);
+        // Nothing else starts builds, so this one's entry is as new as they get
+        assert!(cache.get(&key(build)).is_some(), "build {build}");
+    }
+    done.store(true, Ordering::Relaxed);
+    for pruner in pruners {
+        pruner.join().unwrap();
+    }
+    cache.prune_unused_for(64);
+    assert_eq!(cache.len(), 64);
+}
@@ -0,0 +1,48 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Sets android:localeConfig on a manifest, which must leave the rest of it,
+// including comments, as it was.
+use pack_asset_compiler::locale_config::{manifest_locale_config, set_manifest_locale_config};
+
+const MANIFEST: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<!-- Copyright 2026 Example Ltd. -->
+<manifest xmlns:android="http://schemas.android.com/apk/res/android" package="com.example.locales">
+  <!-- The watch face itself -->
+  <application android:label="Locales" android:hasCode="false"/>
+</manifest>
+"#;
+
+#[test]
+fn locale_config_is_set() {
+    let manifest = set_manifest_locale_config(MANIFEST.as_bytes(), "@xml/locales_config").unwrap();
+    assert_eq!(
+        manifest_locale_config(&manifest).unwrap().as_deref(),
+        Some("@xml/locales_config")
+    );
+}
+
+#[test]
+fn comments_are_kept() {
+    let manifest = set_manifest_locale_config(MANIFEST.as_bytes(), "@xml/locales_config").unwrap();
+    let manifest = String::from_utf8(manifest).unwrap();
+    assert!(
+        manifest.contains("<!-- Copyright 2026 Example Ltd. -->"),
+        "{manifest}"
+    );
+    assert!(
+        manifest.contains("<!-- The watch face itself -->"),
+        "{manifest}"
+    );
+}
@@ -0,0 +1,45 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Parses res/ subdirectory names, whose qualifiers aapt2 only accepts in one
+// order.
+use pack_asset_compiler::resource_config::ResourceConfig;
+use pack_common::PackError;
+
+#[test]
+fn qualifiers_in_order_are_parsed() {
+    let (res_type, config) =
+        ResourceConfig::parse_directory("drawable-de-rAT-round-xxhdpi-v26").unwrap();
+    assert_eq!(res_type, "drawable");
+    assert_eq!(config.directory_suffix(), "-de-rAT-round-xxhdpi-v26");
+}
+
+#[test]
+fn qualifiers_out_of_order_are_rejected() {
+    for directory in [
+        "drawable-xxhdpi-round",
+        "drawable-v26-xxhdpi",
+        "values-round-de",
+        "drawable-round-notround",
+        "drawable-xxhdpi-xxhdpi"
+    ] {
+        assert!(
+            matches!(
+                ResourceConfig::parse_directory(directory),
+                Err(PackError::UnknownResourceQualifier(_))
+            ),
+            "{directory}"
+        );
+    }
+}
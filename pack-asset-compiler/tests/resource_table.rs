@@ -0,0 +1,51 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Builds resource tables with more than one package, eg. an app and a shared
+// library it's linked with.
+use pack_asset_compiler::resource_table::{
+    construct_resource_table_with_packages, TableEncoding, TablePackage, SHARED_LIBRARY_PACKAGE_ID,
+    USER_PACKAGE_MAGIC
+};
+use pack_common::PackError;
+
+fn build(ids: &[u32]) -> Result<(), PackError> {
+    let mut packages: Vec<TablePackage> = ids
+        .iter()
+        .map(|&id| TablePackage {
+            name: "com.example.watchface",
+            id,
+            resources: &mut []
+        })
+        .collect();
+    construct_resource_table_with_packages(&mut packages, &|_| true, TableEncoding::default())
+        .map(|_| ())
+}
+
+#[test]
+fn packages_with_different_ids_are_built() {
+    assert!(build(&[USER_PACKAGE_MAGIC, SHARED_LIBRARY_PACKAGE_ID]).is_ok());
+}
+
+#[test]
+fn repeated_package_ids_are_rejected() {
+    assert!(matches!(
+        build(&[
+            USER_PACKAGE_MAGIC,
+            SHARED_LIBRARY_PACKAGE_ID,
+            USER_PACKAGE_MAGIC
+        ]),
+        Err(PackError::DuplicatePackageId(USER_PACKAGE_MAGIC))
+    ));
+}
@@ -0,0 +1,163 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Compiles XML that declares namespaces below the root, declares a prefix
+// again in a sibling, and shadows a prefix with a different URI, and checks
+// that the namespace chunks start and end where aapt2's do. aapt2 writes a
+// start chunk for each of an element's own declarations before the element,
+// and the end chunks after it in reverse order.
+use std::io::Cursor;
+
+use deku::DekuContainerWrite;
+use pack_asset_compiler::{
+    decoder::decode_xml,
+    xml_file::{xml_to_res_chunk_with_options, XmlOptions}
+};
+
+const START_NAMESPACE: u16 = 0x0100;
+const END_NAMESPACE: u16 = 0x0101;
+const START_ELEMENT: u16 = 0x0102;
+const END_ELEMENT: u16 = 0x0103;
+const STRING_POOL: u16 = 0x0001;
+
+fn compile(xml: &str) -> Vec<u8> {
+    let (chunk, _) =
+        xml_to_res_chunk_with_options(&mut Cursor::new(xml), &[], &XmlOptions::default()).unwrap();
+    chunk.to_bytes().unwrap()
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes(bytes[offset..offset + 2].try_into().unwrap())
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+// Every string in these files is short enough for one-byte lengths
+fn strings(pool: &[u8]) -> Vec<String> {
+    let strings_start = u32_at(pool, 20) as usize;
+    (0..u32_at(pool, 8) as usize)
+        .map(|i| {
+            let start = strings_start + u32_at(pool, 28 + i * 4) as usize;
+            let len = pool[start + 1] as usize;
+            String::from_utf8(pool[start + 2..start + 2 + len].to_vec()).unwrap()
+        })
+        .collect()
+}
+
+// Describes each namespace and element chunk, in order
+fn events(xml: &str) -> Vec<String> {
+    let bytes = compile(xml);
+    let mut pool: Vec<String> = vec![];
+    let mut events = vec![];
+    let mut offset = u16_at(&bytes, 2) as usize;
+    while offset < bytes.len() {
+        let size = u32_at(&bytes, offset + 4) as usize;
+        let chunk = &bytes[offset..offset + size];
+        offset += size;
+        if u16_at(chunk, 0) == STRING_POOL {
+            pool = strings(chunk);
+            continue;
+        }
+        // Node chunks have a line number and a comment before their data
+        let string = |at: usize| pool[u32_at(chunk, 16 + at) as usize].clone();
+        match u16_at(chunk, 0) {
+            START_NAMESPACE => events.push(format!("xmlns:{}={}", string(0), string(4))),
+            END_NAMESPACE => events.push(format!("end xmlns:{}={}", string(0), string(4))),
+            START_ELEMENT => events.push(format!("<{}>", string(4))),
+            END_ELEMENT => events.push(format!("</{}>", string(4))),
+            _ => {}
+        }
+    }
+    events
+}
+
+#[test]
+fn nested_declarations_end_with_their_elements() {
+    let xml = r#"<root xmlns:a="urn:a">
+  <child xmlns:b="urn:b">
+    <leaf a:x="1" b:y="2"/>
+  </child>
+</root>"#;
+    assert_eq!(
+        events(xml),
+        [
+            "xmlns:a=urn:a",
+            "<root>",
+            "xmlns:b=urn:b",
+            "<child>",
+            "<leaf>",
+            "</leaf>",
+            "</child>",
+            "end xmlns:b=urn:b",
+            "</root>",
+            "end xmlns:a=urn:a"
+        ]
+    );
+}
+
+#[test]
+fn shadowed_prefixes_are_declared_again() {
+    let xml = r#"<root xmlns:a="urn:outer">
+  <inner xmlns:a="urn:inner" a:x="1"/>
+  <sibling a:x="2"/>
+</root>"#;
+    assert_eq!(
+        events(xml),
+        [
+            "xmlns:a=urn:outer",
+            "<root>",
+            "xmlns:a=urn:inner",
+            "<inner>",
+            "</inner>",
+            "end xmlns:a=urn:inner",
+            "<sibling>",
+            "</sibling>",
+            "</root>",
+            "end xmlns:a=urn:outer"
+        ]
+    );
+    // Each attribute is in whichever namespace the prefix meant at it
+    let root = decode_xml(&compile(xml)).unwrap();
+    let namespaces: Vec<_> = root
+        .children
+        .iter()
+        .map(|child| child.attributes[0].namespace.as_deref())
+        .collect();
+    assert_eq!(namespaces, [Some("urn:inner"), Some("urn:outer")]);
+}
+
+#[test]
+fn declarations_on_siblings_are_separate() {
+    let xml = r#"<root>
+  <first xmlns:app="urn:app" app:x="1"/>
+  <second xmlns:app="urn:app" app:x="2"/>
+</root>"#;
+    assert_eq!(
+        events(xml),
+        [
+            "<root>",
+            "xmlns:app=urn:app",
+            "<first>",
+            "</first>",
+            "end xmlns:app=urn:app",
+            "xmlns:app=urn:app",
+            "<second>",
+            "</second>",
+            "end xmlns:app=urn:app",
+            "</root>"
+        ]
+    );
+}
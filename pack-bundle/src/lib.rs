@@ -0,0 +1,290 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # PACK Bundle
+//!
+//! Derives installable APKs from an Android App Bundle, similar to running
+//! `bundletool build-apks --mode=universal`, but without needing Java.
+//!
+//! This lets you test exactly the artifact that you upload to Google Play.
+//!
+//! ```ignore
+//! let aab_bytes = compile_and_sign_aab(pkg, signing_keys)?;
+//!
+//! // A single APK which can be installed with `adb install`
+//! let apk_bytes = build_universal_apk(&aab_bytes, signing_keys)?;
+//!
+//! // Or an .apks archive which can be installed with `bundletool install-apks`
+//! let apks_bytes = build_apks_archive(&aab_bytes, signing_keys)?;
+//! ```
+//!
+//! Only bundles with the same structure as those produced by PACK itself are
+//! supported.
+
+// The use of the proto! macro causes some extraneous Default::default() calls,
+// but they are harmless and unavoidable with the current design of the macro.
+#![allow(clippy::needless_update)]
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::Cursor
+};
+
+use pack_aab::{
+    aapt::pb::{
+        file_reference, item, value, xml_node, ConfigValue, ResourceTable, XmlAttribute,
+        XmlElement, XmlNode
+    },
+    android::bundle::{
+        apk_description, ApkDescription, ApkSet, ApkTargeting, BuildApksResult, BundleConfig,
+        DeliveryType, ModuleMetadata, StandaloneApkMetadata, Variant, VariantTargeting
+    },
+    empty_proto, inner_proto, proto, proto_xml_to_xml_string, resource_config_from_proto
+};
+use pack_api::{compile_and_sign_apk, FileResource, Keys, Package, ResourceConfig};
+use pack_common::{PackError, Result};
+use prost::Message;
+
+const BASE_MODULE: &str = "base";
+const UNIVERSAL_APK_PATH: &str = "universal.apk";
+
+/// Builds a single signed APK containing everything in the bundle's base module.
+pub fn build_universal_apk(aab: &[u8], keys: &Keys) -> Result<Vec<u8>> {
+    let bundle = read_bundle(aab)?;
+    compile_and_sign_apk(&bundle.package, keys)
+}
+
+/// Builds an `.apks` archive in the same format as `bundletool build-apks --mode=universal`.
+///
+/// The archive contains a universal APK and a `toc.pb` describing it, so it
+/// can be installed with `bundletool install-apks`.
+pub fn build_apks_archive(aab: &[u8], keys: &Keys) -> Result<Vec<u8>> {
+    let bundle = read_bundle(aab)?;
+    let universal_apk = compile_and_sign_apk(&bundle.package, keys)?;
+
+    let toc = inner_proto! {BuildApksResult,
+        package_name: bundle.package_name,
+        bundletool: bundle.config.bundletool,
+        variant: vec![inner_proto! {Variant,
+            targeting: empty_proto!(VariantTargeting),
+            apk_set: vec![ApkSet {
+                module_metadata: proto! {ModuleMetadata,
+                    name: BASE_MODULE.into(),
+                    delivery_type: DeliveryType::InstallTime as i32
+                },
+                apk_description: vec![inner_proto! {ApkDescription,
+                    targeting: empty_proto!(ApkTargeting),
+                    path: UNIVERSAL_APK_PATH.into(),
+                    apk_metadata_oneof_value: Some(
+                        apk_description::ApkMetadataOneofValue::StandaloneApkMetadata(
+                            StandaloneApkMetadata {
+                                fused_module_name: vec![BASE_MODULE.into()]
+                            }
+                        )
+                    )
+                }]
+            }]
+        }]
+    };
+
+    let apks_files = vec![
+        pack_zip::File {
+            path: "toc.pb".into(),
+            data: toc.encode_to_vec()
+        },
+        pack_zip::File {
+            path: UNIVERSAL_APK_PATH.into(),
+            data: universal_apk
+        },
+    ];
+    let mut apks_buf = vec![];
+    pack_zip::zip_apk(&apks_files, Cursor::new(&mut apks_buf))?;
+    Ok(apks_buf)
+}
+
+/// Reconstructs the [Package] that the bundle's base module was built from.
+///
+/// ProtoXML files are converted back to textual XML and string resources are
+/// collected back into a `values/strings.xml` (or eg. `values-de/strings.xml`
+/// for translations), so the result can be compiled
+/// again with any of `pack-api`'s functions.
+pub fn aab_to_package(aab: &[u8]) -> Result<Package> {
+    Ok(read_bundle(aab)?.package)
+}
+
+struct Bundle {
+    package: Package,
+    package_name: String,
+    config: BundleConfig
+}
+
+fn read_bundle(aab: &[u8]) -> Result<Bundle> {
+    let files: HashMap<String, Vec<u8>> = pack_zip::unzip(aab)?
+        .into_iter()
+        .map(|file| (file.path, file.data))
+        .collect();
+
+    let config = BundleConfig::decode(&get_file(&files, "BundleConfig.pb")?[..])
+        .map_err(|e| PackError::BundleInvalid(format!("BundleConfig.pb is invalid. {e}")))?;
+    let manifest_path = format!("{BASE_MODULE}/manifest/AndroidManifest.xml");
+    let android_manifest = proto_xml_file_to_xml(&files, &manifest_path)?;
+
+    let table_path = format!("{BASE_MODULE}/resources.pb");
+    let table = ResourceTable::decode(&get_file(&files, &table_path)?[..])
+        .map_err(|e| PackError::BundleInvalid(format!("{table_path} is invalid. {e}")))?;
+    let package_name = table
+        .package
+        .first()
+        .map(|pkg| pkg.package_name.clone())
+        .ok_or(PackError::BundleInvalid(format!(
+            "{table_path} does not contain a package."
+        )))?;
+
+    let mut resources = vec![];
+    // Strings and aliases are grouped back into a values*/strings.xml per
+    // config, as their type, name and value
+    let mut values: BTreeMap<ResourceConfig, Vec<(String, String, String)>> = BTreeMap::new();
+    for pkg in &table.package {
+        for res_type in &pkg.r#type {
+            for entry in &res_type.entry {
+                for config_value in &entry.config_value {
+                    let item_value =
+                        match config_value.value.as_ref().and_then(|v| v.value.as_ref()) {
+                            Some(value::Value::Item(item)) => item.value.as_ref(),
+                            _ => None
+                        };
+                    match item_value {
+                        Some(item::Value::File(file_ref)) => {
+                            let (subdirectory, name) = file_ref
+                                .path
+                                .strip_prefix("res/")
+                                .and_then(|path| path.split_once('/'))
+                                .ok_or(PackError::BundleInvalid(format!(
+                                    "Resource path {} is not inside res/.",
+                                    file_ref.path
+                                )))?;
+                            let module_path = format!("{BASE_MODULE}/{}", file_ref.path);
+                            let contents =
+                                if file_ref.r#type == file_reference::Type::ProtoXml as i32 {
+                                    proto_xml_file_to_xml(&files, &module_path)?
+                                } else {
+                                    get_file(&files, &module_path)?.clone()
+                                };
+                            resources.push(FileResource::new(
+                                subdirectory.into(),
+                                name.into(),
+                                contents
+                            ));
+                        }
+                        Some(item::Value::Str(string)) => values
+                            .entry(config_of(config_value))
+                            .or_default()
+                            .push(("string".into(), entry.name.clone(), string.value.clone())),
+                        // Aliases are written back as references by name, so
+                        // they get renumbered along with everything else
+                        Some(item::Value::Ref(reference)) if !reference.name.is_empty() => {
+                            values.entry(config_of(config_value)).or_default().push((
+                                res_type.name.clone(),
+                                entry.name.clone(),
+                                format!("@{}", reference.name)
+                            ))
+                        }
+                        _ => {
+                            return Err(PackError::BundleInvalid(format!(
+                                "Resource {}/{} has a type of value which isn't supported.",
+                                res_type.name, entry.name
+                            )))
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    for (config, config_values) in &values {
+        resources.push(FileResource::new(
+            format!("values{}", config.directory_suffix()),
+            "strings.xml".into(),
+            strings_xml(config_values)?
+        ));
+    }
+
+    Ok(Bundle {
+        package: Package {
+            android_manifest,
+            resources,
+            // Only the base module is converted
+            modules: vec![]
+        },
+        package_name,
+        config
+    })
+}
+
+fn config_of(config_value: &ConfigValue) -> ResourceConfig {
+    config_value
+        .config
+        .as_ref()
+        .map(resource_config_from_proto)
+        .unwrap_or_default()
+}
+
+fn get_file<'a>(files: &'a HashMap<String, Vec<u8>>, path: &str) -> Result<&'a Vec<u8>> {
+    files
+        .get(path)
+        .ok_or(PackError::BundleInvalid(format!("{path} is missing.")))
+}
+
+fn proto_xml_file_to_xml(files: &HashMap<String, Vec<u8>>, path: &str) -> Result<Vec<u8>> {
+    let node = XmlNode::decode(&get_file(files, path)?[..])
+        .map_err(|e| PackError::BundleInvalid(format!("{path} is not valid ProtoXML. {e}")))?;
+    Ok(proto_xml_to_xml_string(&node)?.into_bytes())
+}
+
+// Builds the ProtoXML for a strings.xml file, which takes care of escaping.
+// Values other than strings are written as <item type="..."> elements.
+fn strings_xml(values: &[(String, String, String)]) -> Result<Vec<u8>> {
+    let string_elems = values
+        .iter()
+        .map(|(res_type, name, value)| XmlNode {
+            node: Some(xml_node::Node::Element(inner_proto! {XmlElement,
+                name: if res_type == "string" { "string" } else { "item" }.into(),
+                attribute: (res_type != "string")
+                    .then(|| inner_proto! {XmlAttribute,
+                        name: "type".into(),
+                        value: res_type.clone()
+                    })
+                    .into_iter()
+                    .chain([inner_proto! {XmlAttribute,
+                        name: "name".into(),
+                        value: name.clone()
+                    }])
+                    .collect(),
+                child: vec![XmlNode {
+                    node: Some(xml_node::Node::Text(value.clone())),
+                    source: None
+                }]
+            })),
+            source: None
+        })
+        .collect();
+    let resources_elem = XmlNode {
+        node: Some(xml_node::Node::Element(inner_proto! {XmlElement,
+            name: "resources".into(),
+            child: string_elems
+        })),
+        source: None
+    };
+    Ok(proto_xml_to_xml_string(&resources_elem)?.into_bytes())
+}
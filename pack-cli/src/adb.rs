@@ -0,0 +1,97 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Installs builds on a connected watch or emulator, by running the adb from
+// the Android SDK's platform-tools.
+use std::{
+    env,
+    ffi::OsStr,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    process::Command
+};
+
+use pack_api::{PackError, Result};
+
+use crate::output::status;
+
+pub struct Adb {
+    path: PathBuf,
+    /// Which device to use, if more than one is connected
+    serial: Option<String>
+}
+
+impl Adb {
+    /// Finds adb in the Android SDK pointed to by `ANDROID_HOME` or
+    /// `ANDROID_SDK_ROOT`, or otherwise on the `PATH`.
+    pub fn find(serial: Option<&str>) -> Adb {
+        let adb_name = if cfg!(windows) { "adb.exe" } else { "adb" };
+        let path = ["ANDROID_HOME", "ANDROID_SDK_ROOT"]
+            .iter()
+            .filter_map(env::var_os)
+            .map(|sdk| PathBuf::from(sdk).join("platform-tools").join(adb_name))
+            .find(|path| path.is_file())
+            .unwrap_or_else(|| PathBuf::from(adb_name));
+        Adb {
+            path,
+            serial: serial.map(String::from)
+        }
+    }
+
+    /// Installs an APK, replacing any existing version of the app.
+    pub fn install(&self, apk_path: &Path) -> Result<()> {
+        status(format_args!("Installing {apk_path:?}..."));
+        self.run(&["install".as_ref(), "-r".as_ref(), apk_path.as_os_str()])
+    }
+
+    /// Makes an installed watch face the active one.
+    pub fn set_watch_face(&self, package_name: &str) -> Result<()> {
+        status(format_args!("Setting the watch face to {package_name}..."));
+        self.run(
+            &[
+                "shell",
+                "am",
+                "broadcast",
+                "-a",
+                "com.google.android.wearable.app.DEBUG_SURFACE",
+                "--es",
+                "operation",
+                "set-watchface",
+                "--es",
+                "watchFaceId",
+                package_name
+            ]
+            .map(OsStr::new)
+        )
+    }
+
+    fn run(&self, args: &[&OsStr]) -> Result<()> {
+        let mut command = Command::new(&self.path);
+        // -s has to come before the adb command
+        if let Some(serial) = &self.serial {
+            command.arg("-s").arg(serial);
+        }
+        let status = command.args(args).status().map_err(|e| match e.kind() {
+            ErrorKind::NotFound => PackError::Cli(
+                "Couldn't find adb. Install the Android SDK platform-tools, or set ANDROID_HOME."
+                    .into()
+            ),
+            _ => e.into()
+        })?;
+        if !status.success() {
+            return Err(PackError::Cli(format!("adb failed with {status}.")));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,145 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Just enough argument parsing for pack-cli's subcommands, to avoid pulling in
+// a dependency for it.
+use pack_api::{PackError, Result};
+
+/// An option that a command accepts, eg. `--out <path>`.
+pub struct Flag {
+    /// eg. `--out`
+    pub long: &'static str,
+    /// eg. `-o`
+    pub short: Option<&'static str>,
+    /// Whether the flag is followed by a value, like `--out <path>`, or is a
+    /// switch on its own, like `--verbose`.
+    pub takes_value: bool
+}
+
+impl Flag {
+    pub const fn switch(long: &'static str) -> Flag {
+        Flag {
+            long,
+            short: None,
+            takes_value: false
+        }
+    }
+
+    pub const fn value(long: &'static str) -> Flag {
+        Flag {
+            long,
+            short: None,
+            takes_value: true
+        }
+    }
+
+    pub const fn short(self, short: &'static str) -> Flag {
+        Flag {
+            short: Some(short),
+            ..self
+        }
+    }
+}
+
+/// A command's arguments, split into positional arguments and flags.
+pub struct Args {
+    positional: Vec<String>,
+    // Keyed by each flag's long name, in the order they were given
+    flags: Vec<(&'static str, Option<String>)>
+}
+
+impl Args {
+    /// Parses `args`, which must only use the given `flags`. Flag values can
+    /// be given as `--out path` or `--out=path`, and `--` ends the flags.
+    pub fn parse(args: &[String], flags: &[Flag]) -> Result<Args> {
+        let mut parsed = Args {
+            positional: vec![],
+            flags: vec![]
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            if arg == "--" {
+                parsed.positional.extend(args.cloned());
+                break;
+            }
+            if !arg.starts_with('-') || arg == "-" {
+                parsed.positional.push(arg.clone());
+                continue;
+            }
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (&arg[..], None)
+            };
+            let flag = flags
+                .iter()
+                .find(|flag| flag.long == name || flag.short == Some(name))
+                .ok_or(PackError::Cli(format!("Unknown option \"{name}\".")))?;
+            let value = match (flag.takes_value, inline_value) {
+                (true, Some(value)) => Some(value),
+                (true, None) => Some(
+                    args.next()
+                        .cloned()
+                        .ok_or(PackError::Cli(format!("Option \"{name}\" needs a value.")))?
+                ),
+                (false, None) => None,
+                (false, Some(_)) => {
+                    return Err(PackError::Cli(format!(
+                        "Option \"{name}\" doesn't take a value."
+                    )))
+                }
+            };
+            parsed.flags.push((flag.long, value));
+        }
+        Ok(parsed)
+    }
+
+    /// The `index`th argument that isn't a flag.
+    pub fn positional(&self, index: usize) -> Option<&str> {
+        self.positional.get(index).map(String::as_str)
+    }
+
+    /// Like [Args::positional], but a missing argument is an error. `what`
+    /// describes the argument, eg. `Input directory`.
+    pub fn required_positional(&self, index: usize, what: &str) -> Result<&str> {
+        self.positional(index)
+            .ok_or(PackError::Cli(format!("{what} not provided.")))
+    }
+
+    /// Errors if there are more than `count` positional arguments.
+    pub fn expect_at_most(&self, count: usize) -> Result<()> {
+        match self.positional.get(count) {
+            Some(extra) => Err(PackError::Cli(format!("Unexpected argument \"{extra}\"."))),
+            None => Ok(())
+        }
+    }
+
+    /// Whether a switch, or a flag with a value, was given.
+    pub fn has(&self, long: &str) -> bool {
+        self.flags.iter().any(|(name, _)| *name == long)
+    }
+
+    /// The value of the last occurrence of a flag.
+    pub fn value<'a>(&'a self, long: &'a str) -> Option<&'a str> {
+        self.values(long).last()
+    }
+
+    /// The values of every occurrence of a flag, for flags that can be
+    /// repeated.
+    pub fn values<'a>(&'a self, long: &'a str) -> impl Iterator<Item = &'a str> {
+        self.flags
+            .iter()
+            .filter(move |(name, _)| *name == long)
+            .filter_map(|(_, value)| value.as_deref())
+    }
+}
@@ -0,0 +1,100 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Each subcommand takes the arguments after its name and does its thing.
+use std::{
+    env, fs,
+    io::{self, Write},
+    path::Path
+};
+
+use pack_api::{Keys, PackError, Result};
+
+use crate::args::Args;
+
+pub mod analyze;
+pub mod build;
+pub mod build_all;
+pub mod diff;
+pub mod inspect;
+pub mod keygen;
+pub mod run;
+pub mod serve;
+pub mod sign;
+pub mod verify;
+
+/// Loads signing keys from the keystore given with `--ks`, or a combined
+/// `.pem`, or generates random testing keys if there's neither.
+pub fn load_keys(args: &Args, pem_path: Option<&Path>) -> Result<Keys> {
+    if let Some(keystore_path) = args.value("--ks") {
+        if pem_path.is_some() {
+            return Err(PackError::Cli(
+                "Signing keys can come from a .pem or --ks, but not both.".into()
+            ));
+        }
+        let keystore = fs::read(keystore_path)?;
+        // Like apksigner, ask for the password if it isn't given
+        let store_password = read_password(
+            args.value("--ks-pass").unwrap_or("stdin"),
+            "Keystore password"
+        )?;
+        let key_password = args
+            .value("--key-pass")
+            .map(|source| read_password(source, "Key password"))
+            .transpose()?;
+        return Keys::from_keystore(
+            &keystore,
+            &store_password,
+            args.value("--ks-key-alias"),
+            key_password.as_deref()
+        );
+    }
+    pem_path.map_or_else(Keys::generate_random_testing_keys, |pem_path| {
+        let key_pem_bytes = fs::read(pem_path)?;
+        let key_pem_str = String::from_utf8(key_pem_bytes)
+            .map_err(|_e| PackError::Cli("Key PEM file is not valid UTF-8.".into()))?;
+        Keys::from_combined_pem_string(&key_pem_str)
+    })
+}
+
+/// Reads a password from `source`, which is one of apksigner's formats:
+/// `pass:<password>`, `env:<variable>`, `file:<path>` or `stdin`. The last
+/// three keep passwords out of shell history.
+fn read_password(source: &str, prompt: &str) -> Result<String> {
+    // Only the first line counts, without its line ending
+    let first_line = |text: &str| text.lines().next().unwrap_or("").to_string();
+    if let Some(password) = source.strip_prefix("pass:") {
+        Ok(password.into())
+    } else if let Some(variable) = source.strip_prefix("env:") {
+        env::var(variable)
+            .map_err(|_| PackError::Cli(format!("Environment variable {variable} isn't set.")))
+    } else if let Some(path) = source.strip_prefix("file:") {
+        Ok(first_line(&fs::read_to_string(path)?))
+    } else if source == "stdin" {
+        eprint!("{prompt}: ");
+        io::stderr().flush()?;
+        let mut line = String::new();
+        io::stdin().read_line(&mut line)?;
+        Ok(first_line(&line))
+    } else {
+        Err(PackError::Cli(format!(
+            "Unknown password source \"{source}\". Use pass:<password>, env:<variable>, file:<path> or stdin."
+        )))
+    }
+}
+
+/// Whether a file is an App Bundle rather than an APK, going by its extension.
+pub fn is_aab_path(path: &str) -> bool {
+    path.to_ascii_lowercase().ends_with(".aab")
+}
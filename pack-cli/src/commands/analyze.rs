@@ -0,0 +1,96 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// `pack-cli analyze <path>` shows where the space in an APK or AAB goes: each
+// file's compressed and uncompressed size, totals per resource type, the
+// biggest drawables, and what the resource table and signatures cost. With
+// `--json`, the same report is printed as JSON for scripts. APKs also get an
+// estimate of their download and install sizes.
+use std::fs;
+
+use pack_api::{analyze_package, estimate_install_size, EntrySize, Result};
+
+use crate::{
+    args::{Args, Flag},
+    commands::is_aab_path
+};
+
+const FLAGS: &[Flag] = &[Flag::switch("--json")];
+
+pub fn run(args: &[String]) -> Result<()> {
+    let args = Args::parse(args, FLAGS)?;
+    let path = args.required_positional(0, "APK or AAB path")?;
+    args.expect_at_most(1)?;
+
+    let package = fs::read(path)?;
+    let report = analyze_package(&package)?;
+    if args.has("--json") {
+        print!("{}", report.to_json());
+        return Ok(());
+    }
+
+    println!("Files:");
+    print_entries(&report.entries);
+    if !report.resource_types.is_empty() {
+        println!();
+        println!("Resource types:");
+        println!(
+            "{:>10}  {:>12}  {:>5}  Type",
+            "Compressed", "Uncompressed", "Files"
+        );
+        for type_size in &report.resource_types {
+            println!(
+                "{:>10}  {:>12}  {:>5}  {}",
+                type_size.compressed_size,
+                type_size.uncompressed_size,
+                type_size.file_count,
+                type_size.res_type
+            );
+        }
+    }
+    if !report.largest_drawables.is_empty() {
+        println!();
+        println!("Largest drawables:");
+        print_entries(&report.largest_drawables);
+    }
+    println!();
+    println!("Resource table: {} bytes", report.resource_table_size);
+    println!("Signing: {} bytes", report.signing_size);
+    println!(
+        "Total: {} bytes in {} files",
+        report.total_size,
+        report.entries.len()
+    );
+    if !is_aab_path(path) {
+        let install_size = estimate_install_size(&package)?;
+        println!(
+            "Estimated download: {} bytes, installed: {} bytes",
+            install_size.download_size, install_size.install_size
+        );
+    }
+    Ok(())
+}
+
+fn print_entries(entries: &[EntrySize]) {
+    println!("{:>10}  {:>12}  Path", "Compressed", "Uncompressed");
+    for entry in entries {
+        println!(
+            "{:>10}  {:>12}  {}{}",
+            entry.compressed_size,
+            entry.uncompressed_size,
+            entry.path,
+            if entry.stored { " (stored)" } else { "" }
+        );
+    }
+}
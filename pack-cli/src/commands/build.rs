@@ -0,0 +1,608 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// `pack-cli build <in_dir> <out_path> [keys.pem]` compiles a watch face
+// directory into signed APK and AAB files, or just one of them with
+// `--apk-only` or `--aab-only`. With `--watch`, it keeps rebuilding whenever
+// the directory changes. With `--install`, the APK is then installed on a
+// connected watch or emulator, and `--launch` also makes it the active watch
+// face.
+//
+// Settings can also come from a pack.toml in the current directory (or the
+// one given with `--config`), in which case a plain `pack-cli build` is enough.
+// Anything passed on the command line takes precedence over it.
+//
+// Files matching globs from `--exclude`, pack.toml's `exclude`, or a
+// .packignore in the project directory are left out of the package.
+//
+// With `--build-manifest`, each package gets a JSON sidecar, eg.
+// package.apk.json, recording its SHA-256, its signing certificate's SHA-256,
+// and the SHA-256 of every input file.
+//
+// Resources that nothing refers to, and references to resources that don't
+// exist, are printed as warnings before building.
+//
+// With `--depfile`, each package also gets a Make-style dependency file, eg.
+// package.apk.d, listing every file it was built from. With
+// `--skip-unchanged`, a package is left alone if the build cache shows it was
+// built from exactly the same inputs last time and it hasn't changed since,
+// so build systems can call pack-cli without checking that first.
+//
+// With `--compatibility-mode`, the packages reproduce what aapt2 and
+// bundletool write wherever PACK's output would otherwise differ.
+//
+// With `--emit-intermediates <dir>`, what each stage of the build produced,
+// eg. the compiled AndroidManifest.xml, resources.arsc and the unsigned
+// packages, is written to <dir>/apk/ and <dir>/aab/ for debugging.
+//
+// pack.toml's [size] table sets limits on the size of each package and its
+// drawables. Going over a limit fails the build with a breakdown of where the
+// space went, and going over a warning threshold prints a warning.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::Arc,
+    time::Instant
+};
+
+use pack_api::{
+    analyze_package, apply_manifest_overrides, build_aab, build_apk, package_name,
+    read_ignore_file, read_res_dir, resource_graph, BuildCache, BuildManifest, BuildOptions,
+    BuildOutput, CacheKey, Intermediates, Keys, ManifestOverrides, MappedFs, PackError, PackFs,
+    Package, Result, SizePolicy, StdFs
+};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    adb::Adb,
+    args::{Args, Flag},
+    commands::load_keys,
+    config::{Config, CONFIG_FILE_NAME},
+    depfile::{depfile_path, write_depfile, RecordingFs},
+    output::{detail, status, warning},
+    watch::watch_dir
+};
+
+const FLAGS: &[Flag] = &[
+    Flag::value("--keys").short("-k"),
+    // The same keystore flags as apksigner
+    Flag::value("--ks"),
+    Flag::value("--ks-pass"),
+    Flag::value("--ks-key-alias"),
+    Flag::value("--key-pass"),
+    Flag::switch("--apk-only"),
+    Flag::switch("--aab-only"),
+    Flag::switch("--watch").short("-w"),
+    Flag::value("--on-build"),
+    Flag::switch("--install"),
+    Flag::switch("--launch"),
+    Flag::value("--device").short("-s"),
+    Flag::value("--config"),
+    Flag::value("--exclude").short("-x"),
+    Flag::switch("--build-manifest"),
+    Flag::switch("--depfile"),
+    Flag::switch("--skip-unchanged"),
+    Flag::value("--emit-intermediates"),
+    Flag::switch("--compatibility-mode")
+];
+
+/// Where a project is read from and written to, and what to build.
+pub struct BuildSettings {
+    pub in_dir: PathBuf,
+    pub out_apk_path: PathBuf,
+    pub out_aab_path: PathBuf,
+    pub cache_path: PathBuf,
+    pub build_apk: bool,
+    pub build_aab: bool,
+    /// Changes to make to AndroidManifest.xml, eg. for a debug build.
+    pub overrides: ManifestOverrides,
+    /// Globs of files to leave uncompressed, eg. `res/raw/*.ogg`.
+    pub uncompressed_globs: Vec<String>,
+    /// Globs of files to leave out, eg. `*.psd`, on top of those in the
+    /// project's .packignore.
+    pub exclude_globs: Vec<String>,
+    /// The language of the resources without a locale qualifier, eg. `en`.
+    pub default_locale: Option<String>,
+    /// Whether to write a JSON build manifest next to each package.
+    pub build_manifest: bool,
+    /// Whether to write a Make-style dependency file next to each package.
+    pub depfile: bool,
+    /// Whether to leave packages alone that were built from the same inputs
+    /// last time, going by the build cache.
+    pub skip_unchanged: bool,
+    /// Whether to reproduce what aapt2 and bundletool write, where PACK's
+    /// output would otherwise differ.
+    pub compatibility_mode: bool,
+    /// Where to write what each stage of the build produced, for debugging.
+    pub intermediates_dir: Option<PathBuf>,
+    /// Files from outside the project that went into the build, eg. the keys
+    /// and pack.toml, for dependency files.
+    pub extra_inputs: Vec<PathBuf>,
+    /// Limits on the size of each package and its drawables.
+    pub size_policy: SizePolicy,
+    /// Whether to memory-map large files rather than reading them. This is
+    /// only safe for one-off builds: nothing may change the project's files
+    /// until the build and its [Built::package] are done with, and builds
+    /// that are rerun on every change are run while files are being edited.
+    pub map_files: bool
+}
+
+pub fn run(args: &[String]) -> Result<()> {
+    let args = Args::parse(args, FLAGS)?;
+    args.expect_at_most(3)?;
+    let config_path = match args.value("--config") {
+        Some(config_path) => Some(PathBuf::from(config_path)),
+        None if Path::new(CONFIG_FILE_NAME).is_file() => Some(PathBuf::from(CONFIG_FILE_NAME)),
+        None => None
+    };
+    let config = match &config_path {
+        Some(config_path) => Config::read(config_path)?,
+        None => Config::default()
+    };
+    let in_dir = match args.positional(0) {
+        Some(in_dir) => PathBuf::from(in_dir),
+        None => config
+            .input
+            .ok_or(PackError::Cli("Input directory path not provided.".into()))?
+    };
+    let out_path = match args.positional(1) {
+        Some(out_path) => PathBuf::from(out_path),
+        None => config
+            .output
+            .ok_or(PackError::Cli("Output path not provided.".into()))?
+    };
+    let (build_apk, build_aab) = match (args.has("--apk-only"), args.has("--aab-only")) {
+        (true, true) => {
+            return Err(PackError::Cli(
+                "--apk-only and --aab-only can't be used together.".into()
+            ))
+        }
+        (false, false) => (
+            config.features.apk.unwrap_or(true),
+            config.features.aab.unwrap_or(true)
+        ),
+        (apk_only, aab_only) => (!aab_only, !apk_only)
+    };
+    if !build_apk && !build_aab {
+        return Err(PackError::Cli(format!(
+            "Nothing to build, since both features.apk and features.aab are off in {CONFIG_FILE_NAME}."
+        )));
+    }
+    // Launching needs the watch face to be installed first
+    let install = args.has("--install") || args.has("--launch");
+    if install && !build_apk {
+        return Err(PackError::Cli(
+            "--install and --launch need an APK to be built.".into()
+        ));
+    }
+    let adb = install.then(|| Adb::find(args.value("--device")));
+    // Keys used to only be passed positionally
+    let keys_path = match args.value("--keys").or(args.positional(2)) {
+        Some(keys_path) => Some(PathBuf::from(keys_path)),
+        // A keystore on the command line takes precedence over pack.toml
+        None if args.has("--ks") => None,
+        None => config.keys
+    };
+    let settings = BuildSettings {
+        in_dir,
+        out_apk_path: out_path.with_extension("apk"),
+        out_aab_path: out_path.with_extension("aab"),
+        cache_path: out_path.with_extension("pack-cache"),
+        build_apk,
+        build_aab,
+        overrides: config.overrides,
+        uncompressed_globs: config.no_compress,
+        exclude_globs: config
+            .exclude
+            .into_iter()
+            .chain(args.values("--exclude").map(String::from))
+            .collect(),
+        default_locale: config.default_locale,
+        build_manifest: args.has("--build-manifest"),
+        depfile: args.has("--depfile"),
+        skip_unchanged: args.has("--skip-unchanged"),
+        compatibility_mode: args.has("--compatibility-mode"),
+        intermediates_dir: args.value("--emit-intermediates").map(PathBuf::from),
+        // Dependency files list the keys and pack.toml too, since changing
+        // either changes the packages
+        extra_inputs: config_path
+            .into_iter()
+            .chain(keys_path.clone())
+            .chain(args.value("--ks").map(PathBuf::from))
+            .collect(),
+        size_policy: config.size_policy,
+        // Files are edited between rebuilds, and can be while one is running
+        map_files: !args.has("--watch")
+    };
+
+    let signing_keys = load_keys(&args, keys_path.as_deref())?;
+    let cache = Arc::new(read_build_cache(&settings.cache_path));
+
+    let build_and_install = || {
+        // Each rebuild in watch mode starts a new generation, so pruning
+        // drops what the earlier ones used
+        cache.begin_build();
+        let built = build(&settings, &signing_keys, &cache)?;
+        save_build_cache(&cache, &settings.cache_path, !built.skipped)?;
+        status("Compiled, aligned & signed successfully!");
+        if let Some(adb) = &adb {
+            adb.install(&settings.out_apk_path)?;
+            if args.has("--launch") {
+                adb.set_watch_face(&package_name(&built.package)?)?;
+            }
+        }
+        run_hook(args.value("--on-build"))
+    };
+
+    if !args.has("--watch") {
+        return build_and_install();
+    }
+
+    // Keep watching even if a build fails, since the next change may fix it
+    let rebuild = || {
+        if let Err(err) = build_and_install() {
+            eprintln!("Error: {err}");
+        }
+        status(format_args!(
+            "Watching {:?} for changes...",
+            settings.in_dir
+        ));
+    };
+    rebuild();
+    let outputs = [
+        manifest_path(&settings.out_apk_path),
+        manifest_path(&settings.out_aab_path),
+        depfile_path(&settings.out_apk_path),
+        depfile_path(&settings.out_aab_path)
+    ];
+    let ignored: Vec<&Path> = [
+        &settings.out_apk_path,
+        &settings.out_aab_path,
+        &settings.cache_path
+    ]
+    .into_iter()
+    .chain(&outputs)
+    .chain(&settings.intermediates_dir)
+    .map(PathBuf::as_path)
+    .collect();
+    watch_dir(&settings.in_dir, &ignored, rebuild)
+}
+
+/// What [build] read and whether it built everything.
+pub struct Built {
+    pub package: Package,
+    /// Whether a package was left alone because it was unchanged. Its
+    /// compiled files weren't looked up in the cache, so the cache mustn't be
+    /// pruned after this build.
+    pub skipped: bool
+}
+
+/// Builds a project once, reusing and adding to `cache`.
+pub fn build(
+    settings: &BuildSettings,
+    signing_keys: &Keys,
+    cache: &Arc<BuildCache>
+) -> Result<Built> {
+    let (pkg, mut inputs) = if settings.map_files {
+        // Safety: the project isn't being edited, see BuildSettings::map_files
+        read_recorded(unsafe { &MappedFs::new() }, settings)?
+    } else {
+        read_recorded(&StdFs, settings)?
+    };
+    inputs.extend(settings.extra_inputs.iter().cloned());
+    // Unused resources don't stop a build, but are usually left over. Files
+    // that can't be read are reported by the build itself.
+    if let Ok(graph) = resource_graph(&pkg) {
+        for diagnostic in graph.diagnostics() {
+            match &diagnostic.path {
+                Some(path) => warning(format_args!("{path}: {}", diagnostic.message)),
+                None => warning(&diagnostic.message)
+            }
+        }
+    }
+
+    let mut options = BuildOptions {
+        cache: Some(cache.clone()),
+        uncompressed_globs: settings.uncompressed_globs.clone(),
+        size_policy: settings.size_policy.clone(),
+        default_locale: settings.default_locale.clone(),
+        compatibility_mode: settings.compatibility_mode,
+        ..Default::default()
+    };
+    options.bundle_config.uncompressed_globs = settings.uncompressed_globs.clone();
+    if settings.intermediates_dir.is_some() {
+        options.intermediates = Some(Arc::new(Intermediates::new()));
+    }
+
+    let mut skipped = false;
+    if settings.build_apk {
+        skipped |= build_package(
+            settings,
+            &settings.out_apk_path,
+            "APK",
+            build_apk,
+            (&pkg, &options, signing_keys),
+            &inputs
+        )?;
+    }
+    if settings.build_aab {
+        skipped |= build_package(
+            settings,
+            &settings.out_aab_path,
+            "AAB",
+            build_aab,
+            (&pkg, &options, signing_keys),
+            &inputs
+        )?;
+    }
+    if let (Some(dir), Some(intermediates)) = (&settings.intermediates_dir, &options.intermediates)
+    {
+        write_intermediates(dir, intermediates)?;
+    }
+
+    Ok(Built {
+        package: pkg,
+        skipped
+    })
+}
+
+// Builds and writes one package, unless it's already up to date and
+// --skip-unchanged was given, and writes its dependency file if asked for.
+// Returns whether the package was left alone.
+fn build_package(
+    settings: &BuildSettings,
+    out_path: &Path,
+    kind: &str,
+    compile: fn(&Package, &Keys, &BuildOptions) -> Result<BuildOutput>,
+    (pkg, options, signing_keys): (&Package, &BuildOptions, &Keys),
+    inputs: &[PathBuf]
+) -> Result<bool> {
+    let cache = options
+        .cache
+        .as_deref()
+        .expect("build() always sets a cache");
+    let key = package_key(settings, out_path, pkg, options, signing_keys);
+    // Intermediates are only collected while building, so asking for them
+    // always builds
+    let skipped = settings.skip_unchanged
+        && settings.intermediates_dir.is_none()
+        && is_unchanged(cache, &key, out_path);
+    if skipped {
+        status(format_args!("{out_path:?} is up to date."));
+    } else {
+        let start = Instant::now();
+        let output = compile(pkg, signing_keys, options)?;
+        detail(format_args!(
+            "Built the {kind} for {} in {:?}",
+            describe(&output),
+            start.elapsed()
+        ));
+        write_package(
+            settings,
+            out_path,
+            &output.bytes,
+            pkg,
+            options,
+            signing_keys
+        )?;
+        // Recorded even without --skip-unchanged, so the next build can use it
+        cache.insert(key, Sha256::digest(&output.bytes).to_vec());
+    }
+    if settings.depfile {
+        let depfile_path = depfile_path(out_path);
+        write_depfile(&depfile_path, out_path, inputs)?;
+        status(format_args!("Wrote {depfile_path:?} to disk."));
+    }
+    Ok(skipped)
+}
+
+// eg. `Analogue (com.example.analogue, version 1.2.0, code 12)`
+fn describe(output: &BuildOutput) -> String {
+    let mut details = vec![output.package_name.clone()];
+    if let Some(version_name) = &output.version_name {
+        details.push(format!("version {version_name}"));
+    }
+    if let Some(version_code) = output.version_code {
+        details.push(format!("code {version_code}"));
+    }
+    match &output.label {
+        Some(label) => format!("{label} ({})", details.join(", ")),
+        None => details.join(", ")
+    }
+}
+
+// Identifies everything that goes into the package at `out_path`. Each part
+// is prefixed with its length, so they can't run into each other.
+fn package_key(
+    settings: &BuildSettings,
+    out_path: &Path,
+    pkg: &Package,
+    options: &BuildOptions,
+    signing_keys: &Keys
+) -> CacheKey {
+    let mut inputs = Sha256::new();
+    let mut add = |bytes: &[u8]| {
+        inputs.update((bytes.len() as u64).to_le_bytes());
+        inputs.update(bytes);
+    };
+    add(&pkg.android_manifest);
+    for res in &pkg.resources {
+        add(res.get_path().as_bytes());
+        add(&res.contents);
+    }
+    for glob in &options.uncompressed_globs {
+        add(glob.as_bytes());
+    }
+    add(options.default_locale.as_deref().unwrap_or("").as_bytes());
+    add(&[options.compatibility_mode as u8]);
+    // The build manifest is only written when the package is, so turning it
+    // on has to build again
+    add(&[settings.build_manifest as u8]);
+    // The size policy doesn't change the package, but can fail the build
+    add(format!("{:?}", options.size_policy).as_bytes());
+    add(&signing_keys.certificate);
+    BuildCache::key(
+        &format!("package {}", out_path.display()),
+        &[],
+        &inputs.finalize()
+    )
+}
+
+// Whether the package at `out_path` is still the one that was last built from
+// the inputs `key` stands for
+fn is_unchanged(cache: &BuildCache, key: &CacheKey, out_path: &Path) -> bool {
+    let Some(sha256) = cache.get(key) else {
+        return false;
+    };
+    fs::read(out_path).is_ok_and(|package| Sha256::digest(package)[..] == sha256[..])
+}
+
+// Reads a project, along with the paths of every file that was read
+fn read_recorded(fs: &impl PackFs, settings: &BuildSettings) -> Result<(Package, Vec<PathBuf>)> {
+    let fs = RecordingFs::new(fs);
+    let pkg = read_package(
+        &fs,
+        &settings.in_dir,
+        &settings.overrides,
+        &settings.exclude_globs
+    )?;
+    Ok((pkg, fs.files_read()))
+}
+
+/// Reads a project's manifest, with `overrides` applied, and its res/
+/// directory, leaving out files that match `exclude_globs` or a glob in the
+/// project's .packignore.
+pub fn read_package(
+    fs: &impl PackFs,
+    in_dir: &Path,
+    overrides: &ManifestOverrides,
+    exclude_globs: &[String]
+) -> Result<Package> {
+    let mut in_path = in_dir.to_path_buf();
+
+    in_path.push("AndroidManifest.xml");
+    let android_manifest = apply_manifest_overrides(&fs.read_file(&in_path)?, overrides)?;
+    in_path.pop();
+
+    // Read .packignore every time, so that --watch picks up changes to it
+    let mut exclude_globs = exclude_globs.to_vec();
+    exclude_globs.extend(read_ignore_file(fs, in_dir)?);
+    in_path.push("res");
+    let resources = read_res_dir(fs, &in_path, &exclude_globs)?;
+    detail(format_args!(
+        "Read {} resource files from {in_path:?}",
+        resources.len()
+    ));
+
+    Ok(Package {
+        android_manifest,
+        resources,
+        modules: vec![]
+    })
+}
+
+// Writes a package, and its build manifest if one was asked for
+fn write_package(
+    settings: &BuildSettings,
+    path: &Path,
+    package: &[u8],
+    pkg: &Package,
+    options: &BuildOptions,
+    signing_keys: &Keys
+) -> Result<()> {
+    fs::write(path, package)?;
+    status(format_args!("Wrote {path:?} to disk."));
+    // Anything over a limit already failed the build, so these are warnings
+    if !options.size_policy.is_unlimited() {
+        for diagnostic in options.size_policy.check(&analyze_package(package)?) {
+            match &diagnostic.path {
+                Some(path) => warning(format_args!("{path}: {}", diagnostic.message)),
+                None => warning(&diagnostic.message)
+            }
+        }
+    }
+    let manifest = BuildManifest::new(pkg, options, package, Some(signing_keys));
+    detail(format_args!("SHA-256: {}", manifest.sha256));
+    if settings.build_manifest {
+        let manifest_path = manifest_path(path);
+        fs::write(&manifest_path, manifest.to_json())?;
+        status(format_args!("Wrote {manifest_path:?} to disk."));
+    }
+    Ok(())
+}
+
+// Writes the files from each stage of the build under `dir`, overwriting any
+// from earlier builds
+fn write_intermediates(dir: &Path, intermediates: &Intermediates) -> Result<()> {
+    let files = intermediates.take();
+    for file in &files {
+        let path = dir.join(&file.path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, &file.data)?;
+    }
+    status(format_args!(
+        "Wrote {} intermediate files to {dir:?}.",
+        files.len()
+    ));
+    Ok(())
+}
+
+// eg. package.apk.json
+fn manifest_path(package_path: &Path) -> PathBuf {
+    let mut path = package_path.as_os_str().to_owned();
+    path.push(".json");
+    PathBuf::from(path)
+}
+
+/// Drops whatever the last builds didn't use from the cache if `prune` is
+/// set, and saves it.
+pub fn save_build_cache(cache: &BuildCache, cache_path: &Path, prune: bool) -> Result<()> {
+    if prune {
+        cache.prune_unused();
+    }
+    fs::write(cache_path, cache.to_bytes())?;
+    Ok(())
+}
+
+// Runs the --on-build command, if there is one, through the system's shell
+fn run_hook(hook: Option<&str>) -> Result<()> {
+    let Some(hook) = hook else {
+        return Ok(());
+    };
+    let status = if cfg!(windows) {
+        Command::new("cmd").arg("/C").arg(hook).status()?
+    } else {
+        Command::new("sh").arg("-c").arg(hook).status()?
+    };
+    if !status.success() {
+        return Err(PackError::Cli(format!(
+            "--on-build command \"{hook}\" failed with {status}."
+        )));
+    }
+    Ok(())
+}
+
+/// Loads the cache from a previous build, starting afresh if there isn't one.
+pub fn read_build_cache(cache_path: &Path) -> BuildCache {
+    let Ok(cache_bytes) = fs::read(cache_path) else {
+        return BuildCache::new();
+    };
+    BuildCache::from_bytes(&cache_bytes).unwrap_or_else(|err| {
+        warning(format_args!("Ignoring build cache {cache_path:?}. {err}"));
+        BuildCache::new()
+    })
+}
@@ -0,0 +1,284 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// `pack-cli build-all <projects_dir> <out_dir>` builds every watch face in a
+// directory, for repos that publish lots of them. Each subdirectory with an
+// AndroidManifest.xml or a pack.toml is a project, and its packages are
+// written to <out_dir>/<project>.apk and .aab.
+//
+// Projects are built in parallel by `--jobs` threads, which share the signing
+// keys and one build cache, since watch faces from the same repo tend to have
+// lots of XML in common. A table of results is printed at the end.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc
+    },
+    thread,
+    time::{Duration, Instant}
+};
+
+use pack_api::{PackError, Result};
+
+use crate::{
+    args::{Args, Flag},
+    commands::{
+        build::{build, read_build_cache, save_build_cache, BuildSettings},
+        load_keys
+    },
+    config::{Config, CONFIG_FILE_NAME},
+    output::{status, warning}
+};
+
+const FLAGS: &[Flag] = &[
+    Flag::value("--keys").short("-k"),
+    Flag::value("--ks"),
+    Flag::value("--ks-pass"),
+    Flag::value("--ks-key-alias"),
+    Flag::value("--key-pass"),
+    Flag::switch("--apk-only"),
+    Flag::switch("--aab-only"),
+    Flag::value("--exclude").short("-x"),
+    Flag::value("--jobs").short("-j"),
+    Flag::switch("--build-manifest"),
+    Flag::switch("--depfile"),
+    Flag::switch("--skip-unchanged"),
+    Flag::switch("--compatibility-mode")
+];
+
+// Shared by every project, in the output directory
+const CACHE_FILE_NAME: &str = "build-all.pack-cache";
+
+struct Project {
+    name: String,
+    settings: BuildSettings
+}
+
+// How building one project went, and whether a package was skipped as
+// unchanged. Errors are kept as messages, since PackError can't be sent
+// between threads.
+struct Outcome {
+    duration: Duration,
+    result: std::result::Result<bool, String>
+}
+
+pub fn run(args: &[String]) -> Result<()> {
+    let args = Args::parse(args, FLAGS)?;
+    let projects_dir = Path::new(args.required_positional(0, "Projects directory")?);
+    let out_dir = Path::new(args.required_positional(1, "Output directory")?);
+    args.expect_at_most(2)?;
+    if args.has("--apk-only") && args.has("--aab-only") {
+        return Err(PackError::Cli(
+            "--apk-only and --aab-only can't be used together.".into()
+        ));
+    }
+    let jobs = match args.value("--jobs") {
+        Some(jobs) => jobs
+            .parse::<usize>()
+            .ok()
+            .filter(|jobs| *jobs > 0)
+            .ok_or(PackError::Cli(format!(
+                "--jobs must be a positive number, not \"{jobs}\"."
+            )))?,
+        None => thread::available_parallelism().map_or(1, |jobs| jobs.get())
+    };
+
+    let projects = find_projects(projects_dir, out_dir, &args)?;
+    if projects.is_empty() {
+        return Err(PackError::Cli(format!(
+            "No projects found in {projects_dir:?}. Each one needs its own directory with an AndroidManifest.xml or {CONFIG_FILE_NAME}."
+        )));
+    }
+    fs::create_dir_all(out_dir)?;
+    let signing_keys = load_keys(&args, args.value("--keys").map(Path::new))?;
+    let cache_path = out_dir.join(CACHE_FILE_NAME);
+    let cache = Arc::new(read_build_cache(&cache_path));
+
+    status(format_args!(
+        "Building {} projects with {} threads...",
+        projects.len(),
+        jobs.min(projects.len())
+    ));
+    // Each thread takes the next project until there are none left
+    let next = AtomicUsize::new(0);
+    let mut outcomes: Vec<(usize, Outcome)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..jobs.min(projects.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut outcomes = vec![];
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(project) = projects.get(index) else {
+                            break;
+                        };
+                        let start = Instant::now();
+                        let result = build(&project.settings, &signing_keys, &cache)
+                            .map(|built| built.skipped)
+                            .map_err(|err| err.to_string());
+                        outcomes.push((
+                            index,
+                            Outcome {
+                                duration: start.elapsed(),
+                                result
+                            }
+                        ));
+                    }
+                    outcomes
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().expect("A build-all thread panicked."))
+            .collect()
+    });
+    outcomes.sort_by_key(|(index, _)| *index);
+    // Projects that were skipped didn't mark their files in the cache as used
+    let skipped = outcomes
+        .iter()
+        .any(|(_, outcome)| outcome.result == Ok(true));
+    save_build_cache(&cache, &cache_path, !skipped)?;
+
+    print_summary(&projects, &outcomes);
+    let failed: Vec<(&Project, &String)> = outcomes
+        .iter()
+        .filter_map(|(index, outcome)| Some((&projects[*index], outcome.result.as_ref().err()?)))
+        .collect();
+    for (project, err) in &failed {
+        eprintln!("Error: {}: {err}", project.name);
+    }
+    if !failed.is_empty() {
+        return Err(PackError::Cli(format!(
+            "{} of {} projects failed to build.",
+            failed.len(),
+            projects.len()
+        )));
+    }
+    Ok(())
+}
+
+// Every subdirectory that's a project, sorted by name. A project's pack.toml
+// can change its input directory, overrides and globs, but the outputs and
+// keys always come from build-all.
+fn find_projects(projects_dir: &Path, out_dir: &Path, args: &Args) -> Result<Vec<Project>> {
+    let mut projects = vec![];
+    for dir_entry in fs::read_dir(projects_dir)? {
+        let dir = dir_entry?.path();
+        let config_path = dir.join(CONFIG_FILE_NAME);
+        let config = if config_path.is_file() {
+            Config::read(&config_path)?
+        } else if dir.join("AndroidManifest.xml").is_file() {
+            Config::default()
+        } else {
+            continue;
+        };
+        // Outputs are named after the project
+        let Some(name) = dir.file_name().and_then(|name| name.to_str()) else {
+            warning(format_args!(
+                "Skipping {dir:?}, since its name isn't valid UTF-8."
+            ));
+            continue;
+        };
+
+        let (build_apk, build_aab) = match (args.has("--apk-only"), args.has("--aab-only")) {
+            (false, false) => (
+                config.features.apk.unwrap_or(true),
+                config.features.aab.unwrap_or(true)
+            ),
+            (apk_only, aab_only) => (!aab_only, !apk_only)
+        };
+        if !build_apk && !build_aab {
+            continue;
+        }
+        projects.push(Project {
+            name: name.to_string(),
+            settings: BuildSettings {
+                in_dir: config.input.unwrap_or(dir.clone()),
+                out_apk_path: out_dir.join(format!("{name}.apk")),
+                out_aab_path: out_dir.join(format!("{name}.aab")),
+                cache_path: out_dir.join(CACHE_FILE_NAME),
+                build_apk,
+                build_aab,
+                overrides: config.overrides,
+                uncompressed_globs: config.no_compress,
+                exclude_globs: config
+                    .exclude
+                    .into_iter()
+                    .chain(args.values("--exclude").map(String::from))
+                    .collect(),
+                default_locale: config.default_locale,
+                build_manifest: args.has("--build-manifest"),
+                depfile: args.has("--depfile"),
+                skip_unchanged: args.has("--skip-unchanged"),
+                compatibility_mode: args.has("--compatibility-mode"),
+                intermediates_dir: None,
+                extra_inputs: config_path
+                    .is_file()
+                    .then_some(config_path.clone())
+                    .into_iter()
+                    .chain(args.value("--keys").map(PathBuf::from))
+                    .chain(args.value("--ks").map(PathBuf::from))
+                    .collect(),
+                size_policy: config.size_policy,
+                map_files: true
+            }
+        });
+    }
+    projects.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(projects)
+}
+
+fn print_summary(projects: &[Project], outcomes: &[(usize, Outcome)]) {
+    let name_width = projects
+        .iter()
+        .map(|project| project.name.len())
+        .max()
+        .unwrap_or(0)
+        .max("Project".len());
+    status(format_args!(
+        "\n{:<name_width$}  {:<6}  {:>8}  {:>10}  {:>10}",
+        "Project", "Result", "Time", "APK", "AAB"
+    ));
+    for (index, outcome) in outcomes {
+        let project = &projects[*index];
+        let size = |built: bool, path: &Path| match fs::metadata(path) {
+            Ok(metadata) if built && outcome.result.is_ok() => format_size(metadata.len()),
+            _ => "-".into()
+        };
+        status(format_args!(
+            "{:<name_width$}  {:<6}  {:>7.2}s  {:>10}  {:>10}",
+            project.name,
+            if outcome.result.is_ok() {
+                "ok"
+            } else {
+                "FAILED"
+            },
+            outcome.duration.as_secs_f64(),
+            size(project.settings.build_apk, &project.settings.out_apk_path),
+            size(project.settings.build_aab, &project.settings.out_aab_path)
+        ));
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    if bytes < 1_000 {
+        format!("{bytes} B")
+    } else if bytes < 1_000_000 {
+        format!("{:.1} KB", bytes as f64 / 1_000.0)
+    } else {
+        format!("{:.1} MB", bytes as f64 / 1_000_000.0)
+    }
+}
@@ -0,0 +1,85 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// `pack-cli diff <old> <new>` compares two APKs or two AABs, eg. releases out
+// of a generator pipeline, listing the files, resources and manifest
+// attributes that were added, removed or changed. With `--json`, the same
+// differences are printed as JSON for scripts.
+use std::fs;
+
+use pack_api::{diff_packages, Result};
+
+use crate::args::{Args, Flag};
+
+const FLAGS: &[Flag] = &[Flag::switch("--json")];
+
+pub fn run(args: &[String]) -> Result<()> {
+    let args = Args::parse(args, FLAGS)?;
+    let old_path = args.required_positional(0, "old APK or AAB path")?;
+    let new_path = args.required_positional(1, "new APK or AAB path")?;
+    args.expect_at_most(2)?;
+
+    let diff = diff_packages(&fs::read(old_path)?, &fs::read(new_path)?)?;
+    if args.has("--json") {
+        print!("{}", diff.to_json());
+        return Ok(());
+    }
+    if diff.is_empty() {
+        println!("The packages have the same files");
+        return Ok(());
+    }
+
+    print_section(
+        "Files",
+        &diff.added_entries,
+        &diff.removed_entries,
+        &diff.changed_entries
+    );
+    print_section(
+        "Resources",
+        &diff.added_resources,
+        &diff.removed_resources,
+        &diff.changed_resources
+    );
+    if !diff.manifest_changes.is_empty() {
+        println!("Manifest:");
+        for change in &diff.manifest_changes {
+            let (marker, values) = match (&change.old, &change.new) {
+                (None, Some(new)) => ('+', new.clone()),
+                (Some(old), None) => ('-', old.clone()),
+                (Some(old), Some(new)) => ('~', format!("{old} -> {new}")),
+                (None, None) => continue
+            };
+            println!(
+                "  {marker} {} {}: {values}",
+                change.element, change.attribute
+            );
+        }
+    }
+    Ok(())
+}
+
+// Marks each path with +, - or ~ like a unified diff
+fn print_section(title: &str, added: &[String], removed: &[String], changed: &[String]) {
+    if added.is_empty() && removed.is_empty() && changed.is_empty() {
+        return;
+    }
+    println!("{title}:");
+    for (marker, paths) in [('+', added), ('-', removed), ('~', changed)] {
+        for path in paths {
+            println!("  {marker} {path}");
+        }
+    }
+    println!();
+}
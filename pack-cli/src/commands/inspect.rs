@@ -0,0 +1,303 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// `pack-cli inspect <path>` lists what's inside an APK or AAB.
+//
+// `pack-cli inspect badging|resources <apk>` prints what an APK's manifest and
+// resource table declare, laid out like `aapt2 dump badging` and
+// `aapt2 dump resources`, so that scripts written against aapt2 keep working.
+use std::fs;
+
+use pack_api::{
+    decode_apk, AttributeDataType, BinaryValue, DecodedApk, DecodedElement, DecodedTable,
+    ResourceConfig, Result
+};
+
+use crate::args::{Args, Flag};
+
+const FLAGS: &[Flag] = &[];
+
+// What aapt2 reports for resources without a density qualifier
+const DEFAULT_DENSITY: u16 = 160;
+
+pub fn run(args: &[String]) -> Result<()> {
+    let args = Args::parse(args, FLAGS)?;
+    match (args.positional(0), args.positional(1)) {
+        (Some("badging"), Some(path)) => {
+            args.expect_at_most(2)?;
+            print_badging(&decode_apk(&fs::read(path)?)?);
+        }
+        (Some("resources"), Some(path)) => {
+            args.expect_at_most(2)?;
+            print_resources(&decode_apk(&fs::read(path)?)?.resources);
+        }
+        _ => {
+            let path = args.required_positional(0, "APK or AAB path")?;
+            args.expect_at_most(1)?;
+            print_files(path)?;
+        }
+    }
+    Ok(())
+}
+
+fn print_files(path: &str) -> Result<()> {
+    let files = pack_zip::unzip(&fs::read(path)?)?;
+    let mut total_size = 0;
+    for file in &files {
+        println!("{:>10}  {}", file.data.len(), file.path);
+        total_size += file.data.len();
+    }
+    println!("{total_size:>10}  {} files", files.len());
+    Ok(())
+}
+
+fn print_badging(apk: &DecodedApk) {
+    let manifest = &apk.manifest;
+    let table = &apk.resources;
+    let attr = |element: &DecodedElement, name: &str| {
+        element
+            .attribute(name)
+            .map(|value| format_value(value, table))
+            .unwrap_or_default()
+    };
+
+    let mut package_line = format!(
+        "package: name='{}' versionCode='{}' versionName='{}'",
+        escape(&attr(manifest, "package")),
+        escape(&attr(manifest, "versionCode")),
+        escape(&attr(manifest, "versionName"))
+    );
+    for (name, printed_as) in [
+        ("split", "split"),
+        ("platformBuildVersionName", "platformBuildVersionName"),
+        ("platformBuildVersionCode", "platformBuildVersionCode"),
+        ("compileSdkVersion", "compileSdkVersion"),
+        ("compileSdkVersionCodename", "compileSdkVersionCodename"),
+        // PACK writes the codename under this name
+        ("compileSdkCodename", "compileSdkVersionCodename")
+    ] {
+        if let Some(value) = manifest.attribute(name) {
+            package_line += &format!(" {printed_as}='{}'", escape(&format_value(value, table)));
+        }
+    }
+    println!("{package_line}");
+
+    if let Some(uses_sdk) = manifest.children_named("uses-sdk").next() {
+        if uses_sdk.attribute("minSdkVersion").is_some() {
+            println!("sdkVersion:'{}'", escape(&attr(uses_sdk, "minSdkVersion")));
+        }
+        if uses_sdk.attribute("targetSdkVersion").is_some() {
+            println!(
+                "targetSdkVersion:'{}'",
+                escape(&attr(uses_sdk, "targetSdkVersion"))
+            );
+        }
+    }
+
+    for permission in manifest.children_named("uses-permission") {
+        let mut line = format!(
+            "uses-permission: name='{}'",
+            escape(&attr(permission, "name"))
+        );
+        if permission.attribute("maxSdkVersion").is_some() {
+            line += &format!(
+                " maxSdkVersion='{}'",
+                escape(&attr(permission, "maxSdkVersion"))
+            );
+        }
+        println!("{line}");
+    }
+
+    let application = manifest.children_named("application").next();
+    let labels = application
+        .and_then(|application| application.attribute("label"))
+        .map(|label| resolve(label, table))
+        .unwrap_or_default();
+    let icons = application
+        .and_then(|application| application.attribute("icon"))
+        .map(|icon| resolve(icon, table))
+        .unwrap_or_default();
+    let default_value = |values: &[(ResourceConfig, String)]| {
+        values
+            .iter()
+            .find(|(config, _)| config.is_default())
+            .or(values.first())
+            .map(|(_, value)| value.clone())
+            .unwrap_or_default()
+    };
+
+    for (config, label) in &labels {
+        match config.locale_tag() {
+            Some(locale) => println!("application-label-{locale}:'{}'", escape(label)),
+            None if config.is_default() => println!("application-label:'{}'", escape(label)),
+            None => {}
+        }
+    }
+    let densities = densities(table);
+    if !icons.is_empty() {
+        for density in &densities {
+            // The icon for exactly this density, or otherwise the default one
+            let icon = icons
+                .iter()
+                .find(|(config, _)| config.density.unwrap_or(DEFAULT_DENSITY) == *density)
+                .map(|(_, icon)| icon.clone())
+                .unwrap_or_else(|| default_value(&icons));
+            println!("application-icon-{density}:'{}'", escape(&icon));
+        }
+    }
+    if application.is_some() {
+        println!(
+            "application: label='{}' icon='{}'",
+            escape(&default_value(&labels)),
+            escape(&default_value(&icons))
+        );
+    }
+
+    println!("feature-group: label=''");
+    let mut has_faketouch = false;
+    for feature in manifest.children_named("uses-feature") {
+        let name = attr(feature, "name");
+        has_faketouch |= name == "android.hardware.faketouch";
+        if feature.attribute("required") == Some(&BinaryValue::Boolean(false)) {
+            println!("  uses-feature-not-required: name='{}'", escape(&name));
+        } else {
+            println!("  uses-feature: name='{}'", escape(&name));
+        }
+    }
+    if !has_faketouch {
+        println!("  uses-feature: name='android.hardware.faketouch'");
+        println!("  uses-implied-feature: name='android.hardware.faketouch' reason='default feature for all apps'");
+    }
+
+    println!("supports-screens: 'small' 'normal' 'large' 'xlarge'");
+    println!("supports-any-density: 'true'");
+    let locales: Vec<String> = locales(table)
+        .iter()
+        .map(|locale| format!("'{locale}'"))
+        .collect();
+    println!("locales: {}", locales.join(" "));
+    let densities: Vec<String> = densities
+        .iter()
+        .map(|density| format!("'{density}'"))
+        .collect();
+    println!("densities: {}", densities.join(" "));
+}
+
+fn print_resources(table: &DecodedTable) {
+    println!("Binary APK");
+    for package in &table.packages {
+        println!("Package name={} id={:02x}", package.name, package.id);
+        for res_type in &package.types {
+            println!(
+                "  type {} id={:02x} entryCount={}",
+                res_type.name, res_type.id, res_type.entry_count
+            );
+            for entry in &res_type.entries {
+                let id = (package.id << 24) | ((res_type.id as u32) << 16) | entry.id as u32;
+                println!("    resource {id:#010x} {}/{}", res_type.name, entry.name);
+                for (config, value) in &entry.values {
+                    let config = config.qualifiers().join("-");
+                    let value = match value {
+                        // Files are stored as their paths, see Resource::get_string_pool_string
+                        BinaryValue::String(path)
+                            if res_type.name != "string" && path.starts_with("res/") =>
+                        {
+                            file_reference(path)
+                        }
+                        BinaryValue::String(string) => format!("\"{string}\""),
+                        value => format_value(value, table)
+                    };
+                    println!("      ({config}) {value}");
+                }
+            }
+        }
+    }
+}
+
+fn file_reference(path: &str) -> String {
+    if path.ends_with(".png") {
+        format!("(file) {path} type=PNG")
+    } else if path.ends_with(".xml") {
+        format!("(file) {path} type=XML")
+    } else {
+        format!("(file) {path}")
+    }
+}
+
+// Returns every config's value for an attribute, following references
+fn resolve(value: &BinaryValue, table: &DecodedTable) -> Vec<(ResourceConfig, String)> {
+    match value {
+        BinaryValue::Reference(id) => table
+            .resolve(*id)
+            .iter()
+            .map(|(config, value)| (config.clone(), format_value(value, table)))
+            .collect(),
+        value => vec![(ResourceConfig::default(), format_value(value, table))]
+    }
+}
+
+fn format_value(value: &BinaryValue, table: &DecodedTable) -> String {
+    match value {
+        BinaryValue::Null => "@null".into(),
+        BinaryValue::Reference(id) => table.reference_name(*id).unwrap_or(format!("@{id:#010x}")),
+        BinaryValue::AttributeReference(id) => format!("?{id:#010x}"),
+        BinaryValue::String(string) => string.clone(),
+        BinaryValue::Integer(integer) => integer.to_string(),
+        BinaryValue::Boolean(boolean) => boolean.to_string(),
+        BinaryValue::Float(float) => float.to_string(),
+        BinaryValue::Color(color) => format!("#{color:08x}"),
+        BinaryValue::Other(data_type, data) => match AttributeDataType::from_id(*data_type) {
+            Some(data_type) => format!("({data_type:?}) {data:#010x}"),
+            None => format!("(type {data_type:#04x}) {data:#010x}")
+        },
+        BinaryValue::Bag { items, .. } => format!("(bag) {} items", items.len())
+    }
+}
+
+// Locales with any resources, with the default first as '--_--' like aapt2
+fn locales(table: &DecodedTable) -> Vec<String> {
+    let mut locales = vec!["--_--".to_string()];
+    for config in configs(table) {
+        if let Some(locale) = config.locale_tag() {
+            if !locales.contains(&locale) {
+                locales.push(locale);
+            }
+        }
+    }
+    locales[1..].sort();
+    locales
+}
+
+fn densities(table: &DecodedTable) -> Vec<u16> {
+    let mut densities = vec![DEFAULT_DENSITY];
+    densities.extend(configs(table).filter_map(|config| config.density));
+    densities.sort();
+    densities.dedup();
+    densities
+}
+
+fn configs(table: &DecodedTable) -> impl Iterator<Item = &ResourceConfig> {
+    table
+        .packages
+        .iter()
+        .flat_map(|package| &package.types)
+        .flat_map(|res_type| &res_type.entries)
+        .flat_map(|entry| &entry.values)
+        .map(|(config, _)| config)
+}
+
+// aapt2 escapes quotes so that values can be split on them
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('\'', "\\'")
+}
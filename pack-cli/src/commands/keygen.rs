@@ -0,0 +1,120 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// `pack-cli keygen --out keys.pem` writes a new signing key and certificate,
+// so that the same keys can be used for every build. Keys are always RSA:
+// `--ec` is recognized, but rejected until signing supports EC keys.
+use std::{fs, path::Path};
+
+use pack_api::{KeyOptions, Keys, PackError, Result};
+
+use crate::{
+    args::{Args, Flag},
+    commands::read_password,
+    output::status
+};
+
+const FLAGS: &[Flag] = &[
+    Flag::value("--out").short("-o"),
+    Flag::value("--cn"),
+    Flag::value("--validity"),
+    Flag::value("--rsa"),
+    Flag::switch("--ec"),
+    Flag::value("--ks"),
+    Flag::value("--ks-key-alias"),
+    Flag::value("--ks-pass")
+];
+
+// Google Play recommends upload keys that last at least this long
+const DEFAULT_VALIDITY: &str = "25y";
+
+pub fn run(args: &[String]) -> Result<()> {
+    let args = Args::parse(args, FLAGS)?;
+    args.expect_at_most(0)?;
+    // Signing only handles RSA keys, so an EC key couldn't be used to build
+    if args.has("--ec") {
+        return Err(PackError::Cli(
+            "--ec isn't supported yet, as packages can only be signed with RSA keys. Use --rsa instead."
+                .into()
+        ));
+    }
+    let out_path = args.value("--out").unwrap_or("keys.pem");
+    let keystore_path = args.value("--ks");
+    // Losing a key that's already been used to publish would be very bad
+    for path in [Some(out_path), keystore_path].into_iter().flatten() {
+        if Path::new(path).exists() {
+            return Err(PackError::Cli(format!(
+                "{path} already exists. Delete it first if you really want to replace it."
+            )));
+        }
+    }
+
+    let mut options = KeyOptions::default();
+    if let Some(common_name) = args.value("--cn") {
+        options.common_name = common_name.into();
+    }
+    options.validity_days = Some(parse_validity(
+        args.value("--validity").unwrap_or(DEFAULT_VALIDITY)
+    )?);
+    if let Some(bits) = args.value("--rsa") {
+        options.rsa_bits = match bits.parse() {
+            Ok(bits @ (2048 | 3072 | 4096)) => bits,
+            _ => {
+                return Err(PackError::Cli(format!(
+                    "--rsa must be 2048, 3072 or 4096, not \"{bits}\"."
+                )))
+            }
+        };
+    }
+    // Ask for the password before the slow part
+    let keystore_password = keystore_path
+        .map(|_| {
+            read_password(
+                args.value("--ks-pass").unwrap_or("stdin"),
+                "Keystore password"
+            )
+        })
+        .transpose()?;
+
+    let keys = Keys::generate_with_options(&options)?;
+    fs::write(out_path, keys.to_combined_pem_string()?)?;
+    status(format_args!(
+        "Wrote a new signing key and certificate to {out_path}."
+    ));
+    if let (Some(keystore_path), Some(password)) = (keystore_path, keystore_password) {
+        let alias = args.value("--ks-key-alias").unwrap_or("upload");
+        fs::write(keystore_path, keys.to_pkcs12(alias, &password)?)?;
+        status(format_args!(
+            "Wrote them to {keystore_path} too, with the alias \"{alias}\"."
+        ));
+    }
+    Ok(())
+}
+
+// Parses durations like "25y" or "365d" into days, counting years as 365 days
+fn parse_validity(validity: &str) -> Result<u32> {
+    let (number, days_per_unit) = if let Some(years) = validity.strip_suffix('y') {
+        (years, 365)
+    } else {
+        (validity.strip_suffix('d').unwrap_or(validity), 1)
+    };
+    number
+        .parse::<u32>()
+        .ok()
+        .and_then(|number| number.checked_mul(days_per_unit))
+        .filter(|days| *days > 0)
+        .ok_or(PackError::Cli(format!(
+            "--validity should be a number of years or days, like 25y or 9125d, not \"{validity}\"."
+        )))
+}
@@ -0,0 +1,26 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// `pack-cli run <in_dir> <out_path>` is shorthand for building just the APK,
+// installing it and setting it as the watch face, which is what you want
+// most of the time while designing.
+use pack_api::Result;
+
+use crate::commands::build;
+
+pub fn run(args: &[String]) -> Result<()> {
+    let mut build_args = args.to_vec();
+    build_args.extend(["--apk-only", "--launch"].map(String::from));
+    build::run(&build_args)
+}
@@ -0,0 +1,209 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// `pack-cli serve [--keys keys.pem | --ks keystore] [--socket <path>]` keeps
+// running and builds packages on request, so build systems like Bazel or
+// Gradle don't pay for starting a process and parsing keys on every build.
+//
+// Requests and responses are JSON objects, one per line. They're read from
+// stdin and written to stdout, or with `--socket`, read from and written to
+// each connection to a Unix socket, with connections handled in parallel. A
+// request builds either a project directory or a package given inline, in
+// pack-api's serde format with Base64 file contents:
+//
+//   {"id": 1, "project": "watchface/", "format": "aab", "out": "out/face.aab"}
+//   {"id": 2, "package": {"android_manifest": "PD94...", "resources": [...]}}
+//
+// `format` is `apk` unless it's given. With `out`, the package is written
+// there, otherwise it's sent back as Base64. Each response has the request's
+// `id`, and either `"ok": true` with `out` or `package` and what the
+// manifest declares, or `"ok": false` with the error, its PACK code and the
+// exit code `build` would have failed with:
+//
+//   {"id": 1, "ok": true, "out": "out/face.aab", "package_name": "com.example.face",
+//    "version_code": 12, "version_name": "1.2.0", "min_sdk": 33, "label": "Face"}
+//   {"id": 2, "ok": false, "error": "...", "code": "PACK1004", "exit_code": 2}
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    sync::Arc
+};
+
+use base64::{engine::general_purpose, Engine};
+use pack_api::{
+    BuildOptions, BuildOutput, ManifestOverrides, PackEngine, PackError, Package, Result, StdFs
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::{
+    args::{Args, Flag},
+    commands::{build::read_package, load_keys},
+    exit_code::exit_code,
+    output::warning
+};
+
+const FLAGS: &[Flag] = &[
+    Flag::value("--keys").short("-k"),
+    // The same keystore flags as apksigner
+    Flag::value("--ks"),
+    Flag::value("--ks-pass"),
+    Flag::value("--ks-key-alias"),
+    Flag::value("--key-pass"),
+    Flag::value("--socket")
+];
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Request {
+    /// Copied into the response as it is, so clients can match them up.
+    #[serde(default)]
+    id: Value,
+    /// A project directory with AndroidManifest.xml and res/.
+    project: Option<PathBuf>,
+    /// A package given inline, instead of a project.
+    package: Option<Package>,
+    #[serde(default)]
+    format: Format,
+    /// Where to write the package. It's sent back in the response otherwise.
+    out: Option<PathBuf>,
+    /// Globs of files in the project to leave out, on top of its .packignore.
+    #[serde(default)]
+    exclude: Vec<String>
+}
+
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+enum Format {
+    #[default]
+    Apk,
+    Aab
+}
+
+pub fn run(args: &[String]) -> Result<()> {
+    let args = Args::parse(args, FLAGS)?;
+    args.expect_at_most(0)?;
+    let signing_keys = load_keys(&args, args.value("--keys").map(Path::new))?;
+    // The engine keeps a cache, so files shared between requests, eg. a
+    // template's XML, are only compiled once
+    let engine = Arc::new(PackEngine::new(signing_keys, BuildOptions::default()));
+    match args.value("--socket") {
+        Some(socket_path) => serve_socket(engine, Path::new(socket_path)),
+        None => serve_lines(&engine, io::stdin().lock(), io::stdout().lock())
+    }
+}
+
+// Answers each request in turn, until the input ends
+fn serve_lines(engine: &PackEngine, input: impl BufRead, mut output: impl Write) -> Result<()> {
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        writeln!(output, "{}", respond(engine, &line))?;
+        output.flush()?;
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn serve_socket(engine: Arc<PackEngine>, socket_path: &Path) -> Result<()> {
+    use std::os::unix::net::UnixListener;
+
+    let listener = UnixListener::bind(socket_path)?;
+    eprintln!("Listening on {}", socket_path.display());
+    for stream in listener.incoming() {
+        let stream = stream?;
+        let engine = engine.clone();
+        std::thread::spawn(move || {
+            let result = stream
+                .try_clone()
+                .map_err(PackError::from)
+                .and_then(|input| serve_lines(&engine, BufReader::new(input), stream));
+            if let Err(err) = result {
+                warning(format_args!("Connection closed: {err}"));
+            }
+        });
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn serve_socket(_engine: Arc<PackEngine>, _socket_path: &Path) -> Result<()> {
+    Err(PackError::Cli(
+        "--socket needs Unix sockets, so use stdin and stdout on this platform.".into()
+    ))
+}
+
+fn respond(engine: &PackEngine, line: &str) -> Value {
+    let (id, result) = match serde_json::from_str::<Request>(line) {
+        Ok(request) => (request.id.clone(), handle(engine, request)),
+        Err(err) => (
+            Value::Null,
+            Err(PackError::Cli(format!("Invalid request: {err}")))
+        )
+    };
+    match result {
+        Ok((output, out_path)) => {
+            let mut response = json!({
+                "id": id,
+                "ok": true,
+                "package_name": output.package_name,
+                "version_code": output.version_code,
+                "version_name": output.version_name,
+                "min_sdk": output.min_sdk,
+                "label": output.label
+            });
+            match out_path {
+                Some(out_path) => response["out"] = json!(out_path),
+                None => response["package"] = json!(general_purpose::STANDARD.encode(output.bytes))
+            }
+            response
+        }
+        Err(err) => json!({
+            "id": id,
+            "ok": false,
+            "error": err.to_string(),
+            "code": err.code().to_string(),
+            "exit_code": exit_code(&err)
+        })
+    }
+}
+
+// Returns the package, and where it was written if the request gave a path
+fn handle(engine: &PackEngine, request: Request) -> Result<(BuildOutput, Option<PathBuf>)> {
+    let package = match (request.project, request.package) {
+        (Some(project), None) => read_package(
+            &StdFs,
+            &project,
+            &ManifestOverrides::default(),
+            &request.exclude
+        )?,
+        (None, Some(package)) => package,
+        _ => {
+            return Err(PackError::Cli(
+                "A request needs either a project or a package.".into()
+            ))
+        }
+    };
+    let output = match request.format {
+        Format::Apk => engine.build_apk(&package)?,
+        Format::Aab => engine.build_aab(&package)?
+    };
+    if let Some(out_path) = &request.out {
+        fs::write(out_path, &output.bytes)?;
+    }
+    Ok((output, request.out))
+}
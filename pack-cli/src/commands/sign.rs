@@ -0,0 +1,54 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// `pack-cli sign <in_path> [--keys keys.pem | --ks keystore] [--out out_path]`
+// signs an APK or AAB that was built elsewhere.
+use std::{fs, path::Path};
+
+use pack_api::{sign_aab, sign_apk, Result};
+
+use crate::{
+    args::{Args, Flag},
+    commands::{is_aab_path, load_keys},
+    output::status
+};
+
+const FLAGS: &[Flag] = &[
+    Flag::value("--keys").short("-k"),
+    // The same keystore flags as apksigner
+    Flag::value("--ks"),
+    Flag::value("--ks-pass"),
+    Flag::value("--ks-key-alias"),
+    Flag::value("--key-pass"),
+    Flag::value("--out").short("-o")
+];
+
+pub fn run(args: &[String]) -> Result<()> {
+    let args = Args::parse(args, FLAGS)?;
+    let in_path = args.required_positional(0, "APK or AAB path")?;
+    args.expect_at_most(1)?;
+    // Sign in place unless told otherwise
+    let out_path = args.value("--out").unwrap_or(in_path);
+
+    let signing_keys = load_keys(&args, args.value("--keys").map(Path::new))?;
+    let package = fs::read(in_path)?;
+    let signed = if is_aab_path(in_path) {
+        sign_aab(&package, &signing_keys)?
+    } else {
+        sign_apk(&package, &signing_keys)?
+    };
+    fs::write(out_path, signed)?;
+    status(format_args!("Signed {in_path} and wrote it to {out_path}."));
+    Ok(())
+}
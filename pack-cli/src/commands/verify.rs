@@ -0,0 +1,75 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// `pack-cli verify <path>` checks an APK or AAB's signatures before it's
+// uploaded or installed, and fails if any of them are broken.
+use std::fs;
+
+use pack_api::{verify_package, PackError, Result, SchemeStatus};
+
+use crate::{
+    args::{Args, Flag},
+    output::status
+};
+
+const FLAGS: &[Flag] = &[];
+
+pub fn run(args: &[String]) -> Result<()> {
+    let args = Args::parse(args, FLAGS)?;
+    let path = args.required_positional(0, "APK or AAB path")?;
+    args.expect_at_most(1)?;
+
+    let report = verify_package(&fs::read(path)?)?;
+    status(format_args!("Zip: OK, {} files", report.entry_count));
+    if report.misaligned_entries.is_empty() {
+        status("Alignment: OK");
+    } else {
+        status(format_args!(
+            "Alignment: {} uncompressed files aren't 4-byte aligned",
+            report.misaligned_entries.len()
+        ));
+        for entry in &report.misaligned_entries {
+            status(format_args!("  {entry}"));
+        }
+    }
+
+    for (scheme, scheme_status) in [
+        ("v1 (JAR)", &report.v1),
+        ("v2", &report.v2),
+        ("v3", &report.v3)
+    ] {
+        let description = match scheme_status {
+            SchemeStatus::Missing => "missing".into(),
+            SchemeStatus::Verified => "verified".into(),
+            SchemeStatus::Unsupported(reason) => format!("can't be checked. {reason}"),
+            SchemeStatus::Invalid(reason) => format!("INVALID. {reason}")
+        };
+        status(format_args!("Signature scheme {scheme}: {description}"));
+    }
+
+    for signer in &report.signers {
+        let fingerprint: Vec<String> = signer
+            .sha256_fingerprint
+            .iter()
+            .map(|byte| format!("{byte:02X}"))
+            .collect();
+        status(format_args!("Signer: {}", signer.subject));
+        status(format_args!("  SHA-256: {}", fingerprint.join(":")));
+    }
+
+    if !report.is_valid() {
+        return Err(PackError::PackageVerificationFailed(path.into()));
+    }
+    Ok(())
+}
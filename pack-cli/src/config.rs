@@ -0,0 +1,127 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// A pack.toml in the project directory holds the settings that would otherwise
+// have to be passed to every build, so they can be committed and shared:
+//
+// input = "watchface"
+// output = "build/package"
+// keys = "keys.pem"
+// no_compress = ["res/raw/*.ogg"]
+// exclude = ["*.psd"]
+// default_locale = "en-US"
+//
+// [overrides]
+// version_code = 12
+// version_name = "1.2.0"
+// package_suffix = ".debug"
+//
+// [features]
+// apk = true
+// aab = false
+//
+// [size]
+// max_package = 10_000_000
+// warn_package = 8_000_000
+// max_drawable = 1_000_000
+// warn_drawable = 500_000
+//
+use std::{
+    fs,
+    path::{Path, PathBuf}
+};
+
+use pack_api::{ManifestOverrides, PackError, Result, SizePolicy};
+use serde::{Deserialize, Deserializer};
+
+pub const CONFIG_FILE_NAME: &str = "pack.toml";
+
+/// Settings from a pack.toml. Anything left out is `None`, so that it can fall
+/// back to a command line argument or default. Unknown settings are errors,
+/// since they're most likely typos that would otherwise be silently ignored.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// The directory containing AndroidManifest.xml and res/. Defaults to the
+    /// directory containing pack.toml.
+    pub input: Option<PathBuf>,
+    /// Where to write the outputs, without an extension.
+    pub output: Option<PathBuf>,
+    /// A combined `.pem` file with the signing keys.
+    pub keys: Option<PathBuf>,
+    /// Globs of files to leave uncompressed, eg. `res/raw/*.ogg`.
+    pub no_compress: Vec<String>,
+    /// Globs of files to leave out of the package, eg. `*.psd`.
+    pub exclude: Vec<String>,
+    /// The language of the resources without a locale qualifier, for the
+    /// generated locale config.
+    pub default_locale: Option<String>,
+    /// pack.toml's `[overrides]` table, in pack-api's serde format.
+    pub overrides: ManifestOverrides,
+    /// Which packages to build.
+    pub features: Features,
+    /// Limits on the size of packages and their drawables, in bytes.
+    #[serde(rename = "size", deserialize_with = "size_policy")]
+    pub size_policy: SizePolicy
+}
+
+/// pack.toml's `[features]` table.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Features {
+    /// Whether to build an APK.
+    pub apk: Option<bool>,
+    /// Whether to build an AAB.
+    pub aab: Option<bool>
+}
+
+impl Config {
+    /// Reads a pack.toml. Relative paths in it are resolved against the
+    /// directory it's in.
+    pub fn read(path: &Path) -> Result<Config> {
+        let text = fs::read_to_string(path)?;
+        let mut config: Config =
+            toml::from_str(&text).map_err(|err| PackError::Cli(format!("{path:?}: {err}")))?;
+        let dir = path.parent().unwrap_or(Path::new(""));
+        config.input = Some(match config.input {
+            Some(input) => dir.join(input),
+            None => dir.to_path_buf()
+        });
+        config.output = config.output.map(|output| dir.join(output));
+        config.keys = config.keys.map(|keys| dir.join(keys));
+        Ok(config)
+    }
+}
+
+// pack.toml's [size] table, whose names are shorter than SizePolicy's
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SizeLimits {
+    max_package: Option<u64>,
+    warn_package: Option<u64>,
+    max_drawable: Option<u64>,
+    warn_drawable: Option<u64>
+}
+
+fn size_policy<'de, D: Deserializer<'de>>(
+    deserializer: D
+) -> std::result::Result<SizePolicy, D::Error> {
+    let limits = SizeLimits::deserialize(deserializer)?;
+    Ok(SizePolicy {
+        max_package_size: limits.max_package,
+        warn_package_size: limits.warn_package,
+        max_drawable_size: limits.max_drawable,
+        warn_drawable_size: limits.warn_drawable
+    })
+}
@@ -0,0 +1,111 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Make-style dependency files, eg. package.apk.d, so that Make, Ninja or
+// Gradle know which files a package was built from and can rebuild it exactly
+// when one of them changes. The inputs are recorded as they're read, so the
+// list always matches what the build actually used.
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex
+};
+
+use pack_api::{FileContents, FsMetadata, PackFs, Result};
+
+/// A [PackFs] that remembers which files were read through it.
+pub struct RecordingFs<'a, F: PackFs> {
+    inner: &'a F,
+    read: Mutex<Vec<PathBuf>>
+}
+
+impl<'a, F: PackFs> RecordingFs<'a, F> {
+    pub fn new(inner: &'a F) -> RecordingFs<'a, F> {
+        RecordingFs {
+            inner,
+            read: Mutex::new(vec![])
+        }
+    }
+
+    /// Every file read so far, in the order they were read.
+    pub fn files_read(&self) -> Vec<PathBuf> {
+        self.read
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .clone()
+    }
+
+    fn record(&self, path: &Path) {
+        self.read
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .push(path.to_path_buf());
+    }
+}
+
+impl<F: PackFs> PackFs for RecordingFs<'_, F> {
+    fn read_dir(&self, path: &Path) -> Result<Vec<String>> {
+        self.inner.read_dir(path)
+    }
+
+    fn read_file(&self, path: &Path) -> Result<Vec<u8>> {
+        self.record(path);
+        self.inner.read_file(path)
+    }
+
+    fn read_file_contents(&self, path: &Path) -> Result<FileContents> {
+        self.record(path);
+        self.inner.read_file_contents(path)
+    }
+
+    fn metadata(&self, path: &Path) -> Result<FsMetadata> {
+        self.inner.metadata(path)
+    }
+}
+
+/// The dependency file for a package, eg. `package.apk.d`.
+pub fn depfile_path(package_path: &Path) -> PathBuf {
+    let mut path = package_path.as_os_str().to_owned();
+    path.push(".d");
+    PathBuf::from(path)
+}
+
+/// Writes a dependency file saying that `target` depends on `inputs`.
+pub fn write_depfile(path: &Path, target: &Path, inputs: &[PathBuf]) -> Result<()> {
+    let mut depfile = format!("{}:", escape(target));
+    for input in inputs {
+        depfile.push_str(" \\\n  ");
+        depfile.push_str(&escape(input));
+    }
+    depfile.push('\n');
+    fs::write(path, depfile)?;
+    Ok(())
+}
+
+// Spaces and #s are escaped with backslashes, and $s are doubled, which both
+// Make and Ninja understand
+fn escape(path: &Path) -> String {
+    let mut escaped = String::new();
+    for c in path.to_string_lossy().chars() {
+        match c {
+            ' ' | '#' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '$' => escaped.push_str("$$"),
+            c => escaped.push(c)
+        }
+    }
+    escaped
+}
@@ -0,0 +1,96 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// pack-cli exits with a different code for each kind of failure, so that CI
+// scripts can react to them without parsing error messages.
+use pack_api::PackError;
+
+/// Bad arguments or project files, eg. an invalid resource or manifest.
+pub const INPUT_ERROR: u8 = 2;
+/// A package was read, but failed verification.
+pub const VALIDATION_ERROR: u8 = 3;
+/// Keys couldn't be loaded, or signing failed.
+pub const SIGNING_ERROR: u8 = 4;
+/// A file couldn't be read or written.
+pub const IO_ERROR: u8 = 5;
+/// Something that should never happen, ie. a bug in PACK.
+pub const INTERNAL_ERROR: u8 = 6;
+
+/// Picks the exit code for an error. Every current variant is listed, and
+/// PackError is non_exhaustive, so any added later count as internal errors
+/// until they're classified here.
+pub fn exit_code(err: &PackError) -> u8 {
+    use PackError::*;
+    match err {
+        Cli(_)
+        | ManifestIsNotUTF8
+        | ManifestDoesNotHavePackageName
+        | StringPoolStringTooLong(_)
+        | PackageNameTooLong(_)
+        | UnknownResourceQualifier(_)
+        | ResourceDirectoryInvalid(_)
+        | DuplicateResource(_)
+        | DuplicatePackageId(_)
+        | ResourceFileNameInvalid(_)
+        | ResourceNameInvalid(_)
+        | PackageNameInvalid(_)
+        | ResourceTableEditInvalid(_)
+        | ResourceDeclarationInvalid(_)
+        | UnknownAndroidInternalAttribute(_)
+        | XmlParsingFailed(_)
+        | IntegerAttributeParsingFailed(_)
+        | ReferenceAttributeParsingFailed(_)
+        | ReferenceAttributeLookupFailed(_)
+        | ReferenceCycle(_)
+        | ToolsAttributeNotAllowed(_)
+        | MergeMarkerInvalid(_)
+        | LocaleConfigInvalid(_)
+        | OverlayInvalid(_)
+        | ProjectArchiveInvalid(_)
+        | BundleInvalid(_)
+        | BinaryResourceInvalid(_)
+        | ModuleInvalid(_)
+        | BundleMetadataPathInvalid(_)
+        | DirectoryTargetingInvalid(_)
+        | DuplicateEntry(_)
+        | ZipAlignmentInvalid(_)
+        | SizePolicyExceeded(_)
+        | EntryPathInvalid(_)
+        | ZipReadingFailed(_) => INPUT_ERROR,
+        ZipStructureInvalid(_) | PackageVerificationFailed(_) => VALIDATION_ERROR,
+        SignerZipParsingFailed
+        | SignerPemParsingFailed(_)
+        | SignerNoKeys
+        | SignerRsaPrivateKeyParsingFailed(_)
+        | SignerRsaSigningFailed(_)
+        | SignerRsaKeySerialisationFailed(_)
+        | SignerCertificateDecodingFailed(_)
+        | SignerPKCS7EncodingFailed(_)
+        | PackageAlreadySigned
+        | Asn1ParsingFailed(_)
+        | SigningBlockInvalid(_)
+        | SigningConfigInvalid(_)
+        | KeystoreInvalid(_)
+        | KeystorePasswordIncorrect
+        | KeystoreKeyNotFound(_) => SIGNING_ERROR,
+        FileIoError(_) | ZipWritingFailed(_) => IO_ERROR,
+        ByteSerialisationFailed(_)
+        | TooManyUniqueAndroidInternalAttributes
+        | XmlWritingFailed(_)
+        | ProtoXmlNodeIsNotAnElement
+        | BuildCacheInvalid => INTERNAL_ERROR,
+        InFile(_, err) => exit_code(err),
+        _ => INTERNAL_ERROR
+    }
+}
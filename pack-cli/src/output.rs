@@ -0,0 +1,64 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// How chatty pack-cli is, set once from --quiet or --verbose. Errors are
+// always printed, whatever the verbosity.
+use std::{
+    fmt::Display,
+    sync::atomic::{AtomicU8, Ordering}
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Only errors, for scripts that go by the exit code.
+    Quiet,
+    Normal,
+    /// Extra detail for debugging, like timings and internal errors.
+    Verbose
+}
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(Verbosity::Normal as u8);
+
+pub fn set_verbosity(verbosity: Verbosity) {
+    VERBOSITY.store(verbosity as u8, Ordering::Relaxed);
+}
+
+fn verbosity() -> Verbosity {
+    match VERBOSITY.load(Ordering::Relaxed) {
+        0 => Verbosity::Quiet,
+        1 => Verbosity::Normal,
+        _ => Verbosity::Verbose
+    }
+}
+
+/// Prints progress or results to stdout, unless --quiet.
+pub fn status(msg: impl Display) {
+    if verbosity() >= Verbosity::Normal {
+        println!("{msg}");
+    }
+}
+
+/// Prints a warning to stderr, unless --quiet.
+pub fn warning(msg: impl Display) {
+    if verbosity() >= Verbosity::Normal {
+        eprintln!("Warning: {msg}");
+    }
+}
+
+/// Prints extra detail to stderr, only with --verbose.
+pub fn detail(msg: impl Display) {
+    if verbosity() >= Verbosity::Verbose {
+        eprintln!("{msg}");
+    }
+}
@@ -0,0 +1,86 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Designers iterate on watch face XML constantly, so `build --watch` rebuilds
+// whenever they save.
+use std::{
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::Duration
+};
+
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use pack_api::{PackError, Result};
+
+use crate::output::warning;
+
+// Editors often save a file as several operations in quick succession, so wait
+// for things to settle before rebuilding
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Calls `on_change` whenever anything in `dir` changes, apart from the
+/// `ignored` files and anything in the `ignored` directories, such as the
+/// build's own outputs. Only returns on error.
+pub fn watch_dir(dir: &Path, ignored: &[&Path], mut on_change: impl FnMut()) -> Result<()> {
+    let watch_err = |e: notify::Error| PackError::Cli(format!("Failed to watch {dir:?}. {e}"));
+    let ignored: Vec<PathBuf> = ignored.iter().map(|path| comparable_path(path)).collect();
+
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender).map_err(watch_err)?;
+    watcher
+        .watch(dir, RecursiveMode::Recursive)
+        .map_err(watch_err)?;
+
+    let is_relevant = |event: &notify::Result<Event>| match event {
+        Ok(event) => {
+            !matches!(event.kind, EventKind::Access(_))
+                && event.paths.iter().any(|path| {
+                    let path = comparable_path(path);
+                    !ignored.iter().any(|ignored| path.starts_with(ignored))
+                })
+        }
+        Err(_) => true
+    };
+
+    loop {
+        let event = receiver
+            .recv()
+            .map_err(|_e| PackError::Cli(format!("Stopped watching {dir:?}.")))?;
+        if let Err(e) = &event {
+            warning(e);
+        }
+        if !is_relevant(&event) {
+            continue;
+        }
+        while receiver.recv_timeout(DEBOUNCE).is_ok() {}
+        on_change();
+    }
+}
+
+// Paths from the watcher are absolute, and may have had symlinks resolved.
+// Files that don't exist yet can't be canonicalised, but their directory can.
+fn comparable_path(path: &Path) -> PathBuf {
+    let (Some(parent), Some(file_name)) = (path.parent(), path.file_name()) else {
+        return path.to_path_buf();
+    };
+    let parent = if parent.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        parent
+    };
+    match parent.canonicalize() {
+        Ok(parent) => parent.join(file_name),
+        Err(_) => path.to_path_buf()
+    }
+}
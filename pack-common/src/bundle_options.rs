@@ -0,0 +1,73 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// How a bundle and its modules should be set up. These live here rather than
+// in pack-aab, so that a Package can describe modules and bundle settings
+// without pulling in pack-aab's protobuf code for APK-only builds.
+
+/// What a module contains, which decides how Play can deliver it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum ModuleKind {
+    /// A feature module, which can have its own resources and assets.
+    Feature,
+    /// An asset pack for Play Asset Delivery, which only has assets.
+    AssetPack
+}
+
+/// When Play delivers a module to devices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum ModuleDelivery {
+    /// Along with the base module when the app is installed.
+    #[default]
+    InstallTime,
+    /// Automatically, straight after the app is installed. Only available for
+    /// asset packs.
+    FastFollow,
+    /// Only when the app requests it.
+    OnDemand
+}
+
+/// Settings for how Google Play generates APKs from a bundle.
+///
+/// The defaults leave every decision to Play, which is the same as
+/// bundletool's behaviour when building a bundle with no config.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct BundleConfigOptions {
+    /// Files to leave uncompressed in the generated APKs, as globs over their
+    /// path within the APK, eg. `res/raw/**` or `assets/**/*.wav`.
+    pub uncompressed_globs: Vec<String>,
+    /// Keep native libraries uncompressed, so they can be loaded directly
+    /// from the APK.
+    pub uncompress_native_libraries: bool,
+    /// Keep dex files uncompressed on devices that support it.
+    pub uncompress_dex_files: bool,
+    /// Whether to generate a split APK per screen density. `None` leaves
+    /// this to Play, which currently does split.
+    pub split_by_density: Option<bool>,
+    /// Whether to generate a split APK per language. `None` leaves this to
+    /// Play, which currently does split.
+    pub split_by_language: Option<bool>,
+    /// Strip resource names from the generated resource tables, so resources
+    /// can only be looked up by ID.
+    pub collapse_resource_names: bool,
+    /// Whether Play may archive the app to free up space. `None` leaves this to
+    /// Play, which currently allows it.
+    pub store_archive: Option<bool>
+}
@@ -0,0 +1,119 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Serde helpers for file contents, for use with `#[serde(with = "...")]`.
+// Human-readable formats like JSON and TOML get Base64 strings, and binary
+// formats like CBOR get raw bytes. Self-describing formats accept either when
+// reading, so eg. a JS object can hold a Uint8Array or a Base64 string.
+use std::fmt;
+
+use base64::{engine::general_purpose, Engine};
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer
+};
+
+pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&general_purpose::STANDARD.encode(bytes))
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
+}
+
+// Generic over the container so shared contents like `Arc<[u8]>` work too
+pub fn deserialize<'de, D: Deserializer<'de>, T: From<Vec<u8>>>(
+    deserializer: D
+) -> Result<T, D::Error> {
+    let bytes = if deserializer.is_human_readable() {
+        deserializer.deserialize_any(BytesVisitor)
+    } else {
+        deserializer.deserialize_byte_buf(BytesVisitor)
+    }?;
+    Ok(bytes.into())
+}
+
+/// For files given as `(path, contents)` pairs, which are written as
+/// `{ path, contents }` objects.
+pub mod files {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct FileRef<'a> {
+        path: &'a str,
+        #[serde(with = "super")]
+        contents: &'a [u8]
+    }
+
+    #[derive(Deserialize)]
+    struct File {
+        path: String,
+        #[serde(with = "super")]
+        contents: Vec<u8>
+    }
+
+    pub fn serialize<S: Serializer>(
+        files: &[(String, Vec<u8>)],
+        serializer: S
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(
+            files
+                .iter()
+                .map(|(path, contents)| FileRef { path, contents })
+        )
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D
+    ) -> Result<Vec<(String, Vec<u8>)>, D::Error> {
+        let files = Vec::<File>::deserialize(deserializer)?;
+        Ok(files
+            .into_iter()
+            .map(|file| (file.path, file.contents))
+            .collect())
+    }
+}
+
+struct BytesVisitor;
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "bytes, or a Base64 string")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Vec<u8>, E> {
+        general_purpose::STANDARD
+            .decode(value)
+            .map_err(|e| E::custom(format!("invalid Base64: {e}")))
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<Vec<u8>, E> {
+        Ok(value.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, value: Vec<u8>) -> Result<Vec<u8>, E> {
+        Ok(value)
+    }
+
+    // Formats without a bytes type, eg. plain JS arrays of numbers
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Ok(bytes)
+    }
+}
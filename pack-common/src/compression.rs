@@ -0,0 +1,32 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// How a file should be stored in a zip. Most files are deflated, but some,
+// like audio and images that are already compressed, are better stored as
+// they are, since deflating them again only slows down reading them.
+
+/// Whether a file is compressed when it's zipped into an APK or AAB.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "kebab-case"))]
+pub enum Compression {
+    /// Let pack decide, which deflates the file unless it matches one of the
+    /// uncompressed globs.
+    #[default]
+    Auto,
+    /// Store the file uncompressed.
+    Store,
+    /// Deflate the file, even if it matches one of the uncompressed globs.
+    Deflate
+}
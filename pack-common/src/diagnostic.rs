@@ -0,0 +1,126 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Unlike PackError, which stops a build at the first problem, diagnostics are
+// collected so that validation can report everything wrong with a package in
+// one go.
+use core::fmt;
+
+use xml::common::TextPosition;
+
+use crate::{ErrorCode, PackError};
+
+/// How serious a [Diagnostic] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Something that's probably a mistake, but won't be rejected.
+    Warning,
+    /// Something that will be rejected, by Google Play or by the device.
+    Error
+}
+
+/// Where in a file a [Diagnostic] is, both starting from 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub line: u64,
+    pub column: u64
+}
+
+/// A problem found while validating a package.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub code: ErrorCode,
+    pub severity: Severity,
+    /// The file the problem is in, if it's about a specific file.
+    pub path: Option<String>,
+    /// Where in `path` the problem is, if it's about a specific place.
+    pub span: Option<Span>,
+    pub message: String
+}
+
+impl Diagnostic {
+    pub fn error(code: ErrorCode, path: Option<&str>, message: impl Into<String>) -> Self {
+        Diagnostic {
+            code,
+            severity: Severity::Error,
+            path: path.map(String::from),
+            span: None,
+            message: message.into()
+        }
+    }
+
+    pub fn warning(code: ErrorCode, path: Option<&str>, message: impl Into<String>) -> Self {
+        Diagnostic {
+            code,
+            severity: Severity::Warning,
+            path: path.map(String::from),
+            span: None,
+            message: message.into()
+        }
+    }
+
+    /// Points the diagnostic at a place in its file.
+    pub fn at(mut self, span: impl Into<Span>) -> Self {
+        self.span = Some(span.into());
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error"
+        };
+        let code = self.code;
+        match (&self.path, self.span) {
+            (Some(path), Some(Span { line, column })) => write!(
+                f,
+                "{severity}[{code}]: {path}:{line}:{column}: {}",
+                self.message
+            ),
+            (Some(path), None) => write!(f, "{severity}[{code}]: {path}: {}", self.message),
+            (None, _) => write!(f, "{severity}[{code}]: {}", self.message)
+        }
+    }
+}
+
+// xml-rs counts from 0
+impl From<TextPosition> for Span {
+    fn from(position: TextPosition) -> Self {
+        Span {
+            line: position.row + 1,
+            column: position.column + 1
+        }
+    }
+}
+
+// For front-ends that show failed builds alongside validation results
+impl From<&PackError> for Diagnostic {
+    fn from(err: &PackError) -> Self {
+        Diagnostic {
+            code: err.code(),
+            severity: Severity::Error,
+            path: err.path().map(String::from),
+            span: err
+                .line_column()
+                .map(|(line, column)| Span { line, column }),
+            message: err.root().to_string()
+        }
+    }
+}
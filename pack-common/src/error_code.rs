@@ -0,0 +1,179 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Every kind of problem PACK reports has a code like `PACK2001`, which stays
+// the same between releases even when messages are reworded, so front-ends
+// can link to documentation or pick what to show in their UI.
+use core::fmt;
+
+// Defines ErrorCode along with its name lookup, so that the two can't drift
+// apart
+macro_rules! error_codes {
+    ($($(#[$doc:meta])* $name:ident = $number:literal,)*) => {
+        /// A stable identifier for a kind of [crate::PackError] or
+        /// [crate::Diagnostic]. Displayed as eg. `PACK2001`.
+        ///
+        /// Numbers are grouped by area: 1xxx for inputs and resources, 2xxx
+        /// for XML, 3xxx for bundles, 4xxx for files and zips, 5xxx for
+        /// signing, 6xxx for verification, 7xxx for watch faces and 9xxx for
+        /// internal bugs. Numbers are never reused.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #[non_exhaustive]
+        #[repr(u16)]
+        pub enum ErrorCode {
+            $($(#[$doc])* $name = $number,)*
+        }
+
+        impl ErrorCode {
+            /// eg. `XmlParsingFailed`. For PackErrors, this is the name of the
+            /// variant.
+            pub fn name(self) -> &'static str {
+                match self {
+                    $(ErrorCode::$name => stringify!($name),)*
+                }
+            }
+
+            /// The code with this number, eg. 2001 for
+            /// [ErrorCode::XmlParsingFailed].
+            pub fn from_number(number: u16) -> Option<ErrorCode> {
+                match number {
+                    $($number => Some(ErrorCode::$name),)*
+                    _ => None
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    Cli = 1000,
+    ManifestIsNotUTF8 = 1001,
+    ManifestDoesNotHavePackageName = 1002,
+    StringPoolStringTooLong = 1003,
+    PackageNameTooLong = 1004,
+    UnknownResourceQualifier = 1005,
+    ResourceDirectoryInvalid = 1006,
+    DuplicateResource = 1007,
+    ResourceDeclarationInvalid = 1008,
+    UnknownAndroidInternalAttribute = 1009,
+    IntegerAttributeParsingFailed = 1010,
+    ReferenceAttributeParsingFailed = 1011,
+    ReferenceAttributeLookupFailed = 1012,
+    OverlayInvalid = 1013,
+    ProjectArchiveInvalid = 1014,
+    /// A resource that neither the manifest nor any other resource refers to.
+    ResourceUnreferenced = 1015,
+    /// A layer laid over a template replaced a resource with a different
+    /// file, or left out strings that the template's strings.xml had.
+    LayerConflict = 1016,
+    ResourceFileNameInvalid = 1017,
+    ResourceNameInvalid = 1018,
+    PackageNameInvalid = 1019,
+    ResourceTableEditInvalid = 1020,
+    ReferenceCycle = 1021,
+    LocaleConfigInvalid = 1022,
+    DuplicatePackageId = 1023,
+
+    XmlParsingFailed = 2001,
+    ToolsAttributeNotAllowed = 2002,
+    MergeMarkerInvalid = 2003,
+
+    BundleInvalid = 3001,
+    BinaryResourceInvalid = 3002,
+    ModuleInvalid = 3003,
+    BundleMetadataPathInvalid = 3004,
+    DirectoryTargetingInvalid = 3005,
+    /// A bundle's BundleConfig.pb is missing or can't be decoded.
+    BundleConfigInvalid = 3101,
+    /// A file in a bundle has a path that bundletool doesn't allow, eg. one
+    /// outside of any module.
+    BundleFilePathInvalid = 3102,
+    /// A bundle doesn't have a `base` module.
+    BundleBaseModuleMissing = 3103,
+    /// One of a module's protobufs, like assets.pb or its ProtoXML manifest,
+    /// can't be decoded.
+    BundleProtobufInvalid = 3104,
+    /// A module's files in res/ don't match its resources.pb.
+    BundleResourceTableMismatch = 3105,
+
+    FileIoError = 4001,
+    ZipWritingFailed = 4002,
+    ZipReadingFailed = 4003,
+    ZipStructureInvalid = 4004,
+    BuildCacheInvalid = 4005,
+    DuplicateEntry = 4006,
+    ZipAlignmentInvalid = 4007,
+    SizePolicyExceeded = 4008,
+    /// A package is bigger than its size policy's limit or warning threshold.
+    PackageTooLarge = 4009,
+    /// A drawable is bigger than its size policy's limit or warning threshold.
+    DrawableTooLarge = 4010,
+    EntryPathInvalid = 4011,
+
+    SignerZipParsingFailed = 5001,
+    SignerPemParsingFailed = 5002,
+    SignerNoKeys = 5003,
+    SignerRsaPrivateKeyParsingFailed = 5004,
+    SignerRsaSigningFailed = 5005,
+    SignerRsaKeySerialisationFailed = 5006,
+    SignerCertificateDecodingFailed = 5007,
+    SignerPKCS7EncodingFailed = 5008,
+    PackageAlreadySigned = 5009,
+    Asn1ParsingFailed = 5010,
+    SigningBlockInvalid = 5011,
+    SigningConfigInvalid = 5012,
+    KeystoreInvalid = 5101,
+    KeystorePasswordIncorrect = 5102,
+    KeystoreKeyNotFound = 5103,
+
+    PackageVerificationFailed = 6001,
+
+    /// A watch face's manifest doesn't have `android:hasCode="false"`.
+    WatchFaceHasCode = 7001,
+    /// A watch face's manifest doesn't declare the watch feature.
+    WatchFeatureMissing = 7002,
+    /// A watch face has no `res/xml/watch_face_info.xml`.
+    WatchFaceInfoMissing = 7003,
+    /// A watch face's preview is missing, or isn't a drawable that exists.
+    WatchFacePreviewInvalid = 7004,
+    /// A watch face's manifest doesn't declare a format version.
+    FormatVersionMissing = 7005,
+    /// A watch face declares a format version PACK doesn't know.
+    FormatVersionUnsupported = 7006,
+    /// A watch face uses an element that doesn't exist.
+    WatchFaceUnknownElement = 7007,
+    /// A watch face element is somewhere it isn't allowed, needs a newer
+    /// format version, or is missing an attribute.
+    WatchFaceSchemaViolation = 7008,
+    /// An expression uses a source or function that doesn't exist.
+    WatchFaceExpressionInvalid = 7009,
+
+    ByteSerialisationFailed = 9001,
+    TooManyUniqueAndroidInternalAttributes = 9002,
+    XmlWritingFailed = 9003,
+    ProtoXmlNodeIsNotAnElement = 9004,
+}
+
+impl ErrorCode {
+    /// eg. 2001 for `PACK2001`.
+    pub fn number(self) -> u16 {
+        self as u16
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "PACK{}", self.number())
+    }
+}
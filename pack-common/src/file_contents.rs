@@ -0,0 +1,65 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// The contents of a resource file, shared between the stages of a build
+// rather than copied into each one. Most files are read into memory, but
+// large ones can be memory-mapped, see pack-api's `mmap` feature.
+use std::{fmt, ops::Deref, sync::Arc};
+
+/// Bytes that are cheap to clone, whether they're held in memory or mapped
+/// from a file. Derefs to `[u8]`.
+#[derive(Clone)]
+pub struct FileContents(Arc<dyn AsRef<[u8]> + Send + Sync>);
+
+impl FileContents {
+    /// Wraps anything that holds bytes, eg. a `Vec<u8>` or a memory map.
+    pub fn new(bytes: impl AsRef<[u8]> + Send + Sync + 'static) -> FileContents {
+        FileContents(Arc::new(bytes))
+    }
+}
+
+impl Deref for FileContents {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        (*self.0).as_ref()
+    }
+}
+
+impl AsRef<[u8]> for FileContents {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl From<Vec<u8>> for FileContents {
+    fn from(bytes: Vec<u8>) -> FileContents {
+        FileContents::new(bytes)
+    }
+}
+
+impl PartialEq for FileContents {
+    fn eq(&self, other: &FileContents) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for FileContents {}
+
+// Files can be hundreds of megabytes, so only their size is shown
+impl fmt::Debug for FileContents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "FileContents({} bytes)", self.len())
+    }
+}
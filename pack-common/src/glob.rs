@@ -0,0 +1,44 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Globs in the style bundletool uses for BundleConfig's uncompressed_glob,
+// which pack also accepts for APKs so that the same patterns work for both.
+
+/// Whether `path` matches `pattern`, where `*` matches anything within a
+/// single path segment, `**` matches across segments, and `?` matches a
+/// single character other than `/`. eg. `res/raw/*.ogg` or `assets/**/*.wav`.
+pub fn glob_matches(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let path: Vec<char> = path.chars().collect();
+    matches(&pattern, &path)
+}
+
+fn matches(pattern: &[char], path: &[char]) -> bool {
+    match pattern {
+        [] => path.is_empty(),
+        // "**/" matches zero or more whole directories
+        ['*', '*', '/', rest @ ..] => {
+            matches(rest, path)
+                || (0..path.len()).any(|i| path[i] == '/' && matches(rest, &path[i + 1..]))
+        }
+        ['*', '*', rest @ ..] => (0..=path.len()).any(|i| matches(rest, &path[i..])),
+        ['*', rest @ ..] => (0..=path.len())
+            .take_while(|&i| i == 0 || path[i - 1] != '/')
+            .any(|i| matches(rest, &path[i..])),
+        ['?', rest @ ..] => {
+            matches!(path.first(), Some(c) if *c != '/') && matches(rest, &path[1..])
+        }
+        [c, rest @ ..] => path.first() == Some(c) && matches(rest, &path[1..])
+    }
+}
@@ -13,16 +13,42 @@
 // limitations under the License.
 
 use core::fmt;
-use std::{io, num::ParseIntError, rc::Rc};
+use std::{io, num::ParseIntError, sync::Arc};
 
 use deku::prelude::*;
 use rsa::pkcs8;
 use zip::result::ZipError;
 
+mod bundle_options;
+#[cfg(feature = "serde")]
+pub mod bytes_or_base64;
+mod compression;
+mod diagnostic;
+mod error_code;
+mod file_contents;
+mod glob;
+mod parallel;
+
+pub use bundle_options::{BundleConfigOptions, ModuleDelivery, ModuleKind};
+pub use compression::Compression;
+pub use diagnostic::{Diagnostic, Severity, Span};
+pub use error_code::ErrorCode;
+pub use file_contents::FileContents;
+pub use glob::glob_matches;
+pub use parallel::map_in_order;
+
 /// Common error type making it easier to share `Result`s between PACK crates.
 ///
 /// In general designed to avoid needing utilities like `map_err`.
+///
+/// Errors from other crates that caused this one are available through
+/// [std::error::Error::source], so PackError works with `anyhow` and similar
+/// crates. More variants may be added in future releases.
+///
+/// Errors from other crates are held in [Arc]s, which keeps PackError cheap
+/// to clone while still letting it be sent between threads.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub enum PackError {
     /// pack-cli encountered an error while processing something specific to the
     /// command line implementation. For example, not enough arguments were
@@ -39,6 +65,34 @@ pub enum PackError {
     /// Attempted to construct an APK resource table with a package identifier
     /// longer than 128 bytes long.
     PackageNameTooLong(String),
+    /// A `res/` subdirectory had a qualifier, like the `xxhdpi` in
+    /// `drawable-xxhdpi`, that PACK doesn't support.
+    UnknownResourceQualifier(String),
+    /// A `res/` directory didn't have the layout Android expects, eg. a
+    /// subdirectory that isn't named after a resource type.
+    ResourceDirectoryInvalid(String),
+    /// Two resources had the same type, name and configuration, so it's
+    /// ambiguous which one should be used.
+    DuplicateResource(String),
+    /// Two packages in one resource table had the same package ID. Holds the
+    /// ID.
+    DuplicatePackageId(u32),
+    /// A resource file's name can't be used as a resource name, eg. it has
+    /// nothing before its extension, or two files in the same type and
+    /// configuration only differ by extension.
+    ResourceFileNameInvalid(String),
+    /// A resource's name has a character Android doesn't allow, eg. a dash,
+    /// or an uppercase letter in the name of a file.
+    ResourceNameInvalid(String),
+    /// The manifest's package name isn't one Android can install, eg. it
+    /// only has one part, or a part starts with a digit.
+    PackageNameInvalid(String),
+    /// An edit to a decoded resource table couldn't be made, eg. adding a
+    /// resource that already exists.
+    ResourceTableEditInvalid(String),
+    /// A `public.xml`-style file was malformed, or declared something about a
+    /// resource that doesn't exist.
+    ResourceDeclarationInvalid(String),
     /// When AssetCompiler was trying to serialise a struct similar to AAPT,
     /// something went wrong. See [DekuError].
     ByteSerialisationFailed(DekuError),
@@ -56,6 +110,9 @@ pub enum PackError {
     /// Parsing failed while reading an XML file (`AndroidManifest.xml`,
     /// `strings.xml`, or any file in `res/xml`). See [xml::reader::Error].
     XmlParsingFailed(xml::reader::Error),
+    /// Writing out a modified XML file failed, eg. when applying manifest
+    /// overrides. See [xml::writer::Error].
+    XmlWritingFailed(Arc<xml::writer::Error>),
     /// An attribute was persent in an XML file which was expected to be an
     /// integer (eg. `android:minSdkVersion`), but its value was not a valid
     /// integer (eg. `"abc"`).
@@ -66,23 +123,60 @@ pub enum PackError {
     ReferenceAttributeParsingFailed(String),
     /// An XML attribute value was parsed, but its target wasn't in the APK.
     ReferenceAttributeLookupFailed(String),
+    /// References were followed to inline a string, but led back to where
+    /// they started, eg. `@string/a` is `@string/b`, which is `@string/a`.
+    ReferenceCycle(String),
+    /// An XML file had a `tools:` attribute (eg. `tools:ignore`) while
+    /// building with `ToolsNamespace::Error`.
+    ToolsAttributeNotAllowed(String),
+    /// A manifest merger marker, eg. `tools:node`, had a value that the
+    /// manifest merger wouldn't accept.
+    MergeMarkerInvalid(String),
+    /// A locale config, which lists the languages people can choose for the
+    /// app, was malformed or left out locales the package has resources for.
+    LocaleConfigInvalid(String),
+    /// A runtime resource overlay was set up in a way Android won't load, eg.
+    /// targeting its own package.
+    OverlayInvalid(String),
+    /// A zip or tar of a project couldn't be read, or didn't have an
+    /// AndroidManifest.xml and `res/` directory laid out like a project.
+    ProjectArchiveInvalid(String),
     /// PACK's AAB compiler tried to cast a ProtoXML Node to an Element.
     ///
     /// **If you experience this, it is considered an internal bug in PACK.
     /// Please report it.**
     ProtoXmlNodeIsNotAnElement,
+    /// An App Bundle passed in for conversion was missing a required file, or
+    /// one of its files couldn't be decoded. PACK can only convert bundles
+    /// with the same structure as those it produces itself.
+    BundleInvalid(String),
+    /// A binary XML file or `resources.arsc` inside an APK couldn't be decoded,
+    /// eg. because it was truncated or referred to a string that doesn't exist.
+    BinaryResourceInvalid(String),
+    /// One of the extra modules passed in for an App Bundle was set up in a
+    /// way that Google Play doesn't allow.
+    ModuleInvalid(String),
+    /// A bundle metadata file's path wasn't in the form `<namespace>/<name>`,
+    /// or was used more than once.
+    BundleMetadataPathInvalid(String),
+    /// An assets or native library directory's name declared targeting that
+    /// PACK doesn't understand, eg. `assets/textures#tcf_unknown`.
+    DirectoryTargetingInvalid(String),
     /// An error occurred while a package was writing to disk. Since only
     /// `pack-cli` interacts with the disk, it's likely that one of the file
     /// paths you passed to it is invalid, or the disk was full or similar.
-    FileIoError(Rc<io::Error>),
+    FileIoError(Arc<io::Error>),
     /// `pack-zip` failed to create a zip file in-memory.
-    ZipWritingFailed(Rc<ZipError>),
+    ZipWritingFailed(Arc<ZipError>),
+    /// `pack-zip` failed to read an existing zip file, such as an APK or AAB
+    /// passed in for conversion or inspection.
+    ZipReadingFailed(Arc<ZipError>),
     /// The APK Signature Scheme v2/v3 code failed to find the ZIP End Of
     /// Central Directory marker within the zip file.
     SignerZipParsingFailed,
     /// An error occurred while trying to instantiate a `Keys` object from a
     /// `.pem` string.
-    SignerPemParsingFailed(Rc<pem::PemError>),
+    SignerPemParsingFailed(Arc<pem::PemError>),
     /// The `.pem` file passed to `Keys` was valid, but it was missing either
     /// a certificate or private key.
     SignerNoKeys,
@@ -90,16 +184,65 @@ pub enum PackError {
     /// Private Key.
     SignerRsaPrivateKeyParsingFailed(pkcs8::Error),
     /// An error occurred while signing a hash, see [rsa::Error].
-    SignerRsaSigningFailed(Rc<rsa::Error>),
+    SignerRsaSigningFailed(Arc<rsa::Error>),
     /// An error occurred while serialising the RSA key, see
     /// [pkcs8::spki::Error].
     SignerRsaKeySerialisationFailed(pkcs8::spki::Error),
-    /// The signing certificate couldn't be loaded for V1 AAB signing.
-    SignerCertificateDecodingFailed(Rc<rasn::error::DecodeError>),
+    /// The signing certificate couldn't be loaded for V1 AAB signing. Holds
+    /// an ASN.1 decoding error, which isn't named here so that the ASN.1
+    /// crates are only built along with V1 signing.
+    SignerCertificateDecodingFailed(Arc<dyn std::error::Error + Send + Sync>),
     /// V1 Signing data couldn't be serialised
-    SignerPKCS7EncodingFailed(Rc<rasn::error::EncodeError>)
+    SignerPKCS7EncodingFailed(Arc<dyn std::error::Error + Send + Sync>),
+    /// Tried to sign an APK which already has an APK Signing Block.
+    PackageAlreadySigned,
+    /// Some ASN.1 DER data, like a certificate or PKCS #7 signature, was
+    /// malformed.
+    Asn1ParsingFailed(String),
+    /// A package's APK Signing Block, which holds its v2 and v3 signatures,
+    /// couldn't be read.
+    SigningBlockInvalid(String),
+    /// A signing config couldn't be used, eg. because its chunk size was 0.
+    SigningConfigInvalid(String),
+    /// A zip's central directory or local headers are inconsistent, eg. a
+    /// file decompresses to more than its header says.
+    ZipStructureInvalid(String),
+    /// A package's signatures, alignment or zip structure didn't pass
+    /// verification. Holds the package's path.
+    PackageVerificationFailed(String),
+    /// A keystore couldn't be read, either because it's corrupt or because it
+    /// uses a format or algorithm that PACK doesn't support.
+    KeystoreInvalid(String),
+    /// The password for a keystore, or for a key within it, was wrong.
+    KeystorePasswordIncorrect,
+    /// A keystore doesn't contain a private key with the requested alias.
+    KeystoreKeyNotFound(String),
+    /// A persisted `BuildCache` was truncated, corrupted, or written by an
+    /// incompatible version of PACK. It is always safe to delete it.
+    BuildCacheInvalid,
+    /// Two files in a package had the same path. Holds the path.
+    DuplicateEntry(String),
+    /// A zip alignment wasn't a power of two. Holds the alignment.
+    ZipAlignmentInvalid(u16),
+    /// A package was bigger than its size policy allows, either as a whole
+    /// or because of a drawable. Holds what was too big, and where the
+    /// package's space went.
+    SizePolicyExceeded(String),
+    /// A file's path in a package couldn't be extracted safely, eg. because
+    /// it had a `..` segment or only differed in case from another file's.
+    EntryPathInvalid(String),
+    /// Another error, which happened in a specific input file. Holds the
+    /// file's path in the package, eg. `res/xml/watchface.xml`.
+    InFile(String, Box<PackError>)
 }
 
+// Servers and parallel builds pass errors between threads, so this should
+// fail to compile if a variant ever stops PackError being Send and Sync
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<PackError>();
+};
+
 /// Result type where the error is always [PackError].
 pub type Result<T> = std::result::Result<T, PackError>;
 
@@ -112,16 +255,38 @@ impl fmt::Display for PackError {
             ManifestDoesNotHavePackageName => write!(f, "AndroidManifest.xml file does not define a 'package' attribute on its <manifest /> element."),
             StringPoolStringTooLong(_) => write!(f, "XML file contained a string longer than 32,767 (0x7FFF) characters. Pack does not support arbitrary-size string pools."),
             PackageNameTooLong(pkg) => write!(f, "Package name \"{pkg}\" is too long. Maximum length is 128 characters."),
+            UnknownResourceQualifier(dir) => write!(f, "Resource directory \"{dir}\" has a qualifier that Pack doesn't understand. Supported qualifiers are languages and regions (\"de\", \"de-rAT\"), \"round\"/\"notround\", densities (\"xxhdpi\") and platform versions (\"v26\"), in that order."),
+            ResourceDirectoryInvalid(reason) => write!(f, "Invalid res/ directory: {reason}"),
+            ResourceDeclarationInvalid(reason) => write!(f, "Invalid resource declaration: {reason}"),
+            DuplicateResource(res) => write!(f, "Resource \"{res}\" is defined more than once for the same configuration."),
+            DuplicatePackageId(id) => write!(f, "More than one package in the resource table has the package ID 0x{id:02X}."),
+            ResourceFileNameInvalid(reason) => write!(f, "Invalid resource file name: {reason}"),
+            ResourceNameInvalid(reason) => write!(f, "Invalid resource name: {reason}"),
+            PackageNameInvalid(reason) => write!(f, "Invalid package name: {reason}"),
+            ResourceTableEditInvalid(reason) => write!(f, "Can't edit resource table: {reason}"),
             ByteSerialisationFailed(deku_error) => write!(f, "Failed to get byte representation of an object.\nInternal error: {deku_error:?}"),
             TooManyUniqueAndroidInternalAttributes => write!(f, "Internal Pack bug: Too many unique Android Internal Attributes. This shouldn't be possible, please file a bug in the Pack repo."),
             UnknownAndroidInternalAttribute(attr) => write!(f, "Unknown Android Internal Attribute \"{attr}\". This may be because the attribute is not valid, or because Pack is not up-to-date on the latest added attributes. If you believe the latter, please file a bug in the Pack repo."),
             XmlParsingFailed(xml_error) => write!(f, "XML parsing error.\nInternal error: {xml_error:?}"),
+            XmlWritingFailed(xml_error) => write!(f, "XML writing error.\nInternal error: {xml_error:?}"),
             IntegerAttributeParsingFailed(err) => write!(f, "Encountered a non-integer value in an attribute that was expected to be an integer.\nInternal error: {err:?}"),
             ReferenceAttributeParsingFailed(attr) => write!(f, "Failed to parse attribute reference \"{attr}\". Expected a format like \"@drawable/preview\" since the value begins with \"@\"."),
             ReferenceAttributeLookupFailed(attr) => write!(f, "Failed to lookup attribute reference \"{attr}\". Does it exist in the input files?"),
+            ReferenceCycle(chain) => write!(f, "References form a cycle, so there's no string to inline: {chain}"),
+            ToolsAttributeNotAllowed(attr) => write!(f, "Attribute \"{attr}\" is in the tools namespace, which this build doesn't allow. Remove it, or strip or keep tools attributes instead."),
+            MergeMarkerInvalid(msg) => write!(f, "Invalid manifest merger marker. {msg}"),
+            LocaleConfigInvalid(msg) => write!(f, "Invalid locale config. {msg}"),
+            OverlayInvalid(msg) => write!(f, "Invalid overlay. {msg}"),
+            ProjectArchiveInvalid(reason) => write!(f, "Failed to read project archive: {reason}"),
             ProtoXmlNodeIsNotAnElement => write!(f, "Internal Pack bug: Failed to cast ProtoXml Node to Element. This shouldn't be possible, please file a bug in the Pack repo."),
+            BundleInvalid(reason) => write!(f, "Failed to read App Bundle: {reason}"),
+            BinaryResourceInvalid(reason) => write!(f, "Failed to decode binary resource: {reason}"),
+            ModuleInvalid(reason) => write!(f, "Invalid App Bundle module: {reason}"),
+            DirectoryTargetingInvalid(dir) => write!(f, "Directory \"{dir}\" has invalid targeting. Supported assets suffixes are \"#tcf_<format>\", \"#tier_<number>\" and \"#lang_<language>\", and native libraries must be in lib/<abi>/."),
+            BundleMetadataPathInvalid(path) => write!(f, "Bundle metadata path \"{path}\" is invalid. Paths must be unique and in the form \"<namespace>/<name>\", eg. \"com.example.tool/data.bin\"."),
             FileIoError(io_err) => write!(f, "File I/O failed. Did you specify a valid input/output path?\nInternal error: {io_err:?}"),
             ZipWritingFailed(zip_error) => write!(f, "Failed to create in-memory Zip archive.\nInternal error: {zip_error:?}"),
+            ZipReadingFailed(zip_error) => write!(f, "Failed to read Zip archive. Is the file a valid APK or AAB?\nInternal error: {zip_error:?}"),
             SignerZipParsingFailed => write!(f, "Signer failed to find the Zip End of Central Directory Marker."),
             SignerPemParsingFailed(pem_error) => write!(f, "A signing .pem was provided, but it didn't parse as valid syntax.\nInternal error: {pem_error:?}"),
             SignerNoKeys => write!(f, "A signing .pem was provided, but it didn't contain one usable PRIVATE KEY and CERTIFICATE.\nEnsure keys are not protected with passwords, as Pack does not support parsing these. Else, ensure your .pem is formatted correctly so as not to trip up the parser."),
@@ -130,6 +295,155 @@ impl fmt::Display for PackError {
             SignerRsaKeySerialisationFailed(pkcs_error) => write!(f, "Failed to serialise RSA key for APK Signing Scheme v1.\nInternal error: {pkcs_error:?}"),
             SignerCertificateDecodingFailed(decode_error) => write!(f, "Failed to decode certificate from .pem.\nInternal error: {decode_error:?}"),
             SignerPKCS7EncodingFailed(encode_error) => write!(f, "Failed to write PKCS7 signature for APK Signature Scheme v1.\nInternal error: {encode_error:?}"),
+            PackageAlreadySigned => write!(f, "The APK is already signed. Sign an unsigned build of it instead."),
+            Asn1ParsingFailed(msg) => write!(f, "Failed to parse ASN.1 data. {msg}"),
+            SigningBlockInvalid(msg) => write!(f, "The APK Signing Block is malformed. {msg}"),
+            SigningConfigInvalid(msg) => write!(f, "Invalid signing config. {msg}"),
+            ZipStructureInvalid(msg) => write!(f, "The zip file's structure is invalid. {msg}"),
+            PackageVerificationFailed(path) => write!(f, "{path} failed verification."),
+            KeystoreInvalid(msg) => write!(f, "Failed to read keystore. {msg}"),
+            KeystorePasswordIncorrect => write!(f, "The keystore or key password is incorrect."),
+            KeystoreKeyNotFound(alias) if alias.is_empty() => write!(f, "The keystore doesn't contain any private keys."),
+            KeystoreKeyNotFound(alias) => write!(f, "The keystore doesn't contain a private key called \"{alias}\"."),
+            BuildCacheInvalid => write!(f, "The build cache file could not be read. It may be corrupted or from a different version of Pack, and can safely be deleted."),
+            DuplicateEntry(path) => write!(f, "\"{path}\" was added to the package more than once."),
+            ZipAlignmentInvalid(alignment) => write!(f, "Zip entries can't be aligned to {alignment} bytes. The alignment must be a power of two, eg. 4 or 16384."),
+            SizePolicyExceeded(breakdown) => write!(f, "The package is too big for its size policy.\n{breakdown}"),
+            EntryPathInvalid(reason) => write!(f, "Invalid path in package: {reason}"),
+            InFile(path, err) => write!(f, "{path}: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for PackError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        use PackError::*;
+        match self {
+            ByteSerialisationFailed(err) => Some(err),
+            XmlParsingFailed(err) => Some(err),
+            XmlWritingFailed(err) => Some(err.as_ref()),
+            IntegerAttributeParsingFailed(err) => Some(err),
+            FileIoError(err) => Some(err.as_ref()),
+            ZipWritingFailed(err) | ZipReadingFailed(err) => Some(err.as_ref()),
+            SignerPemParsingFailed(err) => Some(err.as_ref()),
+            SignerRsaPrivateKeyParsingFailed(err) => Some(err),
+            SignerRsaSigningFailed(err) => Some(err.as_ref()),
+            SignerRsaKeySerialisationFailed(err) => Some(err),
+            SignerCertificateDecodingFailed(err) => Some(err.as_ref()),
+            SignerPKCS7EncodingFailed(err) => Some(err.as_ref()),
+            // The message already includes the inner error's, so its cause is
+            // skipped to rather than it being reported twice
+            InFile(_, err) => err.source(),
+            _ => None
+        }
+    }
+}
+
+impl PackError {
+    /// Records which input file the error happened in, unless it already says.
+    pub fn in_file(self, path: impl Into<String>) -> PackError {
+        match self {
+            PackError::InFile(..) => self,
+            err => PackError::InFile(path.into(), Box::new(err))
+        }
+    }
+
+    /// The error without any [PackError::InFile] context.
+    pub fn root(&self) -> &PackError {
+        match self {
+            PackError::InFile(_, err) => err.root(),
+            err => err
+        }
+    }
+
+    /// The input file the error happened in, if it's known.
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            PackError::InFile(path, _) => Some(path),
+            _ => None
+        }
+    }
+
+    /// A stable code for the kind of error, eg. `PACK2001` for
+    /// [PackError::XmlParsingFailed].
+    pub fn code(&self) -> ErrorCode {
+        use PackError::*;
+        match self {
+            Cli(_) => ErrorCode::Cli,
+            ManifestIsNotUTF8 => ErrorCode::ManifestIsNotUTF8,
+            ManifestDoesNotHavePackageName => ErrorCode::ManifestDoesNotHavePackageName,
+            StringPoolStringTooLong(_) => ErrorCode::StringPoolStringTooLong,
+            PackageNameTooLong(_) => ErrorCode::PackageNameTooLong,
+            UnknownResourceQualifier(_) => ErrorCode::UnknownResourceQualifier,
+            ResourceDirectoryInvalid(_) => ErrorCode::ResourceDirectoryInvalid,
+            DuplicateResource(_) => ErrorCode::DuplicateResource,
+            DuplicatePackageId(_) => ErrorCode::DuplicatePackageId,
+            ResourceFileNameInvalid(_) => ErrorCode::ResourceFileNameInvalid,
+            ResourceNameInvalid(_) => ErrorCode::ResourceNameInvalid,
+            PackageNameInvalid(_) => ErrorCode::PackageNameInvalid,
+            ResourceTableEditInvalid(_) => ErrorCode::ResourceTableEditInvalid,
+            ResourceDeclarationInvalid(_) => ErrorCode::ResourceDeclarationInvalid,
+            ByteSerialisationFailed(_) => ErrorCode::ByteSerialisationFailed,
+            TooManyUniqueAndroidInternalAttributes => {
+                ErrorCode::TooManyUniqueAndroidInternalAttributes
+            }
+            UnknownAndroidInternalAttribute(_) => ErrorCode::UnknownAndroidInternalAttribute,
+            XmlParsingFailed(_) => ErrorCode::XmlParsingFailed,
+            XmlWritingFailed(_) => ErrorCode::XmlWritingFailed,
+            IntegerAttributeParsingFailed(_) => ErrorCode::IntegerAttributeParsingFailed,
+            ReferenceAttributeParsingFailed(_) => ErrorCode::ReferenceAttributeParsingFailed,
+            ReferenceAttributeLookupFailed(_) => ErrorCode::ReferenceAttributeLookupFailed,
+            ReferenceCycle(_) => ErrorCode::ReferenceCycle,
+            ToolsAttributeNotAllowed(_) => ErrorCode::ToolsAttributeNotAllowed,
+            MergeMarkerInvalid(_) => ErrorCode::MergeMarkerInvalid,
+            LocaleConfigInvalid(_) => ErrorCode::LocaleConfigInvalid,
+            OverlayInvalid(_) => ErrorCode::OverlayInvalid,
+            ProjectArchiveInvalid(_) => ErrorCode::ProjectArchiveInvalid,
+            ProtoXmlNodeIsNotAnElement => ErrorCode::ProtoXmlNodeIsNotAnElement,
+            BundleInvalid(_) => ErrorCode::BundleInvalid,
+            BinaryResourceInvalid(_) => ErrorCode::BinaryResourceInvalid,
+            ModuleInvalid(_) => ErrorCode::ModuleInvalid,
+            BundleMetadataPathInvalid(_) => ErrorCode::BundleMetadataPathInvalid,
+            DirectoryTargetingInvalid(_) => ErrorCode::DirectoryTargetingInvalid,
+            FileIoError(_) => ErrorCode::FileIoError,
+            ZipWritingFailed(_) => ErrorCode::ZipWritingFailed,
+            ZipReadingFailed(_) => ErrorCode::ZipReadingFailed,
+            SignerZipParsingFailed => ErrorCode::SignerZipParsingFailed,
+            SignerPemParsingFailed(_) => ErrorCode::SignerPemParsingFailed,
+            SignerNoKeys => ErrorCode::SignerNoKeys,
+            SignerRsaPrivateKeyParsingFailed(_) => ErrorCode::SignerRsaPrivateKeyParsingFailed,
+            SignerRsaSigningFailed(_) => ErrorCode::SignerRsaSigningFailed,
+            SignerRsaKeySerialisationFailed(_) => ErrorCode::SignerRsaKeySerialisationFailed,
+            SignerCertificateDecodingFailed(_) => ErrorCode::SignerCertificateDecodingFailed,
+            SignerPKCS7EncodingFailed(_) => ErrorCode::SignerPKCS7EncodingFailed,
+            PackageAlreadySigned => ErrorCode::PackageAlreadySigned,
+            Asn1ParsingFailed(_) => ErrorCode::Asn1ParsingFailed,
+            SigningBlockInvalid(_) => ErrorCode::SigningBlockInvalid,
+            SigningConfigInvalid(_) => ErrorCode::SigningConfigInvalid,
+            ZipStructureInvalid(_) => ErrorCode::ZipStructureInvalid,
+            PackageVerificationFailed(_) => ErrorCode::PackageVerificationFailed,
+            KeystoreInvalid(_) => ErrorCode::KeystoreInvalid,
+            KeystorePasswordIncorrect => ErrorCode::KeystorePasswordIncorrect,
+            KeystoreKeyNotFound(_) => ErrorCode::KeystoreKeyNotFound,
+            BuildCacheInvalid => ErrorCode::BuildCacheInvalid,
+            DuplicateEntry(_) => ErrorCode::DuplicateEntry,
+            ZipAlignmentInvalid(_) => ErrorCode::ZipAlignmentInvalid,
+            SizePolicyExceeded(_) => ErrorCode::SizePolicyExceeded,
+            EntryPathInvalid(_) => ErrorCode::EntryPathInvalid,
+            InFile(_, err) => err.code()
+        }
+    }
+
+    /// The line and column in [PackError::path] the error is at, both
+    /// starting from 1, if they're known.
+    pub fn line_column(&self) -> Option<(u64, u64)> {
+        use xml::common::Position;
+        match self.root() {
+            PackError::XmlParsingFailed(xml_error) => {
+                let position = xml_error.position();
+                Some((position.row + 1, position.column + 1))
+            }
+            _ => None
         }
     }
 }
@@ -190,14 +504,16 @@ impl From<pkcs8::spki::Error> for PackError {
     }
 }
 
+#[cfg(feature = "rasn")]
 impl From<rasn::error::DecodeError> for PackError {
     fn from(value: rasn::error::DecodeError) -> Self {
-        PackError::SignerCertificateDecodingFailed(value.into())
+        PackError::SignerCertificateDecodingFailed(Arc::new(value))
     }
 }
 
+#[cfg(feature = "rasn")]
 impl From<rasn::error::EncodeError> for PackError {
     fn from(value: rasn::error::EncodeError) -> Self {
-        PackError::SignerPKCS7EncodingFailed(value.into())
+        PackError::SignerPKCS7EncodingFailed(Arc::new(value))
     }
 }
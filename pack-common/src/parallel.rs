@@ -0,0 +1,37 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Compiling a package's files is independent work, so with the `parallel`
+// feature it's spread over rayon's thread pool. Packages must come out the
+// same either way, so results are always kept in their inputs' order.
+
+/// Runs `f` on every item and returns the results in the same order as
+/// `items`. With the `parallel` feature, items are processed on rayon's
+/// thread pool. If any fail, the error for the earliest item is returned, so
+/// the outcome doesn't depend on which thread finishes first.
+pub fn map_in_order<T, R, F>(items: &[T], f: F) -> crate::Result<Vec<R>>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> crate::Result<R> + Sync + Send
+{
+    #[cfg(feature = "parallel")]
+    let results: Vec<crate::Result<R>> = {
+        use rayon::prelude::*;
+        items.par_iter().map(f).collect()
+    };
+    #[cfg(not(feature = "parallel"))]
+    let results: Vec<crate::Result<R>> = items.iter().map(f).collect();
+    results.into_iter().collect()
+}
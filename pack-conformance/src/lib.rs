@@ -0,0 +1,596 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # PACK Conformance
+//!
+//! Builds sample projects with PACK and compares the packages against ones
+//! built from the same projects by aapt2, bundletool and apksigner, so that
+//! changes which would break on-device compatibility show up before they're
+//! released.
+//!
+//! ```ignore
+//! let mut corpus = Corpus::builtin()?;
+//! corpus.add(Project::read_dir(Path::new("my-watch-face"))?);
+//! for report in corpus.run(&keys) {
+//!     println!("{report}");
+//! }
+//! ```
+//!
+//! A project is a directory laid out like one passed to `pack-cli build`,
+//! with an `AndroidManifest.xml` and a `res/` directory. Its golden packages
+//! go in `golden/`, eg. `golden/aapt2.apk` and `golden/bundletool.aab`.
+//! These are compared by decoding both packages, since other tools encode
+//! the same resources differently: the manifests, resources and compiled XML
+//! files have to match, and so do files that are copied as they are, like
+//! PNGs. A golden signed with a scheme, eg. by apksigner, needs PACK's package
+//! to be signed with it too. Goldens named `*.exact.apk` or `*.exact.aab` have
+//! to match byte for byte instead, which only makes sense for packages built
+//! by PACK with the same keys.
+//!
+//! Whatever the goldens, PACK's own packages have to build and their
+//! signatures have to verify. [update_goldens] writes PACK's packages as
+//! goldens, to catch unintended changes to its output.
+
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    io::Cursor,
+    path::{Path, PathBuf}
+};
+
+use pack_api::{
+    compile_and_sign_aab, compile_and_sign_apk, decode_resource_table, decode_xml, diff_packages,
+    read_ignore_file, read_res_dir, verify_package, BinaryValue, DecodedElement, DecodedTable,
+    Keys, Package, Result, SchemeStatus, StdFs, VerificationReport
+};
+
+/// The sample projects that come with PACK.
+pub const BUILTIN_CORPUS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/corpus");
+
+/// Keys for signing the built-in corpus in tests, so that they don't have to
+/// be generated every time. Never sign anything else with them.
+pub const TESTING_KEYS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/corpus/testing-keys.pem");
+
+/// The directory within a project that holds its golden packages.
+pub const GOLDEN_DIR: &str = "golden";
+
+/// The goldens that [update_goldens] writes.
+pub const PACK_GOLDENS: [&str; 2] = ["pack.apk", "pack.aab"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageKind {
+    Apk,
+    Aab
+}
+
+/// How PACK's package is compared with a golden one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    /// Every byte has to be the same.
+    Exact,
+    /// The decoded manifests and resources have to be the same, along with
+    /// any files that aren't compiled. Signatures are ignored.
+    Decoded
+}
+
+/// A package built by another tool, or an earlier version of PACK, that
+/// PACK's output is checked against.
+#[derive(Debug, Clone)]
+pub struct Golden {
+    /// eg. `aapt2.apk`
+    pub name: String,
+    pub kind: PackageKind,
+    pub comparison: Comparison,
+    pub bytes: Vec<u8>
+}
+
+impl Golden {
+    /// Makes a golden from a package's file name and contents. Its kind and
+    /// comparison come from the name, see the crate docs. Returns `None` if
+    /// the name doesn't end in `.apk` or `.aab`.
+    pub fn new(name: &str, bytes: Vec<u8>) -> Option<Golden> {
+        let (stem, kind) = if let Some(stem) = name.strip_suffix(".apk") {
+            (stem, PackageKind::Apk)
+        } else {
+            (name.strip_suffix(".aab")?, PackageKind::Aab)
+        };
+        let comparison = if stem.ends_with(".exact") {
+            Comparison::Exact
+        } else {
+            Comparison::Decoded
+        };
+        Some(Golden {
+            name: name.into(),
+            kind,
+            comparison,
+            bytes
+        })
+    }
+}
+
+/// A sample project and the packages that PACK should build from it.
+#[derive(Clone)]
+pub struct Project {
+    pub name: String,
+    pub package: Package,
+    pub goldens: Vec<Golden>
+}
+
+impl Project {
+    /// Reads a project directory, along with any golden packages in its
+    /// `golden/` directory. Other files there are ignored, so notes on how
+    /// the goldens were built can be kept alongside them.
+    pub fn read_dir(path: &Path) -> Result<Project> {
+        let android_manifest = fs::read(path.join("AndroidManifest.xml"))?;
+        let exclude_globs = read_ignore_file(&StdFs, path)?;
+        let res_path = path.join("res");
+        let resources = if res_path.is_dir() {
+            read_res_dir(&StdFs, &res_path, &exclude_globs)?
+        } else {
+            vec![]
+        };
+
+        let mut goldens = vec![];
+        let golden_path = path.join(GOLDEN_DIR);
+        if golden_path.is_dir() {
+            for golden in sorted_entries(&golden_path)? {
+                let name = file_name(&golden);
+                if name.ends_with(".apk") || name.ends_with(".aab") {
+                    goldens.extend(Golden::new(&name, fs::read(&golden)?));
+                }
+            }
+        }
+
+        Ok(Project {
+            name: file_name(path),
+            package: Package {
+                android_manifest,
+                resources,
+                modules: vec![]
+            },
+            goldens
+        })
+    }
+}
+
+/// A set of projects to check.
+#[derive(Clone, Default)]
+pub struct Corpus {
+    pub projects: Vec<Project>
+}
+
+impl Corpus {
+    /// The projects in [BUILTIN_CORPUS].
+    pub fn builtin() -> Result<Corpus> {
+        Corpus::read_dir(Path::new(BUILTIN_CORPUS))
+    }
+
+    /// Reads every project in a directory, see [find_projects].
+    pub fn read_dir(path: &Path) -> Result<Corpus> {
+        let mut corpus = Corpus::default();
+        for project in find_projects(path)? {
+            corpus.add(Project::read_dir(&project)?);
+        }
+        Ok(corpus)
+    }
+
+    pub fn add(&mut self, project: Project) {
+        self.projects.push(project);
+    }
+
+    /// Builds every project and compares it with its goldens. Projects are
+    /// built as both an APK and an AAB even without goldens, so that build
+    /// failures are still caught.
+    pub fn run(&self, keys: &Keys) -> Vec<ProjectReport> {
+        self.projects
+            .iter()
+            .map(|project| check_project(project, keys))
+            .collect()
+    }
+}
+
+/// The project directories in `path`: `path` itself if it has an
+/// `AndroidManifest.xml`, or otherwise every subdirectory that does, in
+/// alphabetical order.
+pub fn find_projects(path: &Path) -> Result<Vec<PathBuf>> {
+    if is_project(path) {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    Ok(sorted_entries(path)?
+        .into_iter()
+        .filter(|entry| is_project(entry))
+        .collect())
+}
+
+/// Builds the project in `path` and writes its APK and AAB to its `golden/`
+/// directory as [PACK_GOLDENS], replacing any that are there. They're
+/// compared by decoding, like goldens from other tools. Run this when PACK's
+/// output changes on purpose, after checking that the differences from the
+/// old goldens are the intended ones.
+pub fn update_goldens(path: &Path, keys: &Keys) -> Result<()> {
+    let project = Project::read_dir(path)?;
+    let golden_path = path.join(GOLDEN_DIR);
+    fs::create_dir_all(&golden_path)?;
+    let [apk_name, aab_name] = PACK_GOLDENS;
+    fs::write(
+        golden_path.join(apk_name),
+        compile_and_sign_apk(&project.package, keys)?
+    )?;
+    fs::write(
+        golden_path.join(aab_name),
+        compile_and_sign_aab(&project.package, keys)?
+    )?;
+    Ok(())
+}
+
+/// How one project compared with its goldens.
+#[derive(Debug, Clone)]
+pub struct ProjectReport {
+    pub project: String,
+    /// Why PACK couldn't build the project, or why what it built wouldn't
+    /// install, if either happened.
+    pub build_errors: Vec<String>,
+    pub goldens: Vec<GoldenReport>
+}
+
+/// The differences between PACK's package and one golden. Empty if they
+/// match.
+#[derive(Debug, Clone)]
+pub struct GoldenReport {
+    pub golden: String,
+    pub differences: Vec<String>
+}
+
+impl ProjectReport {
+    pub fn passed(&self) -> bool {
+        self.build_errors.is_empty()
+            && self
+                .goldens
+                .iter()
+                .all(|golden| golden.differences.is_empty())
+    }
+}
+
+impl fmt::Display for ProjectReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = if self.passed() { "OK" } else { "FAILED" };
+        write!(f, "{}: {status}", self.project)?;
+        if self.goldens.is_empty() && self.build_errors.is_empty() {
+            write!(f, " (built, but there are no goldens to compare with)")?;
+        }
+        for error in &self.build_errors {
+            write!(f, "\n  {error}")?;
+        }
+        for golden in &self.goldens {
+            if golden.differences.is_empty() {
+                write!(f, "\n  {}: matches", golden.golden)?;
+            }
+            for difference in &golden.differences {
+                write!(f, "\n  {}: {difference}", golden.golden)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn check_project(project: &Project, keys: &Keys) -> ProjectReport {
+    let mut report = ProjectReport {
+        project: project.name.clone(),
+        build_errors: vec![],
+        goldens: vec![]
+    };
+    let mut build = |kind: PackageKind| {
+        let built = match kind {
+            PackageKind::Apk => compile_and_sign_apk(&project.package, keys),
+            PackageKind::Aab => compile_and_sign_aab(&project.package, keys)
+        };
+        let built = built
+            .map_err(|e| {
+                report
+                    .build_errors
+                    .push(format!("The {kind:?} failed to build: {e}"))
+            })
+            .ok()?;
+        // The way apksigner would check it, which doesn't need a golden
+        match verify_package(&built) {
+            Ok(verification) => report
+                .build_errors
+                .extend(verification_errors(kind, &verification)),
+            Err(e) => report
+                .build_errors
+                .push(format!("The {kind:?} couldn't be verified: {e}"))
+        }
+        Some(built)
+    };
+    let apk = build(PackageKind::Apk);
+    let aab = build(PackageKind::Aab);
+
+    for golden in &project.goldens {
+        let built = match golden.kind {
+            PackageKind::Apk => &apk,
+            PackageKind::Aab => &aab
+        };
+        // The build error has already been reported
+        let Some(built) = built else {
+            continue;
+        };
+        let differences = match golden.comparison {
+            Comparison::Exact if *built == golden.bytes => vec![],
+            Comparison::Exact => vec!["The packages aren't byte for byte the same.".into()],
+            Comparison::Decoded => compare_decoded(&golden.bytes, built)
+                .and_then(|mut differences| {
+                    differences.extend(compare_signatures(&golden.bytes, built)?);
+                    Ok(differences)
+                })
+                .unwrap_or_else(|e| vec![e.to_string()])
+        };
+        report.goldens.push(GoldenReport {
+            golden: golden.name.clone(),
+            differences
+        });
+    }
+    report
+}
+
+fn verification_errors(kind: PackageKind, verification: &VerificationReport) -> Vec<String> {
+    let mut errors = vec![];
+    for (scheme, status) in schemes(verification) {
+        if let SchemeStatus::Invalid(reason) = status {
+            errors.push(format!(
+                "The {kind:?}'s {scheme} signature is invalid: {reason}"
+            ));
+        }
+    }
+    if !verification.misaligned_entries.is_empty() {
+        errors.push(format!(
+            "The {kind:?} has misaligned entries: {}",
+            verification.misaligned_entries.join(", ")
+        ));
+    }
+    errors
+}
+
+fn schemes(verification: &VerificationReport) -> [(&'static str, &SchemeStatus); 3] {
+    [
+        ("v1", &verification.v1),
+        ("v2", &verification.v2),
+        ("v3", &verification.v3)
+    ]
+}
+
+fn compare_decoded(golden: &[u8], built: &[u8]) -> Result<Vec<String>> {
+    let golden = DescribedPackage::read(golden)?;
+    let built = DescribedPackage::read(built)?;
+    let diff = diff_packages(&golden.package, &built.package)?;
+    let mut differences = vec![];
+    for entry in diff
+        .added_entries
+        .iter()
+        .filter(|entry| !is_signature(entry))
+    {
+        differences.push(format!("{entry} is only in PACK's package."));
+    }
+    for entry in diff
+        .removed_entries
+        .iter()
+        .filter(|entry| !is_signature(entry))
+    {
+        differences.push(format!("{entry} is missing from PACK's package."));
+    }
+    // The manifest and resource table are compared through what they decode
+    // to below
+    for entry in diff
+        .changed_entries
+        .iter()
+        .filter(|entry| !is_encoded_by_tool(entry))
+    {
+        differences.push(match (golden.xml.get(entry), built.xml.get(entry)) {
+            (Some(golden_xml), Some(built_xml)) => first_difference(entry, golden_xml, built_xml),
+            _ => format!("{entry} has different contents.")
+        });
+    }
+    for resource in &diff.added_resources {
+        differences.push(format!("{resource} is only in PACK's package."));
+    }
+    for resource in &diff.removed_resources {
+        differences.push(format!("{resource} is missing from PACK's package."));
+    }
+    for resource in &diff.changed_resources {
+        differences.push(format!("{resource} has a different value."));
+    }
+    for change in &diff.manifest_changes {
+        differences.push(format!(
+            "{} {} is {} in the golden, but {} in PACK's package.",
+            change.element,
+            change.attribute,
+            change.old.as_deref().unwrap_or("missing"),
+            change.new.as_deref().unwrap_or("missing")
+        ));
+    }
+    Ok(differences)
+}
+
+// PACK has to sign with every scheme a golden is signed with, eg. by
+// apksigner. Unsigned goldens, like the APKs aapt2 links, don't need any.
+fn compare_signatures(golden: &[u8], built: &[u8]) -> Result<Vec<String>> {
+    let golden = verify_package(golden)?;
+    let built = verify_package(built)?;
+    Ok(schemes(&golden)
+        .into_iter()
+        .zip(schemes(&built))
+        .filter(|((_, golden), (_, built))| {
+            **golden != SchemeStatus::Missing && **built == SchemeStatus::Missing
+        })
+        .map(|((scheme, _), _)| {
+            format!("The golden is signed with {scheme}, but PACK's package isn't.")
+        })
+        .collect())
+}
+
+// A package with its compiled XML files replaced by descriptions of what they
+// decode to, so that diff_packages compares them by their elements and
+// attributes rather than by bytes, which differ between compilers
+struct DescribedPackage {
+    package: Vec<u8>,
+    // The descriptions, by path
+    xml: BTreeMap<String, String>
+}
+
+impl DescribedPackage {
+    fn read(package: &[u8]) -> Result<DescribedPackage> {
+        let mut files = pack_zip::unzip(package)?;
+        let find = |path: &str| files.iter().find(|file| file.path == path);
+        let is_apk = find("AndroidManifest.xml").is_some();
+        // References are described by name, which needs the resource table
+        let table = match (is_apk, find("resources.arsc"), find("base/resources.pb")) {
+            (true, Some(table), _) => decode_resource_table(&table.data)?,
+            (false, _, Some(table)) => pack_aab::decode_proto_resource_table(&table.data)?,
+            _ => DecodedTable::default()
+        };
+        let mut xml = BTreeMap::new();
+        for file in &mut files {
+            if !is_compiled_xml(&file.path) {
+                continue;
+            }
+            let element = if is_apk {
+                decode_xml(&file.data)?
+            } else {
+                pack_aab::decode_proto_xml(&file.data)?
+            };
+            let description = describe_xml(&element, &table);
+            file.data = description.clone().into_bytes();
+            xml.insert(file.path.clone(), description);
+        }
+        let mut package = Cursor::new(vec![]);
+        pack_zip::zip_apk(&files, &mut package)?;
+        Ok(DescribedPackage {
+            package: package.into_inner(),
+            xml
+        })
+    }
+}
+
+// One line per element, indented by depth, with its attributes sorted by name
+// and references given by resource name, since tools assign IDs differently
+fn describe_xml(element: &DecodedElement, table: &DecodedTable) -> String {
+    let mut lines = vec![];
+    describe_element(element, table, 0, &mut lines);
+    lines.join("\n")
+}
+
+fn describe_element(
+    element: &DecodedElement,
+    table: &DecodedTable,
+    depth: usize,
+    lines: &mut Vec<String>
+) {
+    let mut attributes: Vec<String> = element
+        .attributes
+        .iter()
+        .map(|attribute| {
+            format!(
+                " {}={}",
+                qualified_name(&attribute.namespace, &attribute.name),
+                describe_value(&attribute.value, table)
+            )
+        })
+        .collect();
+    attributes.sort();
+    lines.push(format!(
+        "{}<{}{}>",
+        "  ".repeat(depth),
+        qualified_name(&element.namespace, &element.name),
+        attributes.concat()
+    ));
+    for child in &element.children {
+        describe_element(child, table, depth + 1, lines);
+    }
+}
+
+fn qualified_name(namespace: &Option<String>, name: &str) -> String {
+    match namespace {
+        Some(namespace) => format!("{{{namespace}}}{name}"),
+        None => name.into()
+    }
+}
+
+fn describe_value(value: &BinaryValue, table: &DecodedTable) -> String {
+    match value {
+        BinaryValue::Reference(id) => table.reference_name(*id).unwrap_or(format!("@{id:#010x}")),
+        BinaryValue::AttributeReference(id) => match table.reference_name(*id) {
+            Some(name) => name.replacen('@', "?", 1),
+            None => format!("?{id:#010x}")
+        },
+        value => format!("{value:?}")
+    }
+}
+
+// Describes where two descriptions of an XML file first differ
+fn first_difference(path: &str, golden: &str, built: &str) -> String {
+    let mut golden_lines = golden.lines();
+    let mut built_lines = built.lines();
+    loop {
+        match (golden_lines.next(), built_lines.next()) {
+            (Some(golden_line), Some(built_line)) if golden_line == built_line => {}
+            (golden_line, built_line) => {
+                return format!(
+                    "{path} decodes to {} in the golden, but {} in PACK's package.",
+                    golden_line.map_or("nothing more".into(), |line| format!("`{}`", line.trim())),
+                    built_line.map_or("nothing more".into(), |line| format!("`{}`", line.trim()))
+                )
+            }
+        }
+    }
+}
+
+// v1 signatures, which differ between signers even with the same key
+fn is_signature(path: &str) -> bool {
+    path.starts_with("META-INF/")
+}
+
+// XML files in res/ other than res/raw/, which are compiled to binary XML in
+// APKs and ProtoXML in AABs. Only the base module's are decoded.
+fn is_compiled_xml(path: &str) -> bool {
+    let path = path.strip_prefix("base/").unwrap_or(path);
+    path.starts_with("res/") && !path.starts_with("res/raw") && path.ends_with(".xml")
+}
+
+// Files whose bytes depend on the tool that wrote them, and which are compared
+// through what they decode to instead
+fn is_encoded_by_tool(path: &str) -> bool {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    file_name == "AndroidManifest.xml"
+        || file_name == "resources.arsc"
+        || file_name.ends_with(".pb")
+        || is_signature(path)
+}
+
+fn is_project(path: &Path) -> bool {
+    path.join("AndroidManifest.xml").is_file()
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+// A directory's entries, sorted so that results don't depend on the file system
+fn sorted_entries(path: &Path) -> Result<Vec<PathBuf>> {
+    let mut entries = fs::read_dir(path)?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<Result<Vec<_>>>()?;
+    entries.sort();
+    Ok(entries)
+}
@@ -0,0 +1,78 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Runs the built-in corpus, or the project and corpus directories passed as
+// arguments. Packages are signed with the corpus' testing keys unless
+// `--keys keys.pem` is passed, which `.exact` goldens might need. With
+// `--update`, it writes PACK's packages as the projects' goldens instead of
+// comparing them. Exits with 1 if anything doesn't match.
+use std::{env, fs, path::Path, process::ExitCode};
+
+use pack_api::{Keys, PackError, Result};
+use pack_conformance::{
+    find_projects, update_goldens, Corpus, Project, BUILTIN_CORPUS, TESTING_KEYS
+};
+
+fn main() -> ExitCode {
+    match run() {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("Error: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run() -> Result<bool> {
+    let mut paths: Vec<String> = env::args().skip(1).collect();
+    let pem_path = match paths.iter().position(|arg| arg == "--keys") {
+        Some(index) if index + 1 < paths.len() => {
+            paths.drain(index..index + 2).nth(1).unwrap_or_default()
+        }
+        Some(_) => return Err(PackError::Cli("--keys needs a path to a .pem file.".into())),
+        None => TESTING_KEYS.into()
+    };
+    let keys = Keys::from_combined_pem_string(&fs::read_to_string(pem_path)?)?;
+    let update = match paths.iter().position(|arg| arg == "--update") {
+        Some(index) => {
+            paths.remove(index);
+            true
+        }
+        None => false
+    };
+    if paths.is_empty() {
+        paths.push(BUILTIN_CORPUS.into());
+    }
+
+    let mut corpus = Corpus::default();
+    for path in &paths {
+        // A single project, or a directory of them
+        for project in find_projects(Path::new(path))? {
+            if update {
+                update_goldens(&project, &keys)?;
+                println!("Updated the goldens in {}", project.display());
+            } else {
+                corpus.add(Project::read_dir(&project)?);
+            }
+        }
+    }
+
+    let mut passed = true;
+    for report in corpus.run(&keys) {
+        println!("{report}");
+        passed &= report.passed();
+    }
+    Ok(passed)
+}
@@ -0,0 +1,114 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Runs the built-in corpus against its checked-in goldens, so that changes to
+// what PACK builds fail `cargo test` unless the goldens are updated too.
+use std::fs;
+
+use pack_api::{FileResource, Keys};
+use pack_conformance::{Corpus, Project, ProjectReport, TESTING_KEYS};
+
+fn keys() -> Keys {
+    Keys::from_combined_pem_string(&fs::read_to_string(TESTING_KEYS).unwrap()).unwrap()
+}
+
+// Runs one built-in project, after changing one of its files
+fn run_changed(project: &str, subdirectory: &str, name: &str, contents: &str) -> ProjectReport {
+    let mut project: Project = Corpus::builtin()
+        .unwrap()
+        .projects
+        .into_iter()
+        .find(|candidate| candidate.name == project)
+        .unwrap();
+    let resource = project
+        .package
+        .resources
+        .iter_mut()
+        .find(|resource| resource.subdirectory == subdirectory && resource.name == name)
+        .unwrap();
+    *resource = FileResource::new(subdirectory.into(), name.into(), contents.into());
+    let corpus = Corpus {
+        projects: vec![project]
+    };
+    corpus.run(&keys()).remove(0)
+}
+
+fn differences(report: &ProjectReport) -> Vec<&str> {
+    report
+        .goldens
+        .iter()
+        .flat_map(|golden| &golden.differences)
+        .map(String::as_str)
+        .collect()
+}
+
+#[test]
+fn builtin_corpus_matches_goldens() {
+    let corpus = Corpus::builtin().unwrap();
+    assert!(!corpus.projects.is_empty());
+    for report in corpus.run(&keys()) {
+        assert!(
+            !report.goldens.is_empty(),
+            "{} has no goldens",
+            report.project
+        );
+        assert!(report.passed(), "{report}");
+    }
+}
+
+#[test]
+fn changed_strings_are_reported() {
+    let report = run_changed(
+        "qualifiers",
+        "values",
+        "strings.xml",
+        r#"<resources>
+    <string name="app_name">Qualifiers</string>
+    <string name="greeting">Hi</string>
+    <string name="screen_shape">Square</string>
+</resources>"#
+    );
+    assert_eq!(
+        differences(&report),
+        [
+            "@string/greeting has a different value.",
+            "@string/greeting has a different value."
+        ]
+    );
+}
+
+#[test]
+fn compiled_xml_is_compared_by_what_it_decodes_to() {
+    // The same attributes in another order compile to different bytes
+    let reordered = run_changed(
+        "qualifiers",
+        "xml",
+        "settings.xml",
+        r#"<settings icon="@drawable/icon" greeting="@string/greeting" />"#
+    );
+    assert!(reordered.passed(), "{reordered}");
+
+    let changed = run_changed(
+        "qualifiers",
+        "xml",
+        "settings.xml",
+        r#"<settings greeting="@string/app_name" icon="@drawable/icon" />"#
+    );
+    assert!(
+        differences(&changed).contains(
+            &"res/xml/settings.xml decodes to `<settings greeting=@string/greeting icon=@drawable/icon>` in the golden, but `<settings greeting=@string/app_name icon=@drawable/icon>` in PACK's package."
+        ),
+        "{changed}"
+    );
+}
@@ -0,0 +1,149 @@
+#ifndef PACK_H
+#define PACK_H
+
+// Generated by cbindgen from pack-ffi/src/lib.rs, don't edit by hand.
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// What happened in a call. Anything other than `PACK_STATUS_OK` leaves a
+// message in `pack_last_error`.
+typedef enum PackStatus {
+  PACK_STATUS_OK = 0,
+  // A required pointer was null, or a string wasn't valid UTF-8
+  PACK_STATUS_INVALID_ARGUMENT = 1,
+  // Building, signing or reading keys failed
+  PACK_STATUS_FAILED = 2,
+  // pack itself went wrong. This is reported rather than crashing the
+  // calling app.
+  PACK_STATUS_PANICKED = 3,
+} PackStatus;
+
+// Signing keys, from one of the `pack_keys_` functions. Free with
+// `pack_keys_free`.
+typedef struct PackKeys PackKeys;
+
+// An app being built, from `pack_package_new`. Free with
+// `pack_package_free`.
+typedef struct PackPackage PackPackage;
+
+// Bytes allocated by pack. Free with `pack_buffer_free`.
+typedef struct PackBuffer {
+  uint8_t *data;
+  size_t len;
+} PackBuffer;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Describes why the last call on this thread failed, or returns null if it
+// succeeded. The string belongs to pack, and stays valid until the next call
+// on the same thread.
+const char *pack_last_error(void);
+
+// Starts a package from the contents of its AndroidManifest.xml.
+//
+// # Safety
+//
+// `manifest` must point to `manifest_len` readable bytes, and `out_package`
+// to writable memory for a pointer.
+enum PackStatus pack_package_new(const uint8_t *manifest,
+                                 size_t manifest_len,
+                                 struct PackPackage **out_package);
+
+// Adds a file under `res/`, eg. `drawable-xxhdpi` and `preview.png`. The
+// contents are copied, so they can be freed straight afterwards.
+//
+// # Safety
+//
+// `package` must come from `pack_package_new`, `subdirectory` and `name` must
+// be null-terminated, and `contents` must point to `contents_len` readable
+// bytes.
+enum PackStatus pack_package_add_resource(struct PackPackage *package,
+                                          const char *subdirectory,
+                                          const char *name,
+                                          const uint8_t *contents,
+                                          size_t contents_len);
+
+// # Safety
+//
+// `package` must come from `pack_package_new` and not be used afterwards.
+// Null is ignored.
+void pack_package_free(struct PackPackage *package);
+
+// Reads signing keys from a .pem file holding both a CERTIFICATE and a
+// PRIVATE KEY.
+//
+// # Safety
+//
+// `pem` must be null-terminated, and `out_keys` must point to writable memory
+// for a pointer.
+enum PackStatus pack_keys_from_pem(const char *pem, struct PackKeys **out_keys);
+
+// Reads signing keys from a JKS or PKCS #12 keystore. `alias` can be null if
+// the keystore only holds one key, and `key_password` can be null if it's the
+// same as `store_password`.
+//
+// # Safety
+//
+// `keystore` must point to `keystore_len` readable bytes, the strings must be
+// null-terminated, and `out_keys` must point to writable memory for a
+// pointer.
+enum PackStatus pack_keys_from_keystore(const uint8_t *keystore,
+                                        size_t keystore_len,
+                                        const char *store_password,
+                                        const char *alias,
+                                        const char *key_password,
+                                        struct PackKeys **out_keys);
+
+// Generates throwaway keys, which are fine for testing but can't be used for
+// updates on Google Play.
+//
+// # Safety
+//
+// `out_keys` must point to writable memory for a pointer.
+enum PackStatus pack_keys_generate_testing(struct PackKeys **out_keys);
+
+// # Safety
+//
+// `keys` must come from one of the `pack_keys_` functions and not be used
+// afterwards. Null is ignored.
+void pack_keys_free(struct PackKeys *keys);
+
+// Compiles an APK, signed with `keys`, or unsigned if `keys` is null.
+//
+// # Safety
+//
+// `package` must come from `pack_package_new`, `keys` must be null or come
+// from one of the `pack_keys_` functions, and `out_apk` must point to
+// writable memory for a PackBuffer.
+enum PackStatus pack_compile_apk(const struct PackPackage *package,
+                                 const struct PackKeys *keys,
+                                 struct PackBuffer *out_apk);
+
+// Compiles an Android App Bundle, signed with `keys`.
+//
+// # Safety
+//
+// `package` must come from `pack_package_new`, `keys` from one of the
+// `pack_keys_` functions, and `out_aab` must point to writable memory for a
+// PackBuffer.
+enum PackStatus pack_compile_aab(const struct PackPackage *package,
+                                 const struct PackKeys *keys,
+                                 struct PackBuffer *out_aab);
+
+// # Safety
+//
+// `buffer` must come from pack and not be used afterwards. Empty buffers are
+// ignored.
+void pack_buffer_free(struct PackBuffer buffer);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* PACK_H */
@@ -0,0 +1,366 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// A C ABI for pack, so that C, C++, Swift and Kotlin/Native apps can build
+// packages in-process. Files are passed as pointers and lengths, packages and
+// keys are opaque handles, and every call that can fail returns a PackStatus.
+// include/pack.h is generated from this file with cbindgen.
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CStr, CString},
+    panic::{catch_unwind, AssertUnwindSafe},
+    ptr, slice
+};
+
+use pack_api::{
+    compile_and_sign_aab, compile_and_sign_apk, compile_apk, FileResource, Keys, PackError, Package
+};
+
+/// What happened in a call. Anything other than `PACK_STATUS_OK` leaves a
+/// message in `pack_last_error`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackStatus {
+    Ok = 0,
+    /// A required pointer was null, or a string wasn't valid UTF-8
+    InvalidArgument = 1,
+    /// Building, signing or reading keys failed
+    Failed = 2,
+    /// pack itself went wrong. This is reported rather than crashing the
+    /// calling app.
+    Panicked = 3
+}
+
+/// An app being built, from `pack_package_new`. Free with
+/// `pack_package_free`.
+pub struct PackPackage {
+    package: Package
+}
+
+/// Signing keys, from one of the `pack_keys_` functions. Free with
+/// `pack_keys_free`.
+pub struct PackKeys {
+    keys: Keys
+}
+
+/// Bytes allocated by pack. Free with `pack_buffer_free`.
+#[repr(C)]
+pub struct PackBuffer {
+    pub data: *mut u8,
+    pub len: usize
+}
+
+enum Error {
+    InvalidArgument(String),
+    Pack(PackError)
+}
+
+impl From<PackError> for Error {
+    fn from(err: PackError) -> Self {
+        Error::Pack(err)
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Describes why the last call on this thread failed, or returns null if it
+/// succeeded. The string belongs to pack, and stays valid until the next call
+/// on the same thread.
+#[no_mangle]
+pub extern "C" fn pack_last_error() -> *const c_char {
+    LAST_ERROR.with(|last_error| {
+        last_error
+            .borrow()
+            .as_ref()
+            .map_or(ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Starts a package from the contents of its AndroidManifest.xml.
+///
+/// # Safety
+///
+/// `manifest` must point to `manifest_len` readable bytes, and `out_package`
+/// to writable memory for a pointer.
+#[no_mangle]
+pub unsafe extern "C" fn pack_package_new(
+    manifest: *const u8,
+    manifest_len: usize,
+    out_package: *mut *mut PackPackage
+) -> PackStatus {
+    run(|| {
+        let out_package = output(out_package, "out_package")?;
+        let package = Package {
+            android_manifest: bytes(manifest, manifest_len, "manifest")?.to_vec(),
+            resources: vec![],
+            modules: vec![]
+        };
+        out_package.write(Box::into_raw(Box::new(PackPackage { package })));
+        Ok(())
+    })
+}
+
+/// Adds a file under `res/`, eg. `drawable-xxhdpi` and `preview.png`. The
+/// contents are copied, so they can be freed straight afterwards.
+///
+/// # Safety
+///
+/// `package` must come from `pack_package_new`, `subdirectory` and `name` must
+/// be null-terminated, and `contents` must point to `contents_len` readable
+/// bytes.
+#[no_mangle]
+pub unsafe extern "C" fn pack_package_add_resource(
+    package: *mut PackPackage,
+    subdirectory: *const c_char,
+    name: *const c_char,
+    contents: *const u8,
+    contents_len: usize
+) -> PackStatus {
+    run(|| {
+        let resource = FileResource::new(
+            string(subdirectory, "subdirectory")?.into(),
+            string(name, "name")?.into(),
+            bytes(contents, contents_len, "contents")?.to_vec()
+        );
+        let package = package
+            .as_mut()
+            .ok_or(Error::InvalidArgument("package is null".into()))?;
+        package.package.resources.push(resource);
+        Ok(())
+    })
+}
+
+/// # Safety
+///
+/// `package` must come from `pack_package_new` and not be used afterwards.
+/// Null is ignored.
+#[no_mangle]
+pub unsafe extern "C" fn pack_package_free(package: *mut PackPackage) {
+    if !package.is_null() {
+        drop(Box::from_raw(package));
+    }
+}
+
+/// Reads signing keys from a .pem file holding both a CERTIFICATE and a
+/// PRIVATE KEY.
+///
+/// # Safety
+///
+/// `pem` must be null-terminated, and `out_keys` must point to writable memory
+/// for a pointer.
+#[no_mangle]
+pub unsafe extern "C" fn pack_keys_from_pem(
+    pem: *const c_char,
+    out_keys: *mut *mut PackKeys
+) -> PackStatus {
+    run(|| {
+        let out_keys = output(out_keys, "out_keys")?;
+        let keys = Keys::from_combined_pem_string(string(pem, "pem")?)?;
+        out_keys.write(Box::into_raw(Box::new(PackKeys { keys })));
+        Ok(())
+    })
+}
+
+/// Reads signing keys from a JKS or PKCS #12 keystore. `alias` can be null if
+/// the keystore only holds one key, and `key_password` can be null if it's the
+/// same as `store_password`.
+///
+/// # Safety
+///
+/// `keystore` must point to `keystore_len` readable bytes, the strings must be
+/// null-terminated, and `out_keys` must point to writable memory for a
+/// pointer.
+#[no_mangle]
+pub unsafe extern "C" fn pack_keys_from_keystore(
+    keystore: *const u8,
+    keystore_len: usize,
+    store_password: *const c_char,
+    alias: *const c_char,
+    key_password: *const c_char,
+    out_keys: *mut *mut PackKeys
+) -> PackStatus {
+    run(|| {
+        let out_keys = output(out_keys, "out_keys")?;
+        let keys = Keys::from_keystore(
+            bytes(keystore, keystore_len, "keystore")?,
+            string(store_password, "store_password")?,
+            optional_string(alias, "alias")?,
+            optional_string(key_password, "key_password")?
+        )?;
+        out_keys.write(Box::into_raw(Box::new(PackKeys { keys })));
+        Ok(())
+    })
+}
+
+/// Generates throwaway keys, which are fine for testing but can't be used for
+/// updates on Google Play.
+///
+/// # Safety
+///
+/// `out_keys` must point to writable memory for a pointer.
+#[no_mangle]
+pub unsafe extern "C" fn pack_keys_generate_testing(out_keys: *mut *mut PackKeys) -> PackStatus {
+    run(|| {
+        let out_keys = output(out_keys, "out_keys")?;
+        let keys = Keys::generate_random_testing_keys()?;
+        out_keys.write(Box::into_raw(Box::new(PackKeys { keys })));
+        Ok(())
+    })
+}
+
+/// # Safety
+///
+/// `keys` must come from one of the `pack_keys_` functions and not be used
+/// afterwards. Null is ignored.
+#[no_mangle]
+pub unsafe extern "C" fn pack_keys_free(keys: *mut PackKeys) {
+    if !keys.is_null() {
+        drop(Box::from_raw(keys));
+    }
+}
+
+/// Compiles an APK, signed with `keys`, or unsigned if `keys` is null.
+///
+/// # Safety
+///
+/// `package` must come from `pack_package_new`, `keys` must be null or come
+/// from one of the `pack_keys_` functions, and `out_apk` must point to
+/// writable memory for a PackBuffer.
+#[no_mangle]
+pub unsafe extern "C" fn pack_compile_apk(
+    package: *const PackPackage,
+    keys: *const PackKeys,
+    out_apk: *mut PackBuffer
+) -> PackStatus {
+    run(|| {
+        let out_apk = output(out_apk, "out_apk")?;
+        let package = &handle(package, "package")?.package;
+        let apk = match keys.as_ref() {
+            Some(keys) => compile_and_sign_apk(package, &keys.keys)?,
+            None => compile_apk(package)?
+        };
+        out_apk.write(buffer(apk));
+        Ok(())
+    })
+}
+
+/// Compiles an Android App Bundle, signed with `keys`.
+///
+/// # Safety
+///
+/// `package` must come from `pack_package_new`, `keys` from one of the
+/// `pack_keys_` functions, and `out_aab` must point to writable memory for a
+/// PackBuffer.
+#[no_mangle]
+pub unsafe extern "C" fn pack_compile_aab(
+    package: *const PackPackage,
+    keys: *const PackKeys,
+    out_aab: *mut PackBuffer
+) -> PackStatus {
+    run(|| {
+        let out_aab = output(out_aab, "out_aab")?;
+        let aab = compile_and_sign_aab(
+            &handle(package, "package")?.package,
+            &handle(keys, "keys")?.keys
+        )?;
+        out_aab.write(buffer(aab));
+        Ok(())
+    })
+}
+
+/// # Safety
+///
+/// `buffer` must come from pack and not be used afterwards. Empty buffers are
+/// ignored.
+#[no_mangle]
+pub unsafe extern "C" fn pack_buffer_free(buffer: PackBuffer) {
+    if !buffer.data.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+            buffer.data,
+            buffer.len
+        )));
+    }
+}
+
+// Records the outcome for pack_last_error, and stops panics from unwinding
+// into the calling language
+fn run(f: impl FnOnce() -> Result<(), Error>) -> PackStatus {
+    let (status, message) = match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => (PackStatus::Ok, None),
+        Ok(Err(Error::InvalidArgument(message))) => (PackStatus::InvalidArgument, Some(message)),
+        Ok(Err(Error::Pack(err))) => (PackStatus::Failed, Some(err.to_string())),
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_default();
+            (
+                PackStatus::Panicked,
+                Some(format!("pack panicked: {message}"))
+            )
+        }
+    };
+    // Messages can't contain null bytes, since they end the C string
+    let message = message.map(|message| CString::new(message.replace('\0', "")).unwrap());
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = message);
+    status
+}
+
+unsafe fn bytes<'a>(data: *const u8, len: usize, name: &str) -> Result<&'a [u8], Error> {
+    match (data.is_null(), len) {
+        (true, 0) => Ok(&[]),
+        (true, _) => Err(Error::InvalidArgument(format!("{name} is null"))),
+        (false, _) => Ok(slice::from_raw_parts(data, len))
+    }
+}
+
+unsafe fn string<'a>(string: *const c_char, name: &str) -> Result<&'a str, Error> {
+    optional_string(string, name)?.ok_or(Error::InvalidArgument(format!("{name} is null")))
+}
+
+unsafe fn optional_string<'a>(string: *const c_char, name: &str) -> Result<Option<&'a str>, Error> {
+    if string.is_null() {
+        return Ok(None);
+    }
+    CStr::from_ptr(string)
+        .to_str()
+        .map(Some)
+        .map_err(|_| Error::InvalidArgument(format!("{name} is not valid UTF-8")))
+}
+
+unsafe fn handle<'a, T>(handle: *const T, name: &str) -> Result<&'a T, Error> {
+    handle
+        .as_ref()
+        .ok_or(Error::InvalidArgument(format!("{name} is null")))
+}
+
+// Output pointers are checked before doing any work, so that nothing is
+// leaked by having nowhere to put it
+fn output<T>(out: *mut T, name: &str) -> Result<*mut T, Error> {
+    if out.is_null() {
+        return Err(Error::InvalidArgument(format!("{name} is null")));
+    }
+    Ok(out)
+}
+
+fn buffer(bytes: Vec<u8>) -> PackBuffer {
+    let len = bytes.len();
+    PackBuffer {
+        data: Box::into_raw(bytes.into_boxed_slice()).cast(),
+        len
+    }
+}
@@ -0,0 +1,68 @@
+/*
+ * Copyright 2026 Google LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+package com.google.pack;
+
+/**
+ * Why compiling a package failed. Where pack knows which file is broken, eg.
+ * for malformed XML, {@link #getPath()} says which, and {@link #getLine()} and
+ * {@link #getColumn()} say where in it.
+ */
+public class PackException extends Exception {
+
+    private final String code;
+    private final String path;
+    private final int line;
+    private final int column;
+
+    // Called from the native library
+    PackException(
+        String message,
+        String code,
+        String path,
+        int line,
+        int column
+    ) {
+        super(message);
+        this.code = code;
+        this.path = path;
+        this.line = line;
+        this.column = column;
+    }
+
+    /**
+     * What kind of failure this is, eg. {@code XmlParsingFailed}, or
+     * {@code InvalidArgument} if something passed in was null.
+     */
+    public String getCode() {
+        return code;
+    }
+
+    /** The file in the package that caused this, or null if it's unknown. */
+    public String getPath() {
+        return path;
+    }
+
+    /** Starting from 1, or 0 if it's unknown. */
+    public int getLine() {
+        return line;
+    }
+
+    /** Starting from 1, or 0 if it's unknown. */
+    public int getColumn() {
+        return column;
+    }
+}
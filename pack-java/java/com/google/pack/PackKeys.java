@@ -0,0 +1,76 @@
+/*
+ * Copyright 2026 Google LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+package com.google.pack;
+
+import java.util.Objects;
+
+/**
+ * The keys a package is signed with. Google Play needs every update signed
+ * with the same keys, so these should be kept somewhere safe.
+ */
+public final class PackKeys {
+
+    // Read by the native library. Either combinedPem or keystore is set.
+    private final String combinedPem;
+    private final byte[] keystore;
+    private final String storePassword;
+    private final String alias;
+    private final String keyPassword;
+
+    private PackKeys(
+        String combinedPem,
+        byte[] keystore,
+        String storePassword,
+        String alias,
+        String keyPassword
+    ) {
+        this.combinedPem = combinedPem;
+        this.keystore = keystore;
+        this.storePassword = storePassword;
+        this.alias = alias;
+        this.keyPassword = keyPassword;
+    }
+
+    /** From a .pem file holding both a CERTIFICATE and a PRIVATE KEY. */
+    public static PackKeys fromPem(String combinedPem) {
+        Objects.requireNonNull(combinedPem);
+        return new PackKeys(combinedPem, null, null, null, null);
+    }
+
+    /**
+     * From a JKS or PKCS #12 keystore, such as one made by keytool or Android
+     * Studio.
+     *
+     * @param alias which key to use, or null if the keystore only holds one
+     * @param keyPassword null if it's the same as the store password
+     */
+    public static PackKeys fromKeystore(
+        byte[] keystore,
+        String storePassword,
+        String alias,
+        String keyPassword
+    ) {
+        Objects.requireNonNull(keystore);
+        Objects.requireNonNull(storePassword);
+        return new PackKeys(null, keystore, storePassword, alias, keyPassword);
+    }
+
+    /** From a keystore holding a single key, with one password for both. */
+    public static PackKeys fromKeystore(byte[] keystore, String storePassword) {
+        return fromKeystore(keystore, storePassword, null, null);
+    }
+}
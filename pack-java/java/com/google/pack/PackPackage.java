@@ -0,0 +1,96 @@
+/*
+ * Copyright 2026 Google LLC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ *     http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+package com.google.pack;
+
+import java.nio.charset.StandardCharsets;
+import java.util.ArrayList;
+import java.util.List;
+import java.util.Objects;
+
+/**
+ * An app to compile into an APK or Android App Bundle, from an
+ * AndroidManifest.xml and the files under res/.
+ */
+public class PackPackage {
+
+    public static class Resource {
+
+        // Read by the native library
+        public final String subdirectory;
+        public final String name;
+        public final byte[] contents;
+
+        /** eg. {@code drawable} and {@code preview.png}. */
+        public Resource(String subdirectory, String name, byte[] contents) {
+            this.subdirectory = Objects.requireNonNull(subdirectory);
+            this.name = Objects.requireNonNull(name);
+            this.contents = Objects.requireNonNull(contents);
+        }
+
+        // Use this for text files like strings.xml
+        public static Resource fromString(
+            String subdirectory,
+            String name,
+            String contents
+        ) {
+            return new Resource(
+                subdirectory,
+                name,
+                contents.getBytes(StandardCharsets.UTF_8)
+            );
+        }
+    }
+
+    public byte[] androidManifest;
+    public List<Resource> resources = new ArrayList<>();
+    public PackKeys keys;
+
+    public void setAndroidManifest(String androidManifest) {
+        this.androidManifest = androidManifest.getBytes(StandardCharsets.UTF_8);
+    }
+
+    public byte[] compileApk() throws PackException {
+        return compilePackage(/* apk= */true);
+    }
+
+    public byte[] compileAab() throws PackException {
+        return compilePackage(/* apk= */false);
+    }
+
+    private byte[] compilePackage(boolean apk) throws PackException {
+        Objects.requireNonNull(androidManifest, "androidManifest is not set");
+        Objects.requireNonNull(keys, "keys are not set");
+        return nativeCompile(
+            androidManifest,
+            resources.toArray(new Resource[0]),
+            keys,
+            apk
+        );
+    }
+
+    // Implemented in pack-java/src/lib.rs
+    private static native byte[] nativeCompile(
+        byte[] androidManifest,
+        Resource[] resources,
+        PackKeys keys,
+        boolean apk
+    ) throws PackException;
+
+    static {
+        System.loadLibrary("pack_java");
+    }
+}
@@ -0,0 +1,203 @@
+// Copyright 2025 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// The native half of the com.google.pack Java classes in ./java. Files are
+// passed as byte[] both ways, and failures are thrown as PackExceptions
+// rather than crashing the app.
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use jni::{
+    objects::{JByteArray, JClass, JObject, JObjectArray, JString, JThrowable, JValue},
+    sys::{jboolean, jbyteArray},
+    JNIEnv
+};
+use pack_api::{
+    compile_and_sign_aab, compile_and_sign_apk, FileResource, Keys, PackError, Package
+};
+
+const PACK_EXCEPTION: &str = "com/google/pack/PackException";
+const PACK_EXCEPTION_CONSTRUCTOR: &str =
+    "(Ljava/lang/String;Ljava/lang/String;Ljava/lang/String;II)V";
+const JAVA_STRING_TYPE: &str = "Ljava/lang/String;";
+const JAVA_BYTE_ARRAY_TYPE: &str = "[B";
+
+enum Error {
+    Pack(PackError),
+    Jni(jni::errors::Error),
+    InvalidArgument(String),
+    Panicked
+}
+
+impl From<PackError> for Error {
+    fn from(err: PackError) -> Self {
+        Error::Pack(err)
+    }
+}
+
+impl From<jni::errors::Error> for Error {
+    fn from(err: jni::errors::Error) -> Self {
+        Error::Jni(err)
+    }
+}
+
+// Name (MUST) follow Java_packageName_className_methodName
+#[no_mangle]
+pub extern "system" fn Java_com_google_pack_PackPackage_nativeCompile<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    android_manifest: JByteArray<'local>,
+    resources: JObjectArray<'local>,
+    keys: JObject<'local>,
+    apk: jboolean
+) -> jbyteArray {
+    // Panics can't unwind into the JVM, so they're thrown as exceptions too
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        compile(&mut env, &android_manifest, &resources, &keys, apk != 0)
+    }));
+    let err = match result {
+        Ok(Ok(package)) => return package.into_raw(),
+        Ok(Err(err)) => err,
+        Err(_) => Error::Panicked
+    };
+    throw(&mut env, err);
+    std::ptr::null_mut()
+}
+
+fn compile<'local>(
+    env: &mut JNIEnv<'local>,
+    android_manifest: &JByteArray,
+    resources: &JObjectArray,
+    keys: &JObject,
+    apk: bool
+) -> Result<JByteArray<'local>, Error> {
+    let mut package = Package {
+        android_manifest: env.convert_byte_array(android_manifest)?,
+        resources: vec![],
+        modules: vec![]
+    };
+    for index in 0..env.get_array_length(resources)? {
+        let resource = env.get_object_array_element(resources, index)?;
+        package.resources.push(FileResource::new(
+            required(
+                string_field(env, &resource, "subdirectory")?,
+                "subdirectory"
+            )?,
+            required(string_field(env, &resource, "name")?, "name")?,
+            required(byte_array_field(env, &resource, "contents")?, "contents")?
+        ));
+        // Only a few local references are allowed at once, so they're freed
+        // as they go for packages with lots of resources
+        env.delete_local_ref(resource)?;
+    }
+
+    let keys = read_keys(env, keys)?;
+    let package = if apk {
+        compile_and_sign_apk(&package, &keys)?
+    } else {
+        compile_and_sign_aab(&package, &keys)?
+    };
+    Ok(env.byte_array_from_slice(&package)?)
+}
+
+// PackKeys holds either a .pem or a keystore and its passwords
+fn read_keys(env: &mut JNIEnv, keys: &JObject) -> Result<Keys, Error> {
+    if keys.is_null() {
+        return Err(Error::InvalidArgument("No signing keys were set".into()));
+    }
+    if let Some(combined_pem) = string_field(env, keys, "combinedPem")? {
+        return Ok(Keys::from_combined_pem_string(&combined_pem)?);
+    }
+
+    let keystore = required(byte_array_field(env, keys, "keystore")?, "keystore")?;
+    let store_password = required(string_field(env, keys, "storePassword")?, "storePassword")?;
+    let alias = string_field(env, keys, "alias")?;
+    let key_password = string_field(env, keys, "keyPassword")?;
+    Ok(Keys::from_keystore(
+        &keystore,
+        &store_password,
+        alias.as_deref(),
+        key_password.as_deref()
+    )?)
+}
+
+fn string_field(env: &mut JNIEnv, object: &JObject, name: &str) -> Result<Option<String>, Error> {
+    let value = env.get_field(object, name, JAVA_STRING_TYPE)?.l()?;
+    if value.is_null() {
+        return Ok(None);
+    }
+    Ok(Some(env.get_string(&JString::from(value))?.into()))
+}
+
+fn byte_array_field(
+    env: &mut JNIEnv,
+    object: &JObject,
+    name: &str
+) -> Result<Option<Vec<u8>>, Error> {
+    let value = env.get_field(object, name, JAVA_BYTE_ARRAY_TYPE)?.l()?;
+    if value.is_null() {
+        return Ok(None);
+    }
+    Ok(Some(env.convert_byte_array(JByteArray::from(value))?))
+}
+
+fn required<T>(value: Option<T>, name: &str) -> Result<T, Error> {
+    value.ok_or(Error::InvalidArgument(format!("{name} is null")))
+}
+
+fn throw(env: &mut JNIEnv, err: Error) {
+    // JNI calls fail when Java has already thrown, eg. for a missing field,
+    // and that exception says more than this one would
+    if env.exception_check().unwrap_or(false) {
+        return;
+    }
+
+    let (message, code, path, (line, column)) = match err {
+        Error::Pack(err) => (
+            err.to_string(),
+            err.code().name().to_string(),
+            err.path().map(String::from),
+            err.line_column().unwrap_or_default()
+        ),
+        Error::Jni(err) => (err.to_string(), "Jni".into(), None, (0, 0)),
+        Error::InvalidArgument(message) => (message, "InvalidArgument".into(), None, (0, 0)),
+        Error::Panicked => (
+            "pack panicked while compiling".into(),
+            "Panicked".into(),
+            None,
+            (0, 0)
+        )
+    };
+    // If the exception itself can't be made, eg. because memory ran out, the
+    // JVM will already have thrown something
+    let _ = (|| -> jni::errors::Result<()> {
+        let message = JObject::from(env.new_string(message)?);
+        let code = JObject::from(env.new_string(code)?);
+        let path = match path {
+            Some(path) => JObject::from(env.new_string(path)?),
+            None => JObject::null()
+        };
+        let exception = env.new_object(
+            PACK_EXCEPTION,
+            PACK_EXCEPTION_CONSTRUCTOR,
+            &[
+                JValue::Object(&message),
+                JValue::Object(&code),
+                JValue::Object(&path),
+                JValue::Int(line as i32),
+                JValue::Int(column as i32)
+            ]
+        )?;
+        env.throw(JThrowable::from(exception))
+    })();
+}
@@ -0,0 +1,196 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// A native Node.js addon for servers that build lots of packages, where the
+// WASM build would be slow. Files are passed as Buffers, and every build runs
+// on libuv's thread pool and returns a Promise, so it doesn't block the event
+// loop.
+use napi::{bindgen_prelude::*, Env, Task};
+use napi_derive::napi;
+use pack_api::{FileResource, Keys, PackError, Package};
+
+#[napi(object)]
+pub struct PackageInput {
+    /// AndroidManifest.xml
+    pub manifest: Buffer,
+    pub resources: Vec<ResourceInput>,
+    /// Not needed for `compileApk`
+    pub keys: Option<KeysInput>
+}
+
+#[napi(object)]
+pub struct ResourceInput {
+    /// eg. `drawable-xxhdpi`
+    pub subdirectory: String,
+    /// eg. `preview.png`
+    pub name: String,
+    pub contents: Buffer
+}
+
+/// Either `pem`, or a JKS or PKCS #12 `keystore` with its passwords.
+/// `alias` can be left out if the keystore only holds one key, and
+/// `keyPassword` if it's the same as `storePassword`.
+#[napi(object)]
+pub struct KeysInput {
+    /// A .pem holding both a CERTIFICATE and a PRIVATE KEY
+    pub pem: Option<String>,
+    pub keystore: Option<Buffer>,
+    pub store_password: Option<String>,
+    pub alias: Option<String>,
+    pub key_password: Option<String>
+}
+
+pub enum Output {
+    UnsignedApk,
+    SignedApk(KeySource),
+    SignedAab(KeySource)
+}
+
+pub enum KeySource {
+    Pem(String),
+    Keystore {
+        keystore: Vec<u8>,
+        store_password: String,
+        alias: Option<String>,
+        key_password: Option<String>
+    }
+}
+
+pub struct Compile {
+    package: Package,
+    output: Output,
+    // napi only passes a reason to reject(), so the PackError is kept here for
+    // it to turn into a JS error with a code and position
+    error: Option<PackError>
+}
+
+impl Task for Compile {
+    type Output = Vec<u8>;
+    type JsValue = Buffer;
+
+    fn compute(&mut self) -> Result<Vec<u8>> {
+        self.compile().map_err(|err| {
+            let reason = err.root().to_string();
+            self.error = Some(err);
+            Error::from_reason(reason)
+        })
+    }
+
+    fn resolve(&mut self, _env: Env, output: Vec<u8>) -> Result<Buffer> {
+        Ok(output.into())
+    }
+
+    // Rejects with eg. `{ code: "XmlParsingFailed", message: "...",
+    // path: "res/raw/watchface.xml", line: 12, column: 5 }`
+    fn reject(&mut self, env: Env, err: Error) -> Result<Buffer> {
+        let Some(pack_error) = self.error.take() else {
+            return Err(err);
+        };
+        let (line, column) = pack_error.line_column().unzip();
+        let mut error = env.create_error(err)?;
+        error.set_named_property("code", pack_error.code().name())?;
+        error.set_named_property("path", pack_error.path())?;
+        error.set_named_property("line", line.map(|line| line as u32))?;
+        error.set_named_property("column", column.map(|column| column as u32))?;
+        Err(Error::from(error.into_unknown()))
+    }
+}
+
+impl Compile {
+    fn new(input: PackageInput, output: Output) -> Self {
+        Compile {
+            package: Package {
+                android_manifest: input.manifest.into(),
+                resources: input
+                    .resources
+                    .into_iter()
+                    .map(|res| FileResource::new(res.subdirectory, res.name, res.contents.into()))
+                    .collect(),
+                modules: vec![]
+            },
+            output,
+            error: None
+        }
+    }
+
+    fn compile(&self) -> pack_api::Result<Vec<u8>> {
+        match &self.output {
+            Output::UnsignedApk => pack_api::compile_apk(&self.package),
+            Output::SignedApk(keys) => pack_api::compile_and_sign_apk(&self.package, &keys.read()?),
+            Output::SignedAab(keys) => pack_api::compile_and_sign_aab(&self.package, &keys.read()?)
+        }
+    }
+}
+
+impl KeySource {
+    // Missing keys are a mistake in the call, so they're thrown straight away
+    // rather than rejecting the Promise
+    fn new(keys: Option<KeysInput>) -> Result<Self> {
+        let invalid = |reason: &str| Error::new(Status::InvalidArg, reason);
+        let keys = keys.ok_or_else(|| invalid("Signing needs `keys`"))?;
+        if let Some(pem) = keys.pem {
+            return Ok(KeySource::Pem(pem));
+        }
+        Ok(KeySource::Keystore {
+            keystore: keys
+                .keystore
+                .ok_or_else(|| invalid("`keys` needs either `pem` or `keystore`"))?
+                .into(),
+            store_password: keys
+                .store_password
+                .ok_or_else(|| invalid("`keys.keystore` needs a `storePassword`"))?,
+            alias: keys.alias,
+            key_password: keys.key_password
+        })
+    }
+
+    // Keys are read on the thread pool too, since keystores are slow to
+    // decrypt on purpose
+    fn read(&self) -> pack_api::Result<Keys> {
+        match self {
+            KeySource::Pem(pem) => Keys::from_combined_pem_string(pem),
+            KeySource::Keystore {
+                keystore,
+                store_password,
+                alias,
+                key_password
+            } => Keys::from_keystore(
+                keystore,
+                store_password,
+                alias.as_deref(),
+                key_password.as_deref()
+            )
+        }
+    }
+}
+
+/// Compiles an unsigned APK.
+#[napi]
+pub fn compile_apk(input: PackageInput) -> AsyncTask<Compile> {
+    AsyncTask::new(Compile::new(input, Output::UnsignedApk))
+}
+
+/// Compiles an APK and signs it with `input.keys`.
+#[napi]
+pub fn compile_and_sign_apk(mut input: PackageInput) -> Result<AsyncTask<Compile>> {
+    let keys = KeySource::new(input.keys.take())?;
+    Ok(AsyncTask::new(Compile::new(input, Output::SignedApk(keys))))
+}
+
+/// Compiles an Android App Bundle and signs it with `input.keys`.
+#[napi]
+pub fn compile_and_sign_aab(mut input: PackageInput) -> Result<AsyncTask<Compile>> {
+    let keys = KeySource::new(input.keys.take())?;
+    Ok(AsyncTask::new(Compile::new(input, Output::SignedAab(keys))))
+}
@@ -0,0 +1,67 @@
+# Types for the `pack` module, which maturin ships in the wheel alongside the
+# compiled library.
+from typing import List, Optional, Tuple
+
+class PackError(Exception):
+    code: str
+    path: Optional[str]
+    line: Optional[int]
+    column: Optional[int]
+
+class Keys:
+    @staticmethod
+    def from_pem(pem: str) -> "Keys": ...
+    @staticmethod
+    def from_keystore(
+        keystore: bytes,
+        store_password: str,
+        alias: Optional[str] = None,
+        key_password: Optional[str] = None,
+    ) -> "Keys": ...
+    @staticmethod
+    def generate(
+        common_name: Optional[str] = None,
+        validity_days: Optional[int] = None,
+        rsa_bits: Optional[int] = None,
+    ) -> "Keys": ...
+    def to_pem(self) -> str: ...
+
+class Package:
+    def __init__(self, android_manifest: bytes) -> None: ...
+    def add_resource(self, subdirectory: str, name: str, contents: bytes) -> None: ...
+
+def compile_apk(package: Package) -> bytes: ...
+def compile_and_sign_apk(package: Package, keys: Keys) -> bytes: ...
+def compile_and_sign_aab(package: Package, keys: Keys) -> bytes: ...
+
+class Scheme:
+    status: str
+    reason: Optional[str]
+
+class Certificate:
+    subject: str
+    sha256_fingerprint: str
+
+class VerificationReport:
+    valid: bool
+    entry_count: int
+    v1: Scheme
+    v2: Scheme
+    v3: Scheme
+    signers: List[Certificate]
+    misaligned_entries: List[str]
+
+class Manifest:
+    package: Optional[str]
+    version_code: Optional[str]
+    version_name: Optional[str]
+    min_sdk_version: Optional[str]
+    target_sdk_version: Optional[str]
+
+class Inspection:
+    files: List[Tuple[str, int]]
+    manifest: Optional[Manifest]
+
+def verify(package: bytes) -> VerificationReport: ...
+def inspect(package: bytes) -> Inspection: ...
+def describe_certificate(pem: str) -> Certificate: ...
@@ -0,0 +1,67 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Failures are raised as pack.PackError, with what went wrong and where as
+// attributes, so that a pipeline can eg. point at the broken XML file rather
+// than matching on the message.
+use pyo3::{create_exception, exceptions::PyException, prelude::*};
+
+create_exception!(
+    pack,
+    PackError,
+    PyException,
+    "Building, signing or reading a package failed. `code` is the kind of \
+     failure, eg. `XmlParsingFailed`, and `path`, `line` and `column` say \
+     where, or are None if that isn't known."
+);
+
+// PyErr can't be converted from PackError directly, since neither type is
+// from this crate, so PackErrors go through this
+pub struct Failure {
+    pub code: String,
+    pub message: String,
+    pub path: Option<String>,
+    /// Starting from 1
+    pub line: Option<u64>,
+    /// Starting from 1
+    pub column: Option<u64>
+}
+
+impl From<pack_api::PackError> for Failure {
+    fn from(err: pack_api::PackError) -> Self {
+        let (line, column) = err.line_column().unzip();
+        Failure {
+            code: err.code().name().into(),
+            message: err.root().to_string(),
+            path: err.path().map(String::from),
+            line,
+            column
+        }
+    }
+}
+
+impl From<Failure> for PyErr {
+    fn from(failure: Failure) -> Self {
+        Python::with_gil(|py| {
+            let err = PackError::new_err(failure.message);
+            let value = err.value(py);
+            // Exceptions take any attributes, so these can't fail
+            let _ = value.setattr("code", failure.code);
+            let _ = value.setattr("path", failure.path);
+            let _ = value.setattr("line", failure.line);
+            let _ = value.setattr("column", failure.column);
+            err
+        })
+    }
+}
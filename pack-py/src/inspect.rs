@@ -0,0 +1,193 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Checks and describes existing APKs and AABs, the same as `pack-cli verify`
+// and `pack-cli inspect`, but as objects instead of text to parse.
+use pack_api::{
+    decode_resource_table, decode_xml, verify_package, BinaryValue, DecodedElement, DecodedTable,
+    SchemeStatus, SignerCertificate
+};
+use pyo3::prelude::*;
+
+use crate::errors::Failure;
+
+/// How a package is signed, from `verify()`.
+#[pyclass(module = "pack", frozen, get_all)]
+pub struct VerificationReport {
+    /// Whether at least one scheme verified, none were invalid, and the zip
+    /// is aligned
+    valid: bool,
+    entry_count: usize,
+    v1: Scheme,
+    v2: Scheme,
+    v3: Scheme,
+    /// Every distinct certificate across all the schemes
+    signers: Vec<Certificate>,
+    /// Uncompressed files that aren't 4-byte aligned
+    misaligned_entries: Vec<String>
+}
+
+#[pyclass(module = "pack", frozen, get_all)]
+#[derive(Clone)]
+pub struct Scheme {
+    /// `missing`, `verified`, `unsupported` or `invalid`
+    status: &'static str,
+    /// Why the scheme is unsupported or invalid
+    reason: Option<String>
+}
+
+#[pyclass(module = "pack", frozen, get_all)]
+#[derive(Clone)]
+pub struct Certificate {
+    /// eg. `CN=My Watch Faces`
+    subject: String,
+    /// eg. `AB:CD:...`, as shown in Play Console
+    sha256_fingerprint: String
+}
+
+/// What's in a package, from `inspect()`.
+#[pyclass(module = "pack", frozen, get_all)]
+pub struct Inspection {
+    /// Every file's path and uncompressed size
+    files: Vec<(String, usize)>,
+    /// Only for APKs, since bundles store their manifest differently
+    manifest: Option<Manifest>
+}
+
+#[pyclass(module = "pack", frozen, get_all)]
+#[derive(Clone, Default)]
+pub struct Manifest {
+    package: Option<String>,
+    version_code: Option<String>,
+    version_name: Option<String>,
+    min_sdk_version: Option<String>,
+    target_sdk_version: Option<String>
+}
+
+/// Checks an APK or AAB's signatures, alignment and zip structure.
+#[pyfunction]
+fn verify(py: Python, package: &[u8]) -> Result<VerificationReport, Failure> {
+    let report = py.allow_threads(|| verify_package(package))?;
+    Ok(VerificationReport {
+        valid: report.is_valid(),
+        entry_count: report.entry_count,
+        v1: scheme(report.v1),
+        v2: scheme(report.v2),
+        v3: scheme(report.v3),
+        signers: report.signers.iter().map(certificate).collect(),
+        misaligned_entries: report.misaligned_entries
+    })
+}
+
+/// Lists the files in an APK or AAB, and for APKs, what the manifest declares.
+#[pyfunction]
+fn inspect(package: &[u8]) -> Result<Inspection, Failure> {
+    let files = pack_zip::unzip(package)?;
+    let find = |path: &str| files.iter().find(|file| file.path == path);
+    let manifest = match find("AndroidManifest.xml") {
+        Some(manifest) => {
+            let table = match find("resources.arsc") {
+                Some(table) => decode_resource_table(&table.data)?,
+                None => DecodedTable::default()
+            };
+            Some(describe_manifest(&decode_xml(&manifest.data)?, &table))
+        }
+        None => None
+    };
+    Ok(Inspection {
+        files: files
+            .iter()
+            .map(|file| (file.path.clone(), file.data.len()))
+            .collect(),
+        manifest
+    })
+}
+
+/// Describes the certificate in a .pem, eg. to check it against a package's
+/// signers.
+#[pyfunction]
+fn describe_certificate(pem: &str) -> Result<Certificate, Failure> {
+    Ok(certificate(&SignerCertificate::from_pem(pem)?))
+}
+
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<VerificationReport>()?;
+    m.add_class::<Scheme>()?;
+    m.add_class::<Certificate>()?;
+    m.add_class::<Inspection>()?;
+    m.add_class::<Manifest>()?;
+    m.add_function(wrap_pyfunction!(verify, m)?)?;
+    m.add_function(wrap_pyfunction!(inspect, m)?)?;
+    m.add_function(wrap_pyfunction!(describe_certificate, m)?)
+}
+
+fn scheme(status: SchemeStatus) -> Scheme {
+    let (status, reason) = match status {
+        SchemeStatus::Missing => ("missing", None),
+        SchemeStatus::Verified => ("verified", None),
+        SchemeStatus::Unsupported(reason) => ("unsupported", Some(reason)),
+        SchemeStatus::Invalid(reason) => ("invalid", Some(reason))
+    };
+    Scheme { status, reason }
+}
+
+fn certificate(certificate: &SignerCertificate) -> Certificate {
+    Certificate {
+        subject: certificate.subject.clone(),
+        sha256_fingerprint: certificate
+            .sha256_fingerprint
+            .iter()
+            .map(|byte| format!("{byte:02X}"))
+            .collect::<Vec<_>>()
+            .join(":")
+    }
+}
+
+fn describe_manifest(manifest: &DecodedElement, table: &DecodedTable) -> Manifest {
+    let attribute = |element: &DecodedElement, name: &str| {
+        element
+            .attribute(name)
+            .and_then(|value| format_value(value, table))
+    };
+    let uses_sdk = manifest.children_named("uses-sdk").next();
+    Manifest {
+        package: attribute(manifest, "package"),
+        version_code: attribute(manifest, "versionCode"),
+        version_name: attribute(manifest, "versionName"),
+        min_sdk_version: uses_sdk.and_then(|uses_sdk| attribute(uses_sdk, "minSdkVersion")),
+        target_sdk_version: uses_sdk.and_then(|uses_sdk| attribute(uses_sdk, "targetSdkVersion"))
+    }
+}
+
+// References are resolved to their default value, eg. a versionName in
+// strings.xml
+fn format_value(value: &BinaryValue, table: &DecodedTable) -> Option<String> {
+    match value {
+        BinaryValue::String(string) => Some(string.clone()),
+        BinaryValue::Integer(integer) => Some(integer.to_string()),
+        BinaryValue::Boolean(boolean) => Some(boolean.to_string()),
+        BinaryValue::Reference(id) => {
+            let values = table.resolve(*id);
+            let (_, value) = values
+                .iter()
+                .find(|(config, _)| config.is_default())
+                .or(values.first())?;
+            match value {
+                BinaryValue::Reference(_) => None,
+                value => format_value(value, table)
+            }
+        }
+        _ => None
+    }
+}
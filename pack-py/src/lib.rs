@@ -0,0 +1,150 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Python bindings, imported as `pack`. Files are passed as bytes, and the GIL
+// is released while compiling so that several packages can be built at once
+// from a thread pool.
+use pyo3::{prelude::*, types::PyBytes};
+
+use errors::{Failure, PackError};
+
+mod errors;
+mod inspect;
+
+/// Signing keys. Google Play needs every update signed with the same keys.
+#[pyclass(module = "pack", frozen)]
+pub struct Keys {
+    keys: pack_api::Keys
+}
+
+#[pymethods]
+impl Keys {
+    /// Reads a .pem holding both a CERTIFICATE and a PRIVATE KEY.
+    #[staticmethod]
+    fn from_pem(pem: &str) -> Result<Self, Failure> {
+        Ok(Keys {
+            keys: pack_api::Keys::from_combined_pem_string(pem)?
+        })
+    }
+
+    /// Reads a JKS or PKCS #12 keystore. `alias` can be left out if the
+    /// keystore only holds one key, and `key_password` if it's the same as
+    /// `store_password`.
+    #[staticmethod]
+    #[pyo3(signature = (keystore, store_password, alias = None, key_password = None))]
+    fn from_keystore(
+        keystore: &[u8],
+        store_password: &str,
+        alias: Option<&str>,
+        key_password: Option<&str>
+    ) -> Result<Self, Failure> {
+        Ok(Keys {
+            keys: pack_api::Keys::from_keystore(keystore, store_password, alias, key_password)?
+        })
+    }
+
+    /// Generates new keys with a self-signed certificate.
+    #[staticmethod]
+    #[pyo3(signature = (common_name = None, validity_days = None, rsa_bits = None))]
+    fn generate(
+        py: Python,
+        common_name: Option<String>,
+        validity_days: Option<u32>,
+        rsa_bits: Option<usize>
+    ) -> Result<Self, Failure> {
+        let defaults = pack_api::KeyOptions::default();
+        let options = pack_api::KeyOptions {
+            common_name: common_name.unwrap_or(defaults.common_name),
+            validity_days,
+            rsa_bits: rsa_bits.unwrap_or(defaults.rsa_bits)
+        };
+        // Finding primes for big keys takes a while
+        let keys = py.allow_threads(|| pack_api::Keys::generate_with_options(&options))?;
+        Ok(Keys { keys })
+    }
+
+    /// The keys as a .pem, to keep for signing later updates.
+    fn to_pem(&self) -> Result<String, Failure> {
+        Ok(self.keys.to_combined_pem_string()?)
+    }
+}
+
+/// An app to compile, from its AndroidManifest.xml and the files under res/.
+#[pyclass(module = "pack")]
+pub struct Package {
+    package: pack_api::Package
+}
+
+#[pymethods]
+impl Package {
+    #[new]
+    fn new(android_manifest: &[u8]) -> Self {
+        Package {
+            package: pack_api::Package {
+                android_manifest: android_manifest.to_vec(),
+                resources: vec![],
+                modules: vec![]
+            }
+        }
+    }
+
+    /// Adds a file under res/, eg. `drawable-xxhdpi` and `preview.png`.
+    fn add_resource(&mut self, subdirectory: String, name: String, contents: &[u8]) {
+        self.package.resources.push(pack_api::FileResource::new(
+            subdirectory,
+            name,
+            contents.to_vec()
+        ));
+    }
+}
+
+/// Compiles an unsigned APK.
+#[pyfunction]
+fn compile_apk<'py>(py: Python<'py>, package: &Package) -> Result<Bound<'py, PyBytes>, Failure> {
+    let apk = py.allow_threads(|| pack_api::compile_apk(&package.package))?;
+    Ok(PyBytes::new(py, &apk))
+}
+
+/// Compiles an APK and signs it with `keys`.
+#[pyfunction]
+fn compile_and_sign_apk<'py>(
+    py: Python<'py>,
+    package: &Package,
+    keys: &Keys
+) -> Result<Bound<'py, PyBytes>, Failure> {
+    let apk = py.allow_threads(|| pack_api::compile_and_sign_apk(&package.package, &keys.keys))?;
+    Ok(PyBytes::new(py, &apk))
+}
+
+/// Compiles an Android App Bundle and signs it with `keys`.
+#[pyfunction]
+fn compile_and_sign_aab<'py>(
+    py: Python<'py>,
+    package: &Package,
+    keys: &Keys
+) -> Result<Bound<'py, PyBytes>, Failure> {
+    let aab = py.allow_threads(|| pack_api::compile_and_sign_aab(&package.package, &keys.keys))?;
+    Ok(PyBytes::new(py, &aab))
+}
+
+#[pymodule]
+fn pack(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add("PackError", m.py().get_type::<PackError>())?;
+    m.add_class::<Keys>()?;
+    m.add_class::<Package>()?;
+    m.add_function(wrap_pyfunction!(compile_apk, m)?)?;
+    m.add_function(wrap_pyfunction!(compile_and_sign_apk, m)?)?;
+    m.add_function(wrap_pyfunction!(compile_and_sign_aab, m)?)?;
+    inspect::register(m)
+}
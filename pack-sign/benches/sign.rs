@@ -0,0 +1,63 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Benchmarks for v2 and v3 signing, which hashes every byte of the package.
+// Generating the keys needs the `cert-gen` feature, so run with
+// `cargo bench -p pack-sign --features cert-gen`.
+use std::io::Cursor;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use pack_sign::{crypto_keys::Keys, sign_apk_buffer};
+use pack_zip::{zip_apk, File};
+
+// Signing hashes the package in 1 MB chunks, so it needs to be several
+// megabytes after zipping. Noise doesn't deflate, and a fixed seed keeps every
+// run signing the same bytes.
+fn noise(len: usize, mut seed: u64) -> Vec<u8> {
+    (0..len)
+        .map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed as u8
+        })
+        .collect()
+}
+
+fn sign(c: &mut Criterion) {
+    let keys = Keys::generate_random_testing_keys().unwrap();
+    let files: Vec<File> = (0..16)
+        .map(|index| File {
+            path: format!("res/raw/data_{index}.bin"),
+            data: noise(512 * 1024, index + 1)
+        })
+        .collect();
+    let mut unsigned = Cursor::new(vec![]);
+    zip_apk(&files, &mut unsigned).unwrap();
+    let unsigned = unsigned.into_inner();
+
+    let mut group = c.benchmark_group("sign_apk_buffer");
+    group.throughput(Throughput::Bytes(unsigned.len() as u64));
+    group.bench_function("v2 and v3", |b| {
+        b.iter_batched(
+            || unsigned.clone(),
+            |mut apk| sign_apk_buffer(&mut apk, &keys).unwrap(),
+            BatchSize::LargeInput
+        )
+    });
+    group.finish();
+}
+
+criterion_group!(benches, sign);
+criterion_main!(benches);
@@ -0,0 +1,120 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Writes self-signed X.509 certificates for generated keys. Android only looks
+// at a signing certificate's public key, so a minimal one with just a common
+// name and a validity period is enough, and writing it by hand avoids pulling
+// in C and assembly crypto code that doesn't build for WASM.
+use pack_common::*;
+use rand::prelude::*;
+use rsa::{pkcs8::EncodePublicKey, Pkcs1v15Sign, RsaPrivateKey, RsaPublicKey};
+use sha2::{Digest, Sha256};
+use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time};
+
+use crate::der::{
+    encode, encode_all, encode_u32, BIT_STRING, CONTEXT_0_CONSTRUCTED, GENERALIZED_TIME, INTEGER,
+    NULL, OID, SEQUENCE, SET, UTC_TIME, UTF8_STRING
+};
+
+// 1.2.840.113549.1.1.11
+const OID_SHA256_WITH_RSA: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x01, 0x0B];
+// 2.5.4.3
+const OID_COMMON_NAME: &[u8] = &[0x55, 0x04, 0x03];
+// X.509 v3, which is stored as 2
+const VERSION_3: u32 = 2;
+
+/// Signs a certificate for `public_key` with its own private key.
+/// `validity` is when the certificate is valid from and until, or if `None`,
+/// from 1975 until 4096.
+pub(crate) fn self_signed_certificate(
+    private_key: &RsaPrivateKey,
+    public_key: &RsaPublicKey,
+    common_name: &str,
+    validity: Option<(OffsetDateTime, OffsetDateTime)>
+) -> Result<Vec<u8>> {
+    let (not_before, not_after) =
+        validity.unwrap_or((date(1975, Month::January, 1), date(4096, Month::January, 1)));
+    // Serial numbers are positive and should be unique per issuer, so they're
+    // random with the top bit clear, and no leading zero byte
+    let mut serial = [0u8; 16];
+    thread_rng().fill(&mut serial);
+    serial[0] = (serial[0] & 0x7F).max(1);
+
+    let algorithm = encode_all(
+        SEQUENCE,
+        &[encode(OID, OID_SHA256_WITH_RSA), encode(NULL, &[])]
+    );
+    // The same name is both the issuer and the subject
+    let name = encode_all(
+        SEQUENCE,
+        &[encode_all(
+            SET,
+            &[encode_all(
+                SEQUENCE,
+                &[
+                    encode(OID, OID_COMMON_NAME),
+                    encode(UTF8_STRING, common_name.as_bytes())
+                ]
+            )]
+        )]
+    );
+    let tbs_certificate = encode_all(
+        SEQUENCE,
+        &[
+            encode(CONTEXT_0_CONSTRUCTED, &encode_u32(VERSION_3)),
+            encode(INTEGER, &serial),
+            algorithm.clone(),
+            name.clone(),
+            encode_all(SEQUENCE, &[encode_time(not_before), encode_time(not_after)]),
+            name,
+            public_key.to_public_key_der()?.as_ref().to_vec()
+        ]
+    );
+
+    let signature = private_key.sign(
+        Pkcs1v15Sign::new::<Sha256>(),
+        &Sha256::digest(&tbs_certificate)
+    )?;
+    // BIT STRINGs start with the number of unused bits in the last byte
+    let signature = [&[0][..], &signature].concat();
+    Ok(encode_all(
+        SEQUENCE,
+        &[tbs_certificate, algorithm, encode(BIT_STRING, &signature)]
+    ))
+}
+
+// Times until 2049 are UTCTime, with a 2 digit year, and later ones are
+// GeneralizedTime, as RFC 5280 requires
+fn encode_time(time: OffsetDateTime) -> Vec<u8> {
+    let time = time.to_offset(time::UtcOffset::UTC);
+    let (tag, year) = match time.year() {
+        1950..=2049 => (UTC_TIME, format!("{:02}", time.year() % 100)),
+        year => (GENERALIZED_TIME, format!("{year:04}"))
+    };
+    let formatted = format!(
+        "{year}{:02}{:02}{:02}{:02}{:02}Z",
+        time.month() as u8,
+        time.day(),
+        time.hour(),
+        time.minute(),
+        time.second()
+    );
+    encode(tag, formatted.as_bytes())
+}
+
+fn date(year: i32, month: Month, day: u8) -> OffsetDateTime {
+    // These are all valid dates
+    let date = Date::from_calendar_date(year, month, day).unwrap();
+    PrimitiveDateTime::new(date, Time::MIDNIGHT).assume_utc()
+}
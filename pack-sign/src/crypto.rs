@@ -12,17 +12,31 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::crypto_keys::Keys;
-use deku::DekuContainerWrite;
+use crate::{crypto_keys::Keys, hasher::DigestAlgorithm};
 use pack_common::*;
-use rsa::Pkcs1v15Sign;
-use sha2::{Digest, Sha256};
+use rsa::{traits::PublicKeyParts, Pkcs1v15Sign};
+use sha2::{Digest, Sha256, Sha512};
 
-pub fn get_signature_for_signed_data<T: DekuContainerWrite>(
-    signed_data: &T,
-    keys: &Keys
+pub fn get_signature_for_signed_data(
+    signed_data: &[u8],
+    keys: &Keys,
+    digest_algorithm: DigestAlgorithm
 ) -> Result<Vec<u8>> {
-    let digest = Sha256::digest(signed_data.to_bytes()?);
-    let padding = Pkcs1v15Sign::new::<Sha256>();
+    let (digest, padding) = match digest_algorithm {
+        DigestAlgorithm::Sha256 => (
+            Sha256::digest(signed_data).to_vec(),
+            Pkcs1v15Sign::new::<Sha256>()
+        ),
+        DigestAlgorithm::Sha512 => (
+            Sha512::digest(signed_data).to_vec(),
+            Pkcs1v15Sign::new::<Sha512>()
+        )
+    };
     Ok(keys.private_key.sign(padding, &digest)?)
 }
+
+// PKCS #1 v1.5 signatures are always as long as the key's modulus, whatever
+// was signed
+pub fn signature_length(keys: &Keys) -> usize {
+    keys.private_key.size()
+}
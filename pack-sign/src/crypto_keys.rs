@@ -14,12 +14,40 @@
 
 use std::collections::HashMap;
 
+#[cfg(feature = "cert-gen")]
+use crate::certificate::self_signed_certificate;
 use pack_common::*;
 use rsa::{
-    pkcs8::{DecodePrivateKey, EncodePublicKey},
+    pkcs8::{DecodePrivateKey, EncodePrivateKey, EncodePublicKey, LineEnding},
     RsaPrivateKey, RsaPublicKey
 };
 
+/// What [Keys::generate_with_options] generates.
+#[cfg(feature = "cert-gen")]
+#[derive(Debug, Clone)]
+pub struct KeyOptions {
+    /// The certificate's common name, eg. `My Watch Faces`.
+    pub common_name: String,
+    /// How many days the certificate is valid for, starting now. Google Play
+    /// requires certificates to be valid until at least 2033, and recommends
+    /// 25 years. `None` makes it valid from 1975 until 4096.
+    pub validity_days: Option<u32>,
+    /// The RSA key size. Android accepts 2048 to 16384 bits.
+    pub rsa_bits: usize
+}
+
+#[cfg(feature = "cert-gen")]
+impl Default for KeyOptions {
+    fn default() -> Self {
+        KeyOptions {
+            // We sign all testing certificates as our crate name
+            common_name: env!("CARGO_PKG_NAME").into(),
+            validity_days: None,
+            rsa_bits: 2048
+        }
+    }
+}
+
 /// Holds the certificate and RSA Private Key used for signing.
 pub struct Keys {
     /// X.509 Signing Certificate in ASN.1 DER form
@@ -58,9 +86,8 @@ impl Keys {
     /// Randomly generates RSA signing keys and an accompanying certificate.
     ///
     /// This API is only enabled when the optional "cert-gen" feature is enabled
-    /// for pack-sign (it's on by default). It introduces a non-trivial amount of
-    /// extra dependencies and includes ASM/C code. For that reason is only enabled
-    /// on the desktop CLI and not on the web by default.
+    /// for pack-sign. It's pure Rust, so it works on the web too, but it's off by
+    /// default since most projects sign with keys they already have.
     ///
     /// It is also very slow. ~150ms. Which on an M1 Pro is 10x pack-cli's entire
     /// run time without it. For that reason, it's recommended that you generate
@@ -91,36 +118,60 @@ impl Keys {
     /// installed.
     #[cfg(feature = "cert-gen")]
     pub fn generate_random_testing_keys() -> Result<Keys> {
+        #[cfg(feature = "tracing")]
+        tracing::warn!("Randomly generating a placeholder signing key. This is slow! It's recommended to generate your own keys first and pass them in.");
+        #[cfg(not(feature = "tracing"))]
+        {
+            eprintln!("Warning: Randomly generating a placeholder signing key. This is slow!");
+            eprintln!("    It's recommended to generate your own keys first and pass them in.");
+        }
+        Self::generate_with_options(&KeyOptions::default())
+    }
+
+    /// Generates RSA signing keys and a self-signed certificate that are good
+    /// enough to publish with, unlike [generate_random_testing_keys](Keys::generate_random_testing_keys).
+    /// The keys need to be saved and kept safe, eg. with
+    /// [to_combined_pem_string](Keys::to_combined_pem_string).
+    ///
+    /// Like [generate_random_testing_keys](Keys::generate_random_testing_keys),
+    /// this needs the "cert-gen" feature, and larger keys are *much* slower to
+    /// generate.
+    #[cfg(feature = "cert-gen")]
+    pub fn generate_with_options(options: &KeyOptions) -> Result<Keys> {
         // These dependencies only exist when compiled with cert-gen
         use rand::prelude::*;
-        use rcgen::{CertificateParams, DistinguishedName, DnType, KeyPair};
-        use rsa::pkcs8::{EncodePrivateKey, LineEnding};
+        use time::{Duration, OffsetDateTime};
 
-        eprintln!("Warning: Randomly generating a placeholder signing key. This is slow!");
-        eprintln!("    It's recommended to generate your own keys first and pass them in.");
-
-        // Randomly generate an RSA Private Key, derive its Public Key,
-        // and prepare it for passing over to the rcgen library.
-        let private_key = RsaPrivateKey::new(&mut thread_rng(), 2048)?;
+        // Randomly generate an RSA Private Key and derive its Public Key
+        let private_key = RsaPrivateKey::new(&mut thread_rng(), options.rsa_bits)?;
         let public_key = RsaPublicKey::from(private_key.clone());
-        let private_key_pem = private_key.to_pkcs8_pem(LineEnding::LF)?.to_string();
 
         // Self-sign an X.509 certificate using the random keys
-        let key_pair = KeyPair::from_pem(&private_key_pem).unwrap();
-        // We sign all testing certificates as our crate name
-        let mut distinguished_name = DistinguishedName::new();
-        distinguished_name.push(DnType::CommonName, env!("CARGO_PKG_NAME"));
-        let mut cert_params = CertificateParams::new(vec![]).unwrap();
-        cert_params.distinguished_name = distinguished_name;
-        let cert = cert_params.self_signed(&key_pair).unwrap();
+        let validity = options.validity_days.map(|validity_days| {
+            let not_before = OffsetDateTime::now_utc();
+            (
+                not_before,
+                not_before + Duration::days(validity_days.into())
+            )
+        });
+        let certificate =
+            self_signed_certificate(&private_key, &public_key, &options.common_name, validity)?;
 
         Ok(Self {
-            certificate: cert.der().to_vec(),
+            certificate,
             private_key,
             public_key
         })
     }
 
+    /// Encodes the keys as a `.pem` with a `BEGIN CERTIFICATE` and a `BEGIN
+    /// PRIVATE KEY` section, which [Keys::from_combined_pem_string] can read.
+    pub fn to_combined_pem_string(&self) -> Result<String> {
+        let certificate = pem::encode(&pem::Pem::new("CERTIFICATE", self.certificate.clone()));
+        let private_key = self.private_key.to_pkcs8_pem(LineEnding::LF)?;
+        Ok(format!("{certificate}{}", private_key.as_str()))
+    }
+
     /// Returns the RSA Private Key encoded in ASN.1 DER format.
     pub fn pub_key_as_der(&self) -> Result<Vec<u8>> {
         Ok(self.public_key.to_public_key_der()?.as_ref().to_vec())
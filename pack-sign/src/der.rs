@@ -0,0 +1,158 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Just enough DER to walk through keystores, certificates and PKCS #7
+// signatures, which are mostly nested sequences of OIDs and octet strings, and
+// to write new keystores and certificates.
+use pack_common::*;
+
+pub(crate) const INTEGER: u8 = 0x02;
+#[cfg(feature = "cert-gen")]
+pub(crate) const BIT_STRING: u8 = 0x03;
+pub(crate) const OCTET_STRING: u8 = 0x04;
+#[cfg(any(feature = "keystore", feature = "cert-gen"))]
+pub(crate) const NULL: u8 = 0x05;
+pub(crate) const OID: u8 = 0x06;
+#[cfg(feature = "cert-gen")]
+pub(crate) const UTF8_STRING: u8 = 0x0C;
+#[cfg(feature = "cert-gen")]
+pub(crate) const UTC_TIME: u8 = 0x17;
+#[cfg(feature = "cert-gen")]
+pub(crate) const GENERALIZED_TIME: u8 = 0x18;
+pub(crate) const BMP_STRING: u8 = 0x1E;
+pub(crate) const SEQUENCE: u8 = 0x30;
+pub(crate) const SET: u8 = 0x31;
+/// `[0]`, as used for implicitly tagged primitive fields.
+#[cfg(feature = "keystore")]
+pub(crate) const CONTEXT_0: u8 = 0x80;
+/// `[0]`, as used for explicitly tagged fields.
+pub(crate) const CONTEXT_0_CONSTRUCTED: u8 = 0xA0;
+
+/// Reads DER elements one after another from a buffer.
+#[derive(Clone, Copy)]
+pub(crate) struct Der<'a> {
+    data: &'a [u8]
+}
+
+impl<'a> Der<'a> {
+    pub fn new(data: &'a [u8]) -> Der<'a> {
+        Der { data }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn peek_tag(&self) -> Option<u8> {
+        self.data.first().copied()
+    }
+
+    /// Reads the next element, returning its tag, contents and the complete
+    /// encoding including the header.
+    pub fn read_any(&mut self) -> Result<(u8, &'a [u8], &'a [u8])> {
+        let invalid = || PackError::Asn1ParsingFailed("Truncated or malformed element.".into());
+        let (&tag, rest) = self.data.split_first().ok_or_else(invalid)?;
+        let (&first_len_byte, rest) = rest.split_first().ok_or_else(invalid)?;
+        let (len, rest) = match first_len_byte {
+            len @ 0..=0x7F => (len as usize, rest),
+            // BER's indefinite lengths aren't allowed in DER
+            0x80 => return Err(invalid()),
+            long_form => {
+                let len_bytes = (long_form & 0x7F) as usize;
+                if len_bytes > 4 || rest.len() < len_bytes {
+                    return Err(invalid());
+                }
+                let len = rest[..len_bytes]
+                    .iter()
+                    .fold(0usize, |len, byte| (len << 8) | *byte as usize);
+                (len, &rest[len_bytes..])
+            }
+        };
+        if rest.len() < len {
+            return Err(invalid());
+        }
+        let header_len = self.data.len() - rest.len();
+        let encoded = &self.data[..header_len + len];
+        self.data = &rest[len..];
+        Ok((tag, &rest[..len], encoded))
+    }
+
+    /// Reads the contents of the next element, which must have the given tag.
+    pub fn read(&mut self, tag: u8) -> Result<&'a [u8]> {
+        match self.read_any()? {
+            (actual, contents, _) if actual == tag => Ok(contents),
+            (actual, ..) => Err(PackError::Asn1ParsingFailed(format!(
+                "Expected ASN.1 tag {tag:#04x} but found {actual:#04x}."
+            )))
+        }
+    }
+
+    /// Reads a SEQUENCE and returns a reader over its elements.
+    pub fn sequence(&mut self) -> Result<Der<'a>> {
+        self.read(SEQUENCE).map(Der::new)
+    }
+
+    /// Reads a non-negative INTEGER that fits in a u32, such as an iteration count.
+    pub fn read_u32(&mut self) -> Result<u32> {
+        let bytes = self.read(INTEGER)?;
+        if bytes.is_empty() || bytes.len() > 5 || (bytes.len() == 5 && bytes[0] != 0) {
+            return Err(PackError::Asn1ParsingFailed("Integer out of range.".into()));
+        }
+        Ok(bytes
+            .iter()
+            .fold(0u32, |value, byte| (value << 8) | *byte as u32))
+    }
+}
+
+/// Encodes an element with the given tag and contents.
+#[cfg(any(feature = "keystore", feature = "cert-gen"))]
+pub(crate) fn encode(tag: u8, contents: &[u8]) -> Vec<u8> {
+    let mut encoded = vec![tag];
+    match contents.len() {
+        len @ 0..=0x7F => encoded.push(len as u8),
+        len => {
+            let len_bytes: Vec<u8> = len
+                .to_be_bytes()
+                .into_iter()
+                .skip_while(|byte| *byte == 0)
+                .collect();
+            encoded.push(0x80 | len_bytes.len() as u8);
+            encoded.extend(len_bytes);
+        }
+    }
+    encoded.extend_from_slice(contents);
+    encoded
+}
+
+/// Encodes a constructed element, such as a SEQUENCE, from elements that are
+/// already encoded.
+#[cfg(any(feature = "keystore", feature = "cert-gen"))]
+pub(crate) fn encode_all(tag: u8, elements: &[Vec<u8>]) -> Vec<u8> {
+    encode(tag, &elements.concat())
+}
+
+/// Encodes a non-negative INTEGER.
+#[cfg(any(feature = "keystore", feature = "cert-gen"))]
+pub(crate) fn encode_u32(value: u32) -> Vec<u8> {
+    let mut bytes: Vec<u8> = value
+        .to_be_bytes()
+        .into_iter()
+        .skip_while(|byte| *byte == 0)
+        .collect();
+    // A leading 1 bit would make it negative
+    if bytes.first().is_none_or(|byte| byte & 0x80 != 0) {
+        bytes.insert(0, 0);
+    }
+    encode(INTEGER, &bytes)
+}
@@ -14,40 +14,126 @@
 
 use std::io::{Cursor, Seek, SeekFrom, Write};
 
-use sha2::{Digest, Sha256};
+use sha2::{Digest, Sha256, Sha512};
 
-use crate::zip_parser::ZipOffsets;
+use crate::{signing_types::SignatureAlgorithmId, zip_parser::ZipOffsets};
 use pack_common::*;
 
-pub type Sha256Hash = [u8; 32];
-
 pub const BYTES_IN_1MB: u32 = 1024 * 1024;
 pub const FIRST_LEVEL_CHUNK_MAGIC: &[u8] = &[0xa5];
 pub const SECOND_LEVEL_CHUNK_MAGIC: &[u8] = &[0x5a];
 
+/// The hash function that a package's contents are digested with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DigestAlgorithm {
+    #[default]
+    Sha256,
+    Sha512
+}
+
+impl DigestAlgorithm {
+    /// The length of a digest in bytes.
+    pub fn output_size(self) -> usize {
+        match self {
+            DigestAlgorithm::Sha256 => 32,
+            DigestAlgorithm::Sha512 => 64
+        }
+    }
+
+    // The RSA PKCS #1 v1.5 signature that goes with digests from this
+    pub(crate) fn signature_algorithm(self) -> SignatureAlgorithmId {
+        match self {
+            DigestAlgorithm::Sha256 => SignatureAlgorithmId::RsaSsaPkcs1v1_5WithSha2_256,
+            DigestAlgorithm::Sha512 => SignatureAlgorithmId::RsaSsaPkcs1v1_5WithSha2_512
+        }
+    }
+}
+
+/// How a package's contents are split into chunks and digested for its
+/// signatures. The defaults, 1MB chunks and SHA-256, are what PACK signs
+/// with. Android only accepts 1MB chunks in v2 and v3 signatures, so other
+/// sizes are for schemes that chunk differently, eg. v4's Merkle trees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SigningConfig {
+    pub(crate) chunk_size: u32,
+    pub(crate) digest_algorithm: DigestAlgorithm
+}
+
+impl SigningConfig {
+    /// Fails with [PackError::SigningConfigInvalid] if `chunk_size` is 0,
+    /// since nothing could be split into chunks that small.
+    pub fn new(chunk_size: u32, digest_algorithm: DigestAlgorithm) -> Result<SigningConfig> {
+        if chunk_size == 0 {
+            return Err(PackError::SigningConfigInvalid(
+                "The chunk size must be at least 1 byte.".into()
+            ));
+        }
+        Ok(SigningConfig {
+            chunk_size,
+            digest_algorithm
+        })
+    }
+
+    /// How many bytes of the package each first-level digest covers.
+    pub fn chunk_size(&self) -> u32 {
+        self.chunk_size
+    }
+
+    pub fn digest_algorithm(&self) -> DigestAlgorithm {
+        self.digest_algorithm
+    }
+}
+
+impl Default for SigningConfig {
+    fn default() -> Self {
+        SigningConfig {
+            chunk_size: BYTES_IN_1MB,
+            digest_algorithm: DigestAlgorithm::Sha256
+        }
+    }
+}
+
 pub fn compute_top_level_hash(
     apk_buf: &mut [u8],
     offsets: &ZipOffsets,
-    signing_block_length: usize
-) -> Result<Sha256Hash> {
-    let first_level_hashes = compute_first_level_hashes(apk_buf, offsets, signing_block_length)?;
+    signing_block_length: usize,
+    config: &SigningConfig
+) -> Result<Vec<u8>> {
+    match config.digest_algorithm {
+        DigestAlgorithm::Sha256 => {
+            top_level_hash::<Sha256>(apk_buf, offsets, signing_block_length, config.chunk_size)
+        }
+        DigestAlgorithm::Sha512 => {
+            top_level_hash::<Sha512>(apk_buf, offsets, signing_block_length, config.chunk_size)
+        }
+    }
+}
+
+fn top_level_hash<D: Digest>(
+    apk_buf: &mut [u8],
+    offsets: &ZipOffsets,
+    signing_block_length: usize,
+    chunk_size: u32
+) -> Result<Vec<u8>> {
+    let first_level_hashes =
+        compute_first_level_hashes::<D>(apk_buf, offsets, signing_block_length, chunk_size)?;
 
-    let mut hasher = Sha256::new();
+    let mut hasher = D::new();
     hasher.update(SECOND_LEVEL_CHUNK_MAGIC);
     hasher.update((first_level_hashes.len() as u32).to_le_bytes());
     for hash in &first_level_hashes {
         hasher.update(hash);
     }
-    let second_level_hash: Sha256Hash = hasher.finalize_reset().into();
 
-    Ok(second_level_hash)
+    Ok(hasher.finalize().to_vec())
 }
 
-fn compute_first_level_hashes(
+fn compute_first_level_hashes<D: Digest>(
     apk_buf: &mut [u8],
     offsets: &ZipOffsets,
-    signing_block_length: usize
-) -> Result<Vec<Sha256Hash>> {
+    signing_block_length: usize,
+    chunk_size: u32
+) -> Result<Vec<Vec<u8>>> {
     // The Android Developer documentation calls these chunks 1, 3 and 4 because the
     //   APK Signing Block is chunk 2.
     let chunk1_range = 0..offsets.cd_start;
@@ -58,18 +144,18 @@ fn compute_first_level_hashes(
 
     // Chunk 1: APK contents before the central directory
     let chunk1 = &apk_buf[chunk1_range];
-    first_level_hashes.extend(hash_chunk(chunk1));
+    first_level_hashes.extend(hash_chunk::<D>(chunk1, chunk_size));
 
     // Chunk 3: Central directories
     let chunk3 = &apk_buf[chunk3_range];
-    first_level_hashes.extend(hash_chunk(chunk3));
+    first_level_hashes.extend(hash_chunk::<D>(chunk3, chunk_size));
 
     // Chunk 4 is more complex because we need to modify the EOCD offset to account
     //   for the APK Signing Block, BUT WE HASH BEFORE WRITING THE UPDATED OFFSET!
     //   From my reading of the docs, this is the opposite to what they say. Perhaps
     //   the wording is unclear or the doc needs to be updated.
     let chunk4 = &apk_buf[chunk4_range.clone()];
-    first_level_hashes.extend(hash_chunk(chunk4));
+    first_level_hashes.extend(hash_chunk::<D>(chunk4, chunk_size));
 
     let new_cd_start = offsets.cd_start + signing_block_length;
     let mut cursor = Cursor::new(&mut apk_buf[chunk4_range]);
@@ -79,20 +165,19 @@ fn compute_first_level_hashes(
     Ok(first_level_hashes)
 }
 
-fn hash_chunk(chunk: &[u8]) -> Vec<Sha256Hash> {
-    // TODO: Is it more performant or something to share this as a singleton?
-    let mut hasher = Sha256::new();
+fn hash_chunk<D: Digest>(chunk: &[u8], chunk_size: u32) -> Vec<Vec<u8>> {
     let mut chunk_hashes = vec![];
     let mut pos = 0;
 
     while pos < chunk.len() {
-        // Each chunk is 1MB OR whatever's left in the buffer
-        let end = (pos + BYTES_IN_1MB as usize).min(chunk.len());
-        let chunk_size = end - pos;
+        let mut hasher = D::new();
+        // Each chunk is chunk_size (1MB by default) OR whatever's left in the buffer
+        let end = (pos + chunk_size as usize).min(chunk.len());
+        let size = end - pos;
         hasher.update(FIRST_LEVEL_CHUNK_MAGIC);
-        hasher.update((chunk_size as u32).to_le_bytes());
+        hasher.update((size as u32).to_le_bytes());
         hasher.update(&chunk[pos..end]);
-        chunk_hashes.push(hasher.finalize_reset().into());
+        chunk_hashes.push(hasher.finalize().to_vec());
         pos = end;
     }
 
@@ -0,0 +1,115 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Android Studio and keytool keep signing keys in password-protected Java
+// keystores rather than .pem files. Both of the formats they use are read
+// here, so that existing upload keys work without converting them first.
+use pack_common::*;
+use rsa::{
+    pkcs8::{DecodePrivateKey, EncodePrivateKey},
+    RsaPrivateKey, RsaPublicKey
+};
+
+use crate::crypto_keys::Keys;
+
+mod jks;
+mod pkcs12;
+
+// A private key and its certificate, before the key has been decrypted
+struct KeyEntry {
+    alias: Option<String>,
+    /// DER-encoded `EncryptedPrivateKeyInfo` if `key_is_encrypted`, or
+    /// `PrivateKeyInfo` otherwise.
+    key: Vec<u8>,
+    key_is_encrypted: bool,
+    certificate: Option<Vec<u8>>
+}
+
+impl Keys {
+    /// Reads signing keys from a Java keystore, in either the JKS format or
+    /// PKCS #12 (`.p12` and `.pfx` files, and what keytool creates since Java 9).
+    ///
+    /// `alias` picks which key to use, and can be left out if the keystore only
+    /// holds one. `key_password` defaults to `store_password`, since they're
+    /// usually the same.
+    pub fn from_keystore(
+        keystore: &[u8],
+        store_password: &str,
+        alias: Option<&str>,
+        key_password: Option<&str>
+    ) -> Result<Keys> {
+        let is_jks = keystore.starts_with(jks::MAGIC);
+        let entries = if is_jks {
+            jks::read_entries(keystore, store_password)?
+        } else {
+            pkcs12::read_entries(keystore, store_password)?
+        };
+
+        let entry = match alias {
+            // JKS lowercases aliases, so matching is case insensitive
+            Some(alias) => entries
+                .iter()
+                .find(|entry| {
+                    entry
+                        .alias
+                        .as_ref()
+                        .is_some_and(|name| name.eq_ignore_ascii_case(alias))
+                })
+                .ok_or(PackError::KeystoreKeyNotFound(alias.into()))?,
+            None => match &entries[..] {
+                [entry] => entry,
+                [] => return Err(PackError::KeystoreKeyNotFound("".into())),
+                _ => {
+                    let aliases: Vec<&str> = entries
+                        .iter()
+                        .filter_map(|entry| entry.alias.as_deref())
+                        .collect();
+                    return Err(PackError::KeystoreInvalid(format!(
+                        "The keystore holds several keys ({}), so one has to be chosen by its alias.",
+                        aliases.join(", ")
+                    )));
+                }
+            }
+        };
+
+        let key_password = key_password.unwrap_or(store_password);
+        let private_key_info = match (entry.key_is_encrypted, is_jks) {
+            (false, _) => entry.key.clone(),
+            (true, true) => jks::decrypt_key(&entry.key, key_password)?,
+            (true, false) => pkcs12::decrypt_key(&entry.key, key_password)?
+        };
+        let private_key = RsaPrivateKey::from_pkcs8_der(&private_key_info)?;
+        let certificate = entry.certificate.clone().ok_or(PackError::KeystoreInvalid(
+            "The key doesn't have a certificate.".into()
+        ))?;
+
+        Ok(Keys {
+            public_key: RsaPublicKey::from(private_key.clone()),
+            private_key,
+            certificate
+        })
+    }
+
+    /// Encodes the keys as a password-protected PKCS #12 keystore, which
+    /// keytool, apksigner and Android Studio can all read.
+    pub fn to_pkcs12(&self, alias: &str, password: &str) -> Result<Vec<u8>> {
+        let private_key_info = self.private_key.to_pkcs8_der()?;
+        pkcs12::write_keystore(
+            private_key_info.as_bytes(),
+            &self.certificate,
+            alias,
+            password
+        )
+    }
+}
@@ -0,0 +1,175 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// The original Java keystore format. It's a simple big-endian list of entries,
+// with private keys obscured by Sun's own SHA-1 based "key protector", and a
+// SHA-1 digest at the end to check the store's password.
+use pack_common::*;
+use sha1::{Digest, Sha1};
+
+use super::KeyEntry;
+use crate::der::{Der, OCTET_STRING};
+
+pub(super) const MAGIC: &[u8] = &[0xFE, 0xED, 0xFE, 0xED];
+const PRIVATE_KEY_ENTRY: u32 = 1;
+const TRUSTED_CERT_ENTRY: u32 = 2;
+const SALT_LEN: usize = 20;
+
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        let bytes =
+            self.data
+                .get(self.offset..self.offset + len)
+                .ok_or(PackError::KeystoreInvalid(
+                    "The JKS file is truncated.".into()
+                ))?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        Ok(u16::from_be_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_be_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    // Java's DataOutputStream.writeUTF(), which is close enough to UTF-8 for aliases
+    fn utf(&mut self) -> Result<String> {
+        let len = self.u16()? as usize;
+        Ok(String::from_utf8_lossy(self.bytes(len)?).into_owned())
+    }
+
+    fn blob(&mut self) -> Result<&'a [u8]> {
+        let len = self.u32()? as usize;
+        self.bytes(len)
+    }
+}
+
+/// Reads every private key entry, checking the store password on the way.
+pub(super) fn read_entries(keystore: &[u8], store_password: &str) -> Result<Vec<KeyEntry>> {
+    let Some(content_len) = keystore.len().checked_sub(Sha1::output_size()) else {
+        return Err(PackError::KeystoreInvalid(
+            "The JKS file is truncated.".into()
+        ));
+    };
+    let (content, digest) = keystore.split_at(content_len);
+    let expected = Sha1::new()
+        .chain_update(password_bytes(store_password))
+        .chain_update(b"Mighty Aphrodite")
+        .chain_update(content)
+        .finalize();
+    if expected[..] != *digest {
+        return Err(PackError::KeystorePasswordIncorrect);
+    }
+
+    let mut reader = Reader {
+        data: content,
+        offset: MAGIC.len()
+    };
+    let version = reader.u32()?;
+    if version != 1 && version != 2 {
+        return Err(PackError::KeystoreInvalid(format!(
+            "Unsupported JKS version {version}."
+        )));
+    }
+    let entry_count = reader.u32()?;
+    let mut entries = vec![];
+    for _ in 0..entry_count {
+        let entry_type = reader.u32()?;
+        let alias = reader.utf()?;
+        // Creation time
+        reader.bytes(8)?;
+        match entry_type {
+            PRIVATE_KEY_ENTRY => {
+                let encrypted_key = reader.blob()?.to_vec();
+                let mut chain = vec![];
+                for _ in 0..reader.u32()? {
+                    if version == 2 {
+                        // Certificate type, always X.509
+                        reader.utf()?;
+                    }
+                    chain.push(reader.blob()?.to_vec());
+                }
+                entries.push(KeyEntry {
+                    alias: Some(alias),
+                    key: encrypted_key,
+                    key_is_encrypted: true,
+                    // The signer's own certificate comes first
+                    certificate: chain.into_iter().next()
+                });
+            }
+            TRUSTED_CERT_ENTRY => {
+                if version == 2 {
+                    reader.utf()?;
+                }
+                reader.blob()?;
+            }
+            _ => {
+                return Err(PackError::KeystoreInvalid(format!(
+                    "Unknown JKS entry type {entry_type}."
+                )))
+            }
+        }
+    }
+    Ok(entries)
+}
+
+/// Recovers a PKCS #8 private key from its `EncryptedPrivateKeyInfo`.
+pub(super) fn decrypt_key(encrypted_key_info: &[u8], password: &str) -> Result<Vec<u8>> {
+    let mut info = Der::new(encrypted_key_info).sequence()?;
+    // The algorithm is always Sun's key protector
+    info.sequence()?;
+    let protected = info.read(OCTET_STRING)?;
+    if protected.len() < SALT_LEN + Sha1::output_size() {
+        return Err(PackError::KeystoreInvalid(
+            "The JKS private key is truncated.".into()
+        ));
+    }
+    let (salt, rest) = protected.split_at(SALT_LEN);
+    let (encrypted, check) = rest.split_at(rest.len() - Sha1::output_size());
+    let password = password_bytes(password);
+
+    // XOR with a keystream of chained SHA-1 digests, starting from the salt
+    let mut key = Vec::with_capacity(encrypted.len());
+    let mut digest = salt.to_vec();
+    for block in encrypted.chunks(Sha1::output_size()) {
+        digest = Sha1::new()
+            .chain_update(&password)
+            .chain_update(&digest)
+            .finalize()
+            .to_vec();
+        key.extend(block.iter().zip(&digest).map(|(a, b)| a ^ b));
+    }
+
+    let expected_check = Sha1::new()
+        .chain_update(&password)
+        .chain_update(&key)
+        .finalize();
+    if expected_check[..] != *check {
+        return Err(PackError::KeystorePasswordIncorrect);
+    }
+    Ok(key)
+}
+
+// Java chars as big-endian UTF-16
+fn password_bytes(password: &str) -> Vec<u8> {
+    password.encode_utf16().flat_map(u16::to_be_bytes).collect()
+}
@@ -0,0 +1,595 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// PKCS #12, which keytool has created by default since Java 9, and which
+// OpenSSL and most other tools export as .p12 or .pfx files.
+//
+// A PFX wraps a list of "safe contents", which are optionally encrypted as a
+// whole, and which hold "safe bags" of keys and certificates. Keys are
+// matched up with their certificates through a localKeyId attribute.
+use aes::{Aes128, Aes192, Aes256};
+use cbc::cipher::{
+    block_padding::Pkcs7, BlockCipher, BlockDecryptMut, BlockEncryptMut, BlockSizeUser,
+    InnerIvInit, KeyInit, KeyIvInit
+};
+use des::TdesEde3;
+use hmac::{Hmac, Mac};
+use pack_common::*;
+use rand::prelude::*;
+use rc2::Rc2;
+use sha1::Sha1;
+use sha2::{digest::core_api::BlockSizeUser as DigestBlockSizeUser, Digest, Sha256};
+
+use super::KeyEntry;
+use crate::der::{
+    encode, encode_all, encode_u32, Der, BMP_STRING, CONTEXT_0, CONTEXT_0_CONSTRUCTED, INTEGER,
+    NULL, OCTET_STRING, OID, SEQUENCE, SET
+};
+
+// 1.2.840.113549.1.7.1
+const OID_DATA: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x07, 0x01];
+// 1.2.840.113549.1.7.6
+const OID_ENCRYPTED_DATA: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x07, 0x06];
+// 1.2.840.113549.1.12.10.1.1
+const OID_KEY_BAG: &[u8] = &[
+    0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x0C, 0x0A, 0x01, 0x01
+];
+// 1.2.840.113549.1.12.10.1.2
+const OID_SHROUDED_KEY_BAG: &[u8] = &[
+    0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x0C, 0x0A, 0x01, 0x02
+];
+// 1.2.840.113549.1.12.10.1.3
+const OID_CERT_BAG: &[u8] = &[
+    0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x0C, 0x0A, 0x01, 0x03
+];
+// 1.2.840.113549.1.9.22.1
+const OID_X509_CERTIFICATE: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x09, 0x16, 0x01];
+// 1.2.840.113549.1.9.20
+const OID_FRIENDLY_NAME: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x09, 0x14];
+// 1.2.840.113549.1.9.21
+const OID_LOCAL_KEY_ID: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x09, 0x15];
+// 1.3.14.3.2.26
+const OID_SHA1: &[u8] = &[0x2B, 0x0E, 0x03, 0x02, 0x1A];
+// 2.16.840.1.101.3.4.2.1
+const OID_SHA256: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01];
+// 1.2.840.113549.1.5.13
+const OID_PBES2: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x05, 0x0D];
+// 1.2.840.113549.1.5.12
+const OID_PBKDF2: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x05, 0x0C];
+// 1.2.840.113549.2.7
+const OID_HMAC_SHA1: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x02, 0x07];
+// 1.2.840.113549.2.9
+const OID_HMAC_SHA256: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x02, 0x09];
+// 2.16.840.1.101.3.4.1.2
+const OID_AES128_CBC: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x01, 0x02];
+// 2.16.840.1.101.3.4.1.22
+const OID_AES192_CBC: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x01, 0x16];
+// 2.16.840.1.101.3.4.1.42
+const OID_AES256_CBC: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x01, 0x2A];
+// 1.2.840.113549.3.7
+const OID_DES_EDE3_CBC: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x03, 0x07];
+// 1.2.840.113549.1.12.1.3
+const OID_PBE_SHA1_3DES: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x0C, 0x01, 0x03];
+// 1.2.840.113549.1.12.1.5
+const OID_PBE_SHA1_RC2_128: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x0C, 0x01, 0x05];
+// 1.2.840.113549.1.12.1.6
+const OID_PBE_SHA1_RC2_40: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x0C, 0x01, 0x06];
+
+// The "ID" byte of the PKCS #12 key derivation function, RFC 7292 appendix B.3
+const KDF_KEY: u8 = 1;
+const KDF_IV: u8 = 2;
+const KDF_MAC: u8 = 3;
+
+// What keytool uses for new keystores
+const WRITE_ITERATIONS: u32 = 10000;
+
+// Bags are collected first, and paired up once everything has been read
+struct Bag {
+    local_key_id: Option<Vec<u8>>,
+    friendly_name: Option<String>,
+    contents: BagContents
+}
+
+enum BagContents {
+    Key(Vec<u8>),
+    ShroudedKey(Vec<u8>),
+    Certificate(Vec<u8>)
+}
+
+/// Reads every private key, checking the store password against the MAC.
+pub(super) fn read_entries(keystore: &[u8], store_password: &str) -> Result<Vec<KeyEntry>> {
+    let mut pfx = Der::new(keystore).sequence()?;
+    let version = pfx.read_u32()?;
+    if version != 3 {
+        return Err(PackError::KeystoreInvalid(format!(
+            "Unsupported PKCS #12 version {version}."
+        )));
+    }
+    let mut auth_safe = pfx.sequence()?;
+    if auth_safe.read(OID)? != OID_DATA {
+        return Err(PackError::KeystoreInvalid(
+            "Public-key protected PKCS #12 files aren't supported.".into()
+        ));
+    }
+    let auth_safe_data = Der::new(auth_safe.read(CONTEXT_0_CONSTRUCTED)?).read(OCTET_STRING)?;
+    if !pfx.is_empty() {
+        verify_mac(&mut pfx.sequence()?, auth_safe_data, store_password)?;
+    }
+
+    let mut bags = vec![];
+    let mut content_infos = Der::new(auth_safe_data).sequence()?;
+    while !content_infos.is_empty() {
+        let mut content_info = content_infos.sequence()?;
+        let content_type = content_info.read(OID)?;
+        let content = content_info.read(CONTEXT_0_CONSTRUCTED)?;
+        let safe_contents = if content_type == OID_DATA {
+            Der::new(content).read(OCTET_STRING)?.to_vec()
+        } else if content_type == OID_ENCRYPTED_DATA {
+            let mut encrypted_data = Der::new(content).sequence()?;
+            encrypted_data.read_u32()?;
+            let mut encrypted_content_info = encrypted_data.sequence()?;
+            encrypted_content_info.read(OID)?;
+            let algorithm = encrypted_content_info.sequence()?;
+            let encrypted = encrypted_content_info.read(CONTEXT_0)?;
+            decrypt(algorithm, encrypted, store_password)?
+        } else {
+            return Err(PackError::KeystoreInvalid(
+                "Unsupported PKCS #12 content type.".into()
+            ));
+        };
+        read_bags(&safe_contents, &mut bags)?;
+    }
+
+    let certificates: Vec<&Bag> = bags
+        .iter()
+        .filter(|bag| matches!(bag.contents, BagContents::Certificate(_)))
+        .collect();
+    let entries = bags
+        .iter()
+        .filter_map(|bag| {
+            let (key, key_is_encrypted) = match &bag.contents {
+                BagContents::Key(key) => (key.clone(), false),
+                BagContents::ShroudedKey(key) => (key.clone(), true),
+                BagContents::Certificate(_) => return None
+            };
+            // Fall back to the first certificate for files without localKeyIds
+            let certificate = certificates
+                .iter()
+                .find(|cert| cert.local_key_id.is_some() && cert.local_key_id == bag.local_key_id)
+                .or(certificates.first())
+                .and_then(|cert| match &cert.contents {
+                    BagContents::Certificate(certificate) => Some(certificate.clone()),
+                    _ => None
+                });
+            Some(KeyEntry {
+                alias: bag.friendly_name.clone(),
+                key,
+                key_is_encrypted,
+                certificate
+            })
+        })
+        .collect();
+    Ok(entries)
+}
+
+/// Recovers a PKCS #8 private key from its `EncryptedPrivateKeyInfo`.
+pub(super) fn decrypt_key(encrypted_key_info: &[u8], password: &str) -> Result<Vec<u8>> {
+    let mut info = Der::new(encrypted_key_info).sequence()?;
+    let algorithm = info.sequence()?;
+    let encrypted = info.read(OCTET_STRING)?;
+    decrypt(algorithm, encrypted, password)
+}
+
+/// Writes a PKCS #12 keystore holding a single key and its certificate,
+/// protected the way OpenSSL 3 and keytool do by default: AES-256-CBC with
+/// PBKDF2-HMAC-SHA256 for the key, and an HMAC-SHA256 MAC over everything.
+pub(super) fn write_keystore(
+    private_key_info: &[u8],
+    certificate: &[u8],
+    alias: &str,
+    password: &str
+) -> Result<Vec<u8>> {
+    let mut rng = thread_rng();
+    let attributes = encode_all(
+        SET,
+        &[
+            attribute(OID_FRIENDLY_NAME, encode(BMP_STRING, &bmp_string(alias))),
+            // OpenSSL uses the SHA-1 of the certificate, which is as good as anything
+            attribute(
+                OID_LOCAL_KEY_ID,
+                encode(OCTET_STRING, &Sha1::digest(certificate))
+            )
+        ]
+    );
+
+    let salt: [u8; 16] = rng.gen();
+    let iv: [u8; 16] = rng.gen();
+    let mut key = [0; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), &salt, WRITE_ITERATIONS, &mut key);
+    let encrypted_key = cbc::Encryptor::<Aes256>::new_from_slices(&key, &iv)
+        .map_err(|_| PackError::KeystoreInvalid("Invalid key length.".into()))?
+        .encrypt_padded_vec_mut::<Pkcs7>(private_key_info);
+    let pbes2 = encode_all(
+        SEQUENCE,
+        &[
+            encode(OID, OID_PBES2),
+            encode_all(
+                SEQUENCE,
+                &[
+                    encode_all(
+                        SEQUENCE,
+                        &[
+                            encode(OID, OID_PBKDF2),
+                            encode_all(
+                                SEQUENCE,
+                                &[
+                                    encode(OCTET_STRING, &salt),
+                                    encode_u32(WRITE_ITERATIONS),
+                                    algorithm_identifier(OID_HMAC_SHA256)
+                                ]
+                            )
+                        ]
+                    ),
+                    encode_all(
+                        SEQUENCE,
+                        &[encode(OID, OID_AES256_CBC), encode(OCTET_STRING, &iv)]
+                    )
+                ]
+            )
+        ]
+    );
+    let key_bag = encode_all(
+        SEQUENCE,
+        &[
+            encode(OID, OID_SHROUDED_KEY_BAG),
+            encode_all(
+                CONTEXT_0_CONSTRUCTED,
+                &[encode_all(
+                    SEQUENCE,
+                    &[pbes2, encode(OCTET_STRING, &encrypted_key)]
+                )]
+            ),
+            attributes.clone()
+        ]
+    );
+    // Certificates are public, so they don't need encrypting
+    let cert_bag = encode_all(
+        SEQUENCE,
+        &[
+            encode(OID, OID_CERT_BAG),
+            encode_all(
+                CONTEXT_0_CONSTRUCTED,
+                &[encode_all(
+                    SEQUENCE,
+                    &[
+                        encode(OID, OID_X509_CERTIFICATE),
+                        encode_all(CONTEXT_0_CONSTRUCTED, &[encode(OCTET_STRING, certificate)])
+                    ]
+                )]
+            ),
+            attributes
+        ]
+    );
+    let safe_contents = encode_all(SEQUENCE, &[key_bag, cert_bag]);
+    let auth_safe_data = encode_all(SEQUENCE, &[data_content_info(&safe_contents)]);
+
+    let mac_salt: [u8; 16] = rng.gen();
+    let mac_key = pkcs12_kdf::<Sha256>(
+        &bmp_password(password),
+        &mac_salt,
+        WRITE_ITERATIONS,
+        KDF_MAC,
+        32
+    );
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&mac_key)
+        .map_err(|_| PackError::KeystoreInvalid("Invalid MAC key length.".into()))?;
+    mac.update(&auth_safe_data);
+    let mac_data = encode_all(
+        SEQUENCE,
+        &[
+            encode_all(
+                SEQUENCE,
+                &[
+                    algorithm_identifier(OID_SHA256),
+                    encode(OCTET_STRING, &mac.finalize().into_bytes())
+                ]
+            ),
+            encode(OCTET_STRING, &mac_salt),
+            encode_u32(WRITE_ITERATIONS)
+        ]
+    );
+
+    Ok(encode_all(
+        SEQUENCE,
+        &[encode_u32(3), data_content_info(&auth_safe_data), mac_data]
+    ))
+}
+
+fn attribute(id: &[u8], value: Vec<u8>) -> Vec<u8> {
+    encode_all(SEQUENCE, &[encode(OID, id), encode_all(SET, &[value])])
+}
+
+fn algorithm_identifier(id: &[u8]) -> Vec<u8> {
+    encode_all(SEQUENCE, &[encode(OID, id), encode(NULL, &[])])
+}
+
+fn data_content_info(data: &[u8]) -> Vec<u8> {
+    encode_all(
+        SEQUENCE,
+        &[
+            encode(OID, OID_DATA),
+            encode_all(CONTEXT_0_CONSTRUCTED, &[encode(OCTET_STRING, data)])
+        ]
+    )
+}
+
+fn read_bags(safe_contents: &[u8], bags: &mut Vec<Bag>) -> Result<()> {
+    let mut safe_bags = Der::new(safe_contents).sequence()?;
+    while !safe_bags.is_empty() {
+        let mut safe_bag = safe_bags.sequence()?;
+        let bag_id = safe_bag.read(OID)?;
+        let (_, value, encoded_value) =
+            Der::new(safe_bag.read(CONTEXT_0_CONSTRUCTED)?).read_any()?;
+        let contents = if bag_id == OID_KEY_BAG {
+            BagContents::Key(encoded_value.to_vec())
+        } else if bag_id == OID_SHROUDED_KEY_BAG {
+            BagContents::ShroudedKey(encoded_value.to_vec())
+        } else if bag_id == OID_CERT_BAG {
+            let mut cert_bag = Der::new(value);
+            if cert_bag.read(OID)? != OID_X509_CERTIFICATE {
+                continue;
+            }
+            let certificate = Der::new(cert_bag.read(CONTEXT_0_CONSTRUCTED)?).read(OCTET_STRING)?;
+            BagContents::Certificate(certificate.to_vec())
+        } else {
+            // CRLs, secrets and nested safe contents aren't needed for signing
+            continue;
+        };
+
+        let mut bag = Bag {
+            local_key_id: None,
+            friendly_name: None,
+            contents
+        };
+        if safe_bag.peek_tag() == Some(SET) {
+            let mut attributes = Der::new(safe_bag.read(SET)?);
+            while !attributes.is_empty() {
+                let mut attribute = attributes.sequence()?;
+                let attribute_id = attribute.read(OID)?;
+                let mut values = Der::new(attribute.read(SET)?);
+                if attribute_id == OID_LOCAL_KEY_ID {
+                    bag.local_key_id = Some(values.read(OCTET_STRING)?.to_vec());
+                } else if attribute_id == OID_FRIENDLY_NAME {
+                    let utf16: Vec<u16> = values
+                        .read(BMP_STRING)?
+                        .chunks_exact(2)
+                        .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                        .collect();
+                    bag.friendly_name = Some(String::from_utf16_lossy(&utf16));
+                }
+            }
+        }
+        bags.push(bag);
+    }
+    Ok(())
+}
+
+fn verify_mac(mac_data: &mut Der, auth_safe_data: &[u8], password: &str) -> Result<()> {
+    let mut digest_info = mac_data.sequence()?;
+    let digest_algorithm = digest_info.sequence()?.read(OID)?;
+    let expected = digest_info.read(OCTET_STRING)?;
+    let salt = mac_data.read(OCTET_STRING)?;
+    let iterations = if mac_data.is_empty() {
+        1
+    } else {
+        mac_data.read_u32()?
+    };
+
+    let password = bmp_password(password);
+    let valid = if digest_algorithm == OID_SHA1 {
+        let key = pkcs12_kdf::<Sha1>(&password, salt, iterations, KDF_MAC, 20);
+        hmac_matches::<Hmac<Sha1>>(&key, auth_safe_data, expected)
+    } else if digest_algorithm == OID_SHA256 {
+        let key = pkcs12_kdf::<Sha256>(&password, salt, iterations, KDF_MAC, 32);
+        hmac_matches::<Hmac<Sha256>>(&key, auth_safe_data, expected)
+    } else {
+        return Err(PackError::KeystoreInvalid(
+            "Unsupported PKCS #12 MAC algorithm.".into()
+        ));
+    };
+    if !valid {
+        return Err(PackError::KeystorePasswordIncorrect);
+    }
+    Ok(())
+}
+
+fn hmac_matches<M: Mac + KeyInit>(key: &[u8], data: &[u8], expected: &[u8]) -> bool {
+    let Ok(mut mac) = <M as Mac>::new_from_slice(key) else {
+        return false;
+    };
+    mac.update(data);
+    mac.verify_slice(expected).is_ok()
+}
+
+// Decrypts data given the AlgorithmIdentifier it was encrypted with
+fn decrypt(mut algorithm: Der, encrypted: &[u8], password: &str) -> Result<Vec<u8>> {
+    let algorithm_id = algorithm.read(OID)?;
+    let mut params = algorithm.sequence()?;
+
+    if algorithm_id == OID_PBES2 {
+        return decrypt_pbes2(&mut params, encrypted, password);
+    }
+
+    // The older PKCS #12 schemes derive both the key and IV from the password
+    let salt = params.read(OCTET_STRING)?;
+    let iterations = params.read_u32()?;
+    let password = bmp_password(password);
+    let derive = |id, len| pkcs12_kdf::<Sha1>(&password, salt, iterations, id, len);
+    if algorithm_id == OID_PBE_SHA1_3DES {
+        cbc_decrypt(
+            new_cipher::<TdesEde3>(&derive(KDF_KEY, 24))?,
+            &derive(KDF_IV, 8),
+            encrypted
+        )
+    } else if algorithm_id == OID_PBE_SHA1_RC2_128 || algorithm_id == OID_PBE_SHA1_RC2_40 {
+        let key_len = if algorithm_id == OID_PBE_SHA1_RC2_40 {
+            5
+        } else {
+            16
+        };
+        cbc_decrypt(
+            Rc2::new_with_eff_key_len(&derive(KDF_KEY, key_len), key_len * 8),
+            &derive(KDF_IV, 8),
+            encrypted
+        )
+    } else {
+        Err(PackError::KeystoreInvalid(
+            "Unsupported PKCS #12 encryption algorithm.".into()
+        ))
+    }
+}
+
+// PBES2 from PKCS #5, with PBKDF2 and a CBC block cipher
+fn decrypt_pbes2(params: &mut Der, encrypted: &[u8], password: &str) -> Result<Vec<u8>> {
+    let unsupported = || PackError::KeystoreInvalid("Unsupported PBES2 parameters.".into());
+
+    let mut kdf = params.sequence()?;
+    if kdf.read(OID)? != OID_PBKDF2 {
+        return Err(unsupported());
+    }
+    let mut kdf_params = kdf.sequence()?;
+    let salt = kdf_params.read(OCTET_STRING)?;
+    let iterations = kdf_params.read_u32()?;
+    let mut key_len = None;
+    if kdf_params.peek_tag() == Some(INTEGER) {
+        key_len = Some(kdf_params.read_u32()? as usize);
+    }
+    // HMAC-SHA1 is the default
+    let prf = if kdf_params.is_empty() {
+        OID_HMAC_SHA1
+    } else {
+        kdf_params.sequence()?.read(OID)?
+    };
+
+    let mut scheme = params.sequence()?;
+    let scheme_id = scheme.read(OID)?;
+    let iv = scheme.read(OCTET_STRING)?;
+    let scheme_key_len = match scheme_id {
+        id if id == OID_AES128_CBC => 16,
+        id if id == OID_AES192_CBC => 24,
+        id if id == OID_AES256_CBC => 32,
+        id if id == OID_DES_EDE3_CBC => 24,
+        _ => return Err(unsupported())
+    };
+    if key_len.is_some_and(|key_len| key_len != scheme_key_len) {
+        return Err(unsupported());
+    }
+
+    let mut key = vec![0; scheme_key_len];
+    match prf {
+        id if id == OID_HMAC_SHA1 => {
+            pbkdf2::pbkdf2_hmac::<Sha1>(password.as_bytes(), salt, iterations, &mut key)
+        }
+        id if id == OID_HMAC_SHA256 => {
+            pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, iterations, &mut key)
+        }
+        _ => return Err(unsupported())
+    }
+
+    match scheme_id {
+        id if id == OID_AES128_CBC => cbc_decrypt(new_cipher::<Aes128>(&key)?, iv, encrypted),
+        id if id == OID_AES192_CBC => cbc_decrypt(new_cipher::<Aes192>(&key)?, iv, encrypted),
+        id if id == OID_AES256_CBC => cbc_decrypt(new_cipher::<Aes256>(&key)?, iv, encrypted),
+        _ => cbc_decrypt(new_cipher::<TdesEde3>(&key)?, iv, encrypted)
+    }
+}
+
+fn new_cipher<C: KeyInit>(key: &[u8]) -> Result<C> {
+    C::new_from_slice(key).map_err(|_| PackError::KeystoreInvalid("Invalid key length.".into()))
+}
+
+fn cbc_decrypt<C: BlockCipher + BlockDecryptMut + BlockSizeUser>(
+    cipher: C,
+    iv: &[u8],
+    encrypted: &[u8]
+) -> Result<Vec<u8>> {
+    let decryptor = cbc::Decryptor::inner_iv_slice_init(cipher, iv)
+        .map_err(|_| PackError::KeystoreInvalid("Invalid IV length.".into()))?;
+    let mut buffer = encrypted.to_vec();
+    // Bad padding is almost always down to the wrong password
+    let len = decryptor
+        .decrypt_padded_mut::<Pkcs7>(&mut buffer)
+        .map_err(|_| PackError::KeystorePasswordIncorrect)?
+        .len();
+    buffer.truncate(len);
+    Ok(buffer)
+}
+
+// The PKCS #12 KDF takes passwords as null-terminated big-endian UTF-16
+fn bmp_password(password: &str) -> Vec<u8> {
+    let mut bmp = bmp_string(password);
+    bmp.extend([0, 0]);
+    bmp
+}
+
+fn bmp_string(text: &str) -> Vec<u8> {
+    text.encode_utf16().flat_map(u16::to_be_bytes).collect()
+}
+
+// RFC 7292 appendix B.2
+fn pkcs12_kdf<D: Digest + DigestBlockSizeUser>(
+    password: &[u8],
+    salt: &[u8],
+    iterations: u32,
+    id: u8,
+    len: usize
+) -> Vec<u8> {
+    let v = <D as DigestBlockSizeUser>::block_size();
+    let diversifier = vec![id; v];
+    // Repeats data to fill a whole number of v-byte blocks
+    let fill = |data: &[u8]| -> Vec<u8> {
+        data.iter()
+            .cycle()
+            .take(v * data.len().div_ceil(v))
+            .copied()
+            .collect()
+    };
+    let mut input = fill(salt);
+    input.extend(fill(password));
+
+    let mut output = vec![];
+    while output.len() < len {
+        let mut hash = D::new()
+            .chain_update(&diversifier)
+            .chain_update(&input)
+            .finalize();
+        for _ in 1..iterations {
+            hash = D::digest(&hash);
+        }
+        output.extend_from_slice(&hash);
+
+        // Each block of the input becomes (block + B + 1) mod 2^(v * 8)
+        let b: Vec<u8> = hash.iter().cycle().take(v).copied().collect();
+        for block in input.chunks_mut(v) {
+            let mut carry = 1u16;
+            for i in (0..v).rev() {
+                let sum = block[i] as u16 + b[i] as u16 + carry;
+                block[i] = sum as u8;
+                carry = sum >> 8;
+            }
+        }
+    }
+    output.truncate(len);
+    output
+}
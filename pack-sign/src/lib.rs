@@ -13,37 +13,60 @@
 // limitations under the License.
 
 use crypto_keys::Keys;
-use deku::DekuContainerWrite;
 use hasher::compute_top_level_hash;
+pub use hasher::{DigestAlgorithm, SigningConfig};
 use pack_common::Result;
-use signing_block::compute_signing_block;
-use zip_parser::find_offsets;
+use signing_block::{compute_signing_block, signing_block_size_for};
 use zip_rebuilder::rebuild_zip_with_signing_block;
 
+#[cfg(feature = "cert-gen")]
+mod certificate;
 mod crypto;
 pub mod crypto_keys;
+mod der;
 mod hasher;
+#[cfg(feature = "keystore")]
+mod keystore;
 mod signed_data_block;
 mod signing_block;
-mod signing_types;
+pub mod signing_types;
+#[cfg(feature = "v1")]
 pub mod v1_signing;
+pub mod verification;
 mod zip_parser;
 mod zip_rebuilder;
 
+pub use signed_data_block::{SIGNATURE_SCHEME_V2_BLOCK_ID, SIGNATURE_SCHEME_V3_BLOCK_ID};
+pub use signing_block::read_signing_block;
+pub use zip_parser::{find_offsets, ZipOffsets};
+
 // APK Signature Scheme v2 based on https://source.android.com/docs/security/features/apksigning/v2
 // APK Signature Scheme v3 based on https://source.android.com/docs/security/features/apksigning/v3
 /// Signs a ZIP file buffer, adding an APK Signature Block before its Central Directory.
 /// Can be used for both APK and AAB files.
 pub fn sign_apk_buffer(apk_buf: &mut [u8], keys: &Keys) -> Result<Vec<u8>> {
-    // Dry-run the block to figure out how long it will be given our key
-    let dry_run = compute_signing_block([0; 32], keys)?;
-    let signing_block_size = dry_run.to_bytes()?.len();
+    sign_apk_buffer_with_config(apk_buf, keys, &SigningConfig::default())
+}
+
+/// Like [sign_apk_buffer], but the signatures are RSA with the config's
+/// digest algorithm, over chunks of the config's size.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(name = "sign_v2_v3", skip_all, fields(bytes = apk_buf.len()))
+)]
+pub fn sign_apk_buffer_with_config(
+    apk_buf: &mut [u8],
+    keys: &Keys,
+    config: &SigningConfig
+) -> Result<Vec<u8>> {
+    // Figure out how long the block will be given our key, without signing
+    let signing_block_size = signing_block_size_for(keys, config)?;
     // Read ZIP file to find central directory
     let offsets = find_offsets(apk_buf)?;
     // SHA-256 hash of ZIP contents (accounting for APK Signing Block)
-    let top_level_hash = compute_top_level_hash(apk_buf, &offsets, signing_block_size)?;
-    // Compute again using the real hash this time
-    let signing_block = compute_signing_block(top_level_hash, keys)?;
+    let top_level_hash = compute_top_level_hash(apk_buf, &offsets, signing_block_size, config)?;
+    // Now compute and sign it for real
+    let signing_block = compute_signing_block(&top_level_hash, keys, config)?;
     // Build up the final zip file again
     rebuild_zip_with_signing_block(&offsets, apk_buf, signing_block)
 }
@@ -14,26 +14,25 @@
 
 use crate::{
     crypto_keys::Keys,
-    hasher::Sha256Hash,
     signing_types::{
-        len_pfx_u32, len_pfx_u64, ApkSigningBlock, Digest, Signature, SignatureAlgorithmId::*,
-        SignatureSchemeV2Block, SignatureSchemeV3Block, SignedData, Signer,
-        SigningBlockIdValuePair, SigningBlockPairs, V3SignedData, V3Signer
+        len_pfx_u32, len_pfx_u64, ApkSigningBlock, Digest, PrefixedValue, Signature,
+        SignatureAlgorithmId, SignatureSchemeV2Block, SignatureSchemeV3Block, SignedData, Signer,
+        SigningBlockIdValuePair, SigningBlockPairs, U64LengthPrefixed, V3SignedData, V3Signer
     }
 };
-use deku::DekuContainerWrite;
+use deku::{DekuContainerWrite, DekuError};
 use pack_common::*;
 
 // Constructs the Signed Data block for the V2 Scheme
 // This is the data that gets signed by the crypto module
 // It does not, itself, contain a cryptographic signature
 impl SignedData {
-    pub fn new(top_level_hash: Sha256Hash, keys: &Keys) -> SignedData {
+    pub fn new(top_level_hash: &[u8], keys: &Keys, algorithm: SignatureAlgorithmId) -> SignedData {
         SignedData {
             // TODO: len_vec macro that makes a length-prefixed list of length-prefixed T
             digests: len_pfx_u32(vec![len_pfx_u32(Digest {
-                digest: len_pfx_u32(top_level_hash),
-                signature_algorithm_id: RsaSsaPkcs1v1_5WithSha2_256
+                digest: len_pfx_u32(top_level_hash.to_vec()),
+                signature_algorithm_id: algorithm
             })]),
             certificates: len_pfx_u32(vec![len_pfx_u32(keys.certificate.clone())]),
             additional_attributes: 0
@@ -57,13 +56,14 @@ impl SignatureSchemeV2Block {
     pub fn new(
         signed_data: SignedData,
         signature: Vec<u8>,
+        algorithm: SignatureAlgorithmId,
         keys: &Keys
     ) -> Result<SignatureSchemeV2Block> {
         Ok(SignatureSchemeV2Block {
             signers: len_pfx_u32(vec![len_pfx_u32(Signer {
                 signed_data: len_pfx_u32(signed_data),
                 signatures: len_pfx_u32(vec![len_pfx_u32(Signature {
-                    signature_algorithm_id: RsaSsaPkcs1v1_5WithSha2_256,
+                    signature_algorithm_id: algorithm,
                     signature: len_pfx_u32(signature)
                 })]),
                 public_key: len_pfx_u32(keys.pub_key_as_der()?)
@@ -76,6 +76,7 @@ impl SignatureSchemeV3Block {
     pub fn new(
         signed_data: V3SignedData,
         signature: Vec<u8>,
+        algorithm: SignatureAlgorithmId,
         keys: &Keys,
         min_sdk: u32,
         max_sdk: u32
@@ -86,7 +87,7 @@ impl SignatureSchemeV3Block {
                 min_sdk,
                 max_sdk,
                 signatures: len_pfx_u32(vec![len_pfx_u32(Signature {
-                    signature_algorithm_id: RsaSsaPkcs1v1_5WithSha2_256,
+                    signature_algorithm_id: algorithm,
                     signature: len_pfx_u32(signature)
                 })]),
                 public_key: len_pfx_u32(keys.pub_key_as_der()?)
@@ -103,19 +104,16 @@ impl ApkSigningBlock {
         v2_sig_block: SignatureSchemeV2Block,
         v3_sig_block: SignatureSchemeV3Block
     ) -> Result<ApkSigningBlock> {
-        let pairs = SigningBlockPairs {
-            pairs: vec![
-                len_pfx_u64(SigningBlockIdValuePair {
-                    id: SIGNATURE_SCHEME_V2_BLOCK_ID,
-                    value: v2_sig_block.to_bytes()?
-                }),
-                len_pfx_u64(SigningBlockIdValuePair {
-                    id: SIGNATURE_SCHEME_V3_BLOCK_ID,
-                    value: v3_sig_block.to_bytes()?
-                }),
-            ]
-        };
+        let mut block = ApkSigningBlock::from_pairs(vec![])?;
+        block.set_value(SIGNATURE_SCHEME_V2_BLOCK_ID, v2_sig_block.to_bytes()?)?;
+        block.set_value(SIGNATURE_SCHEME_V3_BLOCK_ID, v3_sig_block.to_bytes()?)?;
+        Ok(block)
+    }
 
+    fn from_pairs(
+        pairs: Vec<U64LengthPrefixed<SigningBlockIdValuePair>>
+    ) -> Result<ApkSigningBlock> {
+        let pairs = SigningBlockPairs { pairs };
         let pairs_length = pairs.to_bytes()?.len();
         // Plus size_of_self_counted plus magic
         let sig_block_size = (pairs_length + 8 + 16) as u64;
@@ -127,4 +125,58 @@ impl ApkSigningBlock {
             magic: *APK_SIGNING_BLOCK_MAGIC
         })
     }
+
+    /// Returns the value of the pair with this ID, eg.
+    /// [SIGNATURE_SCHEME_V2_BLOCK_ID] for the v2 signatures.
+    pub fn value(&self, id: u32) -> Option<&[u8]> {
+        self.pairs
+            .pairs
+            .iter()
+            .find(|pair| pair.value.id == id)
+            .map(|pair| pair.value.value.as_slice())
+    }
+
+    /// Sets the value of the pair with this ID, adding it after the others if
+    /// there isn't one yet. Every other pair is kept as it is.
+    pub fn set_value(&mut self, id: u32, value: Vec<u8>) -> Result<()> {
+        let mut pairs = std::mem::take(&mut self.pairs.pairs);
+        let pair = len_pfx_u64(SigningBlockIdValuePair { id, value });
+        match pairs.iter_mut().find(|pair| pair.value.id == id) {
+            Some(existing) => *existing = pair,
+            None => pairs.push(pair)
+        }
+        *self = ApkSigningBlock::from_pairs(pairs)?;
+        Ok(())
+    }
+
+    /// Removes the pair with this ID, if there is one.
+    pub fn remove_value(&mut self, id: u32) -> Result<()> {
+        let mut pairs = std::mem::take(&mut self.pairs.pairs);
+        pairs.retain(|pair| pair.value.id != id);
+        *self = ApkSigningBlock::from_pairs(pairs)?;
+        Ok(())
+    }
+
+    /// Parses the v2 signatures, if the block has them.
+    pub fn v2_block(&self) -> Result<Option<SignatureSchemeV2Block>> {
+        self.value(SIGNATURE_SCHEME_V2_BLOCK_ID)
+            .map(SignatureSchemeV2Block::from_prefixed_bytes)
+            .transpose()
+            .map_err(invalid_block)
+    }
+
+    /// Parses the v3 signatures, if the block has them.
+    pub fn v3_block(&self) -> Result<Option<SignatureSchemeV3Block>> {
+        self.value(SIGNATURE_SCHEME_V3_BLOCK_ID)
+            .map(SignatureSchemeV3Block::from_prefixed_bytes)
+            .transpose()
+            .map_err(invalid_block)
+    }
+}
+
+pub fn invalid_block(error: DekuError) -> PackError {
+    PackError::SigningBlockInvalid(match error {
+        DekuError::Parse(msg) | DekuError::Assertion(msg) => msg.into_owned(),
+        error => error.to_string()
+    })
 }
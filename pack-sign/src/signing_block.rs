@@ -13,33 +13,110 @@
 // limitations under the License.
 
 use crate::{
-    crypto::get_signature_for_signed_data,
+    crypto::{get_signature_for_signed_data, signature_length},
     crypto_keys::Keys,
+    hasher::SigningConfig,
+    signed_data_block::{invalid_block, APK_SIGNING_BLOCK_MAGIC},
     signing_types::{
         ApkSigningBlock, SignatureSchemeV2Block, SignatureSchemeV3Block, SignedData, V3SignedData
-    }
+    },
+    zip_parser::find_offsets
 };
-use pack_common::Result;
+use byteorder::{ByteOrder, LittleEndian};
+use deku::{DekuContainerRead, DekuContainerWrite};
+use pack_common::{PackError, Result};
+
+pub fn compute_signing_block(
+    top_level_hash: &[u8],
+    keys: &Keys,
+    config: &SigningConfig
+) -> Result<ApkSigningBlock> {
+    construct_signing_block(top_level_hash, keys, config, |signed_data| {
+        get_signature_for_signed_data(signed_data, keys, config.digest_algorithm)
+    })
+}
 
-pub fn compute_signing_block(top_level_hash: [u8; 32], keys: &Keys) -> Result<ApkSigningBlock> {
+/// Returns how long the block from [compute_signing_block] will be, without
+/// signing anything. Only the hash and the signatures change between calls,
+/// and neither changes in length, so placeholder signatures give the same
+/// size. This saves an RSA operation per signature, which matters when the
+/// key is slow to use.
+pub fn signing_block_size_for(keys: &Keys, config: &SigningConfig) -> Result<usize> {
+    let placeholder_hash = vec![0; config.digest_algorithm.output_size()];
+    let placeholder = vec![0; signature_length(keys)];
+    let block =
+        construct_signing_block(&placeholder_hash, keys, config, |_| Ok(placeholder.clone()))?;
+    Ok(block.to_bytes()?.len())
+}
+
+fn construct_signing_block(
+    top_level_hash: &[u8],
+    keys: &Keys,
+    config: &SigningConfig,
+    sign: impl Fn(&[u8]) -> Result<Vec<u8>>
+) -> Result<ApkSigningBlock> {
     // TODO: Allow the user to customise this
-    // NOTE: Must be 24 or higher. 23 does not support our hash algorithm.
+    // NOTE: Must be 24 or higher. 23 does not support our hash algorithms.
     let min_sdk = 24;
     // We deal with this unsigned, but it seems Android parses it as signed, hence the 7F.
     let max_sdk = 0x7FFFFFFF;
     // Construct the data block that we're going to sign
     // NOTE: The signature does NOT include the length prefix
-    let signed_data = SignedData::new(top_level_hash, keys);
+    let algorithm = config.digest_algorithm.signature_algorithm();
+    let signed_data = SignedData::new(top_level_hash, keys, algorithm.clone());
     // Prepare the V3 block simultaneously
     let v3_signed_data = V3SignedData::from(&signed_data, min_sdk, max_sdk);
     // Sign them with RSA
-    let signature = get_signature_for_signed_data(&signed_data, keys)?;
-    let v3_signature = get_signature_for_signed_data(&v3_signed_data, keys)?;
+    let signature = sign(&signed_data.to_bytes()?)?;
+    let v3_signature = sign(&v3_signed_data.to_bytes()?)?;
     // Create the whole APK Signature Scheme block
-    let scheme_block = SignatureSchemeV2Block::new(signed_data, signature, keys)?;
-    let v3_scheme_block =
-        SignatureSchemeV3Block::new(v3_signed_data, v3_signature, keys, min_sdk, max_sdk)?;
+    let scheme_block =
+        SignatureSchemeV2Block::new(signed_data, signature, algorithm.clone(), keys)?;
+    let v3_scheme_block = SignatureSchemeV3Block::new(
+        v3_signed_data,
+        v3_signature,
+        algorithm,
+        keys,
+        min_sdk,
+        max_sdk
+    )?;
     // Create and serialise the entire APK Signing Block that goes straight into the zip file
     let signing_block = ApkSigningBlock::new(scheme_block, v3_scheme_block)?;
     Ok(signing_block)
 }
+
+/// Reads the APK Signing Block of an existing package, if it has one. Along
+/// with the v2 and v3 signatures, it keeps any other pairs in the block, so
+/// that they survive when it's written again.
+pub fn read_signing_block(package: &[u8]) -> Result<Option<ApkSigningBlock>> {
+    let cd_start = find_offsets(package)?.cd_start;
+    Ok(signing_block_before(package, cd_start)?.map(|(_, block)| block))
+}
+
+// Returns where the APK Signing Block right before the central directory
+// starts, and the block, if there is one
+pub fn signing_block_before(
+    package: &[u8],
+    cd_start: usize
+) -> Result<Option<(usize, ApkSigningBlock)>> {
+    // The block ends with its size and the magic
+    let Some(footer_start) = cd_start.checked_sub(8 + APK_SIGNING_BLOCK_MAGIC.len()) else {
+        return Ok(None);
+    };
+    if package.get(footer_start + 8..cd_start) != Some(&APK_SIGNING_BLOCK_MAGIC[..]) {
+        return Ok(None);
+    }
+    let block_size = LittleEndian::read_u64(&package[footer_start..]);
+    // The size doesn't count the leading size field, but does count the footer
+    let block_start = usize::try_from(block_size)
+        .ok()
+        .and_then(|size| size.checked_add(8))
+        .and_then(|len| cd_start.checked_sub(len))
+        .ok_or(PackError::SigningBlockInvalid(
+            "Its size is larger than the package.".into()
+        ))?;
+
+    let (_, block) =
+        ApkSigningBlock::from_bytes((&package[block_start..cd_start], 0)).map_err(invalid_block)?;
+    Ok(Some((block_start, block)))
+}
@@ -13,46 +13,75 @@
 // limitations under the License.
 
 // Types involved in the APK Signature Scheme v2
-use deku::prelude::*;
+//
+// These can be read as well as written, so that existing APK Signing Blocks
+// can be parsed. Every length-prefixed value is read from exactly its length,
+// so a value that's shorter or longer than its prefix says is an error rather
+// than throwing off everything after it.
+use deku::{
+    ctx::Limit,
+    no_std_io::{Cursor, Read, Seek},
+    prelude::*
+};
 
-use crate::hasher::Sha256Hash;
+use crate::signed_data_block::APK_SIGNING_BLOCK_MAGIC;
 
 // Named according to the APK Signature Scheme v2 doc
 
-#[derive(Debug, PartialEq, DekuWrite, Clone)]
+#[derive(Debug, PartialEq, DekuRead, DekuWrite, Clone)]
 pub struct ApkSigningBlock {
     // Size of this structure MINUS this field!
     // This field appears twice, the 'minus' is only for one of them.
     // So if the structure is 128 bytes, this reads 120, NOT 112.
     pub size_of_self_not_counted: u64,
+    #[deku(reader = "read_pairs(deku::reader, *size_of_self_not_counted)")]
     pub pairs: SigningBlockPairs,
+    #[deku(assert_eq = "*size_of_self_not_counted")]
     pub size_of_self_counted: u64,
+    #[deku(assert_eq = "*APK_SIGNING_BLOCK_MAGIC")]
     pub magic: [u8; 16]
 }
 
+// The pairs are everything between the two sizes
+fn read_pairs<R: Read + Seek>(
+    reader: &mut Reader<R>,
+    size_of_self_not_counted: u64
+) -> Result<SigningBlockPairs, DekuError> {
+    let length = size_of_self_not_counted
+        .checked_sub(8 + 16)
+        .ok_or(DekuError::Parse(
+            "The APK Signing Block is too short.".into()
+        ))?;
+    SigningBlockPairs::from_prefixed_bytes(&read_prefixed_bytes(reader, length)?)
+}
+
 // This is in its own block so that we can determine its size before serialising its parent
-#[derive(Debug, PartialEq, DekuWrite, Clone)]
+#[derive(Debug, PartialEq, DekuRead, DekuWrite, Clone)]
 pub struct SigningBlockPairs {
+    #[deku(read_all)]
     pub pairs: Vec<U64LengthPrefixed<SigningBlockIdValuePair>>
 }
 
-#[derive(Debug, PartialEq, DekuWrite, Clone)]
+// Besides the v2 and v3 blocks, there can be pairs for other schemes, like
+// v3.1 or source stamps, and padding. Their values are kept as they are.
+#[derive(Debug, PartialEq, DekuRead, DekuWrite, Clone)]
 pub struct SigningBlockIdValuePair {
     pub id: u32,
+    #[deku(read_all)]
     pub value: Vec<u8>
 }
 
-#[derive(Debug, PartialEq, DekuWrite, Clone)]
+#[derive(Debug, PartialEq, DekuRead, DekuWrite, Clone)]
 pub struct SignatureSchemeV2Block {
     pub signers: U32LengthPrefixed<Vec<U32LengthPrefixed<Signer>>>
 }
 
-#[derive(Debug, PartialEq, DekuWrite, Clone)]
+#[derive(Debug, PartialEq, DekuRead, DekuWrite, Clone)]
 pub struct SignatureSchemeV3Block {
     pub signers: U32LengthPrefixed<Vec<U32LengthPrefixed<V3Signer>>>
 }
 
-#[derive(Debug, PartialEq, DekuWrite, Clone)]
+#[derive(Debug, PartialEq, DekuRead, DekuWrite, Clone)]
 pub struct Signer {
     pub signed_data: U32LengthPrefixed<SignedData>,
     pub signatures: U32LengthPrefixed<Vec<U32LengthPrefixed<Signature>>>,
@@ -60,7 +89,7 @@ pub struct Signer {
     pub public_key: U32LengthPrefixed<Vec<u8>>
 }
 
-#[derive(Debug, PartialEq, DekuWrite, Clone)]
+#[derive(Debug, PartialEq, DekuRead, DekuWrite, Clone)]
 pub struct V3Signer {
     pub signed_data: U32LengthPrefixed<V3SignedData>,
 
@@ -72,13 +101,13 @@ pub struct V3Signer {
     pub public_key: U32LengthPrefixed<Vec<u8>>
 }
 
-#[derive(Debug, PartialEq, DekuWrite, Clone)]
+#[derive(Debug, PartialEq, DekuRead, DekuWrite, Clone)]
 pub struct Signature {
     pub signature_algorithm_id: SignatureAlgorithmId,
     pub signature: U32LengthPrefixed<Vec<u8>>
 }
 
-#[derive(Debug, PartialEq, DekuWrite, Clone)]
+#[derive(Debug, PartialEq, DekuRead, DekuWrite, Clone)]
 pub struct SignedData {
     pub digests: U32LengthPrefixed<Vec<U32LengthPrefixed<Digest>>>,
     // Array of X.509 Certificates (ASN.1 DER form) as bytes
@@ -87,7 +116,7 @@ pub struct SignedData {
     pub additional_attributes: u32
 }
 
-#[derive(Debug, PartialEq, DekuWrite, Clone)]
+#[derive(Debug, PartialEq, DekuRead, DekuWrite, Clone)]
 pub struct V3SignedData {
     pub digests: U32LengthPrefixed<Vec<U32LengthPrefixed<Digest>>>,
     // Array of X.509 Certificates (ASN.1 DER form) as bytes
@@ -98,17 +127,22 @@ pub struct V3SignedData {
     pub additional_attributes: u32
 }
 
-#[derive(Debug, PartialEq, DekuWrite, Clone)]
+#[derive(Debug, PartialEq, DekuRead, DekuWrite, Clone)]
 pub struct Digest {
     pub signature_algorithm_id: SignatureAlgorithmId,
-    pub digest: U32LengthPrefixed<Sha256Hash>
+    pub digest: U32LengthPrefixed<Vec<u8>>
 }
 
-#[derive(Debug, PartialEq, DekuWrite, Clone)]
+#[derive(Debug, PartialEq, DekuRead, DekuWrite, Clone)]
 #[deku(id_type = "u32")]
 pub enum SignatureAlgorithmId {
     #[deku(id = 0x0103)]
-    RsaSsaPkcs1v1_5WithSha2_256
+    RsaSsaPkcs1v1_5WithSha2_256,
+    #[deku(id = 0x0104)]
+    RsaSsaPkcs1v1_5WithSha2_512,
+    // Algorithms that PACK doesn't sign with, but other signers might
+    #[deku(id_pat = "_")]
+    Other(u32)
 }
 
 // Helper structures
@@ -131,6 +165,94 @@ pub struct RawWrapper<T: DekuWriter> {
     pub value: T
 }
 
+// Reading a length-prefixed value reads its length, then parses exactly that
+// many bytes
+impl<'a, T: DekuWriter + PrefixedValue> DekuReader<'a> for U64LengthPrefixed<T> {
+    fn from_reader_with_ctx<R: Read + Seek>(
+        reader: &mut Reader<R>,
+        _: ()
+    ) -> Result<Self, DekuError> {
+        let length = u64::from_reader_with_ctx(reader, ())?;
+        let value = T::from_prefixed_bytes(&read_prefixed_bytes(reader, length)?)?;
+        Ok(U64LengthPrefixed { length, value })
+    }
+}
+
+impl<'a, T: DekuWriter + PrefixedValue> DekuReader<'a> for U32LengthPrefixed<T> {
+    fn from_reader_with_ctx<R: Read + Seek>(
+        reader: &mut Reader<R>,
+        _: ()
+    ) -> Result<Self, DekuError> {
+        let length = u32::from_reader_with_ctx(reader, ())?;
+        let value = T::from_prefixed_bytes(&read_prefixed_bytes(reader, length as u64)?)?;
+        Ok(U32LengthPrefixed { length, value })
+    }
+}
+
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+fn read_prefixed_bytes<R: Read + Seek>(
+    reader: &mut Reader<R>,
+    length: u64
+) -> Result<Vec<u8>, DekuError> {
+    let length = usize::try_from(length)
+        .map_err(|_| DekuError::Parse("A length prefix is too large.".into()))?;
+    // Read in chunks, so that a bogus length runs out of input rather than
+    // allocating all of it up front
+    let mut bytes = vec![];
+    while bytes.len() < length {
+        let chunk = (length - bytes.len()).min(READ_CHUNK_SIZE);
+        bytes.extend(Vec::<u8>::from_reader_with_ctx(
+            reader,
+            Limit::new_count(chunk)
+        )?);
+    }
+    Ok(bytes)
+}
+
+/// A value that fills the whole of a length-prefixed field.
+pub trait PrefixedValue: Sized {
+    fn from_prefixed_bytes(bytes: &[u8]) -> Result<Self, DekuError>;
+}
+
+// Lists take up however many bytes their prefix says
+impl<T: for<'a> DekuReader<'a>> PrefixedValue for Vec<T> {
+    fn from_prefixed_bytes(bytes: &[u8]) -> Result<Self, DekuError> {
+        let mut reader = Reader::new(Cursor::new(bytes));
+        Vec::from_reader_with_ctx(&mut reader, Limit::end())
+    }
+}
+
+// Everything else has to be exactly as long as its prefix
+macro_rules! prefixed_values {
+    ($($value:ty),*) => {
+        $(impl PrefixedValue for $value {
+            fn from_prefixed_bytes(bytes: &[u8]) -> Result<Self, DekuError> {
+                let mut reader = Reader::new(Cursor::new(bytes));
+                let value = <$value>::from_reader_with_ctx(&mut reader, ())?;
+                if !reader.end() {
+                    return Err(DekuError::Parse(
+                        concat!("A ", stringify!($value), " is shorter than its length prefix.").into()
+                    ));
+                }
+                Ok(value)
+            }
+        })*
+    };
+}
+prefixed_values!(
+    SigningBlockPairs,
+    SigningBlockIdValuePair,
+    SignatureSchemeV2Block,
+    SignatureSchemeV3Block,
+    Signer,
+    V3Signer,
+    Signature,
+    SignedData,
+    V3SignedData,
+    Digest
+);
+
 // Constructs length-prefixed things
 pub fn len_pfx_u32<T: DekuWriter + Clone>(thing: T) -> U32LengthPrefixed<T> {
     let wrap = RawWrapper {
@@ -37,6 +37,10 @@ const OID_PKCS7_SIGNED_DATA: &Oid = rasn::types::Oid::ISO_MEMBER_BODY_US_RSADSI_
 
 // TODO: It would seem that AAPT sorts these files before creating the manifest,
 //   This doesn't seem to be required but might be good for consistent output.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(name = "sign_v1", skip_all, fields(files = zip_contents.len()))
+)]
 pub fn add_v1_signature_files(zip_contents: &mut Vec<pack_zip::File>, keys: &Keys) -> Result<()> {
     // Create all META-INF files first so they don't hash themselves
     let manifest = create_manifest(zip_contents);
@@ -0,0 +1,705 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Checks the signatures on an existing APK or AAB, the way a device or Play
+// would, so that problems show up before uploading rather than after.
+//
+// Only RSA signatures can be fully checked. v2 and v3 signatures can use
+// SHA-256 or SHA-512, but v1 ones have to use SHA-256, which is what PACK,
+// apksigner and jarsigner produce by default. Anything else is reported as
+// unsupported rather than invalid.
+use std::collections::HashMap;
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+use byteorder::{ByteOrder, LittleEndian};
+use pack_common::*;
+use rsa::{pkcs8::DecodePublicKey, Pkcs1v15Sign, Pss, RsaPublicKey};
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::{
+    der::{Der, BMP_STRING, CONTEXT_0_CONSTRUCTED, OCTET_STRING, OID, SET},
+    hasher::{compute_top_level_hash, DigestAlgorithm, SigningConfig},
+    signed_data_block::{SIGNATURE_SCHEME_V2_BLOCK_ID, SIGNATURE_SCHEME_V3_BLOCK_ID},
+    signing_block::signing_block_before,
+    signing_types::ApkSigningBlock,
+    zip_parser::{find_offsets, ZipOffsets}
+};
+
+// Signature algorithm IDs from the APK Signature Scheme v2 spec
+const RSA_PSS_SHA256: u32 = 0x0101;
+const RSA_PSS_SHA512: u32 = 0x0102;
+const RSA_PKCS1_SHA256: u32 = 0x0103;
+const RSA_PKCS1_SHA512: u32 = 0x0104;
+
+// 2.16.840.1.101.3.4.2.1
+const OID_SHA256: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01];
+// 1.2.840.113549.1.9.4
+const OID_MESSAGE_DIGEST: &[u8] = &[0x2A, 0x86, 0x48, 0x86, 0xF7, 0x0D, 0x01, 0x09, 0x04];
+
+// Checking a scheme either returns its signers' certificates, or why it
+// didn't verify
+type SchemeResult<T> = std::result::Result<T, SchemeStatus>;
+
+const CENTRAL_DIRECTORY_MAGIC: u32 = 0x02014B50;
+const LOCAL_HEADER_MAGIC: u32 = 0x04034B50;
+
+/// Whether a package is signed with a particular scheme, and whether that
+/// signature is any good.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemeStatus {
+    /// The package isn't signed with this scheme.
+    Missing,
+    /// The signature matches the package's contents.
+    Verified,
+    /// The package is signed with an algorithm PACK can't check, eg. ECDSA.
+    Unsupported(String),
+    /// The signature is malformed, or doesn't match the package's contents.
+    Invalid(String)
+}
+
+/// A certificate that a package was signed with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignerCertificate {
+    /// X.509 certificate in ASN.1 DER form
+    pub der: Vec<u8>,
+    /// eg. `CN=Jane Doe, O=Example`
+    pub subject: String,
+    /// SHA-256 of the certificate, which is how Play Console identifies it.
+    pub sha256_fingerprint: [u8; 32]
+}
+
+impl SignerCertificate {
+    /// Describes an X.509 certificate in ASN.1 DER form.
+    pub fn from_der(der: Vec<u8>) -> Result<SignerCertificate> {
+        Ok(SignerCertificate {
+            subject: certificate_subject(&der)?,
+            sha256_fingerprint: Sha256::digest(&der).into(),
+            der
+        })
+    }
+
+    /// Describes the certificate in a `.pem` file, which can also contain a
+    /// private key like the ones [Keys::from_combined_pem_string](crate::crypto_keys::Keys::from_combined_pem_string)
+    /// reads.
+    pub fn from_pem(pem: &str) -> Result<SignerCertificate> {
+        let certificate = pem::parse_many(pem)?
+            .into_iter()
+            .find(|part| part.tag() == "CERTIFICATE")
+            .ok_or(PackError::SignerNoKeys)?;
+        Self::from_der(certificate.into_contents())
+    }
+}
+
+/// Everything [verify_package] found out about a package.
+#[derive(Debug, Clone)]
+pub struct VerificationReport {
+    /// The number of files in the zip, which were all read successfully.
+    pub entry_count: usize,
+    /// JAR signing, which AABs need for Play.
+    pub v1: SchemeStatus,
+    pub v2: SchemeStatus,
+    pub v3: SchemeStatus,
+    /// Every distinct certificate across all the schemes.
+    pub signers: Vec<SignerCertificate>,
+    /// Uncompressed files whose data doesn't start on a 4-byte boundary,
+    /// which stops Android from memory-mapping them.
+    pub misaligned_entries: Vec<String>
+}
+
+impl VerificationReport {
+    /// Whether at least one scheme verified, none were invalid, and the zip
+    /// is aligned.
+    pub fn is_valid(&self) -> bool {
+        let statuses = [&self.v1, &self.v2, &self.v3];
+        statuses
+            .iter()
+            .any(|status| **status == SchemeStatus::Verified)
+            && !statuses
+                .iter()
+                .any(|status| matches!(status, SchemeStatus::Invalid(_)))
+            && self.misaligned_entries.is_empty()
+    }
+}
+
+/// Checks a package's zip structure, alignment, and APK Signature Scheme v1,
+/// v2 and v3 signatures. Can be used for both APK and AAB files.
+///
+/// Returns an error if the zip itself can't be read. Problems with the
+/// signatures are reported in the [VerificationReport] instead.
+pub fn verify_package(package: &[u8]) -> Result<VerificationReport> {
+    // Reading every file also checks their CRCs
+    let files = pack_zip::unzip(package)?;
+    let offsets = find_offsets(package)?;
+
+    let mut signers = vec![];
+    let mut record = |result: SchemeResult<Vec<Vec<u8>>>| match result {
+        Ok(certificates) => {
+            signers.extend(certificates);
+            SchemeStatus::Verified
+        }
+        Err(status) => status
+    };
+    let v1 = record(verify_v1(&files));
+    let (v2, v3) = match find_signing_block(package, offsets.cd_start) {
+        Ok(Some((block_start, block))) => {
+            let mut verify = |id, is_v3| {
+                block.value(id).map_or(SchemeStatus::Missing, |scheme| {
+                    record(verify_signature_scheme_block(
+                        package,
+                        &offsets,
+                        block_start,
+                        scheme,
+                        is_v3
+                    ))
+                })
+            };
+            (
+                verify(SIGNATURE_SCHEME_V2_BLOCK_ID, false),
+                verify(SIGNATURE_SCHEME_V3_BLOCK_ID, true)
+            )
+        }
+        Ok(None) => (SchemeStatus::Missing, SchemeStatus::Missing),
+        Err(status) => (status.clone(), status)
+    };
+
+    let mut signer_certificates: Vec<SignerCertificate> = vec![];
+    for der in signers {
+        if signer_certificates.iter().any(|signer| signer.der == der) {
+            continue;
+        }
+        signer_certificates.push(SignerCertificate {
+            subject: certificate_subject(&der).unwrap_or_default(),
+            sha256_fingerprint: Sha256::digest(&der).into(),
+            der
+        });
+    }
+
+    Ok(VerificationReport {
+        entry_count: files.len(),
+        v1,
+        v2,
+        v3,
+        signers: signer_certificates,
+        misaligned_entries: find_misaligned_entries(package, offsets.cd_start, offsets.eocd_start)?
+    })
+}
+
+/// Returns the size in bytes of a package's APK Signing Block, which holds its
+/// v2 and v3 signatures, or 0 if it doesn't have one or it's malformed.
+pub fn signing_block_size(package: &[u8]) -> Result<usize> {
+    let cd_start = find_offsets(package)?.cd_start;
+    Ok(match find_signing_block(package, cd_start) {
+        Ok(Some((block_start, _))) => cd_start - block_start,
+        _ => 0
+    })
+}
+
+// Returns where the APK Signing Block right before the central directory
+// starts, and the block, if there is one
+fn find_signing_block(
+    package: &[u8],
+    cd_start: usize
+) -> SchemeResult<Option<(usize, ApkSigningBlock)>> {
+    signing_block_before(package, cd_start).map_err(|e| SchemeStatus::Invalid(e.to_string()))
+}
+
+// Verifies a v2 or v3 block, returning the certificates of its signers
+fn verify_signature_scheme_block(
+    package: &[u8],
+    offsets: &ZipOffsets,
+    block_start: usize,
+    mut block: &[u8],
+    is_v3: bool
+) -> SchemeResult<Vec<Vec<u8>>> {
+    let malformed = || SchemeStatus::Invalid("The signature block is malformed.".into());
+    let mut signers = read_u32_prefixed(&mut block).ok_or_else(malformed)?;
+    if signers.is_empty() {
+        return Err(SchemeStatus::Invalid("There are no signers.".into()));
+    }
+
+    let mut certificates = vec![];
+    while !signers.is_empty() {
+        let mut signer = read_u32_prefixed(&mut signers).ok_or_else(malformed)?;
+        let signed_data = read_u32_prefixed(&mut signer).ok_or_else(malformed)?;
+        if is_v3 {
+            // The signer's SDK range, which is also inside the signed data
+            read_u32(&mut signer).ok_or_else(malformed)?;
+            read_u32(&mut signer).ok_or_else(malformed)?;
+        }
+        let mut signatures = read_u32_prefixed(&mut signer).ok_or_else(malformed)?;
+        let public_key_der = read_u32_prefixed(&mut signer).ok_or_else(malformed)?;
+
+        // Check the strongest signature that can be checked
+        let mut best: Option<(u32, &[u8])> = None;
+        let mut unsupported = None;
+        while !signatures.is_empty() {
+            let mut signature = read_u32_prefixed(&mut signatures).ok_or_else(malformed)?;
+            let algorithm = read_u32(&mut signature).ok_or_else(malformed)?;
+            let signature = read_u32_prefixed(&mut signature).ok_or_else(malformed)?;
+            match algorithm {
+                RSA_PSS_SHA256 | RSA_PSS_SHA512 | RSA_PKCS1_SHA256 | RSA_PKCS1_SHA512 => {
+                    if best.is_none_or(|(best, _)| {
+                        algorithm_strength(algorithm) > algorithm_strength(best)
+                    }) {
+                        best = Some((algorithm, signature));
+                    }
+                }
+                _ => unsupported = Some(algorithm)
+            }
+        }
+        let Some((algorithm, signature)) = best else {
+            return Err(SchemeStatus::Unsupported(format!(
+                "Signature algorithm {:#06x} isn't supported.",
+                unsupported.unwrap_or_default()
+            )));
+        };
+
+        let public_key = RsaPublicKey::from_public_key_der(public_key_der)
+            .map_err(|_| SchemeStatus::Invalid("The signer's public key is malformed.".into()))?;
+        if !verify_rsa(&public_key, algorithm, signed_data, signature) {
+            return Err(SchemeStatus::Invalid(
+                "The signature doesn't match the signed data.".into()
+            ));
+        }
+
+        // The signed data is now trustworthy, so check what it says
+        let mut signed_data = signed_data;
+        let mut digests = read_u32_prefixed(&mut signed_data).ok_or_else(malformed)?;
+        let mut signer_certificates = read_u32_prefixed(&mut signed_data).ok_or_else(malformed)?;
+        let mut digest = None;
+        while !digests.is_empty() {
+            let mut entry = read_u32_prefixed(&mut digests).ok_or_else(malformed)?;
+            if read_u32(&mut entry).ok_or_else(malformed)? == algorithm {
+                digest = read_u32_prefixed(&mut entry);
+            }
+        }
+        let digest = digest.ok_or(SchemeStatus::Invalid(
+            "The signed data has no digest for the signature's algorithm.".into()
+        ))?;
+        let digest_algorithm = match algorithm {
+            RSA_PSS_SHA512 | RSA_PKCS1_SHA512 => DigestAlgorithm::Sha512,
+            _ => DigestAlgorithm::Sha256
+        };
+        if content_digest(package, offsets, block_start, digest_algorithm)? != digest {
+            return Err(SchemeStatus::Invalid(
+                "The package's contents have changed since it was signed.".into()
+            ));
+        }
+
+        let first_certificate = read_u32_prefixed(&mut signer_certificates).ok_or(
+            SchemeStatus::Invalid("The signer has no certificate.".into())
+        )?;
+        let certificate_key = certificate_public_key(first_certificate)
+            .map_err(|e| SchemeStatus::Invalid(e.to_string()))?;
+        if certificate_key != public_key_der {
+            return Err(SchemeStatus::Invalid(
+                "The signer's public key doesn't match its certificate.".into()
+            ));
+        }
+        certificates.push(first_certificate.to_vec());
+    }
+    Ok(certificates)
+}
+
+// Recomputes the chunked digest of the package as it was before the signing
+// block was added
+fn content_digest(
+    package: &[u8],
+    offsets: &ZipOffsets,
+    block_start: usize,
+    digest_algorithm: DigestAlgorithm
+) -> SchemeResult<Vec<u8>> {
+    let mut unsigned = package[..block_start].to_vec();
+    unsigned.extend_from_slice(&package[offsets.cd_start..]);
+    // Point the EOCD back at where the central directory was before signing
+    let eocd_start = offsets.eocd_start - (offsets.cd_start - block_start);
+    unsigned[eocd_start + 16..eocd_start + 20].copy_from_slice(&(block_start as u32).to_le_bytes());
+
+    let invalid = |e: PackError| SchemeStatus::Invalid(e.to_string());
+    let unsigned_offsets = find_offsets(&unsigned).map_err(invalid)?;
+    let config = SigningConfig {
+        digest_algorithm,
+        ..SigningConfig::default()
+    };
+    compute_top_level_hash(&mut unsigned, &unsigned_offsets, 0, &config).map_err(invalid)
+}
+
+// Android picks the strongest of a signer's signatures
+fn algorithm_strength(algorithm: u32) -> u32 {
+    match algorithm {
+        RSA_PKCS1_SHA256 => 1,
+        RSA_PSS_SHA256 => 2,
+        RSA_PKCS1_SHA512 => 3,
+        _ => 4
+    }
+}
+
+fn verify_rsa(public_key: &RsaPublicKey, algorithm: u32, data: &[u8], signature: &[u8]) -> bool {
+    match algorithm {
+        RSA_PKCS1_SHA256 => public_key
+            .verify(
+                Pkcs1v15Sign::new::<Sha256>(),
+                &Sha256::digest(data),
+                signature
+            )
+            .is_ok(),
+        RSA_PKCS1_SHA512 => public_key
+            .verify(
+                Pkcs1v15Sign::new::<Sha512>(),
+                &Sha512::digest(data),
+                signature
+            )
+            .is_ok(),
+        RSA_PSS_SHA256 => public_key
+            .verify(Pss::new::<Sha256>(), &Sha256::digest(data), signature)
+            .is_ok(),
+        RSA_PSS_SHA512 => public_key
+            .verify(Pss::new::<Sha512>(), &Sha512::digest(data), signature)
+            .is_ok(),
+        _ => false
+    }
+}
+
+// Checks JAR signing: the manifest lists every file's digest, the .SF file
+// has the manifest's digest, and the PKCS #7 block signs the .SF file
+fn verify_v1(files: &[pack_zip::File]) -> SchemeResult<Vec<Vec<u8>>> {
+    let Some(signature_file) = files
+        .iter()
+        .find(|file| file.path.starts_with("META-INF/") && file.path.ends_with(".SF"))
+    else {
+        return Err(SchemeStatus::Missing);
+    };
+    let base_path = signature_file.path.trim_end_matches(".SF");
+    let signature_block = files
+        .iter()
+        .find(|file| {
+            [".RSA", ".EC", ".DSA"]
+                .iter()
+                .any(|extension| file.path == format!("{base_path}{extension}"))
+        })
+        .ok_or(SchemeStatus::Invalid(format!(
+            "{} has no matching signature block.",
+            signature_file.path
+        )))?;
+    if !signature_block.path.ends_with(".RSA") {
+        return Err(SchemeStatus::Unsupported(format!(
+            "{} isn't an RSA signature.",
+            signature_block.path
+        )));
+    }
+    let manifest = files
+        .iter()
+        .find(|file| file.path == "META-INF/MANIFEST.MF")
+        .ok_or(SchemeStatus::Invalid(
+            "META-INF/MANIFEST.MF is missing.".into()
+        ))?;
+
+    let certificate = verify_pkcs7(&signature_block.data, &signature_file.data)
+        .map_err(|e| SchemeStatus::Invalid(e.to_string()))??;
+
+    let signature_file_text = String::from_utf8_lossy(&signature_file.data);
+    let manifest_digest = manifest_sections(&signature_file_text)
+        .first()
+        .and_then(|main| main.get("SHA-256-Digest-Manifest").cloned())
+        .ok_or(SchemeStatus::Unsupported(format!(
+            "{} doesn't have a SHA-256-Digest-Manifest.",
+            signature_file.path
+        )))?;
+    if manifest_digest != BASE64_STANDARD.encode(Sha256::digest(&manifest.data)) {
+        return Err(SchemeStatus::Invalid(format!(
+            "{} doesn't match META-INF/MANIFEST.MF.",
+            signature_file.path
+        )));
+    }
+
+    // The first section holds the manifest's own attributes
+    let manifest_text = String::from_utf8_lossy(&manifest.data);
+    let entries: HashMap<String, HashMap<String, String>> = manifest_sections(&manifest_text)
+        .into_iter()
+        .skip(1)
+        .filter_map(|section| Some((section.get("Name")?.clone(), section)))
+        .collect();
+    for file in files {
+        if is_signature_related(&file.path) {
+            continue;
+        }
+        let Some(entry) = entries.get(&file.path) else {
+            return Err(SchemeStatus::Invalid(format!(
+                "{} isn't in META-INF/MANIFEST.MF.",
+                file.path
+            )));
+        };
+        let Some(digest) = entry.get("SHA-256-Digest") else {
+            return Err(SchemeStatus::Unsupported(format!(
+                "{} doesn't have a SHA-256 digest.",
+                file.path
+            )));
+        };
+        if *digest != BASE64_STANDARD.encode(Sha256::digest(&file.data)) {
+            return Err(SchemeStatus::Invalid(format!(
+                "{} has changed since it was signed.",
+                file.path
+            )));
+        }
+    }
+
+    Ok(vec![certificate])
+}
+
+/// Whether a file in a package is part of its JAR signature, eg.
+/// `META-INF/MANIFEST.MF` or `META-INF/CERT.RSA`, which JAR signing doesn't
+/// cover.
+pub fn is_signature_related(path: &str) -> bool {
+    let Some(name) = path.strip_prefix("META-INF/") else {
+        return false;
+    };
+    !name.contains('/')
+        && (name == "MANIFEST.MF"
+            || name.starts_with("SIG-")
+            || [".SF", ".RSA", ".DSA", ".EC"]
+                .iter()
+                .any(|extension| name.ends_with(extension)))
+}
+
+// Splits a manifest into sections of attributes, joining continuation lines
+fn manifest_sections(text: &str) -> Vec<HashMap<String, String>> {
+    let mut sections = vec![];
+    let mut section: HashMap<String, String> = HashMap::new();
+    let mut last_key: Option<String> = None;
+    for line in text.split('\n').map(|line| line.trim_end_matches('\r')) {
+        if line.is_empty() {
+            if !section.is_empty() {
+                sections.push(std::mem::take(&mut section));
+            }
+            last_key = None;
+        } else if let (Some(continuation), Some(key)) = (line.strip_prefix(' '), &last_key) {
+            if let Some(value) = section.get_mut(key) {
+                value.push_str(continuation);
+            }
+        } else if let Some((key, value)) = line.split_once(": ") {
+            section.insert(key.into(), value.into());
+            last_key = Some(key.into());
+        }
+    }
+    if !section.is_empty() {
+        sections.push(section);
+    }
+    sections
+}
+
+// Checks a PKCS #7 SignedData over `content`, returning the signer's
+// certificate. The outer Result is for malformed data.
+fn verify_pkcs7(signature_block: &[u8], content: &[u8]) -> Result<SchemeResult<Vec<u8>>> {
+    let mut content_info = Der::new(signature_block).sequence()?;
+    content_info.read(OID)?;
+    let mut signed_data = Der::new(content_info.read(CONTEXT_0_CONSTRUCTED)?).sequence()?;
+    signed_data.read_u32()?;
+    signed_data.read(SET)?;
+    signed_data.sequence()?;
+    let mut certificates = vec![];
+    if signed_data.peek_tag() == Some(CONTEXT_0_CONSTRUCTED) {
+        let mut certificate_set = Der::new(signed_data.read(CONTEXT_0_CONSTRUCTED)?);
+        while !certificate_set.is_empty() {
+            let (_, _, certificate) = certificate_set.read_any()?;
+            certificates.push(certificate);
+        }
+    }
+    // Skip the CRLs
+    if signed_data.peek_tag() == Some(0xA1) {
+        signed_data.read_any()?;
+    }
+    let mut signer_infos = Der::new(signed_data.read(SET)?);
+    let mut signer_info = signer_infos.sequence()?;
+    signer_info.read_u32()?;
+    // The signer's identifier. There's normally only one certificate anyway.
+    signer_info.read_any()?;
+    let digest_algorithm = signer_info.sequence()?.read(OID)?;
+    if digest_algorithm != OID_SHA256 {
+        return Ok(Err(SchemeStatus::Unsupported(
+            "The JAR signature doesn't use SHA-256.".into()
+        )));
+    }
+    let content_digest = Sha256::digest(content);
+    // With signed attributes, the signature covers them rather than the
+    // content, and they include the content's digest
+    let signed = if signer_info.peek_tag() == Some(CONTEXT_0_CONSTRUCTED) {
+        let (_, attributes, encoded) = signer_info.read_any()?;
+        let mut attributes = Der::new(attributes);
+        let mut message_digest = None;
+        while !attributes.is_empty() {
+            let mut attribute = attributes.sequence()?;
+            if attribute.read(OID)? == OID_MESSAGE_DIGEST {
+                message_digest = Some(Der::new(attribute.read(SET)?).read(OCTET_STRING)?);
+            }
+        }
+        if message_digest != Some(&content_digest[..]) {
+            return Ok(Err(SchemeStatus::Invalid(
+                "The JAR signature's digest doesn't match the .SF file.".into()
+            )));
+        }
+        // They're signed as a SET rather than with their implicit tag
+        let mut signed_attributes = encoded.to_vec();
+        signed_attributes[0] = SET;
+        Sha256::digest(&signed_attributes)
+    } else {
+        content_digest
+    };
+    signer_info.sequence()?;
+    let signature = signer_info.read(OCTET_STRING)?;
+
+    for certificate in certificates {
+        let public_key = RsaPublicKey::from_public_key_der(certificate_public_key(certificate)?);
+        if public_key.is_ok_and(|public_key| {
+            public_key
+                .verify(Pkcs1v15Sign::new::<Sha256>(), &signed, signature)
+                .is_ok()
+        }) {
+            return Ok(Ok(certificate.to_vec()));
+        }
+    }
+    Ok(Err(SchemeStatus::Invalid(
+        "The JAR signature doesn't match any of its certificates.".into()
+    )))
+}
+
+// Returns a certificate's SubjectPublicKeyInfo, ASN.1 DER form
+fn certificate_public_key(certificate: &[u8]) -> Result<&[u8]> {
+    let mut tbs_certificate = tbs_certificate(certificate)?;
+    // Serial number, signature algorithm, issuer, validity and subject
+    for _ in 0..5 {
+        tbs_certificate.read_any()?;
+    }
+    let (_, _, public_key) = tbs_certificate.read_any()?;
+    Ok(public_key)
+}
+
+/// Formats a certificate's subject like `CN=Jane Doe, O=Example`.
+pub fn certificate_subject(certificate: &[u8]) -> Result<String> {
+    const ATTRIBUTE_NAMES: &[(&[u8], &str)] = &[
+        (&[0x55, 0x04, 0x03], "CN"),
+        (&[0x55, 0x04, 0x06], "C"),
+        (&[0x55, 0x04, 0x07], "L"),
+        (&[0x55, 0x04, 0x08], "ST"),
+        (&[0x55, 0x04, 0x0A], "O"),
+        (&[0x55, 0x04, 0x0B], "OU")
+    ];
+    let mut tbs_certificate = tbs_certificate(certificate)?;
+    // Serial number, signature algorithm, issuer and validity
+    for _ in 0..4 {
+        tbs_certificate.read_any()?;
+    }
+    let mut subject = tbs_certificate.sequence()?;
+    let mut parts = vec![];
+    while !subject.is_empty() {
+        let mut relative_name = Der::new(subject.read(SET)?);
+        while !relative_name.is_empty() {
+            let mut attribute = relative_name.sequence()?;
+            let oid = attribute.read(OID)?;
+            let (tag, value, _) = attribute.read_any()?;
+            let value = match tag {
+                BMP_STRING => String::from_utf16_lossy(
+                    &value
+                        .chunks_exact(2)
+                        .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                        .collect::<Vec<_>>()
+                ),
+                _ => String::from_utf8_lossy(value).into_owned()
+            };
+            let name = ATTRIBUTE_NAMES
+                .iter()
+                .find(|(attribute_oid, _)| *attribute_oid == oid)
+                .map_or("?", |(_, name)| name);
+            parts.push(format!("{name}={value}"));
+        }
+    }
+    // Names are conventionally written most specific first, the reverse of
+    // how they're encoded
+    parts.reverse();
+    Ok(parts.join(", "))
+}
+
+// Returns a reader positioned at the serial number of a certificate's
+// TBSCertificate
+fn tbs_certificate(certificate: &[u8]) -> Result<Der<'_>> {
+    let mut tbs_certificate = Der::new(certificate).sequence()?.sequence()?;
+    // The version is optional, and explicitly tagged
+    if tbs_certificate.peek_tag() == Some(CONTEXT_0_CONSTRUCTED) {
+        tbs_certificate.read_any()?;
+    }
+    Ok(tbs_certificate)
+}
+
+// Finds uncompressed entries whose data isn't 4-byte aligned, by walking the
+// central directory to each local header
+fn find_misaligned_entries(
+    package: &[u8],
+    cd_start: usize,
+    eocd_start: usize
+) -> Result<Vec<String>> {
+    let malformed = || PackError::ZipStructureInvalid("The central directory is malformed.".into());
+    let read_u16 = |offset: usize| -> Result<usize> {
+        package
+            .get(offset..offset + 2)
+            .map(|bytes| LittleEndian::read_u16(bytes) as usize)
+            .ok_or_else(malformed)
+    };
+    let read_u32 = |offset: usize| -> Result<usize> {
+        package
+            .get(offset..offset + 4)
+            .map(|bytes| LittleEndian::read_u32(bytes) as usize)
+            .ok_or_else(malformed)
+    };
+
+    let mut misaligned = vec![];
+    let mut offset = cd_start;
+    while offset < eocd_start {
+        if read_u32(offset)? != CENTRAL_DIRECTORY_MAGIC as usize {
+            return Err(malformed());
+        }
+        let compression_method = read_u16(offset + 10)?;
+        let name_len = read_u16(offset + 28)?;
+        let extra_len = read_u16(offset + 30)?;
+        let comment_len = read_u16(offset + 32)?;
+        let local_header = read_u32(offset + 42)?;
+        let name = package
+            .get(offset + 46..offset + 46 + name_len)
+            .ok_or_else(malformed)?;
+
+        if read_u32(local_header)? != LOCAL_HEADER_MAGIC as usize {
+            return Err(malformed());
+        }
+        let data_start =
+            local_header + 30 + read_u16(local_header + 26)? + read_u16(local_header + 28)?;
+        // Directories don't have any data to align
+        if compression_method == 0 && !name.ends_with(b"/") && data_start % 4 != 0 {
+            misaligned.push(String::from_utf8_lossy(name).into_owned());
+        }
+
+        offset += 46 + name_len + extra_len + comment_len;
+    }
+    Ok(misaligned)
+}
+
+fn read_u32(data: &mut &[u8]) -> Option<u32> {
+    let value = LittleEndian::read_u32(data.get(..4)?);
+    *data = &data[4..];
+    Some(value)
+}
+
+fn read_u32_prefixed<'a>(data: &mut &'a [u8]) -> Option<&'a [u8]> {
+    let len = read_u32(data)? as usize;
+    let value = data.get(..len)?;
+    *data = &data[len..];
+    Some(value)
+}
@@ -26,25 +26,29 @@ pub struct ZipOffsets {
 
 pub const EOCD_MAGIC: &[u8; 4] = &[0x50, 0x4B, 0x05, 0x06];
 
+/// Finds the central directory and End of Central Directory record of a zip
+/// file. Never panics, however malformed `zip_buf` is.
 pub fn find_offsets(zip_buf: &[u8]) -> Result<ZipOffsets> {
-    let mut offsets = ZipOffsets::default();
-    for i in (0..=(zip_buf.len() - 4)).rev() {
-        let magic = &zip_buf[i..(i + 4)];
-        if magic == EOCD_MAGIC {
-            // Found the end of central directory!
-            offsets.eocd_start = i;
+    // The End of Central Directory is at the end, only followed by a comment
+    let eocd_start = zip_buf
+        .windows(EOCD_MAGIC.len())
+        .rposition(|magic| magic == EOCD_MAGIC)
+        .ok_or(PackError::SignerZipParsingFailed)?;
 
-            // The EOCD also tells us where the central directories start
-            let mut eocd_cd_start_field = Cursor::new(&zip_buf[(i + 16)..(i + 20)]);
-            let cd_start = eocd_cd_start_field.read_u32::<LittleEndian>()?;
-            offsets.cd_start = cd_start as usize;
-            break;
-        }
-    }
+    // The EOCD also tells us where the central directories start
+    let eocd_cd_start_field = zip_buf
+        .get((eocd_start + 16)..(eocd_start + 20))
+        .ok_or(PackError::SignerZipParsingFailed)?;
+    let cd_start = Cursor::new(eocd_cd_start_field).read_u32::<LittleEndian>()? as usize;
 
-    match offsets.cd_start {
+    match cd_start {
         // Couldn't find the central directory
         0 => Err(PackError::SignerZipParsingFailed),
-        _ => Ok(offsets)
+        // It has to come before the EOCD for the zip to be split around it
+        _ if cd_start > eocd_start => Err(PackError::SignerZipParsingFailed),
+        _ => Ok(ZipOffsets {
+            cd_start,
+            eocd_start
+        })
     }
 }
@@ -0,0 +1,82 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Errors are thrown to JS as objects rather than strings, so that the page can
+// eg. highlight the XML file that's broken instead of showing a blob of text.
+use pack_api::{ErrorCode, PackError};
+use serde::{Serialize, Serializer};
+use wasm_bindgen::prelude::*;
+
+// Thrown as eg. `{ code: "XmlParsingFailed", id: "PACK2001", message: "...",
+// path: "res/xml/watchface.xml", line: 12, column: 5 }`
+#[derive(Debug, Serialize)]
+pub struct PackWasmError {
+    /// The name of the PackError variant, or `InvalidInput` if the JS input
+    /// itself was wrong, `InvalidOutput` if a result couldn't be converted to
+    /// JS, or `Cancelled` if a progress callback threw
+    pub code: String,
+    /// The stable ErrorCode, eg. `PACK2001`, for PackErrors
+    #[serde(serialize_with = "serialize_id")]
+    pub id: Option<ErrorCode>,
+    pub message: String,
+    pub path: Option<String>,
+    /// Starting from 1
+    pub line: Option<u64>,
+    /// Starting from 1
+    pub column: Option<u64>
+}
+
+impl PackWasmError {
+    pub fn new(code: &str, message: impl Into<String>) -> Self {
+        PackWasmError {
+            code: code.into(),
+            id: None,
+            message: message.into(),
+            path: None,
+            line: None,
+            column: None
+        }
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::new("InvalidInput", message)
+    }
+}
+
+impl From<PackError> for PackWasmError {
+    fn from(err: PackError) -> Self {
+        let (line, column) = err.line_column().unzip();
+        PackWasmError {
+            code: err.code().name().into(),
+            id: Some(err.code()),
+            message: err.root().to_string(),
+            path: err.path().map(String::from),
+            line,
+            column
+        }
+    }
+}
+
+fn serialize_id<S: Serializer>(id: &Option<ErrorCode>, serializer: S) -> Result<S::Ok, S::Error> {
+    match id {
+        Some(id) => serializer.collect_str(id),
+        None => serializer.serialize_none()
+    }
+}
+
+impl From<PackWasmError> for JsValue {
+    fn from(err: PackWasmError) -> Self {
+        serde_wasm_bindgen::to_value(&err).unwrap_or_else(|_| JsValue::from_str(&err.message))
+    }
+}
@@ -12,7 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use pack_api::{Package, ResourceConfig, Result};
 use serde::{Deserialize, Serialize};
+use serde_bytes::ByteBuf;
 
 // This comment can't be inside the struct due to
 // https://github.com/rust-lang/rustfmt/issues/3379?issue=rust-lang%7Crustfmt%7C6347
@@ -21,7 +23,11 @@ use serde::{Deserialize, Serialize};
 pub struct PackWasmResource {
     pub subdirectory: String,
     pub name: String,
-    pub contents_b64: String
+    pub contents_b64: String,
+    /// Which devices the file is for. Can also be given in the subdirectory,
+    /// eg. `drawable-xxhdpi`.
+    #[serde(default)]
+    pub qualifiers: Option<PackWasmQualifiers>
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -35,3 +41,89 @@ pub struct PackWasmInput {
     /// if `true`: Generates an Android App Bundle for Google Play.
     pub generate_aab: bool
 }
+
+// The same as the above, but with files as Uint8Arrays instead of Base64, which
+// saves encoding and decoding large images on both sides.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PackWasmBytesResource {
+    pub subdirectory: String,
+    pub name: String,
+    pub contents: ByteBuf,
+    #[serde(default)]
+    pub qualifiers: Option<PackWasmQualifiers>
+}
+
+// A pack_api::Package as is, so the same JSON package description can be
+// built from Rust or the web. Modules make it possible to build bundles with
+// asset packs.
+#[derive(Deserialize)]
+pub struct PackWasmPackageInput {
+    pub package: Package,
+    pub combined_pem: String,
+    #[serde(default)]
+    pub generate_aab: bool
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PackWasmBytesInput {
+    pub resources: Vec<PackWasmBytesResource>,
+    pub manifest: ByteBuf,
+    /// Contents of a `.pem` file containing both a `BEGIN CERTIFICATE` and `BEGIN PRIVATE KEY` section
+    pub combined_pem: ByteBuf,
+    /// If `false`: Generates an APK file for local device testing.
+    ///
+    /// if `true`: Generates an Android App Bundle for Google Play.
+    pub generate_aab: bool
+}
+
+// eg. `{ locale: "de-AT", round: true, density: "xxhdpi" }` for a file that
+// would otherwise be in `drawable-de-rAT-round-xxhdpi`. Everything is optional.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PackWasmQualifiers {
+    /// BCP-47 language and optional region, eg. `de` or `de-AT`
+    pub locale: Option<String>,
+    /// `true` for round screens only, `false` for other screens only
+    pub round: Option<bool>,
+    /// eg. `xxhdpi`
+    pub density: Option<String>,
+    /// The minimum platform version, eg. `33`
+    pub sdk_version: Option<u16>
+}
+
+impl PackWasmQualifiers {
+    /// Parses the qualifiers as though they were part of the subdirectory's
+    /// name, so that they're checked the same way.
+    pub fn to_config(&self, subdirectory: &str) -> Result<ResourceConfig> {
+        let mut directory = subdirectory.to_string();
+        if let Some(locale) = &self.locale {
+            match locale.split_once('-') {
+                Some((language, region)) => directory += &format!("-{language}-r{region}"),
+                None => directory += &format!("-{locale}")
+            }
+        }
+        match self.round {
+            Some(true) => directory += "-round",
+            Some(false) => directory += "-notround",
+            None => {}
+        }
+        if let Some(density) = &self.density {
+            directory += &format!("-{density}");
+        }
+        if let Some(sdk_version) = self.sdk_version {
+            directory += &format!("-v{sdk_version}");
+        }
+        let (_, config) = ResourceConfig::parse_directory(&directory)?;
+        Ok(config)
+    }
+}
+
+// Everything is optional, see pack_api::KeyOptions for the defaults
+#[cfg(feature = "cert-gen")]
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PackWasmKeyOptions {
+    pub common_name: Option<String>,
+    pub validity_days: Option<u32>,
+    pub rsa_bits: Option<usize>
+}
@@ -0,0 +1,191 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Checks and describes APKs and AABs that users upload, so that the web tool
+// can eg. tell whether a package is signed with the same key as last time,
+// without sending it anywhere.
+use pack_api::{
+    decode_resource_table, decode_xml, verify_package, BinaryValue, DecodedElement, DecodedTable,
+    SchemeStatus
+};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    b64_to_bytes,
+    errors::PackWasmError,
+    keys::{describe, PackWasmCertificate}
+};
+
+#[derive(Debug, Serialize)]
+pub struct PackWasmVerification {
+    /// Whether at least one scheme verified, none were invalid, and the zip
+    /// is aligned
+    pub valid: bool,
+    pub entry_count: usize,
+    pub v1: PackWasmSchemeStatus,
+    pub v2: PackWasmSchemeStatus,
+    pub v3: PackWasmSchemeStatus,
+    /// Every distinct certificate across all the schemes
+    pub signers: Vec<PackWasmCertificate>,
+    /// Uncompressed files that aren't 4-byte aligned
+    pub misaligned_entries: Vec<String>
+}
+
+#[derive(Debug, Serialize)]
+pub struct PackWasmSchemeStatus {
+    /// `missing`, `verified`, `unsupported` or `invalid`
+    pub status: &'static str,
+    /// Why the scheme is unsupported or invalid
+    pub reason: Option<String>
+}
+
+#[derive(Debug, Serialize)]
+pub struct PackWasmInspection {
+    pub files: Vec<PackWasmFile>,
+    /// Only for APKs, since bundles store their manifest differently
+    pub manifest: Option<PackWasmManifest>
+}
+
+#[derive(Debug, Serialize)]
+pub struct PackWasmFile {
+    pub path: String,
+    pub size: usize
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct PackWasmManifest {
+    pub package: Option<String>,
+    pub version_code: Option<String>,
+    pub version_name: Option<String>,
+    pub min_sdk_version: Option<String>,
+    pub target_sdk_version: Option<String>
+}
+
+// Checks an APK or AAB's signatures, alignment and zip structure. Takes the
+// package as a Uint8Array, or in Base64.
+#[wasm_bindgen]
+pub fn verify(package: JsValue) -> Result<JsValue, PackWasmError> {
+    let report = verify_package(&package_bytes(package)?)?;
+    to_js(&PackWasmVerification {
+        valid: report.is_valid(),
+        entry_count: report.entry_count,
+        v1: scheme_status(report.v1),
+        v2: scheme_status(report.v2),
+        v3: scheme_status(report.v3),
+        signers: report
+            .signers
+            .into_iter()
+            .map(|signer| describe(&signer))
+            .collect(),
+        misaligned_entries: report.misaligned_entries
+    })
+}
+
+// Lists the files in an APK or AAB, and for APKs, what the manifest declares.
+// Takes the package as a Uint8Array, or in Base64.
+#[wasm_bindgen]
+pub fn inspect(package: JsValue) -> Result<JsValue, PackWasmError> {
+    let files = pack_zip::unzip(&package_bytes(package)?)?;
+    let find = |path: &str| files.iter().find(|file| file.path == path);
+    let manifest = match find("AndroidManifest.xml") {
+        Some(manifest) => {
+            let table = match find("resources.arsc") {
+                Some(table) => decode_resource_table(&table.data)?,
+                None => DecodedTable::default()
+            };
+            Some(describe_manifest(&decode_xml(&manifest.data)?, &table))
+        }
+        None => None
+    };
+    to_js(&PackWasmInspection {
+        files: files
+            .iter()
+            .map(|file| PackWasmFile {
+                path: file.path.clone(),
+                size: file.data.len()
+            })
+            .collect(),
+        manifest
+    })
+}
+
+fn package_bytes(package: JsValue) -> Result<Vec<u8>, PackWasmError> {
+    if let Some(b64) = package.as_string() {
+        b64_to_bytes(&b64)
+    } else if package.is_instance_of::<js_sys::Uint8Array>() {
+        Ok(js_sys::Uint8Array::new(&package).to_vec())
+    } else {
+        Err(PackWasmError::invalid_input(
+            "Expected the package as a Uint8Array or a Base64 string"
+        ))
+    }
+}
+
+fn scheme_status(status: SchemeStatus) -> PackWasmSchemeStatus {
+    let (status, reason) = match status {
+        SchemeStatus::Missing => ("missing", None),
+        SchemeStatus::Verified => ("verified", None),
+        SchemeStatus::Unsupported(reason) => ("unsupported", Some(reason)),
+        SchemeStatus::Invalid(reason) => ("invalid", Some(reason))
+    };
+    PackWasmSchemeStatus { status, reason }
+}
+
+fn describe_manifest(manifest: &DecodedElement, table: &DecodedTable) -> PackWasmManifest {
+    let attribute = |element: &DecodedElement, name: &str| {
+        element
+            .attribute(name)
+            .and_then(|value| format_value(value, table))
+    };
+    let uses_sdk = manifest.children_named("uses-sdk").next();
+    PackWasmManifest {
+        package: attribute(manifest, "package"),
+        version_code: attribute(manifest, "versionCode"),
+        version_name: attribute(manifest, "versionName"),
+        min_sdk_version: uses_sdk.and_then(|uses_sdk| attribute(uses_sdk, "minSdkVersion")),
+        target_sdk_version: uses_sdk.and_then(|uses_sdk| attribute(uses_sdk, "targetSdkVersion"))
+    }
+}
+
+// References are resolved to their default value, eg. a versionName in
+// strings.xml
+fn format_value(value: &BinaryValue, table: &DecodedTable) -> Option<String> {
+    match value {
+        BinaryValue::String(string) => Some(string.clone()),
+        BinaryValue::Integer(integer) => Some(integer.to_string()),
+        BinaryValue::Boolean(boolean) => Some(boolean.to_string()),
+        BinaryValue::Reference(id) => {
+            let values = table.resolve(*id);
+            let (_, value) = values
+                .iter()
+                .find(|(config, _)| config.is_default())
+                .or(values.first())?;
+            match value {
+                BinaryValue::Reference(_) => None,
+                value => format_value(value, table)
+            }
+        }
+        _ => None
+    }
+}
+
+pub fn to_js<T: Serialize>(value: &T) -> Result<JsValue, PackWasmError> {
+    serde_wasm_bindgen::to_value(value).map_err(|e| {
+        PackWasmError::new(
+            "InvalidOutput",
+            format!("Failed to convert result to JS\n{e:?}")
+        )
+    })
+}
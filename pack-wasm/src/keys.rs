@@ -0,0 +1,83 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Signing keys for the web tool, so that it can create a signing identity once,
+// keep it in the browser, and show users which certificate they're using.
+use pack_api::SignerCertificate;
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::errors::PackWasmError;
+
+#[cfg(feature = "cert-gen")]
+use crate::input_types::PackWasmKeyOptions;
+#[cfg(feature = "cert-gen")]
+use pack_api::{KeyOptions, Keys};
+
+#[derive(Debug, Serialize)]
+pub struct PackWasmCertificate {
+    /// eg. `CN=My Watch Faces`
+    pub subject: String,
+    /// eg. `AB:CD:...`, which is how Play Console shows it
+    pub sha256_fingerprint: String
+}
+
+// Generates a key and a self-signed certificate, and returns them as a .pem
+// that `build` accepts as `combined_pem_string`. Takes an optional
+// `{ common_name, validity_days, rsa_bits }`. It's slow, especially for large
+// keys, so is best done in a web worker.
+#[cfg(feature = "cert-gen")]
+#[wasm_bindgen]
+pub fn generate_keys(options: JsValue) -> Result<String, PackWasmError> {
+    let options: PackWasmKeyOptions = if options.is_undefined() || options.is_null() {
+        PackWasmKeyOptions::default()
+    } else {
+        serde_wasm_bindgen::from_value(options).map_err(|e| {
+            PackWasmError::invalid_input(format!(
+                "JS object input did not match expected format\n{e:?}"
+            ))
+        })?
+    };
+    let defaults = KeyOptions::default();
+    let keys = Keys::generate_with_options(&KeyOptions {
+        common_name: options.common_name.unwrap_or(defaults.common_name),
+        validity_days: options.validity_days.or(defaults.validity_days),
+        rsa_bits: options.rsa_bits.unwrap_or(defaults.rsa_bits)
+    })?;
+    Ok(keys.to_combined_pem_string()?)
+}
+
+// Describes the certificate in a .pem, which can also contain the private key
+#[wasm_bindgen]
+pub fn describe_certificate(pem: &str) -> Result<JsValue, PackWasmError> {
+    let certificate = SignerCertificate::from_pem(pem)?;
+    serde_wasm_bindgen::to_value(&describe(&certificate)).map_err(|e| {
+        PackWasmError::new(
+            "InvalidOutput",
+            format!("Failed to convert certificate to JS\n{e:?}")
+        )
+    })
+}
+
+pub fn describe(certificate: &SignerCertificate) -> PackWasmCertificate {
+    let fingerprint: Vec<String> = certificate
+        .sha256_fingerprint
+        .iter()
+        .map(|byte| format!("{byte:02X}"))
+        .collect();
+    PackWasmCertificate {
+        subject: certificate.subject.clone(),
+        sha256_fingerprint: fingerprint.join(":")
+    }
+}
@@ -12,13 +12,26 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use pack_api::{compile_and_sign_aab, compile_and_sign_apk, FileResource, Keys, Package};
+#[cfg(feature = "aab")]
+use pack_api::{build_aab, compile_and_sign_aab};
+use pack_api::{
+    build_apk, compile_apk, sign_apk, validate_watch_face, BuildOptions, BuildOutput, FileResource,
+    Keys, Package, ResourceConfig
+};
 
 use base64::{engine::general_purpose, Engine};
-use input_types::PackWasmInput;
+use errors::PackWasmError;
+use input_types::{PackWasmBytesInput, PackWasmInput, PackWasmPackageInput, PackWasmQualifiers};
+use inspect::to_js;
+use progress::{Progress, Step};
+use serde::Serialize;
 use wasm_bindgen::prelude::*;
 
+mod errors;
 mod input_types;
+mod inspect;
+mod keys;
+mod progress;
 
 #[wasm_bindgen]
 extern "C" {
@@ -31,11 +44,108 @@ macro_rules! console_log {
     ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
 }
 
+// Panics would otherwise only show up as an "unreachable" RuntimeError
+#[wasm_bindgen(start)]
+fn start() {
+    console_error_panic_hook::set_once();
+}
+
 // Builds and signs an APK in-memory and returns it in Base64
 #[wasm_bindgen]
-pub fn build(input: JsValue) -> std::result::Result<String, String> {
-    let input: PackWasmInput = serde_wasm_bindgen::from_value(input)
-        .map_err(|e| format!("JS object input did not match expected format\n{e:?}"))?;
+pub fn build(input: JsValue) -> Result<String, PackWasmError> {
+    build_b64(input, &Progress::new(None))
+}
+
+// Same as `build`, but calls `on_progress` with an event for each step of the
+// build and each problem found in the watch face. Meant for running in a web
+// worker, with the events posted back to the page.
+#[wasm_bindgen]
+pub fn build_with_progress(
+    input: JsValue,
+    on_progress: &js_sys::Function
+) -> Result<String, PackWasmError> {
+    build_b64(input, &Progress::new(Some(on_progress)))
+}
+
+// Builds and signs an APK in-memory like `build`, but takes and returns files
+// as Uint8Arrays, which avoids copying everything through Base64
+#[wasm_bindgen]
+pub fn build_bytes(input: JsValue) -> Result<Vec<u8>, PackWasmError> {
+    build_from_bytes(input, &Progress::new(None))
+}
+
+// `build_bytes` with progress events, see `build_with_progress`
+#[wasm_bindgen]
+pub fn build_bytes_with_progress(
+    input: JsValue,
+    on_progress: &js_sys::Function
+) -> Result<Vec<u8>, PackWasmError> {
+    build_from_bytes(input, &Progress::new(Some(on_progress)))
+}
+
+// Builds a package described the same way as pack-api's `Package`, eg. from a
+// JSON file. File contents can be Uint8Arrays or Base64 strings.
+#[wasm_bindgen]
+pub fn build_package(input: JsValue) -> Result<Vec<u8>, PackWasmError> {
+    let input: PackWasmPackageInput = serde_wasm_bindgen::from_value(input).map_err(|e| {
+        PackWasmError::invalid_input(format!(
+            "JS object input did not match expected format\n{e:?}"
+        ))
+    })?;
+    compile(
+        input.package,
+        &input.combined_pem,
+        input.generate_aab,
+        &Progress::new(None)
+    )
+}
+
+#[derive(Debug, Serialize)]
+pub struct PackWasmBuildOutput {
+    #[serde(with = "serde_bytes")]
+    pub package: Vec<u8>,
+    pub package_name: String,
+    pub version_code: Option<u32>,
+    pub version_name: Option<String>,
+    pub min_sdk: Option<u32>,
+    /// With `@string/` references resolved
+    pub label: Option<String>
+}
+
+// `build_package`, but returns an object with the package as a Uint8Array
+// along with the package name, version and label from its manifest, eg. for
+// naming the download
+#[wasm_bindgen]
+pub fn build_package_with_output(input: JsValue) -> Result<JsValue, PackWasmError> {
+    let input: PackWasmPackageInput = serde_wasm_bindgen::from_value(input).map_err(|e| {
+        PackWasmError::invalid_input(format!(
+            "JS object input did not match expected format\n{e:?}"
+        ))
+    })?;
+    let signing_keys = Keys::from_combined_pem_string(&input.combined_pem)?;
+    let options = BuildOptions::default();
+    let output = if input.generate_aab {
+        build_aab_output(&input.package, &signing_keys, &options)?
+    } else {
+        build_apk(&input.package, &signing_keys, &options)?
+    };
+    to_js(&PackWasmBuildOutput {
+        package: output.bytes,
+        package_name: output.package_name,
+        version_code: output.version_code,
+        version_name: output.version_name,
+        min_sdk: output.min_sdk,
+        label: output.label
+    })
+}
+
+fn build_b64(input: JsValue, progress: &Progress) -> Result<String, PackWasmError> {
+    let input: PackWasmInput = serde_wasm_bindgen::from_value(input).map_err(|e| {
+        PackWasmError::invalid_input(format!(
+            "JS object input did not match expected format\n{e:?}"
+        ))
+    })?;
+    progress.step(Step::ReadInputs)?;
 
     let android_manifest = b64_to_bytes(&input.manifest_b64[..])?;
 
@@ -44,33 +154,143 @@ pub fn build(input: JsValue) -> std::result::Result<String, String> {
         .resources
         .iter()
         .map(|wasm_res| {
-            Ok::<FileResource, String>(FileResource::new(
-                wasm_res.subdirectory.clone(),
-                wasm_res.name.clone(),
-                b64_to_bytes(&wasm_res.contents_b64)?
-            ))
+            Ok::<FileResource, PackWasmError>(FileResource {
+                config: qualifiers_to_config(&wasm_res.subdirectory, &wasm_res.qualifiers)?,
+                ..FileResource::new(
+                    wasm_res.subdirectory.clone(),
+                    wasm_res.name.clone(),
+                    b64_to_bytes(&wasm_res.contents_b64)?
+                )
+            })
         })
-        .collect::<Result<Vec<_>, String>>()?;
-
-    let signing_keys = Keys::from_combined_pem_string(&input.combined_pem_string)?;
+        .collect::<Result<Vec<_>, PackWasmError>>()?;
 
     let pkg = Package {
         android_manifest,
-        resources
+        resources,
+        modules: vec![]
     };
+    Ok(bytes_to_b64(&compile(
+        pkg,
+        &input.combined_pem_string,
+        input.generate_aab,
+        progress
+    )?))
+}
+
+fn build_from_bytes(input: JsValue, progress: &Progress) -> Result<Vec<u8>, PackWasmError> {
+    let input: PackWasmBytesInput = serde_wasm_bindgen::from_value(input).map_err(|e| {
+        PackWasmError::invalid_input(format!(
+            "JS object input did not match expected format\n{e:?}"
+        ))
+    })?;
+    progress.step(Step::ReadInputs)?;
 
-    if input.generate_aab {
-        Ok(bytes_to_b64(&compile_and_sign_aab(&pkg, &signing_keys)?))
+    let resources: Vec<FileResource> = input
+        .resources
+        .into_iter()
+        .map(|wasm_res| {
+            Ok::<FileResource, PackWasmError>(FileResource {
+                config: qualifiers_to_config(&wasm_res.subdirectory, &wasm_res.qualifiers)?,
+                ..FileResource::new(
+                    wasm_res.subdirectory,
+                    wasm_res.name,
+                    wasm_res.contents.into_vec()
+                )
+            })
+        })
+        .collect::<Result<Vec<_>, PackWasmError>>()?;
+    let combined_pem = String::from_utf8(input.combined_pem.into_vec())
+        .map_err(|_| PackWasmError::invalid_input("combined_pem is not a text .pem file"))?;
+
+    let pkg = Package {
+        android_manifest: input.manifest.into_vec(),
+        resources,
+        modules: vec![]
+    };
+    compile(pkg, &combined_pem, input.generate_aab, progress)
+}
+
+fn compile(
+    pkg: Package,
+    combined_pem: &str,
+    generate_aab: bool,
+    progress: &Progress
+) -> Result<Vec<u8>, PackWasmError> {
+    let signing_keys = Keys::from_combined_pem_string(combined_pem)?;
+
+    // Validation is only for showing problems, so it's skipped without a
+    // callback to show them to. Anything that stops the build is reported
+    // by compiling instead.
+    progress.step(Step::Validate)?;
+    if progress.has_callback() {
+        for diagnostic in validate_watch_face(&pkg).unwrap_or_default() {
+            progress.diagnostic(&diagnostic)?;
+        }
+    }
+
+    progress.step(Step::Compile)?;
+    if generate_aab {
+        // Bundles are signed while they're assembled, so there's no Sign step
+        compile_aab(&pkg, &signing_keys)
     } else {
-        Ok(bytes_to_b64(&compile_and_sign_apk(&pkg, &signing_keys)?))
+        let apk = compile_apk(&pkg)?;
+        progress.step(Step::Sign)?;
+        Ok(sign_apk(&apk, &signing_keys)?)
+    }
+}
+
+#[cfg(feature = "aab")]
+fn compile_aab(pkg: &Package, signing_keys: &Keys) -> Result<Vec<u8>, PackWasmError> {
+    Ok(compile_and_sign_aab(pkg, signing_keys)?)
+}
+
+// APK-only builds leave out the bundle code to keep the .wasm small
+#[cfg(not(feature = "aab"))]
+fn compile_aab(_pkg: &Package, _signing_keys: &Keys) -> Result<Vec<u8>, PackWasmError> {
+    Err(no_aab_error())
+}
+
+#[cfg(feature = "aab")]
+fn build_aab_output(
+    pkg: &Package,
+    signing_keys: &Keys,
+    options: &BuildOptions
+) -> Result<BuildOutput, PackWasmError> {
+    Ok(build_aab(pkg, signing_keys, options)?)
+}
+
+#[cfg(not(feature = "aab"))]
+fn build_aab_output(
+    _pkg: &Package,
+    _signing_keys: &Keys,
+    _options: &BuildOptions
+) -> Result<BuildOutput, PackWasmError> {
+    Err(no_aab_error())
+}
+
+#[cfg(not(feature = "aab"))]
+fn no_aab_error() -> PackWasmError {
+    PackWasmError::invalid_input(
+        "This build of PACK can't generate AABs, since it was built without the aab feature"
+    )
+}
+
+fn qualifiers_to_config(
+    subdirectory: &str,
+    qualifiers: &Option<PackWasmQualifiers>
+) -> Result<ResourceConfig, PackWasmError> {
+    match qualifiers {
+        Some(qualifiers) => Ok(qualifiers.to_config(subdirectory)?),
+        None => Ok(ResourceConfig::default())
     }
 }
 
-fn b64_to_bytes(b64: &str) -> std::result::Result<Vec<u8>, String> {
+fn b64_to_bytes(b64: &str) -> Result<Vec<u8>, PackWasmError> {
     // Slightly unusual API
     general_purpose::STANDARD
         .decode(b64.as_bytes())
-        .map_err(|e| format!("Failed to decode Base64\n{e:?}"))
+        .map_err(|e| PackWasmError::invalid_input(format!("Failed to decode Base64\n{e:?}")))
 }
 
 fn bytes_to_b64(bytes: &Vec<u8>) -> String {
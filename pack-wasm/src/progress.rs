@@ -0,0 +1,116 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Progress events for the `*_with_progress` builds, so that a page running
+// PACK in a web worker can show how far along a build is, and any warnings,
+// instead of waiting seconds with no feedback.
+use pack_api::{Diagnostic, Severity};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::errors::PackWasmError;
+
+// Sent to the JS callback as eg. `{ type: "step", step: "compile", index: 2, count: 4 }`
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProgressEvent<'a> {
+    Step {
+        step: Step,
+        /// Starting from 0
+        index: usize,
+        count: usize
+    },
+    Diagnostic {
+        /// eg. `WatchFaceUnknownElement`
+        code: &'a str,
+        /// eg. `PACK7007`, for linking to documentation
+        id: String,
+        severity: &'a str,
+        path: Option<&'a str>,
+        line: Option<u64>,
+        column: Option<u64>,
+        message: &'a str
+    }
+}
+
+// AABs are signed while they're compiled, so they skip `Sign`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Step {
+    ReadInputs,
+    Validate,
+    Compile,
+    Sign
+}
+
+const STEPS: &[Step] = &[Step::ReadInputs, Step::Validate, Step::Compile, Step::Sign];
+
+// Reports events to an optional JS callback. If the callback throws, the
+// build stops with its error, which lets the page cancel a build.
+pub struct Progress<'a> {
+    callback: Option<&'a js_sys::Function>
+}
+
+impl<'a> Progress<'a> {
+    pub fn new(callback: Option<&'a js_sys::Function>) -> Self {
+        Progress { callback }
+    }
+
+    pub fn has_callback(&self) -> bool {
+        self.callback.is_some()
+    }
+
+    pub fn step(&self, step: Step) -> Result<(), PackWasmError> {
+        self.send(&ProgressEvent::Step {
+            step,
+            index: STEPS.iter().position(|s| *s == step).unwrap_or_default(),
+            count: STEPS.len()
+        })
+    }
+
+    pub fn diagnostic(&self, diagnostic: &Diagnostic) -> Result<(), PackWasmError> {
+        let (line, column) = diagnostic.span.map(|span| (span.line, span.column)).unzip();
+        self.send(&ProgressEvent::Diagnostic {
+            code: diagnostic.code.name(),
+            id: diagnostic.code.to_string(),
+            severity: match diagnostic.severity {
+                Severity::Warning => "warning",
+                Severity::Error => "error"
+            },
+            path: diagnostic.path.as_deref(),
+            line,
+            column,
+            message: &diagnostic.message
+        })
+    }
+
+    fn send(&self, event: &ProgressEvent) -> Result<(), PackWasmError> {
+        let Some(callback) = self.callback else {
+            return Ok(());
+        };
+        let event = serde_wasm_bindgen::to_value(event).map_err(|e| {
+            PackWasmError::new(
+                "InvalidOutput",
+                format!("Failed to convert progress event to JS\n{e:?}")
+            )
+        })?;
+        callback.call1(&JsValue::NULL, &event).map_err(|e| {
+            PackWasmError::new(
+                "Cancelled",
+                format!("Build cancelled by the progress callback\n{e:?}")
+            )
+        })?;
+        Ok(())
+    }
+}
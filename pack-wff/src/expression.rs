@@ -0,0 +1,398 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Watch faces compute things with expressions like "[HOUR_0_23] * 30", which
+// the watch only parses when it draws them. A typo makes the part silently
+// disappear, so these are parsed up front instead.
+//
+// Only the syntax and the data sources are checked, not the types. Operator
+// precedence doesn't change whether an expression is valid, so every binary
+// operator is treated the same.
+use pack_common::{Diagnostic, ErrorCode};
+use xml::{common::Position, reader::XmlEvent, EventReader};
+
+// Data sources, and the format version that introduced them
+const DATA_SOURCES: &[(&str, u32)] = &[
+    ("MILLISECOND", 1),
+    ("SECOND", 1),
+    ("SECOND_Z", 1),
+    ("SECOND_TENS_DIGIT", 1),
+    ("SECOND_UNITS_DIGIT", 1),
+    ("SECOND_MILLISECOND", 1),
+    ("SECONDS_IN_DAY", 1),
+    ("MINUTE", 1),
+    ("MINUTE_Z", 1),
+    ("MINUTE_TENS_DIGIT", 1),
+    ("MINUTE_UNITS_DIGIT", 1),
+    ("MINUTE_SECOND", 1),
+    ("HOUR_0_11", 1),
+    ("HOUR_0_11_Z", 1),
+    ("HOUR_0_11_MINUTE", 1),
+    ("HOUR_1_12", 1),
+    ("HOUR_1_12_Z", 1),
+    ("HOUR_1_12_MINUTE", 1),
+    ("HOUR_0_23", 1),
+    ("HOUR_0_23_Z", 1),
+    ("HOUR_0_23_MINUTE", 1),
+    ("HOUR_1_24", 1),
+    ("HOUR_1_24_Z", 1),
+    ("HOUR_1_24_MINUTE", 1),
+    ("HOUR_TENS_DIGIT", 1),
+    ("HOUR_UNITS_DIGIT", 1),
+    ("DAY", 1),
+    ("DAY_Z", 1),
+    ("DAY_HOUR", 1),
+    ("DAY_0_30", 1),
+    ("DAY_0_30_HOUR", 1),
+    ("DAY_OF_YEAR", 1),
+    ("DAY_OF_WEEK", 1),
+    ("DAY_OF_WEEK_F", 1),
+    ("DAY_OF_WEEK_S", 1),
+    ("DAYS_IN_MONTH", 1),
+    ("MONTH", 1),
+    ("MONTH_Z", 1),
+    ("MONTH_F", 1),
+    ("MONTH_S", 1),
+    ("MONTH_DAY", 1),
+    ("MONTH_0_11", 1),
+    ("MONTH_0_11_DAY", 1),
+    ("YEAR", 1),
+    ("YEAR_S", 1),
+    ("YEAR_MONTH", 1),
+    ("YEAR_MONTH_DAY", 1),
+    ("WEEK_IN_MONTH", 1),
+    ("WEEK_IN_YEAR", 1),
+    ("IS_24_HOUR_MODE", 1),
+    ("IS_DAYLIGHT_SAVING_TIME", 1),
+    ("AMPM_STATE", 1),
+    ("AMPM_POSITION", 1),
+    ("AMPM_STRING", 1),
+    ("TIMEZONE", 1),
+    ("TIMEZONE_ABB", 1),
+    ("TIMEZONE_ID", 1),
+    ("TIMEZONE_OFFSET", 1),
+    ("TIMEZONE_OFFSET_DST", 1),
+    ("UTC_TIMESTAMP", 1),
+    ("MOON_PHASE_POSITION", 1),
+    ("MOON_PHASE_TYPE", 1),
+    ("MOON_PHASE_TYPE_STRING", 1),
+    ("STEP_COUNT", 1),
+    ("STEP_GOAL", 1),
+    ("STEP_PERCENT", 1),
+    ("HEART_RATE", 1),
+    ("HEART_RATE_Z", 1),
+    ("BATTERY_PERCENT", 1),
+    ("BATTERY_CHARGING_STATUS", 1),
+    ("BATTERY_IS_LOW", 1),
+    ("BATTERY_TEMPERATURE_CELSIUS", 1),
+    ("BATTERY_TEMPERATURE_FAHRENHEIT", 1),
+    ("ACCELEROMETER_IS_SUPPORTED", 1),
+    ("ACCELEROMETER_X", 1),
+    ("ACCELEROMETER_Y", 1),
+    ("ACCELEROMETER_Z", 1),
+    ("ACCELEROMETER_ANGLE_X", 1),
+    ("ACCELEROMETER_ANGLE_Y", 1),
+    ("ACCELEROMETER_ANGLE_Z", 1),
+    ("ACCELEROMETER_ANGLE_XY", 1),
+    ("UNREAD_NOTIFICATION_COUNT", 1),
+    ("LANGUAGE_LOCALE_NAME", 1)
+];
+
+// Families of data sources whose names are only known at runtime, eg.
+// [CONFIGURATION.theme] or [WEATHER.TEMPERATURE]
+const DATA_SOURCE_PREFIXES: &[(&str, u32)] = &[
+    ("CONFIGURATION.", 1),
+    ("COMPLICATION.", 1),
+    ("WEATHER.", 2),
+    ("REFERENCE.", 4)
+];
+
+const FUNCTIONS: &[(&str, u32)] = &[
+    ("round", 1),
+    ("floor", 1),
+    ("ceil", 1),
+    ("fract", 1),
+    ("sin", 1),
+    ("cos", 1),
+    ("tan", 1),
+    ("asin", 1),
+    ("acos", 1),
+    ("atan", 1),
+    ("abs", 1),
+    ("clamp", 1),
+    ("rand", 1),
+    ("log", 1),
+    ("log2", 1),
+    ("log10", 1),
+    ("sqrt", 1),
+    ("cbrt", 1),
+    ("expm1", 1),
+    ("deg", 1),
+    ("rad", 1),
+    ("pow", 1),
+    ("numberFormat", 1),
+    ("icuText", 1),
+    ("icuBestText", 1),
+    ("subText", 1),
+    ("textLength", 1),
+    ("extractColorFromColors", 2),
+    ("extractColorFromWeightedColors", 2)
+];
+
+const BINARY_OPERATORS: &[&str] = &[
+    "==", "!=", "<=", ">=", "&&", "||", "+", "-", "*", "/", "%", "<", ">", "&", "|", "^"
+];
+const UNARY_OPERATORS: &[&str] = &["-", "+", "!", "~"];
+
+#[derive(Debug, PartialEq)]
+enum Token<'a> {
+    Number,
+    String,
+    DataSource(&'a str),
+    Identifier(&'a str),
+    // Operators and punctuation
+    Symbol(&'a str)
+}
+
+fn tokenise(expression: &str) -> Result<Vec<Token<'_>>, String> {
+    let mut tokens = vec![];
+    let mut rest = expression.trim_start();
+    while let Some(c) = rest.chars().next() {
+        let length = if c.is_ascii_digit() || c == '.' {
+            tokens.push(Token::Number);
+            rest.find(|c: char| !(c.is_ascii_digit() || c == '.'))
+                .unwrap_or(rest.len())
+        } else if c == '"' || c == '\'' {
+            tokens.push(Token::String);
+            rest[1..]
+                .find(c)
+                .map(|end| end + 2)
+                .ok_or("A string is missing its closing quote.")?
+        } else if c == '[' {
+            let end = rest
+                .find(']')
+                .ok_or("A data source is missing its closing \"]\".")?;
+            tokens.push(Token::DataSource(rest[1..end].trim()));
+            end + 1
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let length = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            tokens.push(Token::Identifier(&rest[..length]));
+            length
+        } else {
+            let symbol = BINARY_OPERATORS
+                .iter()
+                .chain(UNARY_OPERATORS)
+                .chain(&["(", ")", ",", "?", ":"])
+                .filter(|symbol| rest.starts_with(**symbol))
+                .max_by_key(|symbol| symbol.len())
+                .copied()
+                .ok_or(format!("Unexpected character \"{c}\"."))?;
+            tokens.push(Token::Symbol(symbol));
+            symbol.len()
+        };
+        rest = rest[length..].trim_start();
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    position: usize
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token<'a>> {
+        self.tokens.get(self.position)
+    }
+
+    fn next_is(&self, symbol: &str) -> bool {
+        self.peek() == Some(&Token::Symbol(symbol))
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<(), String> {
+        if self.next_is(symbol) {
+            self.position += 1;
+            Ok(())
+        } else {
+            Err(format!("Expected \"{symbol}\"."))
+        }
+    }
+
+    // expression := operand (binary-operator operand)* ("?" expression ":" expression)?
+    fn expression(&mut self) -> Result<(), String> {
+        self.operand()?;
+        while let Some(Token::Symbol(symbol)) = self.peek() {
+            if !BINARY_OPERATORS.contains(symbol) {
+                break;
+            }
+            self.position += 1;
+            self.operand()?;
+        }
+        if self.next_is("?") {
+            self.position += 1;
+            self.expression()?;
+            self.expect(":")?;
+            self.expression()?;
+        }
+        Ok(())
+    }
+
+    fn operand(&mut self) -> Result<(), String> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .ok_or("The expression ends too early.")?;
+        self.position += 1;
+        match token {
+            Token::Number | Token::String | Token::DataSource(_) => Ok(()),
+            Token::Symbol(symbol) if UNARY_OPERATORS.contains(symbol) => self.operand(),
+            Token::Symbol("(") => {
+                self.expression()?;
+                self.expect(")")
+            }
+            Token::Identifier("true" | "false") => Ok(()),
+            Token::Identifier(name) => {
+                let name = *name;
+                if !self.next_is("(") {
+                    return Err(format!(
+                        "\"{name}\" isn't a function call. Data sources must be in brackets, eg. \"[{name}]\"."
+                    ));
+                }
+                self.position += 1;
+                if !self.next_is(")") {
+                    self.expression()?;
+                    while self.next_is(",") {
+                        self.position += 1;
+                        self.expression()?;
+                    }
+                }
+                self.expect(")")
+            }
+            Token::Symbol(symbol) => Err(format!("Unexpected \"{symbol}\"."))
+        }
+    }
+}
+
+/// Returns a message for each thing wrong with an expression.
+pub(crate) fn check_expression(expression: &str, format_version: u32) -> Vec<String> {
+    let tokens = match tokenise(expression) {
+        Ok(tokens) => tokens,
+        Err(message) => return vec![format!("Invalid expression \"{expression}\": {message}")]
+    };
+    let mut parser = Parser {
+        tokens,
+        position: 0
+    };
+    let syntax = parser.expression().and_then(|_| match parser.peek() {
+        None => Ok(()),
+        Some(_) => Err("Unexpected text after the end of the expression.".into())
+    });
+    if let Err(message) = syntax {
+        return vec![format!("Invalid expression \"{expression}\": {message}")];
+    }
+
+    let mut messages = vec![];
+    for (i, token) in parser.tokens.iter().enumerate() {
+        let (kind, name, since) = match token {
+            Token::DataSource(name) => (
+                "Data source",
+                format!("[{name}]"),
+                DATA_SOURCES
+                    .iter()
+                    .find(|(data_source, _)| data_source == name)
+                    .or_else(|| {
+                        DATA_SOURCE_PREFIXES
+                            .iter()
+                            .find(|(prefix, _)| name.starts_with(prefix))
+                    })
+                    .map(|(_, since)| *since)
+            ),
+            Token::Identifier(name) if parser.tokens.get(i + 1) == Some(&Token::Symbol("(")) => (
+                "Function",
+                format!("{name}()"),
+                FUNCTIONS
+                    .iter()
+                    .find(|(function, _)| function == name)
+                    .map(|(_, since)| *since)
+            ),
+            _ => continue
+        };
+        match since {
+            None => messages.push(format!("{kind} {name} doesn't exist.")),
+            Some(since) if since > format_version => messages.push(format!(
+                "{kind} {name} requires Watch Face Format version {since}, but the watch face declares version {format_version}."
+            )),
+            Some(_) => {}
+        }
+    }
+    messages
+}
+
+/// Checks the syntax of every expression in a watch face, and that the data
+/// sources and functions they use exist in `format_version`.
+///
+/// Expressions are read from `expression` attributes, `<Transform value>`
+/// and the text of `<Expression>` elements.
+pub fn validate_expressions(
+    watch_face: &[u8],
+    watch_face_path: &str,
+    format_version: u32
+) -> Vec<Diagnostic> {
+    let path = Some(watch_face_path);
+    let mut diagnostics = vec![];
+    let mut in_expression_element = false;
+    let mut reader = EventReader::new(watch_face);
+    loop {
+        let event = reader.next();
+        let position = reader.position();
+        let expressions: Vec<String> = match event {
+            Ok(XmlEvent::StartElement {
+                name, attributes, ..
+            }) => {
+                in_expression_element = name.local_name == "Expression";
+                attributes
+                    .into_iter()
+                    .filter(|attribute| {
+                        attribute.name.local_name == "expression"
+                            || (name.local_name == "Transform"
+                                && attribute.name.local_name == "value")
+                    })
+                    .map(|attribute| attribute.value)
+                    .collect()
+            }
+            Ok(XmlEvent::Characters(text) | XmlEvent::CData(text)) if in_expression_element => {
+                vec![text]
+            }
+            Ok(XmlEvent::EndElement { .. }) => {
+                in_expression_element = false;
+                vec![]
+            }
+            Ok(XmlEvent::EndDocument) => break,
+            Ok(_) => vec![],
+            // Syntax errors in the XML itself are reported by the schema validation
+            Err(_) => break
+        };
+        for expression in expressions {
+            for message in check_expression(&expression, format_version) {
+                diagnostics.push(
+                    Diagnostic::error(ErrorCode::WatchFaceExpressionInvalid, path, message)
+                        .at(position)
+                );
+            }
+        }
+    }
+    diagnostics
+}
@@ -0,0 +1,160 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// A watch face can be perfectly valid XML and still not show up on the watch,
+// because the files around it are wrong. The usual culprits are a preview that
+// points at a drawable that doesn't exist, and a manifest that doesn't say
+// it's a watch face.
+//
+// <WatchFaceInfo>
+//     <Preview value="@drawable/preview" />
+//     <Editable value="true" />
+// </WatchFaceInfo>
+use pack_common::{Diagnostic, ErrorCode};
+use xml::{common::Position, reader::XmlEvent, EventReader};
+
+use crate::android_attribute;
+
+/// Where the system looks for a watch face's info.
+pub const WATCH_FACE_INFO_PATH: &str = "res/xml/watch_face_info.xml";
+
+const WATCH_FEATURE: &str = "android.hardware.type.watch";
+
+/// Checks that the manifest declares what a watch face needs to be installed
+/// and recognised: `<uses-feature android:name="android.hardware.type.watch">`
+/// and `<application android:hasCode="false">`.
+pub fn validate_manifest(android_manifest: &[u8]) -> Vec<Diagnostic> {
+    let path = Some("AndroidManifest.xml");
+    let mut diagnostics = vec![];
+    let mut uses_watch_feature = false;
+    let mut reader = EventReader::new(android_manifest);
+    loop {
+        let event = reader.next();
+        let position = reader.position();
+        match event {
+            Ok(XmlEvent::StartElement {
+                name, attributes, ..
+            }) => match &name.local_name[..] {
+                "uses-feature" => {
+                    uses_watch_feature |=
+                        android_attribute(&attributes, "name") == Some(WATCH_FEATURE);
+                }
+                "application" if android_attribute(&attributes, "hasCode") != Some("false") => {
+                    diagnostics.push(
+                        Diagnostic::error(
+                            ErrorCode::WatchFaceHasCode,
+                            path,
+                            "Watch faces can't contain code, so <application> must have android:hasCode=\"false\"."
+                        )
+                        .at(position)
+                    );
+                }
+                _ => {}
+            },
+            Ok(XmlEvent::EndDocument) => break,
+            Ok(_) => {}
+            Err(e) => {
+                diagnostics.push(
+                    Diagnostic::error(
+                        ErrorCode::XmlParsingFailed,
+                        path,
+                        format!("XML parsing error: {e}")
+                    )
+                    .at(e.position())
+                );
+                return diagnostics;
+            }
+        }
+    }
+    if !uses_watch_feature {
+        diagnostics.push(Diagnostic::error(
+            ErrorCode::WatchFeatureMissing,
+            path,
+            format!("Watch faces must declare <uses-feature android:name=\"{WATCH_FEATURE}\" />, or Google Play won't offer them to watches.")
+        ));
+    }
+    diagnostics
+}
+
+/// Checks that the preview in `watch_face_info.xml` refers to a drawable that
+/// exists. `drawables` are the drawables in the package, as pairs of their
+/// name without an extension and their contents.
+pub fn validate_watch_face_info(
+    watch_face_info: &[u8],
+    watch_face_info_path: &str,
+    drawables: &[(&str, &[u8])]
+) -> Vec<Diagnostic> {
+    let path = Some(watch_face_info_path);
+    let mut diagnostics = vec![];
+    let mut has_preview = false;
+    let mut reader = EventReader::new(watch_face_info);
+    loop {
+        let event = reader.next();
+        let position = reader.position();
+        match event {
+            Ok(XmlEvent::StartElement {
+                name, attributes, ..
+            }) if name.local_name == "Preview" => {
+                has_preview = true;
+                let value = attributes
+                    .iter()
+                    .find(|attribute| attribute.name.local_name == "value")
+                    .map(|attribute| &attribute.value[..])
+                    .unwrap_or_default();
+                if let Some(message) = check_preview(value, drawables) {
+                    diagnostics.push(
+                        Diagnostic::error(ErrorCode::WatchFacePreviewInvalid, path, message)
+                            .at(position)
+                    );
+                }
+            }
+            Ok(XmlEvent::EndDocument) => break,
+            Ok(_) => {}
+            Err(e) => {
+                diagnostics.push(
+                    Diagnostic::error(
+                        ErrorCode::XmlParsingFailed,
+                        path,
+                        format!("XML parsing error: {e}")
+                    )
+                    .at(e.position())
+                );
+                return diagnostics;
+            }
+        }
+    }
+    if !has_preview {
+        diagnostics.push(Diagnostic::error(
+            ErrorCode::WatchFacePreviewInvalid,
+            path,
+            "<WatchFaceInfo> must have a <Preview value=\"@drawable/...\" />, which is shown in the watch face picker."
+        ));
+    }
+    diagnostics
+}
+
+fn check_preview(value: &str, drawables: &[(&str, &[u8])]) -> Option<String> {
+    let Some(drawable_name) = value.strip_prefix("@drawable/") else {
+        return Some(format!(
+            "The preview \"{value}\" must be a drawable, eg. \"@drawable/preview\"."
+        ));
+    };
+    match drawables.iter().find(|(name, _)| *name == drawable_name) {
+        None => Some(format!(
+            "The preview \"{value}\" doesn't exist. Is it in res/drawable/?"
+        )),
+        Some((_, [])) => Some(format!("The preview \"{value}\" is an empty file.")),
+        Some(_) => None
+    }
+}
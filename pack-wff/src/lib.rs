@@ -0,0 +1,259 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # PACK WFF
+//!
+//! Checks watch faces written in the [Watch Face Format] before they're
+//! packaged, so that mistakes show up as build errors rather than as a blank
+//! watch face on the device.
+//!
+//! ```ignore
+//! let diagnostics = validate_watch_face(&manifest, &watch_face_xml, "res/raw/watchface.xml");
+//! for diagnostic in &diagnostics {
+//!     eprintln!("{diagnostic}");
+//! }
+//! ```
+//!
+//! [Watch Face Format]: https://developer.android.com/training/wearables/wff
+
+use std::ops::RangeInclusive;
+
+use pack_common::{Diagnostic, ErrorCode};
+use xml::{attribute::OwnedAttribute, common::Position, reader::XmlEvent, EventReader};
+
+mod expression;
+mod info;
+mod schema;
+
+pub use expression::validate_expressions;
+pub use info::{validate_manifest, validate_watch_face_info, WATCH_FACE_INFO_PATH};
+use schema::{find_element, ElementSchema, ROOT_ELEMENT};
+
+/// The manifest `<property>` that declares which version of the format a
+/// watch face is written in.
+pub const FORMAT_VERSION_PROPERTY: &str = "com.google.wear.watchface.format.version";
+
+/// The format versions that PACK knows the schema for.
+pub const SUPPORTED_FORMAT_VERSIONS: RangeInclusive<u32> = 1..=4;
+
+/// Returns whether an XML file is a watch face, ie. its root element is
+/// `<WatchFace>`.
+pub fn is_watch_face(xml: &[u8]) -> bool {
+    EventReader::new(xml)
+        .into_iter()
+        .find_map(|event| match event {
+            Ok(XmlEvent::StartElement { name, .. }) => Some(name.local_name == ROOT_ELEMENT),
+            Ok(_) => None,
+            Err(_) => Some(false)
+        })
+        .unwrap_or(false)
+}
+
+/// Checks a watch face against the schema of the format version declared in
+/// its AndroidManifest.xml. `watch_face_path` is only used in diagnostics, eg.
+/// `res/raw/watchface.xml`.
+pub fn validate_watch_face(
+    android_manifest: &[u8],
+    watch_face: &[u8],
+    watch_face_path: &str
+) -> Vec<Diagnostic> {
+    match declared_format_version(android_manifest) {
+        Ok(version) => validate_watch_face_xml(watch_face, watch_face_path, version),
+        Err(diagnostic) => vec![diagnostic]
+    }
+}
+
+/// Reads the format version from the manifest's
+/// `<property android:name="com.google.wear.watchface.format.version">`.
+pub fn declared_format_version(android_manifest: &[u8]) -> Result<u32, Diagnostic> {
+    let path = Some("AndroidManifest.xml");
+    let mut reader = EventReader::new(android_manifest);
+    loop {
+        let event = reader.next();
+        let position = reader.position();
+        match event {
+            Ok(XmlEvent::StartElement {
+                name, attributes, ..
+            }) if name.local_name == "property" || name.local_name == "meta-data" => {
+                if android_attribute(&attributes, "name") != Some(FORMAT_VERSION_PROPERTY) {
+                    continue;
+                }
+                let value = android_attribute(&attributes, "value").unwrap_or_default();
+                return match value.parse::<u32>() {
+                    Ok(version) if SUPPORTED_FORMAT_VERSIONS.contains(&version) => Ok(version),
+                    _ => Err(Diagnostic::error(
+                        ErrorCode::FormatVersionUnsupported,
+                        path,
+                        format!(
+                            "Watch Face Format version \"{value}\" isn't supported. Supported versions are {} to {}.",
+                            SUPPORTED_FORMAT_VERSIONS.start(),
+                            SUPPORTED_FORMAT_VERSIONS.end()
+                        )
+                    )
+                    .at(position))
+                };
+            }
+            Ok(XmlEvent::EndDocument) => {
+                return Err(Diagnostic::error(
+                    ErrorCode::FormatVersionMissing,
+                    path,
+                    format!("Watch faces must declare their format version with <property android:name=\"{FORMAT_VERSION_PROPERTY}\" android:value=\"...\" /> inside <application>.")
+                ))
+            }
+            Ok(_) => {}
+            Err(e) => {
+                return Err(Diagnostic::error(ErrorCode::XmlParsingFailed, path, format!("XML parsing error: {e}")).at(e.position()))
+            }
+        }
+    }
+}
+
+/// Checks a watch face against the schema for `format_version`: that every
+/// element exists in that version, is somewhere it's allowed to be, and has
+/// all of its required attributes.
+pub fn validate_watch_face_xml(
+    watch_face: &[u8],
+    watch_face_path: &str,
+    format_version: u32
+) -> Vec<Diagnostic> {
+    let path = Some(watch_face_path);
+    let mut diagnostics = vec![];
+    // The elements we're inside, or None for elements we didn't recognise
+    let mut stack: Vec<Option<&ElementSchema>> = vec![];
+    let mut reader = EventReader::new(watch_face);
+    loop {
+        let event = reader.next();
+        let position = reader.position();
+        match event {
+            Ok(XmlEvent::StartElement {
+                name, attributes, ..
+            }) => {
+                let element = &name.local_name[..];
+                let parent = stack.last().copied();
+                let parent_name = parent.flatten().map(|parent| parent.name);
+                let schema = match parent {
+                    // Everything inside an unknown element has already been reported
+                    Some(None) => None,
+                    None if element != ROOT_ELEMENT => {
+                        diagnostics.push(
+                            Diagnostic::error(
+                                ErrorCode::WatchFaceUnknownElement,
+                                path,
+                                format!(
+                                    "The root element must be <{ROOT_ELEMENT}>, not <{element}>."
+                                )
+                            )
+                            .at(position)
+                        );
+                        None
+                    }
+                    _ => match find_element(element, parent_name) {
+                        None => {
+                            diagnostics.push(
+                                Diagnostic::error(
+                                    ErrorCode::WatchFaceUnknownElement,
+                                    path,
+                                    format!("Unknown element <{element}>.")
+                                )
+                                .at(position)
+                            );
+                            None
+                        }
+                        Some(schema) => {
+                            check_element(schema, parent.flatten(), &attributes, format_version)
+                                .into_iter()
+                                .for_each(|message| {
+                                    diagnostics.push(
+                                        Diagnostic::error(
+                                            ErrorCode::WatchFaceSchemaViolation,
+                                            path,
+                                            message
+                                        )
+                                        .at(position)
+                                    )
+                                });
+                            Some(schema)
+                        }
+                    }
+                };
+                stack.push(schema);
+            }
+            Ok(XmlEvent::EndElement { .. }) => {
+                stack.pop();
+            }
+            Ok(XmlEvent::EndDocument) => break,
+            Ok(_) => {}
+            Err(e) => {
+                diagnostics.push(
+                    Diagnostic::error(
+                        ErrorCode::XmlParsingFailed,
+                        path,
+                        format!("XML parsing error: {e}")
+                    )
+                    .at(e.position())
+                );
+                break;
+            }
+        }
+    }
+    diagnostics
+}
+
+// Returns a message for everything wrong with a known element
+fn check_element(
+    schema: &ElementSchema,
+    parent: Option<&ElementSchema>,
+    attributes: &[OwnedAttribute],
+    format_version: u32
+) -> Vec<String> {
+    let mut messages = vec![];
+    if schema.since > format_version {
+        messages.push(format!(
+            "<{}> requires Watch Face Format version {}, but the watch face declares version {format_version}.",
+            schema.name, schema.since
+        ));
+    }
+    if let Some(parent) = parent {
+        if !parent.children.contains(&schema.name) {
+            messages.push(format!(
+                "<{}> isn't allowed inside <{}>.",
+                schema.name, parent.name
+            ));
+        }
+    }
+    for required in schema.required_attributes {
+        if !attributes
+            .iter()
+            .any(|attribute| attribute.name.local_name == *required)
+        {
+            messages.push(format!(
+                "<{}> is missing its required \"{required}\" attribute.",
+                schema.name
+            ));
+        }
+    }
+    messages
+}
+
+pub(crate) fn android_attribute<'a>(
+    attributes: &'a [OwnedAttribute],
+    key: &str
+) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|attribute| {
+            attribute.name.local_name == key && attribute.name.prefix.as_deref() == Some("android")
+        })
+        .map(|attribute| &attribute.value[..])
+}
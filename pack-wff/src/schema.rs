@@ -0,0 +1,394 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// A condensed copy of the Watch Face Format XSDs. It only records what's
+// needed to catch the usual mistakes: which elements exist, which version
+// added them, where they can go and which attributes they can't do without.
+// Optional attributes aren't listed, so unknown attributes aren't reported.
+
+pub(crate) struct ElementSchema {
+    pub name: &'static str,
+    /// Only applies inside this parent, for elements whose meaning depends on
+    /// where they are, eg. <BitmapFont> inside <BitmapFonts> versus <Text>.
+    pub parent: Option<&'static str>,
+    /// The format version that introduced the element.
+    pub since: u32,
+    pub required_attributes: &'static [&'static str],
+    pub children: &'static [&'static str]
+}
+
+const fn element(
+    name: &'static str,
+    required_attributes: &'static [&'static str],
+    children: &'static [&'static str]
+) -> ElementSchema {
+    ElementSchema {
+        name,
+        parent: None,
+        since: 1,
+        required_attributes,
+        children
+    }
+}
+
+const fn since(version: u32, schema: ElementSchema) -> ElementSchema {
+    ElementSchema {
+        since: version,
+        ..schema
+    }
+}
+
+const fn inside(parent: &'static str, schema: ElementSchema) -> ElementSchema {
+    ElementSchema {
+        parent: Some(parent),
+        ..schema
+    }
+}
+
+pub(crate) const ROOT_ELEMENT: &str = "WatchFace";
+
+const BOUNDS: &[&str] = &["x", "y", "width", "height"];
+
+// Anything that can be drawn, which is what most containers hold
+macro_rules! scene_children {
+    ($($extra:literal),*) => {
+        &[
+            "Group", "PartText", "PartImage", "PartDraw", "PartAnimatedImage", "AnalogClock",
+            "DigitalClock", "ComplicationSlot", "Condition", "ListConfiguration",
+            "BooleanConfiguration", "PhotosConfiguration" $(, $extra)*
+        ]
+    };
+}
+
+// Elements that change how the part they're inside is drawn
+macro_rules! part_children {
+    ($($extra:literal),*) => {
+        &[
+            "Variant", "Transform", "Gyro", "Launch", "Localization", "ScreenReader",
+            "Reference" $(, $extra)*
+        ]
+    };
+}
+
+const SHAPE_CHILDREN: &[&str] = &["Stroke", "Fill", "Transform", "Variant"];
+const PAINT_CHILDREN: &[&str] = &[
+    "LinearGradient",
+    "RadialGradient",
+    "SweepGradient",
+    "Transform"
+];
+const FONT_CHILDREN: &[&str] = &[
+    "Template",
+    "Upper",
+    "Lower",
+    "Underline",
+    "StrikeThrough",
+    "Shadow",
+    "Outline",
+    "OutGlow",
+    "Transform",
+    "Variant"
+];
+const HAND_CHILDREN: &[&str] = &["Variant", "Transform"];
+
+pub(crate) const ELEMENTS: &[ElementSchema] = &[
+    element(
+        "WatchFace",
+        &["width", "height"],
+        &["Metadata", "BitmapFonts", "UserConfigurations", "Scene"]
+    ),
+    element("Metadata", &["key", "value"], &[]),
+    element("BitmapFonts", &[], &["BitmapFont"]),
+    inside(
+        "BitmapFonts",
+        element("BitmapFont", &["name"], &["Character", "Word"])
+    ),
+    element("Character", &["name", "resource", "width", "height"], &[]),
+    element("Word", &["name", "resource", "width", "height"], &[]),
+    element(
+        "UserConfigurations",
+        &[],
+        &[
+            "ColorConfiguration",
+            "ListConfiguration",
+            "BooleanConfiguration",
+            "PhotosConfiguration",
+            "Flavors"
+        ]
+    ),
+    element(
+        "ColorConfiguration",
+        &["id", "displayName"],
+        &["ColorOption"]
+    ),
+    element("ColorOption", &["id", "colors"], &[]),
+    inside(
+        "UserConfigurations",
+        element("ListConfiguration", &["id", "displayName"], &["ListOption"])
+    ),
+    element("ListConfiguration", &["id"], &["ListOption"]),
+    element("ListOption", &["id"], scene_children!()),
+    inside(
+        "UserConfigurations",
+        element("BooleanConfiguration", &["id", "displayName"], &[])
+    ),
+    element("BooleanConfiguration", &["id"], &["BooleanOption"]),
+    element("BooleanOption", &["id"], scene_children!()),
+    since(
+        4,
+        inside(
+            "UserConfigurations",
+            element("PhotosConfiguration", &["id", "configType"], &[])
+        )
+    ),
+    since(4, element("PhotosConfiguration", &["id"], &["Photos"])),
+    since(4, element("Photos", &["source"], &[])),
+    since(2, element("Flavors", &[], &["Flavor"])),
+    since(
+        2,
+        element("Flavor", &["id"], &["Configuration", "ComplicationSlot"])
+    ),
+    since(2, element("Configuration", &["id", "optionId"], &[])),
+    inside(
+        "Flavor",
+        element("ComplicationSlot", &["slotId"], &["DefaultProviderPolicy"])
+    ),
+    element("Scene", &[], scene_children!()),
+    element(
+        "Group",
+        BOUNDS,
+        scene_children!(
+            "Variant",
+            "Transform",
+            "Gyro",
+            "Launch",
+            "Localization",
+            "ScreenReader",
+            "Reference"
+        )
+    ),
+    element("PartImage", BOUNDS, part_children!("Image")),
+    element("Image", &["resource"], &[]),
+    element("PartText", BOUNDS, part_children!("Text", "TextCircular")),
+    element("Text", &[], &["Font", "BitmapFont"]),
+    element(
+        "TextCircular",
+        &[
+            "centerX",
+            "centerY",
+            "width",
+            "height",
+            "startAngle",
+            "endAngle"
+        ],
+        &["Font"]
+    ),
+    element("Font", &["family", "size"], FONT_CHILDREN),
+    element("BitmapFont", &["family", "size"], FONT_CHILDREN),
+    element("Template", &[], &["Parameter"]),
+    element("Parameter", &["expression"], &[]),
+    element("Upper", &[], &["Template"]),
+    element("Lower", &[], &["Template"]),
+    element("Underline", &[], &[]),
+    element("StrikeThrough", &[], &[]),
+    element("Shadow", &[], &["Transform"]),
+    element("Outline", &[], &["Transform"]),
+    element("OutGlow", &[], &["Transform"]),
+    element(
+        "PartDraw",
+        BOUNDS,
+        part_children!("Line", "Arc", "Rectangle", "RoundRectangle", "Ellipse")
+    ),
+    element(
+        "Line",
+        &["startX", "startY", "endX", "endY"],
+        SHAPE_CHILDREN
+    ),
+    element(
+        "Arc",
+        &[
+            "centerX",
+            "centerY",
+            "width",
+            "height",
+            "startAngle",
+            "endAngle"
+        ],
+        SHAPE_CHILDREN
+    ),
+    element("Rectangle", BOUNDS, SHAPE_CHILDREN),
+    element(
+        "RoundRectangle",
+        &[
+            "x",
+            "y",
+            "width",
+            "height",
+            "cornerRadiusX",
+            "cornerRadiusY"
+        ],
+        SHAPE_CHILDREN
+    ),
+    element("Ellipse", BOUNDS, SHAPE_CHILDREN),
+    element("Stroke", &["color", "thickness"], PAINT_CHILDREN),
+    element("Fill", &["color"], PAINT_CHILDREN),
+    element(
+        "LinearGradient",
+        &["startX", "startY", "endX", "endY", "colors", "positions"],
+        &["Transform"]
+    ),
+    element(
+        "RadialGradient",
+        &["centerX", "centerY", "radius", "colors", "positions"],
+        &["Transform"]
+    ),
+    element(
+        "SweepGradient",
+        &[
+            "centerX",
+            "centerY",
+            "startAngle",
+            "endAngle",
+            "colors",
+            "positions"
+        ],
+        &["Transform"]
+    ),
+    element(
+        "PartAnimatedImage",
+        BOUNDS,
+        part_children!("AnimatedImage", "AnimationController", "Thumbnail")
+    ),
+    element("AnimatedImage", &["resource", "format"], &[]),
+    element("AnimationController", &["play"], &[]),
+    element("Thumbnail", &["resource"], &[]),
+    element(
+        "AnalogClock",
+        BOUNDS,
+        &[
+            "HourHand",
+            "MinuteHand",
+            "SecondHand",
+            "Variant",
+            "Transform",
+            "Localization",
+            "ScreenReader"
+        ]
+    ),
+    element(
+        "HourHand",
+        &["resource", "x", "y", "width", "height"],
+        HAND_CHILDREN
+    ),
+    element(
+        "MinuteHand",
+        &["resource", "x", "y", "width", "height"],
+        HAND_CHILDREN
+    ),
+    element(
+        "SecondHand",
+        &["resource", "x", "y", "width", "height"],
+        &["Variant", "Transform", "Sweep", "Tick"]
+    ),
+    element("Sweep", &["frequency"], &[]),
+    element("Tick", &["duration", "strength"], &[]),
+    element(
+        "DigitalClock",
+        BOUNDS,
+        &[
+            "TimeText",
+            "Variant",
+            "Transform",
+            "Localization",
+            "ScreenReader"
+        ]
+    ),
+    element(
+        "TimeText",
+        &["format", "x", "y", "width", "height"],
+        &["Font", "BitmapFont", "Variant", "Transform"]
+    ),
+    element(
+        "ComplicationSlot",
+        &["slotId", "supportedTypes", "x", "y", "width", "height"],
+        &[
+            "BoundingBox",
+            "BoundingOval",
+            "BoundingArc",
+            "DefaultProviderPolicy",
+            "Complication",
+            "Variant",
+            "Transform"
+        ]
+    ),
+    element("BoundingBox", BOUNDS, &[]),
+    element("BoundingOval", BOUNDS, &[]),
+    since(
+        2,
+        element(
+            "BoundingArc",
+            &[
+                "centerX",
+                "centerY",
+                "width",
+                "height",
+                "startAngle",
+                "endAngle"
+            ],
+            &[]
+        )
+    ),
+    element(
+        "DefaultProviderPolicy",
+        &["defaultSystemProvider", "defaultSystemProviderType"],
+        &[]
+    ),
+    element(
+        "Complication",
+        &["type"],
+        &[
+            "Group",
+            "PartText",
+            "PartImage",
+            "PartDraw",
+            "PartAnimatedImage",
+            "Condition"
+        ]
+    ),
+    element("Condition", &[], &["Expressions", "Compare", "Default"]),
+    element("Expressions", &[], &["Expression"]),
+    element("Expression", &["name"], &[]),
+    element("Compare", &["expression"], scene_children!()),
+    element("Default", &[], scene_children!()),
+    element("Variant", &["mode", "target", "value"], &[]),
+    element("Transform", &["target", "value"], &["Animation"]),
+    element("Animation", &["duration"], &[]),
+    element("Gyro", &[], &[]),
+    element("Launch", &["target"], &[]),
+    element("Localization", &[], &[]),
+    element("ScreenReader", &[], &["Template"]),
+    since(4, element("Reference", &["source", "name"], &[]))
+];
+
+/// Finds the schema for an element, preferring one specific to its parent.
+pub(crate) fn find_element(name: &str, parent: Option<&str>) -> Option<&'static ElementSchema> {
+    ELEMENTS
+        .iter()
+        .find(|schema| schema.name == name && schema.parent.is_some() && schema.parent == parent)
+        .or_else(|| {
+            ELEMENTS
+                .iter()
+                .find(|schema| schema.name == name && schema.parent.is_none())
+        })
+}
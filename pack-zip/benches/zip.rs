@@ -0,0 +1,56 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Benchmarks for zipping a package with many large images. Run with
+// `cargo bench -p pack-zip`.
+use std::{hint::black_box, io::Cursor};
+
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use pack_zip::{zip_apk, File};
+
+// Compressed images barely deflate, so they're stood in for by noise. A fixed
+// seed keeps every run zipping the same bytes.
+fn noise(len: usize, mut seed: u64) -> Vec<u8> {
+    (0..len)
+        .map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed as u8
+        })
+        .collect()
+}
+
+fn zip_pngs(c: &mut Criterion) {
+    let files: Vec<File> = (0..64)
+        .map(|index| File {
+            path: format!("res/drawable-xxhdpi/image_{index}.png"),
+            data: noise(256 * 1024, index + 1)
+        })
+        .collect();
+    let total: usize = files.iter().map(|file| file.data.len()).sum();
+    let mut group = c.benchmark_group("zip_apk");
+    group.throughput(Throughput::Bytes(total as u64));
+    group.bench_function("64 large PNGs", |b| {
+        b.iter(|| {
+            let mut output = Cursor::new(Vec::with_capacity(total));
+            zip_apk(black_box(&files), &mut output).unwrap();
+            output
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, zip_pngs);
+criterion_main!(benches);
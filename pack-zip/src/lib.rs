@@ -13,38 +13,276 @@
 // limitations under the License.
 
 use pack_common::*;
-use std::io::{Seek, Write};
-use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+use std::{
+    collections::{HashMap, HashSet},
+    io::{Cursor, Read, Seek, Write},
+    sync::Arc
+};
+use zip::{result::ZipError, write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter};
 
+#[derive(Debug, Clone)]
 pub struct File {
     pub path: String,
     pub data: Vec<u8>
 }
 
+/// How much space a file takes up in a zip, from [entry_sizes].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntrySize {
+    pub path: String,
+    /// The size of the file's data in the zip, not counting its headers.
+    pub compressed_size: u64,
+    pub uncompressed_size: u64,
+    /// Whether the file is stored as it is rather than deflated.
+    pub stored: bool
+}
+
+/// Settings for [zip_apk_with_options].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZipOptions {
+    /// Leaves files whose paths match any of the globs uncompressed, eg.
+    /// `res/raw/*.ogg`.
+    pub uncompressed_globs: Vec<String>,
+    /// Stores or deflates the files at these paths, whatever the globs say.
+    /// Paths are matched after normalising, see [normalize_entry_path].
+    pub compression: HashMap<String, Compression>,
+    /// What the data of every file is aligned to, in bytes. Android needs
+    /// uncompressed files to be at least 4-byte aligned so that it can map
+    /// them straight from the APK, but some stores ask for 8 or 16. Must be a
+    /// power of two.
+    pub alignment: u16,
+    /// What the data of uncompressed native libraries, ie. `.so` files in a
+    /// `lib/` directory, is aligned to instead. Android can only load them
+    /// from the APK if they're aligned to the page size, which is 16 KB on
+    /// newer devices. Must be a power of two.
+    pub native_library_alignment: u16
+}
+
+impl Default for ZipOptions {
+    fn default() -> Self {
+        ZipOptions {
+            uncompressed_globs: vec![],
+            compression: HashMap::new(),
+            alignment: DEFAULT_ALIGNMENT,
+            native_library_alignment: DEFAULT_NATIVE_LIBRARY_ALIGNMENT
+        }
+    }
+}
+
+pub const DEFAULT_ALIGNMENT: u16 = 4;
+pub const DEFAULT_NATIVE_LIBRARY_ALIGNMENT: u16 = 16384;
+
 const UNCOMPRESSED_FILES: &[&str] = &["resources.arsc"];
 
+// The most unzip allocates for an entry before reading it
+const MAX_PREALLOCATED_SIZE: u64 = 1024 * 1024;
+// The biggest file unzip extracts. Google Play's largest asset packs are
+// 1.5GB, so anything bigger is more likely to be a zip bomb than a package.
+const MAX_ENTRY_SIZE: u64 = 2 * 1024 * 1024 * 1024;
+
 // Output can be a file *or* a buffer in memory
 pub fn zip_apk<T: Write + Seek>(files: &[File], output: T) -> Result<()> {
+    zip_apk_with_uncompressed(files, output, &[])
+}
+
+/// Same as [zip_apk], but also leaves files whose paths match any of
+/// `uncompressed_globs` uncompressed, eg. `res/raw/*.ogg`.
+pub fn zip_apk_with_uncompressed<T: Write + Seek>(
+    files: &[File],
+    output: T,
+    uncompressed_globs: &[String]
+) -> Result<()> {
+    zip_apk_with_compression(files, output, uncompressed_globs, &HashMap::new())
+}
+
+/// Same as [zip_apk_with_uncompressed], but files whose paths are in
+/// `compression` are stored or deflated as it says, whatever the globs say.
+pub fn zip_apk_with_compression<T: Write + Seek>(
+    files: &[File],
+    output: T,
+    uncompressed_globs: &[String],
+    compression: &HashMap<String, Compression>
+) -> Result<()> {
+    let options = ZipOptions {
+        uncompressed_globs: uncompressed_globs.to_vec(),
+        compression: compression.clone(),
+        ..Default::default()
+    };
+    zip_apk_with_options(files, output, &options)
+}
+
+/// Same as [zip_apk], but with customisable [ZipOptions].
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(name = "zip", skip_all, fields(files = files.len()))
+)]
+pub fn zip_apk_with_options<T: Write + Seek>(
+    files: &[File],
+    output: T,
+    options: &ZipOptions
+) -> Result<()> {
+    for alignment in [options.alignment, options.native_library_alignment] {
+        if !alignment.is_power_of_two() {
+            return Err(PackError::ZipAlignmentInvalid(alignment));
+        }
+    }
+
     let mut zip = ZipWriter::new(output);
     let compressed_options = SimpleFileOptions::default()
         .compression_method(CompressionMethod::Deflated)
-        .with_alignment(4);
+        .with_alignment(options.alignment);
     // Some files in APKs are not allowed to be compressed
     // TODO: AAPT2 doesn't compress drawable PNGs, but maybe it could?
     let uncompressed_options = SimpleFileOptions::default()
         .compression_method(CompressionMethod::Stored)
-        .with_alignment(4);
+        .with_alignment(options.alignment);
+    let native_library_options =
+        uncompressed_options.with_alignment(options.native_library_alignment);
 
-    for file in files {
-        let options = if UNCOMPRESSED_FILES.contains(&&file.path[..]) {
-            uncompressed_options
-        } else {
+    let paths = files
+        .iter()
+        .map(|file| normalize_entry_path(&file.path))
+        .collect::<Result<Vec<String>>>()?;
+    // The zip writer can't store two files at the same path, and files whose
+    // paths only differ in case would overwrite each other when the package
+    // is extracted on macOS or Windows
+    let mut exact_paths = HashSet::new();
+    let mut folded_paths: HashMap<String, &str> = HashMap::new();
+    for path in &paths {
+        if !exact_paths.insert(path) {
+            return Err(PackError::DuplicateEntry(path.clone()));
+        }
+        if let Some(other) = folded_paths.insert(path.to_lowercase(), path) {
+            return Err(PackError::EntryPathInvalid(format!(
+                "\"{other}\" and \"{path}\" only differ in case, so one would overwrite the other on a case-insensitive file system."
+            )));
+        }
+    }
+
+    // Looked up by normalised path, like the globs, so `res\raw\a.bin`
+    // and `res/raw/a.bin` are the same file
+    let compression: HashMap<String, Compression> = options
+        .compression
+        .iter()
+        .map(|(path, compression)| (path.replace('\\', "/"), *compression))
+        .collect();
+    for (file, path) in files.iter().zip(&paths) {
+        let store = match compression.get(path) {
+            Some(Compression::Store) => true,
+            Some(Compression::Deflate) => false,
+            Some(Compression::Auto) | None => {
+                UNCOMPRESSED_FILES.contains(&&path[..])
+                    || options
+                        .uncompressed_globs
+                        .iter()
+                        .any(|glob| glob_matches(glob, path))
+            }
+        };
+        let file_options = if !store {
             compressed_options
+        } else if is_native_library(path) {
+            native_library_options
+        } else {
+            uncompressed_options
         };
-        zip.start_file_from_path(&file.path, options).unwrap();
+        zip.start_file_from_path(path, file_options).unwrap();
         zip.write_all(&file.data)?;
     }
 
     zip.finish()?;
     Ok(())
 }
+
+/// Normalises the path of a file in a zip, eg. one built on Windows, by
+/// turning backslashes into forward slashes as aapt2 does. Fails for paths
+/// that can't be extracted where they belong: empty or absolute ones, and
+/// ones with empty, `.` or `..` segments.
+pub fn normalize_entry_path(path: &str) -> Result<String> {
+    let normalized = path.replace('\\', "/");
+    let invalid = |reason: &str| PackError::EntryPathInvalid(format!("\"{path}\" {reason}"));
+    if normalized.is_empty() {
+        return Err(PackError::EntryPathInvalid(
+            "A file has an empty path.".into()
+        ));
+    }
+    if normalized.starts_with('/') {
+        return Err(invalid(
+            "is absolute, but paths in a package are relative to its root."
+        ));
+    }
+    if normalized
+        .split('/')
+        .any(|segment| segment.is_empty() || segment == "." || segment == "..")
+    {
+        return Err(invalid("has an empty, \".\" or \"..\" segment."));
+    }
+    Ok(normalized)
+}
+
+// eg. lib/arm64-v8a/libfoo.so in an APK, or base/lib/arm64-v8a/libfoo.so in
+// a bundle
+fn is_native_library(path: &str) -> bool {
+    path.ends_with(".so") && (path.starts_with("lib/") || path.contains("/lib/"))
+}
+
+/// Reads every file out of an existing zip, such as an APK or AAB.
+///
+/// Directory entries are skipped. Files are returned in the order they appear
+/// in the central directory. Fails with [PackError::ZipStructureInvalid] if a
+/// file decompresses to more than its header says, or is bigger than 2GB.
+pub fn unzip(zip_bytes: &[u8]) -> Result<Vec<File>> {
+    let read_err = |e: ZipError| PackError::ZipReadingFailed(Arc::new(e));
+    let mut archive = ZipArchive::new(Cursor::new(zip_bytes)).map_err(read_err)?;
+    let mut files = vec![];
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).map_err(read_err)?;
+        if entry.is_dir() {
+            continue;
+        }
+        // The size comes from the zip's headers, which anyone could have
+        // written, so it's only trusted up to a point
+        let size = entry.size();
+        if size > MAX_ENTRY_SIZE {
+            return Err(PackError::ZipStructureInvalid(format!(
+                "\"{}\" is {size} bytes, which is more than PACK will extract.",
+                entry.name()
+            )));
+        }
+        let mut data = Vec::with_capacity(size.min(MAX_PREALLOCATED_SIZE) as usize);
+        // One byte more than the header says is enough to know it lied
+        (&mut entry).take(size + 1).read_to_end(&mut data)?;
+        if data.len() as u64 > size {
+            return Err(PackError::ZipStructureInvalid(format!(
+                "\"{}\" decompresses to more than the {size} bytes its header says.",
+                entry.name()
+            )));
+        }
+        files.push(File {
+            path: entry.name().to_string(),
+            data
+        });
+    }
+    Ok(files)
+}
+
+/// Lists the size of every file in an existing zip, without decompressing
+/// them. Directory entries are skipped, and files are in central directory
+/// order, like [unzip].
+pub fn entry_sizes(zip_bytes: &[u8]) -> Result<Vec<EntrySize>> {
+    let read_err = |e: ZipError| PackError::ZipReadingFailed(Arc::new(e));
+    let mut archive = ZipArchive::new(Cursor::new(zip_bytes)).map_err(read_err)?;
+    let mut sizes = vec![];
+    for i in 0..archive.len() {
+        let entry = archive.by_index_raw(i).map_err(read_err)?;
+        if entry.is_dir() {
+            continue;
+        }
+        sizes.push(EntrySize {
+            path: entry.name().to_string(),
+            compressed_size: entry.compressed_size(),
+            uncompressed_size: entry.size(),
+            stored: entry.compression() == CompressionMethod::Stored
+        });
+    }
+    Ok(sizes)
+}
@@ -0,0 +1,51 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Per-file compression overrides, which apply to whatever form the file's
+// path was given in.
+use std::{collections::HashMap, io::Cursor};
+
+use pack_common::Compression;
+use pack_zip::{entry_sizes, zip_apk_with_options, File, ZipOptions};
+
+// Compresses well, so it's only stored if something asks for it
+const DATA: &[u8] = &[0; 4096];
+
+fn zip(path: &str, override_path: &str) -> bool {
+    let files = [File {
+        path: path.into(),
+        data: DATA.to_vec()
+    }];
+    let options = ZipOptions {
+        compression: HashMap::from([(override_path.to_string(), Compression::Store)]),
+        ..Default::default()
+    };
+    let mut zip = Cursor::new(vec![]);
+    zip_apk_with_options(&files, &mut zip, &options).unwrap();
+    let sizes = entry_sizes(zip.get_ref()).unwrap();
+    assert_eq!(sizes[0].path, "res/raw/a.bin");
+    sizes[0].stored
+}
+
+#[test]
+fn override_applies_to_normalized_path() {
+    assert!(zip("res/raw/a.bin", "res/raw/a.bin"));
+    assert!(zip("res\\raw\\a.bin", "res/raw/a.bin"));
+    assert!(zip("res/raw/a.bin", "res\\raw\\a.bin"));
+}
+
+#[test]
+fn other_paths_are_deflated() {
+    assert!(!zip("res/raw/a.bin", "res/raw/b.bin"));
+}
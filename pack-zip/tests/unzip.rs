@@ -0,0 +1,67 @@
+// Copyright 2026 Google LLC
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Unzips files whose headers claim they're smaller or bigger than they are,
+// the way a zip bomb would.
+use std::io::Cursor;
+
+use pack_common::PackError;
+use pack_zip::{unzip, zip_apk, File};
+
+// Compresses well, so it's deflated
+const DATA: &[u8] = &[0; 4096];
+
+const LOCAL_HEADER_SIGNATURE: &[u8] = &[0x50, 0x4B, 0x03, 0x04];
+const CENTRAL_HEADER_SIGNATURE: &[u8] = &[0x50, 0x4B, 0x01, 0x02];
+
+// Zips DATA, with both of its headers claiming it decompresses to `size`
+fn zip_claiming(size: u32) -> Vec<u8> {
+    let files = [File {
+        path: "res/raw/a.bin".into(),
+        data: DATA.to_vec()
+    }];
+    let mut zip = Cursor::new(vec![]);
+    zip_apk(&files, &mut zip).unwrap();
+    let mut zip = zip.into_inner();
+    for (signature, size_offset) in [(LOCAL_HEADER_SIGNATURE, 22), (CENTRAL_HEADER_SIGNATURE, 24)] {
+        let header = zip
+            .windows(4)
+            .position(|window| window == signature)
+            .unwrap();
+        zip[header + size_offset..header + size_offset + 4].copy_from_slice(&size.to_le_bytes());
+    }
+    zip
+}
+
+#[test]
+fn honest_sizes_are_unzipped() {
+    let files = unzip(&zip_claiming(DATA.len() as u32)).unwrap();
+    assert_eq!(files[0].data, DATA);
+}
+
+#[test]
+fn data_past_the_declared_size_is_rejected() {
+    assert!(matches!(
+        unzip(&zip_claiming(16)),
+        Err(PackError::ZipStructureInvalid(_))
+    ));
+}
+
+#[test]
+fn huge_declared_sizes_are_rejected() {
+    assert!(matches!(
+        unzip(&zip_claiming(u32::MAX - 1)),
+        Err(PackError::ZipStructureInvalid(_))
+    ));
+}